@@ -0,0 +1,28 @@
+// Proving a 2^16-row Fibonacci trace under the Poseidon2 DuplexChallenger and
+// the Keccak-256 SerializingChallenger; the PCS is the same, so the gap is
+// the transcript hashing alone
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use fibonacci_proof::challenger::{ChallengerKind, prove_with_challenger};
+use fibonacci_proof::{Val, generate_fibonacci_trace};
+
+// One row past the last step keeps the trace at exactly 2^16 rows
+const STEPS: usize = (1 << 16) - 1;
+
+fn bench_challengers(c: &mut Criterion) {
+    let trace = generate_fibonacci_trace::<Val>(STEPS).unwrap();
+    let mut group = c.benchmark_group("prove 2^16 rows");
+    group.sample_size(10);
+    for kind in [ChallengerKind::Duplex, ChallengerKind::Keccak] {
+        group.bench_function(kind.name(), |b| {
+            b.iter_batched(
+                || trace.matrix.clone(),
+                |matrix| prove_with_challenger(kind, matrix, &trace.public_values),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_challengers);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+// Records which Plonky3 revision and cargo features this binary was built
+// with, for src/provenance.rs. Plonky3 is a git dependency, so the revision
+// comes from the resolved Cargo.lock rather than a version number.
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let lock = Path::new(&manifest_dir).join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock.display());
+
+    // Entries look like: source = "git+https://github.com/Plonky3/Plonky3#<rev>"
+    let mut revisions = BTreeSet::new();
+    if let Ok(contents) = fs::read_to_string(&lock) {
+        let mut in_p3_package = false;
+        for line in contents.lines() {
+            if let Some(name) = line.strip_prefix("name = ") {
+                in_p3_package = name.trim_matches('"').starts_with("p3-");
+            } else if in_p3_package {
+                if let Some(source) = line.strip_prefix("source = ") {
+                    if let Some((_, rev)) = source.trim_matches('"').rsplit_once('#') {
+                        revisions.insert(rev.to_string());
+                    }
+                }
+            }
+        }
+    }
+    let revision = if revisions.is_empty() {
+        "unknown".to_string()
+    } else {
+        revisions.into_iter().collect::<Vec<_>>().join(",")
+    };
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+
+    println!("cargo:rustc-env=P3_REVISION={}", revision);
+    println!("cargo:rustc-env=P3_FEATURES={}", features.join(","));
+}
@@ -0,0 +1,140 @@
+// Five ways to try to get a false statement accepted, and where each one is
+// stopped: trace validation, before anything is proved; decoding, before the
+// verifier runs; or the verifier itself. Run it with
+//
+//     cargo run --example failures
+//
+// and `cargo test` runs the same scenarios, asserting each stage.
+use std::process::ExitCode;
+
+use fibonacci_proof::cost::CostParams;
+use fibonacci_proof::input::{DecodeStage, ProofInputError, verify_input};
+use fibonacci_proof::proof_file::encode_proof;
+use fibonacci_proof::validate::validate_trace;
+use fibonacci_proof::{
+    ConfigDescriptor, DEFAULT_CONFIG_SEED, FibonacciAir, NUM_FIBONACCI_COLS, Val, create_config_from,
+    generate_fibonacci_trace, prove_fibonacci, prove_fibonacci_trace, verify_bytes_under,
+};
+use p3_field::PrimeCharacteristicRing;
+use simple_arithmetic_proof::{ArithmeticAir, NUM_ARITHMETIC_COLS, generate_arithmetic_trace};
+
+// Where a scenario was stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    TraceValidation,
+    Decoding,
+    Verification,
+}
+
+impl Stage {
+    fn of(e: &ProofInputError) -> Self {
+        match e.stage() {
+            DecodeStage::File | DecodeStage::Text | DecodeStage::Envelope => Self::Decoding,
+            DecodeStage::Verification => Self::Verification,
+        }
+    }
+}
+
+struct Scenario {
+    name: &'static str,
+    expected: Stage,
+    // The stage that caught the attack and its error, or why it was not caught
+    run: fn() -> Result<(Stage, String), String>,
+}
+
+const SCENARIOS: [Scenario; 5] = [
+    Scenario { name: "wrong arithmetic result", expected: Stage::TraceValidation, run: wrong_arithmetic_result },
+    Scenario { name: "broken Fibonacci transition", expected: Stage::TraceValidation, run: broken_transition },
+    Scenario { name: "wrong public value", expected: Stage::Verification, run: wrong_public_value },
+    Scenario { name: "corrupted proof bytes", expected: Stage::Decoding, run: corrupted_bytes },
+    Scenario { name: "mismatched config", expected: Stage::Decoding, run: mismatched_config },
+];
+
+// One row claims 3 + 4 * 5 = 24
+fn wrong_arithmetic_result() -> Result<(Stage, String), String> {
+    let mut trace = generate_arithmetic_trace::<Val>().map_err(|e| e.to_string())?;
+    trace.matrix.values[7 * NUM_ARITHMETIC_COLS + 3] += Val::ONE;
+    match validate_trace(&ArithmeticAir, &trace.matrix, &trace.public_values) {
+        Ok(()) => Err("the edited row passed validation".to_string()),
+        Err(violation) => Ok((Stage::TraceValidation, violation.to_string())),
+    }
+}
+
+// Row 10's b no longer follows from row 9
+fn broken_transition() -> Result<(Stage, String), String> {
+    let mut trace = generate_fibonacci_trace::<Val>(20).map_err(|e| e.to_string())?;
+    trace.matrix.values[10 * NUM_FIBONACCI_COLS + 1] += Val::ONE;
+    match validate_trace(&FibonacciAir, &trace.matrix, &trace.public_values) {
+        Ok(()) => Err("the edited row passed validation".to_string()),
+        Err(violation) => Ok((Stage::TraceValidation, violation.to_string())),
+    }
+}
+
+// An honest proof of F(20), presented as a proof of F(20) + 1
+fn wrong_public_value() -> Result<(Stage, String), String> {
+    let (proof, mut public_values) = prove_fibonacci(20).map_err(|e| e.to_string())?;
+    public_values[2] += Val::ONE;
+    match verify_input(&proof, &public_values) {
+        Ok(_) => Err("the wrong claim verified".to_string()),
+        Err(e) => Ok((Stage::of(&e), e.to_string())),
+    }
+}
+
+// An honest proof's bytes with the last one cut off in transit
+fn corrupted_bytes() -> Result<(Stage, String), String> {
+    let (proof, public_values) = prove_fibonacci(20).map_err(|e| e.to_string())?;
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    let bytes = encode_proof(&descriptor, &proof).map_err(|e| e.to_string())?;
+    match verify_input(&bytes[..bytes.len() - 1], &public_values) {
+        Ok(_) => Err("the truncated bytes verified".to_string()),
+        Err(e) => Ok((Stage::of(&e), e.to_string())),
+    }
+}
+
+// A proof made under seed 1 sent to a verifier using the default seed
+fn mismatched_config() -> Result<(Stage, String), String> {
+    let prover = ConfigDescriptor::new(1, &CostParams::test());
+    let verifier = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    let trace = generate_fibonacci_trace::<Val>(20).map_err(|e| e.to_string())?;
+    let (proof, public_values) = prove_fibonacci_trace(&create_config_from(&prover), trace, &mut ());
+    let bytes = encode_proof(&prover, &proof).map_err(|e| e.to_string())?;
+    match verify_bytes_under(&verifier, &bytes, &public_values) {
+        Ok(()) => Err("the proof verified under another seed".to_string()),
+        Err(e) => {
+            let e = ProofInputError::from(e);
+            Ok((Stage::of(&e), e.to_string()))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    println!(" Attempted false statements:");
+    let mut all_caught = true;
+    for scenario in &SCENARIOS {
+        match (scenario.run)() {
+            Ok((stage, error)) => {
+                let expected = if stage == scenario.expected { "" } else { " (NOT the documented stage)" };
+                all_caught &= stage == scenario.expected;
+                println!("   {}: caught at {:?}{}: {}", scenario.name, stage, expected, error);
+            }
+            Err(e) => {
+                all_caught = false;
+                println!("   {}: NOT caught: {}", scenario.name, e);
+            }
+        }
+    }
+    if all_caught { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_scenario_is_caught_at_its_documented_stage() {
+        for scenario in &SCENARIOS {
+            let (stage, error) = (scenario.run)().unwrap_or_else(|e| panic!("{}: {}", scenario.name, e));
+            assert_eq!(stage, scenario.expected, "{}: {}", scenario.name, error);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+# The Python bindings end to end; not run by cargo. After
+# `maturin develop --features extension-module`, run `pytest python/tests`.
+import pytest
+
+import fibonacci_proof
+
+STEPS = 15
+F_15 = 610
+
+
+def test_prove_and_verify():
+    proof = fibonacci_proof.prove_fibonacci(STEPS, 0)
+    assert fibonacci_proof.verify_fibonacci(proof, STEPS, F_15)
+    assert not fibonacci_proof.verify_fibonacci(proof, STEPS, F_15 + 1)
+
+
+def test_stats_variants():
+    proof, stats = fibonacci_proof.prove_fibonacci_with_stats(STEPS, 0)
+    assert stats["proof_bytes"] == len(proof)
+    assert stats["rows"] >= STEPS + 1
+    verified = fibonacci_proof.verify_fibonacci_with_stats(proof, STEPS, F_15)
+    assert verified["valid"]
+    assert verified["verify_seconds"] >= 0
+
+
+def test_truncated_proof_raises_proof_error():
+    proof = fibonacci_proof.prove_fibonacci(STEPS, 0)
+    with pytest.raises(fibonacci_proof.ProofError, match="decoding the proof"):
+        fibonacci_proof.verify_fibonacci(proof[:-1], STEPS, F_15)
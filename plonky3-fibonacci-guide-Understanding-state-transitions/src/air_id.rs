@@ -0,0 +1,266 @@
+use core::fmt;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_symmetric::CryptographicHasher;
+use p3_uni_stark::{Proof, prove, verify};
+use serde::{Deserialize, Serialize};
+
+use crate::observer::{PhaseId, ProofObserver, observe};
+use crate::provenance::{PROVENANCE, ProvenanceMismatch, check_provenance};
+use crate::{DEFAULT_CONFIG_SEED, MyConfig, MyHash, Perm, StarkAir, Val, constants_rng};
+
+// Names one revision of an AIR's constraint system. Bump the version whenever
+// the constraints change so old proofs stop verifying against the new AIR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AirId {
+    pub name: &'static str,
+    pub version: (u32, u32, u32),
+}
+
+impl AirId {
+    pub const fn new(name: &'static str, major: u32, minor: u32, patch: u32) -> Self {
+        Self { name, version: (major, minor, patch) }
+    }
+
+    // Poseidon2 sponge over the name bytes followed by the version numbers
+    pub fn to_field(&self) -> Val {
+        let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+        let hasher = MyHash::new(Perm::new_from_rng_128(&mut rng));
+        let (major, minor, patch) = self.version;
+        let input = self
+            .name
+            .bytes()
+            .map(Val::from_u8)
+            .chain([major, minor, patch].map(Val::from_u32));
+        hasher.hash_iter(input)[0]
+    }
+}
+
+impl fmt::Display for AirId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (major, minor, patch) = self.version;
+        write!(f, "{}@{}.{}.{}", self.name, major, minor, patch)
+    }
+}
+
+// An AIR that can be proven through the identified wrappers
+pub trait ProvableComputation {
+    fn air_id(&self) -> AirId;
+}
+
+// Runs the inner AIR with the AirId stripped off the front of the public values,
+// so examples index their own public values from zero
+pub struct Identified<'a, A>(pub &'a A);
+
+impl<F, A: BaseAir<F>> BaseAir<F> for Identified<'_, A> {
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+}
+
+impl<AB, A> Air<AB> for Identified<'_, A>
+where
+    AB: AirBuilderWithPublicValues,
+    A: BaseAir<AB::F> + for<'b> Air<WithoutAirId<'b, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.0.eval(&mut WithoutAirId { inner: builder });
+    }
+}
+
+// Builder adapter hiding public value 0 (the AirId) from the inner AIR
+pub struct WithoutAirId<'a, AB> {
+    inner: &'a mut AB,
+}
+
+impl<AB: AirBuilderWithPublicValues> AirBuilder for WithoutAirId<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = AB::M;
+
+    fn main(&self) -> Self::M {
+        self.inner.main()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.inner.is_first_row()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.inner.is_last_row()
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(x);
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for WithoutAirId<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        &self.inner.public_values()[1..]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AirIdError {
+    // The proof was made for a different AIR or revision; no cryptography was run
+    AirVersionMismatch { expected: AirId, found: Val },
+    Verification(String),
+}
+
+impl fmt::Display for AirIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AirVersionMismatch { expected, found } => {
+                write!(f, "proof is for AIR id {} but {} expects {}", found, expected, expected.to_field())
+            }
+            Self::Verification(msg) => write!(f, "verification failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AirIdError {}
+
+// A proof tagged with the hashed AirId it was produced under
+#[derive(Serialize, Deserialize)]
+pub struct IdentifiedProof {
+    pub air_id: Val,
+    // Digest of the prover's build provenance; not part of the transcript
+    pub provenance: u64,
+    pub proof: Proof<MyConfig>,
+}
+
+fn with_air_id(air_id: Val, public_values: &[Val]) -> Vec<Val> {
+    let mut all = Vec::with_capacity(public_values.len() + 1);
+    all.push(air_id);
+    all.extend_from_slice(public_values);
+    all
+}
+
+pub fn check_air_id(expected: &AirId, found: Val) -> Result<(), AirIdError> {
+    if expected.to_field() != found {
+        return Err(AirIdError::AirVersionMismatch { expected: *expected, found });
+    }
+    Ok(())
+}
+
+// Proves `trace` with the AIR's id prepended as public value 0
+pub fn prove_identified<A>(
+    config: &MyConfig,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    public_values: &[Val],
+) -> IdentifiedProof
+where
+    A: ProvableComputation,
+    for<'a> Identified<'a, A>: StarkAir,
+{
+    prove_identified_observed(config, air, trace, public_values, &mut ())
+}
+
+// prove_identified, reporting each phase to `observer`
+pub fn prove_identified_observed<A>(
+    config: &MyConfig,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    public_values: &[Val],
+    observer: &mut dyn ProofObserver,
+) -> IdentifiedProof
+where
+    A: ProvableComputation,
+    for<'a> Identified<'a, A>: StarkAir,
+{
+    observe(observer, PhaseId::Total, |observer| {
+        let air_id = observe(observer, PhaseId::AirId, |_| air.air_id().to_field());
+        let public_values = with_air_id(air_id, public_values);
+        let proof = observe(observer, PhaseId::Stark, |_| {
+            prove(config, &Identified(air), trace, &public_values)
+        });
+        IdentifiedProof { air_id, provenance: PROVENANCE.digest(), proof }
+    })
+}
+
+// Rejects proofs for another AIR revision before running the STARK verifier.
+// A proof from a differently built prover still verifies; the mismatch comes
+// back with the Ok, a hint for the caller to report rather than a verdict.
+pub fn verify_identified<A>(
+    config: &MyConfig,
+    air: &A,
+    proof: &IdentifiedProof,
+    public_values: &[Val],
+) -> Result<Option<ProvenanceMismatch>, AirIdError>
+where
+    A: ProvableComputation,
+    for<'a> Identified<'a, A>: StarkAir,
+{
+    let expected = air.air_id();
+    check_air_id(&expected, proof.air_id)?;
+    verify(config, &Identified(air), &proof.proof, &with_air_id(proof.air_id, public_values))
+        .map_err(|err| AirIdError::Verification(format!("{:?}", err)))?;
+    Ok(check_provenance(proof.provenance).err())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::MIN_TRACE_HEIGHT;
+    use crate::{FIBONACCI_AIR_ID, FibonacciAir, generate_fibonacci_trace_padded, test_config};
+
+    fn proof() -> (IdentifiedProof, Vec<Val>) {
+        let trace = generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap();
+        (prove_identified(&test_config(), &FibonacciAir, trace.matrix, &trace.public_values), trace.public_values)
+    }
+
+    #[test]
+    fn every_part_of_the_id_changes_its_hash() {
+        let id = AirId::new("fibonacci", 1, 2, 3);
+        assert_eq!(id.to_string(), "fibonacci@1.2.3");
+        assert_eq!(id.to_field(), AirId::new("fibonacci", 1, 2, 3).to_field());
+        let renamed = AirId::new("fibonaccj", 1, 2, 3);
+        for other in [renamed, AirId::new("fibonacci", 1, 2, 4), AirId::new("fibonacci", 2, 2, 3)] {
+            assert_ne!(id.to_field(), other.to_field(), "{}", other);
+        }
+    }
+
+    #[test]
+    fn the_inner_air_sees_its_own_public_values() {
+        let (proof, public_values) = proof();
+        assert_eq!(proof.air_id, FIBONACCI_AIR_ID.to_field());
+        assert_eq!(proof.provenance, PROVENANCE.digest());
+        assert_eq!(verify_identified(&test_config(), &FibonacciAir, &proof, &public_values), Ok(None));
+
+        let mut wrong = public_values;
+        wrong[2] += Val::ONE;
+        let rejected = verify_identified(&test_config(), &FibonacciAir, &proof, &wrong);
+        assert!(matches!(rejected, Err(AirIdError::Verification(_))), "{:?}", rejected);
+    }
+
+    #[test]
+    fn a_proof_from_another_build_verifies_and_returns_the_mismatch() {
+        let (mut proof, public_values) = proof();
+        let expected = PROVENANCE.digest();
+        proof.provenance = expected ^ 1;
+        let mismatch = ProvenanceMismatch { found: expected ^ 1, expected };
+        assert_eq!(verify_identified(&test_config(), &FibonacciAir, &proof, &public_values), Ok(Some(mismatch)));
+    }
+
+    #[test]
+    fn a_proof_for_another_revision_is_refused_before_verifying() {
+        let (mut proof, public_values) = proof();
+        let older = AirId::new(FIBONACCI_AIR_ID.name, 4, 0, 0);
+        proof.air_id = older.to_field();
+        assert_eq!(
+            verify_identified(&test_config(), &FibonacciAir, &proof, &public_values),
+            Err(AirIdError::AirVersionMismatch { expected: FIBONACCI_AIR_ID, found: older.to_field() })
+        );
+        assert_eq!(check_air_id(&older, older.to_field()), Ok(()));
+    }
+}
@@ -0,0 +1,282 @@
+// Fibonacci over the integers rather than mod p. F(47) already exceeds the
+// BabyBear modulus, so FibonacciAir proves F(n) mod p and exact.rs has to mark
+// printed values "(mod p)". Here a and b are 128-bit integers held as
+// NUM_LIMBS limbs of LIMB_BITS bits, and a + b is checked limb by limb with
+// boolean carries, so the proven result is the integer F(n) itself.
+//
+// Limbs are 16 bits rather than 32: a 32-bit limb does not fit in a 31-bit
+// field. Every b limb is range-checked through its bits; a limbs need no check
+// of their own, as each row's a is either the public start or the row
+// before's b. The top limb may not carry out, which caps the values at 2^128.
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Range;
+use num_bigint::BigUint;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+
+use crate::Val;
+use crate::air_id::{AirId, ProvableComputation};
+use crate::last_row::{bind_physical_last_row, constrain_possibly_empty_prefix};
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, DEFAULT_MIN_HEIGHT, GeneratedTrace, SizingError, generate_rows, trace_height};
+
+pub const LIMB_BITS: usize = 16;
+pub const NUM_LIMBS: usize = 8;
+
+// Columns: is_real, count, the limbs of a and b, the carries out of all but
+// the top limb of a + b, then the bits of every b limb
+const NUM_BIG_FIBONACCI_COLS: usize = 2 + 2 * NUM_LIMBS + (NUM_LIMBS - 1) + NUM_LIMBS * LIMB_BITS;
+
+// Public values: the limbs of F(num_steps), least significant first, then
+// num_steps
+pub const NUM_BIG_FIBONACCI_PUBLIC_VALUES: usize = NUM_LIMBS + 1;
+
+// The padding holds F(num_steps + 1), and F(186) is the last Fibonacci number
+// below 2^128
+pub const MAX_BIG_STEPS: usize = 185;
+
+pub const BIG_FIBONACCI_AIR_ID: AirId = AirId::new("big_fibonacci", 1, 0, 0);
+
+#[derive(Debug, Clone)]
+pub struct BigFibonacciAir;
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct BigFibonacciRow<F> {
+    pub is_real: F,
+    pub count: F,
+    pub a: [F; NUM_LIMBS],
+    pub b: [F; NUM_LIMBS],
+    // Carry out of limb i of a + b, into limb i + 1 of next.b
+    pub carries: [F; NUM_LIMBS - 1],
+    pub b_bits: [[F; LIMB_BITS]; NUM_LIMBS],
+}
+
+impl<F> Borrow<BigFibonacciRow<F>> for [F] {
+    fn borrow(&self) -> &BigFibonacciRow<F> {
+        debug_assert_eq!(self.len(), NUM_BIG_FIBONACCI_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<BigFibonacciRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for BigFibonacciAir {
+    fn width(&self) -> usize {
+        NUM_BIG_FIBONACCI_COLS
+    }
+}
+
+impl AirLint for BigFibonacciAir {
+    fn num_public_values(&self) -> usize {
+        NUM_BIG_FIBONACCI_PUBLIC_VALUES
+    }
+}
+
+impl ProvableComputation for BigFibonacciAir {
+    fn air_id(&self) -> AirId {
+        BIG_FIBONACCI_AIR_ID
+    }
+}
+
+// The limbs, carries and bits are left unnamed
+impl ColumnNames for BigFibonacciAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["is_real", "count"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for BigFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &BigFibonacciRow<AB::Var> = (*local).borrow();
+        let next: &BigFibonacciRow<AB::Var> = (*next).borrow();
+        let pis = builder.public_values();
+        let result: [AB::PublicVar; NUM_LIMBS] = core::array::from_fn(|i| pis[i]);
+        let num_steps = pis[NUM_LIMBS];
+
+        // Range: every b limb is the sum of LIMB_BITS boolean bits
+        for (limb, bits) in local.b.iter().zip(&local.b_bits) {
+            let mut sum = AB::Expr::ZERO;
+            for (k, bit) in bits.iter().enumerate() {
+                builder.assert_bool(bit.clone());
+                sum += AB::Expr::from_u64(1 << k) * bit.clone();
+            }
+            builder.assert_eq(limb.clone(), sum);
+        }
+        // Carries are bits, and zero on padding rows
+        for carry in &local.carries {
+            builder.assert_bool(carry.clone());
+            builder.when(AB::Expr::ONE - local.is_real.clone()).assert_zero(carry.clone());
+        }
+
+        // Boundary: the sequence starts from (0, 1)
+        let mut when_first_row = builder.when_first_row();
+        for (i, (a, b)) in local.a.iter().zip(&local.b).enumerate() {
+            when_first_row.assert_zero(a.clone());
+            when_first_row.assert_eq(b.clone(), AB::Expr::from_bool(i == 0));
+        }
+        when_first_row.assert_eq(local.count.clone(), local.is_real.clone());
+
+        // Output: as in FibonacciAir, padding carries the result to the last row
+        for (limb, public) in local.a.iter().zip(result) {
+            bind_physical_last_row(builder, limb.clone().into(), public.into());
+        }
+        bind_physical_last_row(builder, local.count.clone().into(), num_steps.into());
+
+        constrain_possibly_empty_prefix(builder, local.is_real.clone().into(), next.is_real.clone().into());
+        builder.when_last_row().assert_zero(local.is_real.clone());
+        builder
+            .when_transition()
+            .assert_eq(next.count.clone(), local.count.clone() + next.is_real.clone());
+
+        // Out of every real row, limb i of a + b plus the carry into it is limb
+        // i of next.b plus 2^LIMB_BITS times the carry out. No value exceeds
+        // 2^(LIMB_BITS + 1), so nothing wraps mod p.
+        let base = AB::Expr::from_u64(1 << LIMB_BITS);
+        let mut when_step = builder.when_transition().when(local.is_real.clone());
+        for i in 0..NUM_LIMBS {
+            let carry_in: AB::Expr = if i == 0 { AB::Expr::ZERO } else { local.carries[i - 1].clone().into() };
+            let carry_out: AB::Expr = if i + 1 < NUM_LIMBS { local.carries[i].clone().into() } else { AB::Expr::ZERO };
+            when_step.assert_eq(
+                local.a[i].clone() + local.b[i].clone() + carry_in,
+                next.b[i].clone() + base.clone() * carry_out,
+            );
+            when_step.assert_eq(next.a[i].clone(), local.b[i].clone());
+        }
+
+        // Padding rows copy the row before them
+        let mut when_padding = builder.when_transition().when(AB::Expr::ONE - local.is_real.clone());
+        for i in 0..NUM_LIMBS {
+            when_padding.assert_eq(next.a[i].clone(), local.a[i].clone());
+            when_padding.assert_eq(next.b[i].clone(), local.b[i].clone());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BigFibonacciError {
+    // F(num_steps + 1) would not fit in NUM_LIMBS limbs
+    TooManySteps(usize),
+    Sizing(SizingError),
+}
+
+impl fmt::Display for BigFibonacciError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManySteps(num_steps) => {
+                write!(f, "{} steps overflow {} bits; at most {} fit", num_steps, NUM_LIMBS * LIMB_BITS, MAX_BIG_STEPS)
+            }
+            Self::Sizing(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for BigFibonacciError {}
+
+impl From<SizingError> for BigFibonacciError {
+    fn from(e: SizingError) -> Self {
+        Self::Sizing(e)
+    }
+}
+
+fn limb(x: u128, i: usize) -> u64 {
+    ((x >> (i * LIMB_BITS)) as u64) & ((1 << LIMB_BITS) - 1)
+}
+
+fn limbs<F: Field>(x: u128) -> [F; NUM_LIMBS] {
+    core::array::from_fn(|i| F::from_u64(limb(x, i)))
+}
+
+// The integer a run of limbs stands for, least significant limb first
+pub fn limbs_to_biguint(limbs: &[Val]) -> BigUint {
+    limbs.iter().rev().fold(BigUint::ZERO, |acc, limb| (acc << LIMB_BITS) + limb.as_canonical_u64())
+}
+
+// F(num_steps) over the integers, carried as meta. Row i holds
+// (F(i), F(i + 1)); rows from num_steps on are padding, as in FibonacciAir.
+pub fn generate_big_fibonacci_trace<F: Field>(
+    num_steps: usize,
+) -> Result<GeneratedTrace<u128, F>, BigFibonacciError> {
+    if num_steps > MAX_BIG_STEPS {
+        return Err(BigFibonacciError::TooManySteps(num_steps));
+    }
+    let height = trace_height(num_steps + 1, DEFAULT_MIN_HEIGHT)?;
+
+    // F(0), ..., F(num_steps + 1)
+    let mut values: Vec<u128> = vec![0, 1];
+    for i in 2..num_steps + 2 {
+        values.push(values[i - 1] + values[i - 2]);
+    }
+
+    let count = F::from_usize(num_steps);
+    let values = &values;
+    let matrix = generate_rows(height, |range: Range<usize>| {
+        range.map(move |i| {
+            let first = i.min(num_steps);
+            let (a, b) = (values[first], values[first + 1]);
+            let is_real = i < num_steps;
+            let mut carries = [F::ZERO; NUM_LIMBS - 1];
+            if is_real {
+                let mut carry = 0;
+                for (j, slot) in carries.iter_mut().enumerate() {
+                    carry = (limb(a, j) + limb(b, j) + carry) >> LIMB_BITS;
+                    *slot = F::from_u64(carry);
+                }
+            }
+            BigFibonacciRow {
+                is_real: F::from_bool(is_real),
+                count: if is_real { F::from_usize(i + 1) } else { count },
+                a: limbs(a),
+                b: limbs(b),
+                carries,
+                b_bits: core::array::from_fn(|j| core::array::from_fn(|k| F::from_u64((limb(b, j) >> k) & 1))),
+            }
+        })
+    })?;
+
+    let mut public_values = limbs(values[num_steps]).to_vec();
+    public_values.push(count);
+    Ok(GeneratedTrace { matrix, real_rows: num_steps, public_values, meta: values[num_steps] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::cost::SecurityLevel;
+    use crate::create_default_config;
+    use crate::exact::fibonacci_value_exact;
+
+    #[test]
+    fn f_100_is_proved_as_the_integer() {
+        let trace = generate_big_fibonacci_trace::<Val>(100).unwrap();
+        let config = create_default_config(SecurityLevel::Test);
+        let proof = prove_identified(&config, &BigFibonacciAir, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&config, &BigFibonacciAir, &proof, &trace.public_values), Ok(None));
+
+        let proven = limbs_to_biguint(&trace.public_values[..NUM_LIMBS]);
+        assert_eq!(proven.to_string(), "354224848179261915075");
+        assert_eq!(proven, fibonacci_value_exact(100));
+        assert_eq!(BigUint::from(trace.meta), proven);
+
+        // One more in the lowest limb is another integer, and refused
+        let mut wrong = trace.public_values.clone();
+        wrong[0] += Val::ONE;
+        assert!(verify_identified(&config, &BigFibonacciAir, &proof, &wrong).is_err());
+    }
+
+    #[test]
+    fn steps_past_the_limbs_are_refused() {
+        let last = generate_big_fibonacci_trace::<Val>(MAX_BIG_STEPS).unwrap();
+        assert_eq!(BigUint::from(last.meta), fibonacci_value_exact(MAX_BIG_STEPS));
+        let refused = generate_big_fibonacci_trace::<Val>(MAX_BIG_STEPS + 1).map(|_| ());
+        assert_eq!(refused, Err(BigFibonacciError::TooManySteps(MAX_BIG_STEPS + 1)));
+    }
+}
@@ -0,0 +1,99 @@
+// MyConfig with Blake3 Merkle trees and transcript in place of Poseidon2.
+// Poseidon2 is built to be cheap inside a circuit, not on a CPU; a proof that
+// is never verified recursively only needs a fast byte hash. Laid out like the
+// keccak module: field elements serialized to bytes, 32-byte digests and a
+// SerializingChallenger.
+//
+// Proof files do not record the hash, so a Blake3 proof saved with the
+// default descriptor reaches the Poseidon2 verifier; verify_as_poseidon2
+// shows it is rejected there.
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use p3_blake3::Blake3;
+use p3_challenger::{HashChallenger, SerializingChallenger32};
+use p3_commit::{ExtensionMmcs, Mmcs};
+use p3_fri::TwoAdicFriPcs;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::cost::{CostParams, SecurityLevel};
+use crate::proof_file::{ConfigFingerprint, encode_proof, load_proof_from_reader};
+use crate::report::panic_message;
+use crate::trace::SizingError;
+use crate::{
+    Challenge, ConfigDescriptor, DEFAULT_CONFIG_SEED, Dft, FibonacciAir, MyCompress, MyConfig, MyHash, Perm, Val,
+    ValMmcs, constants_rng, create_default_config, fri_params_for, generate_fibonacci_trace,
+};
+
+pub type Blake3Hash = SerializingHasher<Blake3>;
+pub type Blake3Compress = CompressionFunctionFromHasher<Blake3, 2, 32>;
+pub type Blake3ValMmcs = MerkleTreeMmcs<Val, u8, Blake3Hash, Blake3Compress, 32>;
+pub type Blake3ChallengeMmcs = ExtensionMmcs<Val, Challenge, Blake3ValMmcs>;
+pub type Blake3Challenger = SerializingChallenger32<Val, HashChallenger<u8, Blake3, 32>>;
+pub type Blake3Pcs = TwoAdicFriPcs<Val, Dft, Blake3ValMmcs, Blake3ChallengeMmcs>;
+pub type Blake3Config = StarkConfig<Blake3Pcs, Challenge, Blake3Challenger>;
+
+fn blake3_val_mmcs() -> Blake3ValMmcs {
+    Blake3ValMmcs::new(Blake3Hash::new(Blake3 {}), Blake3Compress::new(Blake3 {}))
+}
+
+// Blake3 has no constants to sample, so unlike create_config_with_seed this
+// takes no seed
+pub fn create_blake3_config() -> Blake3Config {
+    let val_mmcs = blake3_val_mmcs();
+    let challenge_mmcs = Blake3ChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = Blake3Pcs::new(Dft::default(), val_mmcs, fri_params);
+    Blake3Config::new(pcs, Blake3Challenger::from_hasher(vec![], Blake3 {}))
+}
+
+// prove_fibonacci under the Blake3 config, returning the public values
+// [F(0), F(1), F(num_steps), num_steps]
+pub fn prove_fibonacci_blake3(num_steps: usize) -> Result<(Proof<Blake3Config>, Vec<Val>), SizingError> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps)?;
+    let proof = prove(&create_blake3_config(), &FibonacciAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_fibonacci_blake3(proof: &Proof<Blake3Config>, public_values: &Vec<Val>) -> Result<(), String> {
+    verify(&create_blake3_config(), &FibonacciAir, proof, public_values).map_err(|e| format!("{:?}", e))
+}
+
+// A Blake3 proof saved under the default descriptor and loaded by the
+// Poseidon2 verifier. Both digests are 32 bytes, so the file can decode, but
+// the verifier recomputes every Merkle root and challenge with Poseidon2 and
+// rejects it; Ok would mean the two transcripts agree.
+pub fn verify_as_poseidon2(proof: &Proof<Blake3Config>, public_values: &Vec<Val>) -> Result<(), String> {
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    let bytes = encode_proof(&descriptor, proof).map_err(|e| e.to_string())?;
+    let proof: Proof<MyConfig> = load_proof_from_reader(&bytes[..], ConfigFingerprint::of(&descriptor))
+        .map_err(|e| format!("does not decode: {}", e))?;
+    let verify = || verify(&create_default_config(SecurityLevel::Test), &FibonacciAir, &proof, public_values);
+    panic::catch_unwind(AssertUnwindSafe(verify))
+        .map_err(|payload| format!("verifier panicked: {}", panic_message(payload.as_ref())))?
+        .map_err(|e| format!("{:?}", e))
+}
+
+// Time for one Merkle commitment to `trace` under the Poseidon2 MMCS of
+// create_default_config and under the Blake3 one. The prover commits the
+// trace's LDE the same way, so this is the hashing part of its commit phase.
+pub fn compare_commit_times(trace: &RowMajorMatrix<Val>) -> [(&'static str, Duration); 2] {
+    let perm = Perm::new_from_rng_128(&mut constants_rng(DEFAULT_CONFIG_SEED));
+    let poseidon2_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+    let matrix = trace.clone();
+    let start = Instant::now();
+    let poseidon2 = poseidon2_mmcs.commit_matrix(matrix);
+    let poseidon2_time = start.elapsed();
+    drop(poseidon2);
+
+    let matrix = trace.clone();
+    let start = Instant::now();
+    let blake3 = blake3_val_mmcs().commit_matrix(matrix);
+    let blake3_time = start.elapsed();
+    drop(blake3);
+
+    [("Poseidon2", poseidon2_time), ("Blake3", blake3_time)]
+}
@@ -0,0 +1,278 @@
+// Proofs already made, so a batch that asks for the same statement twice
+// proves it once. Entries are proof_file envelopes, keyed by a Poseidon2
+// digest of everything the bytes depend on: the AIR id, the ConfigDescriptor,
+// the public inputs and the generator's parameters. A config change changes
+// the key, so nothing cached under old parameters is ever returned.
+//
+// The in-memory tier keeps the most recently used entries; with a directory,
+// each entry is also written there as <key>.proof and read back on a memory
+// miss. A file that does not decode under its key's descriptor is a miss and
+// is overwritten.
+use core::fmt;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use p3_field::PrimeCharacteristicRing;
+use p3_symmetric::CryptographicHasher;
+
+use crate::air_id::{AirId, IdentifiedProof};
+use crate::claims::sequence_claim_values;
+use crate::journal::{Digest, digest_hex, hasher};
+use crate::proof_file::{ProofFileError, encode_proof, load_described_proof_from_reader};
+use crate::trace::SizingError;
+use crate::{
+    ConfigDescriptor, FIBONACCI_AIR_ID, PublicValues, Val, create_config_from, generate_fibonacci_trace_padded,
+    prove_fibonacci_trace, recurrence_pair,
+};
+
+// Entries the in-memory tier holds before evicting the least recently used
+pub const DEFAULT_CACHE_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKey(Digest);
+
+impl CacheKey {
+    // Public inputs are field elements, generator parameters plain integers;
+    // each list is preceded by its length so no two tuples hash alike
+    pub fn new(air: &AirId, descriptor: &ConfigDescriptor, public_inputs: &[Val], generator: &[usize]) -> Self {
+        let (major, minor, patch) = air.version;
+        let seed = descriptor.perm_seed;
+        let fri = [descriptor.log_blowup, descriptor.num_queries, descriptor.pow_bits, descriptor.min_log_height];
+        let input = air
+            .name
+            .bytes()
+            .map(Val::from_u8)
+            .chain([major, minor, patch].map(Val::from_u32))
+            .chain((0..4).map(|i| Val::from_u64((seed >> (16 * i)) & 0xffff)))
+            .chain(fri.map(Val::from_usize))
+            .chain([Val::from_usize(public_inputs.len())])
+            .chain(public_inputs.iter().copied())
+            .chain([Val::from_usize(generator.len())])
+            .chain(generator.iter().map(|&p| Val::from_usize(p)));
+        Self(hasher().hash_iter(input))
+    }
+}
+
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&digest_hex(&self.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub memory_hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn hits(&self) -> u64 {
+        self.memory_hits + self.disk_hits
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hits ({} from disk), {} misses", self.hits(), self.disk_hits, self.misses)
+    }
+}
+
+#[derive(Debug)]
+pub enum CacheError {
+    Trace(SizingError),
+    Encoding(ProofFileError),
+    // Writing the entry to the cache directory
+    Io(io::Error),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trace(e) => write!(f, "{}", e),
+            Self::Encoding(e) => write!(f, "could not encode the proof: {}", e),
+            Self::Io(e) => write!(f, "could not write to the cache directory: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl From<io::Error> for CacheError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+pub struct ProofCache {
+    capacity: usize,
+    // Least recently used first
+    entries: VecDeque<(CacheKey, Vec<u8>)>,
+    dir: Option<PathBuf>,
+    stats: CacheStats,
+}
+
+impl ProofCache {
+    // Memory only; a capacity of 0 caches nothing
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: VecDeque::new(), dir: None, stats: CacheStats::default() }
+    }
+
+    // Also backed by `dir`, created on the first insert
+    pub fn with_dir(capacity: usize, dir: &Path) -> Self {
+        Self { dir: Some(dir.to_path_buf()), ..Self::new(capacity) }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(format!("{}.proof", key)))
+    }
+
+    fn remember(&mut self, key: CacheKey, bytes: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, bytes));
+    }
+
+    // The envelope stored under `key`, counted as a hit or a miss
+    fn get(&mut self, key: &CacheKey, descriptor: &ConfigDescriptor) -> Option<Vec<u8>> {
+        if let Some(index) = self.entries.iter().position(|(k, _)| k == key) {
+            let entry = self.entries.remove(index)?;
+            self.entries.push_back(entry.clone());
+            self.stats.memory_hits += 1;
+            return Some(entry.1);
+        }
+        let from_disk = self.entry_path(key).and_then(|path| fs::read(path).ok()).filter(|bytes| {
+            let decoded = load_described_proof_from_reader::<IdentifiedProof>(bytes.as_slice());
+            matches!(decoded, Ok((found, _)) if found == *descriptor)
+        });
+        match from_disk {
+            Some(bytes) => {
+                self.stats.disk_hits += 1;
+                self.remember(*key, bytes.clone());
+                Some(bytes)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: CacheKey, bytes: Vec<u8>) -> io::Result<()> {
+        if let (Some(dir), Some(path)) = (&self.dir, self.entry_path(&key)) {
+            fs::create_dir_all(dir)?;
+            fs::write(path, &bytes)?;
+        }
+        self.remember(key, bytes);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedProof {
+    // proof_file's envelope, as verify_described reads it
+    pub bytes: Vec<u8>,
+    pub public_values: PublicValues,
+    // Whether the bytes came from the cache rather than the prover
+    pub hit: bool,
+}
+
+// F(num_steps) from (0, 1) under `descriptor`, from `cache` when it holds the
+// proof and proved and stored there otherwise. A hit returns the bytes the
+// first call produced.
+pub fn prove_cached(
+    cache: &mut ProofCache,
+    descriptor: &ConfigDescriptor,
+    num_steps: usize,
+) -> Result<CachedProof, CacheError> {
+    let (f0, f1) = (Val::ZERO, Val::ONE);
+    let result = recurrence_pair(f0, f1, num_steps).0;
+    let public_values = sequence_claim_values(f0, f1, result, Val::from_usize(num_steps));
+    let key = CacheKey::new(&FIBONACCI_AIR_ID, descriptor, &[f0, f1], &[num_steps]);
+    if let Some(bytes) = cache.get(&key, descriptor) {
+        return Ok(CachedProof { bytes, public_values, hit: true });
+    }
+
+    let params = descriptor.params();
+    let trace = generate_fibonacci_trace_padded::<Val>(num_steps, params.min_height).map_err(CacheError::Trace)?;
+    let (proof, public_values) = prove_fibonacci_trace(&create_config_from(descriptor), trace, &mut ());
+    let bytes = encode_proof(descriptor, &proof).map_err(CacheError::Encoding)?;
+    cache.insert(key, bytes.clone())?;
+    Ok(CachedProof { bytes, public_values, hit: false })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::CostParams;
+    use crate::{DEFAULT_CONFIG_SEED, verify_described};
+
+    fn test_descriptor() -> ConfigDescriptor {
+        ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test())
+    }
+
+    #[test]
+    fn the_same_request_twice_is_a_hit_with_identical_bytes() {
+        let mut cache = ProofCache::new(DEFAULT_CACHE_CAPACITY);
+        let first = prove_cached(&mut cache, &test_descriptor(), 20).unwrap();
+        let second = prove_cached(&mut cache, &test_descriptor(), 20).unwrap();
+        assert!(!first.hit && second.hit);
+        assert_eq!(second, CachedProof { hit: true, ..first.clone() });
+        assert_eq!(verify_described(&second.bytes, &second.public_values), Ok(test_descriptor()));
+        assert_eq!(cache.stats(), CacheStats { memory_hits: 1, disk_hits: 0, misses: 1 });
+    }
+
+    #[test]
+    fn another_fri_preset_or_step_count_misses() {
+        let mut cache = ProofCache::new(DEFAULT_CACHE_CAPACITY);
+        let wider = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams { log_blowup: 2, ..CostParams::test() });
+        let first = prove_cached(&mut cache, &test_descriptor(), 20).unwrap();
+        let other_preset = prove_cached(&mut cache, &wider, 20).unwrap();
+        let other_steps = prove_cached(&mut cache, &test_descriptor(), 21).unwrap();
+        assert!(!other_preset.hit && !other_steps.hit);
+        assert_ne!(other_preset.bytes, first.bytes);
+        assert_eq!(verify_described(&other_preset.bytes, &other_preset.public_values), Ok(wider));
+        assert_eq!(cache.stats().misses, 3);
+    }
+
+    #[test]
+    fn the_disk_tier_survives_a_new_cache_and_rejects_a_corrupt_entry() {
+        let dir = std::env::temp_dir().join(format!("fibonacci-cache-{}", std::process::id()));
+        let first = prove_cached(&mut ProofCache::with_dir(0, &dir), &test_descriptor(), 20).unwrap();
+
+        let mut reopened = ProofCache::with_dir(DEFAULT_CACHE_CAPACITY, &dir);
+        let from_disk = prove_cached(&mut reopened, &test_descriptor(), 20).unwrap();
+        assert!(from_disk.hit);
+        assert_eq!(from_disk.bytes, first.bytes);
+        assert_eq!(reopened.stats(), CacheStats { memory_hits: 0, disk_hits: 1, misses: 0 });
+
+        let key = CacheKey::new(&FIBONACCI_AIR_ID, &test_descriptor(), &[Val::ZERO, Val::ONE], &[20]);
+        fs::write(dir.join(format!("{}.proof", key)), &first.bytes[..first.bytes.len() - 1]).unwrap();
+        let mut corrupted = ProofCache::with_dir(DEFAULT_CACHE_CAPACITY, &dir);
+        let reproved = prove_cached(&mut corrupted, &test_descriptor(), 20).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert!(!reproved.hit);
+        assert_eq!(reproved.bytes, first.bytes);
+    }
+
+    #[test]
+    fn the_least_recently_used_entry_is_evicted() {
+        let mut cache = ProofCache::new(2);
+        for steps in [20, 21, 20, 22] {
+            prove_cached(&mut cache, &test_descriptor(), steps).unwrap();
+        }
+        // 21 was evicted when 22 arrived; 20 was used more recently
+        assert!(prove_cached(&mut cache, &test_descriptor(), 20).unwrap().hit);
+        assert!(!prove_cached(&mut cache, &test_descriptor(), 21).unwrap().hit);
+    }
+}
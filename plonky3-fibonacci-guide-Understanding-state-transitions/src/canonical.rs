@@ -0,0 +1,62 @@
+// The one way a u64 from outside becomes a field element. Val::from_u64
+// reduces mod p, so a value of p or more read from a file or the command line
+// would silently stand for a different number; every input path goes through
+// parse_canonical instead and reports such a value as NonCanonicalValue.
+// Reducing is only done where the user asked for it (--allow-reduction).
+use core::fmt;
+
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+
+use crate::Val;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonCanonicalValue {
+    pub value: u64,
+    pub modulus: u64,
+}
+
+impl fmt::Display for NonCanonicalValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not below the field modulus {}", self.value, self.modulus)
+    }
+}
+
+impl std::error::Error for NonCanonicalValue {}
+
+// What to do with a value of p or more
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Reduction {
+    #[default]
+    Reject,
+    // Take it mod p, as Val::from_u64 does
+    Allow,
+}
+
+pub fn parse_canonical(value: u64) -> Result<Val, NonCanonicalValue> {
+    if value >= Val::ORDER_U64 {
+        return Err(NonCanonicalValue { value, modulus: Val::ORDER_U64 });
+    }
+    Ok(Val::from_u64(value))
+}
+
+// parse_canonical, or value mod p when `reduction` allows it
+pub fn parse_with(value: u64, reduction: Reduction) -> Result<Val, NonCanonicalValue> {
+    match reduction {
+        Reduction::Reject => parse_canonical(value),
+        Reduction::Allow => Ok(Val::from_u64(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_below_the_modulus_parse_and_the_rest_are_refused() {
+        let p = Val::ORDER_U64;
+        assert_eq!(parse_canonical(p - 1), Ok(Val::NEG_ONE));
+        assert_eq!(parse_canonical(p), Err(NonCanonicalValue { value: p, modulus: p }));
+        assert_eq!(parse_with(p + 5, Reduction::Reject), Err(NonCanonicalValue { value: p + 5, modulus: p }));
+        assert_eq!(parse_with(p + 5, Reduction::Allow), Ok(Val::from_u8(5)));
+    }
+}
@@ -0,0 +1,119 @@
+// Which optional subsystems this build has. The binary registers every flag
+// and subcommand whatever the features, so one tied to a missing feature is
+// still parsed and fails with the feature to recompile with, instead of being
+// an unknown argument. `--version --verbose` prints the report.
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capability {
+    // The cargo feature's name
+    pub feature: &'static str,
+    pub enabled: bool,
+    // What it adds, as the flags or subcommands that reach it
+    pub provides: &'static str,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = if self.enabled { "+" } else { "-" };
+        write!(f, "{}{}: {}", mark, self.feature, self.provides)
+    }
+}
+
+// Every feature in Cargo.toml except extension-module, which only changes how
+// the Python module links
+pub fn capabilities() -> [Capability; 10] {
+    [
+        Capability { feature: "exercises", enabled: cfg!(feature = "exercises"), provides: "--exercises" },
+        Capability {
+            feature: "solutions",
+            enabled: cfg!(feature = "solutions"),
+            provides: "reference solutions in --exercises",
+        },
+        Capability { feature: "tutorial", enabled: cfg!(feature = "tutorial"), provides: "--stage" },
+        Capability {
+            feature: "transcript-log",
+            enabled: cfg!(feature = "transcript-log"),
+            provides: "--transcript-log, export-vectors",
+        },
+        Capability {
+            feature: "regen-fixtures",
+            enabled: cfg!(feature = "regen-fixtures"),
+            provides: "regen-fixtures",
+        },
+        Capability {
+            feature: "goldilocks",
+            enabled: cfg!(feature = "goldilocks"),
+            provides: "the Goldilocks proof in the walkthrough",
+        },
+        Capability {
+            feature: "koala-bear",
+            enabled: cfg!(feature = "koala-bear"),
+            provides: "--compare-fields, the KoalaBear proof in the walkthrough",
+        },
+        Capability {
+            feature: "mersenne-31",
+            enabled: cfg!(feature = "mersenne-31"),
+            provides: "the Mersenne31 proof in the walkthrough",
+        },
+        Capability {
+            feature: "mem-stats",
+            enabled: cfg!(feature = "mem-stats"),
+            provides: "allocator counters for soak",
+        },
+        Capability { feature: "python", enabled: cfg!(feature = "python"), provides: "the Python bindings" },
+    ]
+}
+
+pub fn format_capabilities() -> String {
+    let mut text = String::from("Features (+ compiled in, - not):\n");
+    for capability in capabilities() {
+        text.push_str(&format!("  {}\n", capability));
+    }
+    text
+}
+
+// A flag or subcommand used in a build without its feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingFeature {
+    pub feature: &'static str,
+    pub flag: &'static str,
+}
+
+impl fmt::Display for MissingFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not in this build; recompile with --features {}", self.flag, self.feature)
+    }
+}
+
+impl std::error::Error for MissingFeature {}
+
+// Ok when `feature` was compiled in; a name capabilities() does not list
+// never was
+pub fn require(feature: &'static str, flag: &'static str) -> Result<(), MissingFeature> {
+    let enabled = capabilities().iter().any(|capability| capability.feature == feature && capability.enabled);
+    if enabled { Ok(()) } else { Err(MissingFeature { feature, flag }) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_report_lists_each_feature_once() {
+        let report = capabilities();
+        for (i, capability) in report.iter().enumerate() {
+            assert!(report[i + 1..].iter().all(|other| other.feature != capability.feature));
+        }
+        assert_eq!(format_capabilities().lines().count(), report.len() + 1);
+    }
+
+    #[test]
+    fn require_follows_the_build() {
+        assert_eq!(require("exercises", "--exercises").is_ok(), cfg!(feature = "exercises"));
+        let missing = MissingFeature { feature: "tutorial", flag: "--stage" };
+        assert_eq!(require("tutorial", "--stage").err(), (!cfg!(feature = "tutorial")).then_some(missing));
+        assert!(missing.to_string().ends_with("recompile with --features tutorial"));
+        assert!(require("no-such-feature", "--nothing").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+// The same STARK under two Fiat-Shamir challengers: the Poseidon2
+// DuplexChallenger of MyConfig, and a SerializingChallenger that feeds the
+// transcript's bytes through Keccak-256. The PCS is shared, so only the
+// challenges differ, and the descriptor cannot tell the two apart: a saved
+// challenger proof records its ChallengerKind, which is checked before the
+// verifier runs.
+use core::fmt;
+use std::time::{Duration, Instant};
+
+use p3_challenger::{HashChallenger, SerializingChallenger32};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+use serde::{Deserialize, Serialize};
+
+use crate::air_id::AirIdError;
+use crate::cost::{CostParams, SecurityLevel};
+use crate::proof_file::{ConfigFingerprint, encode_proof, load_proof_from_reader};
+use crate::trace::SizingError;
+use crate::{
+    Challenge, ChallengeMmcs, ConfigDescriptor, DEFAULT_CONFIG_SEED, Dft, FibonacciAir, MyCompress, MyConfig, MyHash,
+    Pcs, Perm, PublicValues, Val, ValMmcs, VerifyError, constants_rng, create_config_from, create_default_config,
+    fri_params_for, generate_fibonacci_trace,
+};
+
+pub type KeccakChallenger = SerializingChallenger32<Val, HashChallenger<u8, Keccak256Hash, 32>>;
+pub type KeccakChallengerConfig = StarkConfig<Pcs, Challenge, KeccakChallenger>;
+
+// Same Merkle trees and FRI parameters as create_default_config
+pub fn create_keccak_challenger_config() -> KeccakChallengerConfig {
+    let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm);
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_params);
+    let challenger = KeccakChallenger::from_hasher(vec![], Keccak256Hash {});
+    KeccakChallengerConfig::new(pcs, challenger)
+}
+
+// The two configs share a PCS, so a proof's parts fit either proof type.
+// Challenger proofs are saved in MyConfig's layout.
+fn duplex_as_keccak(proof: Proof<MyConfig>) -> Proof<KeccakChallengerConfig> {
+    Proof {
+        commitments: proof.commitments,
+        opened_values: proof.opened_values,
+        opening_proof: proof.opening_proof,
+        degree_bits: proof.degree_bits,
+    }
+}
+
+fn keccak_as_duplex(proof: Proof<KeccakChallengerConfig>) -> Proof<MyConfig> {
+    Proof {
+        commitments: proof.commitments,
+        opened_values: proof.opened_values,
+        opening_proof: proof.opening_proof,
+        degree_bits: proof.degree_bits,
+    }
+}
+
+// The challenger a proof was made under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChallengerKind {
+    Duplex,
+    Keccak,
+}
+
+impl ChallengerKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Duplex => "DuplexChallenger",
+            Self::Keccak => "SerializingChallenger (Keccak-256)",
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Self::Duplex => Self::Keccak,
+            Self::Keccak => Self::Duplex,
+        }
+    }
+
+    // The fingerprint of `descriptor`'s config under this challenger; the
+    // duplex one is MyConfig's own
+    pub fn fingerprint(self, descriptor: &ConfigDescriptor) -> ConfigFingerprint {
+        match self {
+            Self::Duplex => ConfigFingerprint::of(descriptor),
+            Self::Keccak => ConfigFingerprint::of_variant(descriptor, "/keccak-256 challenger"),
+        }
+    }
+}
+
+// What create_default_config and create_keccak_challenger_config are built
+// from, saved ahead of every challenger proof
+fn challenger_descriptor() -> ConfigDescriptor {
+    ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::at(SecurityLevel::Test))
+}
+
+// Proves `trace` under `kind`'s config, returning the proof in MyConfig's
+// layout
+pub fn prove_with_challenger(
+    kind: ChallengerKind,
+    trace: RowMajorMatrix<Val>,
+    public_values: &PublicValues,
+) -> Proof<MyConfig> {
+    match kind {
+        ChallengerKind::Duplex => {
+            prove(&create_default_config(SecurityLevel::Test), &FibonacciAir, trace, public_values)
+        }
+        ChallengerKind::Keccak => {
+            keccak_as_duplex(prove(&create_keccak_challenger_config(), &FibonacciAir, trace, public_values))
+        }
+    }
+}
+
+// A prove_with_challenger proof in proof_file's encoding, with `kind` saved
+// ahead of it
+pub fn encode_challenger_proof(kind: ChallengerKind, proof: &Proof<MyConfig>) -> Result<Vec<u8>, ChallengerError> {
+    encode_proof(&challenger_descriptor(), &(kind, proof)).map_err(|e| ChallengerError::Encoding(e.to_string()))
+}
+
+// Verifies encode_challenger_proof's bytes under `expected`'s config. A proof
+// made under the other challenger is VerifyError::ConfigMismatch, named before
+// any challenge is drawn, just as a proof from another seed is.
+pub fn verify_challenger_bytes(
+    expected: ChallengerKind,
+    bytes: &[u8],
+    public_values: &PublicValues,
+) -> Result<(), VerifyError> {
+    let descriptor = challenger_descriptor();
+    let (found, proof): (ChallengerKind, Proof<MyConfig>) =
+        load_proof_from_reader(bytes, ConfigFingerprint::of(&descriptor))?;
+    if found != expected {
+        return Err(VerifyError::ConfigMismatch {
+            expected: expected.fingerprint(&descriptor),
+            found: found.fingerprint(&descriptor),
+        });
+    }
+    let result = match expected {
+        ChallengerKind::Duplex => verify(&create_config_from(&descriptor), &FibonacciAir, &proof, public_values),
+        ChallengerKind::Keccak => {
+            verify(&create_keccak_challenger_config(), &FibonacciAir, &duplex_as_keccak(proof), public_values)
+        }
+    };
+    result.map_err(|e| VerifyError::Proof(AirIdError::Verification(format!("{:?}", e))))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengerTiming {
+    pub name: &'static str,
+    pub prove: Duration,
+    pub verify: Duration,
+    // Proving twice gave the same quotient commitment
+    pub deterministic: bool,
+    // Error from verifying this config's saved proof under the other config
+    pub cross_verify: Option<VerifyError>,
+}
+
+impl fmt::Display for ChallengerTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: prove {:?}, verify {:?}, deterministic: {}, other config: {}",
+            self.name,
+            self.prove,
+            self.verify,
+            self.deterministic,
+            match &self.cross_verify {
+                Some(e) => format!("rejected ({})", e),
+                None => "accepted".to_string(),
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChallengerError {
+    Trace(SizingError),
+    // A proof failed under its own config
+    Verification { name: &'static str, msg: String },
+    Encoding(String),
+}
+
+impl fmt::Display for ChallengerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trace(e) => write!(f, "comparison trace: {}", e),
+            Self::Verification { name, msg } => write!(f, "{} proof failed to verify: {}", name, msg),
+            Self::Encoding(msg) => write!(f, "could not encode a challenger proof: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ChallengerError {}
+
+// Proves one `steps`-step Fibonacci trace under both challengers, timing each
+// and checking every proof verifies under its own config and only there
+pub fn compare_challengers(steps: usize) -> Result<[ChallengerTiming; 2], ChallengerError> {
+    let trace = generate_fibonacci_trace::<Val>(steps).map_err(ChallengerError::Trace)?;
+    Ok([
+        time_challenger(ChallengerKind::Duplex, &trace.matrix, &trace.public_values)?,
+        time_challenger(ChallengerKind::Keccak, &trace.matrix, &trace.public_values)?,
+    ])
+}
+
+fn time_challenger(
+    kind: ChallengerKind,
+    trace: &RowMajorMatrix<Val>,
+    pis: &PublicValues,
+) -> Result<ChallengerTiming, ChallengerError> {
+    let start = Instant::now();
+    let proof = prove_with_challenger(kind, trace.clone(), pis);
+    let prove_time = start.elapsed();
+    let again = prove_with_challenger(kind, trace.clone(), pis);
+    let bytes = encode_challenger_proof(kind, &proof)?;
+    let start = Instant::now();
+    verify_challenger_bytes(kind, &bytes, pis)
+        .map_err(|e| ChallengerError::Verification { name: kind.name(), msg: e.to_string() })?;
+    Ok(ChallengerTiming {
+        name: kind.name(),
+        prove: prove_time,
+        verify: start.elapsed(),
+        deterministic: proof.commitments.quotient_chunks == again.commitments.quotient_chunks,
+        cross_verify: verify_challenger_bytes(kind.other(), &bytes, pis).err(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved_proof(kind: ChallengerKind) -> (Vec<u8>, PublicValues) {
+        let trace = generate_fibonacci_trace::<Val>(15).unwrap();
+        let proof = prove_with_challenger(kind, trace.matrix, &trace.public_values);
+        (encode_challenger_proof(kind, &proof).unwrap(), trace.public_values)
+    }
+
+    #[test]
+    fn each_challenger_verifies_its_own_proof() {
+        for kind in [ChallengerKind::Duplex, ChallengerKind::Keccak] {
+            let (bytes, public_values) = saved_proof(kind);
+            assert_eq!(verify_challenger_bytes(kind, &bytes, &public_values), Ok(()), "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn the_other_challenger_is_a_config_mismatch() {
+        let descriptor = challenger_descriptor();
+        for prover in [ChallengerKind::Duplex, ChallengerKind::Keccak] {
+            let (bytes, public_values) = saved_proof(prover);
+            assert_eq!(
+                verify_challenger_bytes(prover.other(), &bytes, &public_values),
+                Err(VerifyError::ConfigMismatch {
+                    expected: prover.other().fingerprint(&descriptor),
+                    found: prover.fingerprint(&descriptor),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn challenger_fingerprints_differ() {
+        let descriptor = challenger_descriptor();
+        assert_eq!(ChallengerKind::Duplex.fingerprint(&descriptor), ConfigFingerprint::of(&descriptor));
+        assert_ne!(ChallengerKind::Keccak.fingerprint(&descriptor), ConfigFingerprint::of(&descriptor));
+    }
+}
@@ -0,0 +1,203 @@
+// The public-value layouts of CoefficientAir and FibonacciAir proofs and the
+// one claims type both decode to, shared by the prove and verify wrappers of
+// every config so a claim decodes the same whichever config proved it.
+use core::fmt;
+
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_uni_stark::{Proof, StarkGenericConfig};
+
+use crate::Val;
+use crate::canonical::{NonCanonicalValue, Reduction, parse_with};
+
+// [a0, b0, final]
+pub const NUM_FIBONACCI_CLAIM_VALUES: usize = 3;
+
+// The only place the layout is written down; generators encode through it
+pub fn claim_values<F: Copy>(a0: F, b0: F, final_value: F) -> Vec<F> {
+    vec![a0, b0, final_value]
+}
+
+// [F(0), F(1), F(num_steps), num_steps]
+pub const NUM_SEQUENCE_CLAIM_VALUES: usize = 4;
+
+pub fn sequence_claim_values<F: Copy>(f0: F, f1: F, result: F, num_steps: F) -> Vec<F> {
+    vec![f0, f1, result, num_steps]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimsError {
+    // A public-value vector that does not match the claim layout
+    WrongPublicValueCount { expected: usize, found: usize },
+    // Text given as a public value that is not an integer
+    NotAFieldElement(String),
+    // An integer public value of p or more, without --allow-reduction
+    NonCanonical(NonCanonicalValue),
+    // A proof claiming 2^degree_bits rows, more than a usize counts
+    DegreeTooLarge { degree_bits: usize },
+}
+
+impl fmt::Display for ClaimsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongPublicValueCount { expected, found } => {
+                write!(f, "expected {} public values, found {}", expected, found)
+            }
+            Self::NotAFieldElement(text) => write!(f, "{:?} is not a field element", text),
+            Self::NonCanonical(e) => write!(f, "public value {}", e),
+            Self::DegreeTooLarge { degree_bits } => write!(f, "a proof of 2^{} rows", degree_bits),
+        }
+    }
+}
+
+impl std::error::Error for ClaimsError {}
+
+// Public values as comma-separated canonical integers, e.g. "0,1,3314859971,100",
+// the form parse_public_values reads back
+pub fn format_public_values(public_values: &[Val]) -> String {
+    let values: Vec<String> = public_values.iter().map(|v| v.as_canonical_u64().to_string()).collect();
+    values.join(",")
+}
+
+pub fn parse_public_values(text: &str) -> Result<Vec<Val>, ClaimsError> {
+    parse_public_values_with(text, Reduction::Reject)
+}
+
+pub fn parse_public_values_with(text: &str, reduction: Reduction) -> Result<Vec<Val>, ClaimsError> {
+    text.split(',')
+        .map(|value| {
+            let value = value.trim();
+            let n = value.parse::<u64>().map_err(|_| ClaimsError::NotAFieldElement(value.to_string()))?;
+            parse_with(n, reduction).map_err(ClaimsError::NonCanonical)
+        })
+        .collect()
+}
+
+// What a verified FibonacciAir or CoefficientAir proof establishes, read by
+// name instead of by index into the public values. num_steps = 0 is the empty
+// computation: its trace is all padding, it proves like any other, and its
+// result is the starting f0.
+//
+// FibonacciAir carries num_steps as a public value. CoefficientAir does not:
+// its result is the b column of the last row, so num_steps is the row count,
+// which the proof carries as its degree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FibonacciClaims {
+    pub f0: Val,
+    pub f1: Val,
+    pub result: Val,
+    pub num_steps: usize,
+}
+
+impl FibonacciClaims {
+    // The FibonacciAir layout
+    pub fn from_public_values(public_values: &[Val]) -> Result<Self, ClaimsError> {
+        let &[f0, f1, result, num_steps] = public_values else {
+            return Err(ClaimsError::WrongPublicValueCount {
+                expected: NUM_SEQUENCE_CLAIM_VALUES,
+                found: public_values.len(),
+            });
+        };
+        Ok(Self { f0, f1, result, num_steps: num_steps.as_canonical_u64() as usize })
+    }
+
+    // The CoefficientAir layout, for a trace of `num_rows` rows
+    pub fn from_coefficient_public_values(public_values: &[Val], num_rows: usize) -> Result<Self, ClaimsError> {
+        let &[f0, f1, result] = public_values else {
+            return Err(ClaimsError::WrongPublicValueCount {
+                expected: NUM_FIBONACCI_CLAIM_VALUES,
+                found: public_values.len(),
+            });
+        };
+        Ok(Self { f0, f1, result, num_steps: num_rows })
+    }
+
+    // Takes the row count from the proof's degree, under any config. The
+    // degree comes from untrusted bytes and is read before verifying, so one
+    // too large to shift is an error rather than a panic or a wrapped count.
+    pub fn from_coefficient_proof<SC: StarkGenericConfig>(
+        public_values: &[Val],
+        proof: &Proof<SC>,
+    ) -> Result<Self, ClaimsError> {
+        let degree_bits = proof.degree_bits;
+        let num_rows = u32::try_from(degree_bits)
+            .ok()
+            .and_then(|bits| 1usize.checked_shl(bits))
+            .ok_or(ClaimsError::DegreeTooLarge { degree_bits })?;
+        Self::from_coefficient_public_values(public_values, num_rows)
+    }
+
+    // The empty computation, from the pair (f0, f1)
+    pub fn empty(f0: Val, f1: Val) -> Self {
+        Self { f0, f1, result: f0, num_steps: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_steps == 0
+    }
+
+    pub fn to_public_values(&self) -> Vec<Val> {
+        sequence_claim_values(self.f0, self.f1, self.result, Val::from_usize(self.num_steps))
+    }
+
+    pub fn to_coefficient_public_values(&self) -> Vec<Val> {
+        claim_values(self.f0, self.f1, self.result)
+    }
+}
+
+impl fmt::Display for FibonacciClaims {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no steps from ({}, {})", self.f0, self.f1);
+        }
+        write!(
+            f,
+            "from ({}, {}) the recurrence reaches {} (mod p) after {} steps",
+            self.f0, self.f1, self.result, self.num_steps
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prove_fibonacci;
+
+    #[test]
+    fn both_layouts_decode_to_the_same_claims() {
+        let claims = FibonacciClaims { f0: Val::ZERO, f1: Val::ONE, result: Val::from_u8(21), num_steps: 8 };
+        assert_eq!(FibonacciClaims::from_public_values(&claims.to_public_values()), Ok(claims));
+        let coefficient_layout = claims.to_coefficient_public_values();
+        assert_eq!(FibonacciClaims::from_coefficient_public_values(&coefficient_layout, 8), Ok(claims));
+        assert_eq!(claims.to_string(), "from (0, 1) the recurrence reaches 21 (mod p) after 8 steps");
+        assert_eq!(FibonacciClaims::empty(Val::ZERO, Val::ONE).to_string(), "no steps from (0, 1)");
+    }
+
+    #[test]
+    fn a_vector_of_the_wrong_length_is_a_shape_error() {
+        let three = [Val::ZERO, Val::ONE, Val::ONE];
+        assert_eq!(
+            FibonacciClaims::from_public_values(&three),
+            Err(ClaimsError::WrongPublicValueCount { expected: NUM_SEQUENCE_CLAIM_VALUES, found: 3 })
+        );
+        assert_eq!(
+            FibonacciClaims::from_coefficient_public_values(&three[..2], 8),
+            Err(ClaimsError::WrongPublicValueCount { expected: NUM_FIBONACCI_CLAIM_VALUES, found: 2 })
+        );
+    }
+
+    #[test]
+    fn a_degree_too_large_to_count_is_refused() {
+        let (mut proof, _) = prove_fibonacci(15).unwrap();
+        let layout = [Val::ZERO, Val::ONE, Val::ONE];
+        proof.proof.degree_bits = 4;
+        let claims = FibonacciClaims::from_coefficient_proof(&layout, &proof.proof);
+        assert_eq!(claims.map(|claims| claims.num_steps), Ok(16));
+        for degree_bits in [usize::BITS as usize, 200, usize::MAX] {
+            proof.proof.degree_bits = degree_bits;
+            assert_eq!(
+                FibonacciClaims::from_coefficient_proof(&layout, &proof.proof),
+                Err(ClaimsError::DegreeTooLarge { degree_bits })
+            );
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use core::borrow::Borrow;
+use core::fmt;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{Proof, prove, verify};
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::claims::{ClaimsError, FibonacciClaims, NUM_FIBONACCI_CLAIM_VALUES, claim_values};
+use crate::cost::SecurityLevel;
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, GeneratedTrace, SizingError, trace_len};
+use crate::{MyConfig, Val, create_default_config};
+
+// Coefficients are range-checked to [0, 2^COEFF_BITS)
+pub const COEFF_BITS: usize = 4;
+pub const MAX_COEFF: u64 = (1 << COEFF_BITS) - 1;
+
+// Columns: a, b, p, q, then the bits of p and q
+const NUM_COEFF_COLS: usize = 4 + 2 * COEFF_BITS;
+
+// Public values: the FibonacciClaims layout. The coefficients p and q stay
+// private.
+
+// Smallest trace the test FRI parameters accept, as in the other examples
+const MIN_ROWS: usize = 256;
+
+pub const COEFFICIENT_AIR_ID: AirId = AirId::new("coefficients", 1, 0, 0);
+
+// Proves that some p, q in [0, 15] carry the recurrence
+//   x(n+1) = p * x(n) + q * x(n-1)
+// from the public start (a0, b0) to the public final value on the last row
+#[derive(Debug, Clone)]
+pub struct CoefficientAir;
+
+impl<F> BaseAir<F> for CoefficientAir {
+    fn width(&self) -> usize {
+        NUM_COEFF_COLS
+    }
+}
+
+impl AirLint for CoefficientAir {
+    fn num_public_values(&self) -> usize {
+        NUM_FIBONACCI_CLAIM_VALUES
+    }
+}
+
+impl ProvableComputation for CoefficientAir {
+    fn air_id(&self) -> AirId {
+        COEFFICIENT_AIR_ID
+    }
+}
+
+// The bits of p and q are left unnamed
+impl ColumnNames for CoefficientAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["a", "b", "p", "q"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CoefficientAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &CoefficientRow<AB::Var> = (*local).borrow();
+        let next: &CoefficientRow<AB::Var> = (*next).borrow();
+
+        let pis = builder.public_values();
+        let (a0, b0, final_value) = (pis[0], pis[1], pis[2]);
+
+        // p and q are small: each is the sum of COEFF_BITS boolean bits
+        let mut p = AB::Expr::ZERO;
+        let mut q = AB::Expr::ZERO;
+        for i in 0..COEFF_BITS {
+            builder.assert_bool(local.p_bits[i].clone());
+            builder.assert_bool(local.q_bits[i].clone());
+            p += AB::Expr::from_u64(1 << i) * local.p_bits[i].clone();
+            q += AB::Expr::from_u64(1 << i) * local.q_bits[i].clone();
+        }
+        builder.assert_eq(local.p.clone(), p);
+        builder.assert_eq(local.q.clone(), q);
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local.a.clone(), a0);
+        when_first_row.assert_eq(local.b.clone(), b0);
+
+        // The coefficients cannot change mid-trace, and the recurrence holds
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(next.p.clone(), local.p.clone());
+        when_transition.assert_eq(next.q.clone(), local.q.clone());
+        when_transition.assert_eq(next.a.clone(), local.b.clone());
+        when_transition.assert_eq(
+            next.b.clone(),
+            local.p.clone() * local.b.clone() + local.q.clone() * local.a.clone(),
+        );
+
+        builder.when_last_row().assert_eq(local.b.clone(), final_value);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct CoefficientRow<F> {
+    pub a: F,
+    pub b: F,
+    pub p: F,
+    pub q: F,
+    pub p_bits: [F; COEFF_BITS],
+    pub q_bits: [F; COEFF_BITS],
+}
+
+impl<F> Borrow<CoefficientRow<F>> for [F] {
+    fn borrow(&self) -> &CoefficientRow<F> {
+        debug_assert_eq!(self.len(), NUM_COEFF_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<CoefficientRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoefficientError {
+    // Row counts must be powers of two of at least MIN_ROWS
+    InvalidRowCount(usize),
+    CoefficientOutOfRange { p: u64, q: u64 },
+    // No p, q in range reaches the final value
+    Unreachable,
+    Sizing(SizingError),
+    Claims(ClaimsError),
+    Verification(String),
+}
+
+impl fmt::Display for CoefficientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRowCount(rows) => {
+                write!(f, "{} rows is not a power of two of at least {}", rows, MIN_ROWS)
+            }
+            Self::CoefficientOutOfRange { p, q } => {
+                write!(f, "coefficients ({}, {}) exceed {}", p, q, MAX_COEFF)
+            }
+            Self::Unreachable => {
+                write!(f, "no coefficients in [0, {}] reach the final value", MAX_COEFF)
+            }
+            Self::Sizing(e) => write!(f, "{}", e),
+            Self::Claims(e) => write!(f, "{}", e),
+            Self::Verification(msg) => write!(f, "verification failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CoefficientError {}
+
+impl From<SizingError> for CoefficientError {
+    fn from(e: SizingError) -> Self {
+        Self::Sizing(e)
+    }
+}
+
+impl From<ClaimsError> for CoefficientError {
+    fn from(e: ClaimsError) -> Self {
+        Self::Claims(e)
+    }
+}
+
+fn check_rows(num_rows: usize) -> Result<(), CoefficientError> {
+    if !num_rows.is_power_of_two() || num_rows < MIN_ROWS {
+        return Err(CoefficientError::InvalidRowCount(num_rows));
+    }
+    Ok(())
+}
+
+// The value on the last of `num_rows` rows for the given coefficients
+pub fn recurrence_final<F: PrimeField64>(a0: F, b0: F, p: u64, q: u64, num_rows: usize) -> F {
+    let (p, q) = (F::from_u64(p), F::from_u64(q));
+    let (mut a, mut b) = (a0, b0);
+    for _ in 1..num_rows {
+        (a, b) = (b, p * b + q * a);
+    }
+    b
+}
+
+// Every (p, q) in range that reaches `final_value`, in lexicographic order
+pub fn find_coefficients<F: PrimeField64>(
+    a0: F,
+    b0: F,
+    final_value: F,
+    num_rows: usize,
+) -> Vec<(u64, u64)> {
+    (0..=MAX_COEFF)
+        .flat_map(|p| (0..=MAX_COEFF).map(move |q| (p, q)))
+        .filter(|&(p, q)| recurrence_final(a0, b0, p, q, num_rows) == final_value)
+        .collect()
+}
+
+pub fn generate_coefficient_trace<F: PrimeField64>(
+    a0: F,
+    b0: F,
+    p: u64,
+    q: u64,
+    num_rows: usize,
+) -> Result<GeneratedTrace<(u64, u64), F>, CoefficientError> {
+    check_rows(num_rows)?;
+    if p > MAX_COEFF || q > MAX_COEFF {
+        return Err(CoefficientError::CoefficientOutOfRange { p, q });
+    }
+
+    let len = trace_len(num_rows, NUM_COEFF_COLS)?;
+    let mut trace = RowMajorMatrix::new(F::zero_vec(len), NUM_COEFF_COLS);
+
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<CoefficientRow<F>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), num_rows);
+
+    let (p_val, q_val) = (F::from_u64(p), F::from_u64(q));
+    let (mut a, mut b) = (a0, b0);
+    for row in rows.iter_mut() {
+        row.a = a;
+        row.b = b;
+        row.p = p_val;
+        row.q = q_val;
+        for i in 0..COEFF_BITS {
+            row.p_bits[i] = F::from_u64((p >> i) & 1);
+            row.q_bits[i] = F::from_u64((q >> i) & 1);
+        }
+        (a, b) = (b, p_val * b + q_val * a);
+    }
+    let final_value = rows[num_rows - 1].b;
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: num_rows,
+        public_values: claim_values(a0, b0, final_value),
+        meta: (p, q),
+    })
+}
+
+// Searches for small coefficients connecting the endpoints and builds their trace
+pub fn generate_reachable_trace<F: PrimeField64>(
+    a0: F,
+    b0: F,
+    final_value: F,
+    num_rows: usize,
+) -> Result<GeneratedTrace<(u64, u64), F>, CoefficientError> {
+    check_rows(num_rows)?;
+    let &(p, q) = find_coefficients(a0, b0, final_value, num_rows)
+        .first()
+        .ok_or(CoefficientError::Unreachable)?;
+    generate_coefficient_trace(a0, b0, p, q, num_rows)
+}
+
+// Proves that small coefficients connecting the endpoints exist
+pub fn prove_reachable(
+    a0: Val,
+    b0: Val,
+    final_value: Val,
+    num_rows: usize,
+) -> Result<Proof<MyConfig>, CoefficientError> {
+    let trace = generate_reachable_trace(a0, b0, final_value, num_rows)?;
+    let config = create_default_config(SecurityLevel::Test);
+    Ok(prove(&config, &CoefficientAir, trace.matrix, &trace.public_values))
+}
+
+pub fn verify_reachable(
+    a0: Val,
+    b0: Val,
+    final_value: Val,
+    num_rows: usize,
+    proof: &Proof<MyConfig>,
+) -> Result<FibonacciClaims, CoefficientError> {
+    check_rows(num_rows)?;
+    // The final value is claimed for a specific row count, which the proof
+    // only carries as its degree
+    // and which, coming from untrusted bytes, may not even fit a shift
+    let degree_bits = proof.degree_bits;
+    if degree_bits != num_rows.trailing_zeros() as usize {
+        let proof_rows = u32::try_from(degree_bits)
+            .ok()
+            .and_then(|bits| 1usize.checked_shl(bits))
+            .ok_or(ClaimsError::DegreeTooLarge { degree_bits })?;
+        return Err(CoefficientError::InvalidRowCount(proof_rows));
+    }
+    let claims = FibonacciClaims { f0: a0, f1: b0, result: final_value, num_steps: num_rows };
+    let config = create_default_config(SecurityLevel::Test);
+    verify(&config, &CoefficientAir, proof, &claims.to_coefficient_public_values())
+        .map_err(|err| CoefficientError::Verification(format!("{:?}", err)))?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::oracle::fibonacci_mod;
+
+    use super::*;
+
+    #[test]
+    fn fibonacci_is_one_of_the_reachable_coefficient_pairs() {
+        let final_value = recurrence_final(Val::ZERO, Val::ONE, 1, 1, MIN_ROWS);
+        assert_eq!(final_value, fibonacci_mod(MIN_ROWS as u64));
+        assert!(find_coefficients(Val::ZERO, Val::ONE, final_value, MIN_ROWS).contains(&(1, 1)));
+    }
+
+    #[test]
+    fn a_reachable_value_proves_without_revealing_the_coefficients() {
+        let final_value = recurrence_final(Val::ONE, Val::TWO, 3, 5, MIN_ROWS);
+        let proof = prove_reachable(Val::ONE, Val::TWO, final_value, MIN_ROWS).unwrap();
+        let claims = verify_reachable(Val::ONE, Val::TWO, final_value, MIN_ROWS, &proof).unwrap();
+        assert_eq!(claims.result, final_value);
+        assert!(matches!(
+            verify_reachable(Val::ONE, Val::TWO, final_value + Val::ONE, MIN_ROWS, &proof),
+            Err(CoefficientError::Verification(_))
+        ));
+        assert_eq!(
+            verify_reachable(Val::ONE, Val::TWO, final_value, 2 * MIN_ROWS, &proof).err(),
+            Some(CoefficientError::InvalidRowCount(MIN_ROWS))
+        );
+        let mut forged = proof;
+        forged.degree_bits = usize::BITS as usize;
+        assert_eq!(
+            verify_reachable(Val::ONE, Val::TWO, final_value, 2 * MIN_ROWS, &forged).err(),
+            Some(CoefficientError::Claims(ClaimsError::DegreeTooLarge { degree_bits: usize::BITS as usize }))
+        );
+    }
+
+    #[test]
+    fn unusable_statements_are_refused() {
+        let generate = |p, q, rows| generate_coefficient_trace(Val::ZERO, Val::ONE, p, q, rows).err();
+        assert_eq!(generate(1, 1, 100), Some(CoefficientError::InvalidRowCount(100)));
+        assert_eq!(generate(1, 1, 128), Some(CoefficientError::InvalidRowCount(128)));
+        assert_eq!(generate(MAX_COEFF + 1, 0, MIN_ROWS), Some(CoefficientError::CoefficientOutOfRange { p: 16, q: 0 }));
+        // The largest power of two passes the row check but not the sizing
+        let height = usize::MAX / 2 + 1;
+        assert_eq!(
+            generate(1, 1, height),
+            Some(CoefficientError::Sizing(SizingError::LengthOverflow { height, width: NUM_COEFF_COLS }))
+        );
+        // From (0, 0) every coefficient pair stays at zero
+        let unreachable = generate_reachable_trace(Val::ZERO, Val::ZERO, Val::ONE, MIN_ROWS).err();
+        assert_eq!(unreachable, Some(CoefficientError::Unreachable));
+    }
+
+    #[test]
+    fn coefficients_outside_the_bits_are_refused() {
+        let mut trace = generate_coefficient_trace(Val::ZERO, Val::ONE, 1, 1, MIN_ROWS).unwrap();
+        // p = 17 on every row, bits unchanged
+        for row in trace.matrix.values.chunks_exact_mut(NUM_COEFF_COLS) {
+            row[2] = Val::from_u8(17);
+        }
+        let violation = crate::validate::validate_trace(&CoefficientAir, &trace.matrix, &trace.public_values);
+        assert!(violation.is_err());
+    }
+}
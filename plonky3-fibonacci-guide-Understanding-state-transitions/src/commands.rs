@@ -0,0 +1,273 @@
+// The binary's subcommands: each prints its progress and returns whether it
+// succeeded, leaving the exit status to main.rs
+use std::path::Path;
+use std::time::Duration;
+
+use crate::canonical::Reduction;
+use crate::claims::{FibonacciClaims, parse_public_values_with};
+use crate::conformance::run_conformance;
+use crate::cost::{CALIBRATION_PATH, CostParams, calibrate, estimate_proving_cost_with, save_calibration};
+use crate::dataset::{
+    DatasetClaim, format_commitment, load_dataset, parse_commitment, prove_dataset, verify_dataset_bytes,
+};
+use crate::air_id::IdentifiedProof;
+use crate::input::verify_input;
+use crate::proof_file::{load_described_proof, save_proof};
+use crate::provenance::{PROVENANCE, check_provenance};
+use crate::report::{render_report, run_examples};
+use crate::selftest::{ConfigReport, EXPECTED_FINGERPRINTS, parse_fingerprints, self_test};
+use crate::sequence::check_sequence;
+use crate::snapshot::{EXPECTED_SNAPSHOTS, canonical_cases, check_snapshots, current_snapshots, format_snapshots};
+use crate::soak::{MemorySource, SoakConfig, SoakSample, soak};
+use crate::stats::{StatsFile, compare_stats, load_stats, save_stats};
+use crate::{ConfigDescriptor, DEFAULT_CONFIG_SEED, NUM_FIBONACCI_COLS, create_config_from};
+
+// `self-test`: a tiny proof under every compiled-in config, each checked
+// against its fingerprint in fixtures/. Returns whether all of them passed,
+// a config with no recorded fingerprint counting as a failure.
+pub fn run_self_test() -> bool {
+    println!(" Self-test, {}", PROVENANCE);
+    let reports = self_test(&parse_fingerprints(EXPECTED_FINGERPRINTS));
+    for report in &reports {
+        println!("   {}", report);
+    }
+    reports.iter().all(ConfigReport::passed_fingerprint)
+}
+
+// `verify PROOF --public-values F0,F1,RESULT,STEPS [--sequence FILE]`: checks
+// a saved proof with no trace in sight, under a config rebuilt from the
+// descriptor saved with it, then the sequence file against what it proved
+pub fn run_verify_file(
+    path: &Path,
+    public_values: &str,
+    sequence: Option<&Path>,
+    reduction: Reduction,
+) -> Result<(), String> {
+    let public_values =
+        parse_public_values_with(public_values, reduction).map_err(|e| format!("Bad public values: {}", e))?;
+    let verified =
+        verify_input(path, &public_values).map_err(|e| format!("Rejected {}: {}", path.display(), e))?;
+    println!(" Verified {} under {}", path.display(), verified.descriptor);
+    if let Some(mismatch) = verified.provenance {
+        println!(" Warning: {}", mismatch);
+    }
+    if let Some(sequence) = sequence {
+        let claims = FibonacciClaims::from_public_values(&public_values).map_err(|e| e.to_string())?;
+        let entries = check_sequence(sequence, &claims)
+            .map_err(|e| format!("Sequence file {} does not match the proof: {}", sequence.display(), e))?;
+        println!(" Sequence file {}: {} entries match the proof", sequence.display(), entries);
+    }
+    Ok(())
+}
+
+// `inspect PROOF`: what a saved proof says about itself, read without
+// verifying it, including the provenance digest of the build that made it
+pub fn run_inspect(path: &Path) -> Result<(), String> {
+    let (descriptor, proof): (ConfigDescriptor, IdentifiedProof) =
+        load_described_proof(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    println!(" {} (not verified)", path.display());
+    println!("   Config: {}", descriptor);
+    println!("   AIR id: {}", proof.air_id);
+    println!("   Trace height: 2^{}", proof.proof.degree_bits);
+    println!("   Provenance digest: {:016x}", proof.provenance);
+    match check_provenance(proof.provenance) {
+        Ok(()) => println!("   Made by this build: {}", PROVENANCE),
+        Err(mismatch) => println!("   {}", mismatch),
+    }
+    Ok(())
+}
+
+// `prove-dataset --csv FILE --column NAME [--out PROOF]`: proves the column's
+// sum and count under the default config and prints the claim to verify with
+pub fn run_prove_dataset(csv: &Path, column: &str, out: &Path) -> Result<(), String> {
+    let amounts = load_dataset(csv, column).map_err(|e| format!("Could not load {}: {}", csv.display(), e))?;
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    let (claim, proof) = prove_dataset(&create_config_from(&descriptor), &amounts)
+        .map_err(|e| format!("Cannot prove column {} of {}: {}", column, csv.display(), e))?;
+    save_proof(out, &descriptor, &proof).map_err(|e| format!("Could not write {}: {}", out.display(), e))?;
+    println!(" Proved {}, column {}: {}", csv.display(), column, claim);
+    println!("   Proof written to {}; check it with", out.display());
+    println!(
+        "   verify-dataset {} --commitment {} --sum {} --count {}",
+        out.display(),
+        format_commitment(&claim.commitment),
+        claim.sum,
+        claim.count
+    );
+    Ok(())
+}
+
+// `verify-dataset PROOF --commitment HEX --sum S --count N`
+pub fn run_verify_dataset(path: &Path, commitment: &str, sum: u64, count: usize) -> Result<(), String> {
+    let commitment = parse_commitment(commitment).ok_or("Malformed --commitment (64 hex digits)")?;
+    let claim = DatasetClaim { commitment, sum, count };
+    let bytes = std::fs::read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let descriptor =
+        verify_dataset_bytes(&bytes, &claim).map_err(|e| format!("Rejected {}: {}", path.display(), e))?;
+    println!(" Verified {} under {}: {}", path.display(), descriptor, claim);
+    Ok(())
+}
+
+// `demo [--report out.html] [--json stats.json]`: every registered example,
+// one line each, and the HTML page and stats file when asked for. Returns
+// whether every example passed.
+pub fn run_demo(report_path: Option<&Path>, json_path: Option<&Path>) -> bool {
+    println!(" Demo, {}", PROVENANCE);
+    let reports = run_examples();
+    for report in &reports {
+        println!("   {}", report);
+    }
+    if let Some(path) = report_path {
+        match std::fs::write(path, render_report(&reports)) {
+            Ok(()) => println!(" Report written to {}", path.display()),
+            Err(e) => println!(" Could not write {}: {}", path.display(), e),
+        }
+    }
+    if let Some(path) = json_path {
+        match save_stats(path, &StatsFile::from_reports(&reports)) {
+            Ok(()) => println!(" Stats written to {}", path.display()),
+            Err(e) => println!(" Could not write {}: {}", path.display(), e),
+        }
+    }
+    reports.iter().all(|report| report.passed())
+}
+
+// `compare OLD NEW [--threshold PERCENT]`: the change in every metric of two
+// `demo --json` files, flagging those that moved by more than the threshold.
+// Returns whether nothing regressed past it; files from different configs
+// are an error.
+pub fn run_compare(old: &Path, new: &Path, threshold: f64) -> Result<bool, String> {
+    let load = |path: &Path| load_stats(path).map_err(|e| format!("Could not read {}: {}", path.display(), e));
+    let comparison = compare_stats(&load(old)?, &load(new)?).map_err(|e| format!("Cannot compare: {}", e))?;
+    println!(" {} -> {}, flagging changes over {}%:", old.display(), new.display(), threshold);
+    print!("{}", comparison.render(threshold));
+    let regressions = comparison.regressions(threshold);
+    println!(" {} regressions", regressions);
+    Ok(regressions == 0)
+}
+
+// `calibrate [--quick]`: times this machine's field, hash and DFT throughput
+// and writes CALIBRATION_PATH for the cost estimator, replacing any earlier
+// run. A quick run is only checked, not saved, so it cannot overwrite a full
+// one with noisier numbers.
+pub fn run_calibrate(quick: bool) -> Result<(), String> {
+    println!(" Calibration{}:", if quick { " (quick)" } else { "" });
+    let calibration = calibrate(quick);
+    println!("{}", calibration);
+    calibration.check().map_err(|e| format!("Unusable calibration: {}", e))?;
+    let estimate = estimate_proving_cost_with(100, NUM_FIBONACCI_COLS, &CostParams::test(), &calibration)
+        .map_err(|e| format!("The estimator refused the calibration: {}", e))?;
+    println!("   Estimated 100-step proof: {:?} .. {:?}", estimate.time_range.0, estimate.time_range.1);
+    if quick {
+        return Ok(());
+    }
+    save_calibration(Path::new(CALIBRATION_PATH), &calibration)
+        .map_err(|e| format!("Could not write {}: {}", CALIBRATION_PATH, e))?;
+    println!(" Written to {}", CALIBRATION_PATH);
+    Ok(())
+}
+
+// `conformance`: every registered example proved, linted and attacked.
+// Returns whether all of them passed.
+pub fn run_conformance_check() -> bool {
+    println!(" Conformance, {}", PROVENANCE);
+    let reports = run_conformance();
+    for report in &reports {
+        println!("   {}", report);
+    }
+    reports.iter().all(|report| report.passed())
+}
+
+// `constraints [--print]`: every registered AIR's canonical constraints
+// against fixtures/constraints.txt, after checking that the canonical form
+// ignores rewrites that keep the meaning. --print writes the current snapshots
+// instead. Returns whether every case and snapshot passed.
+pub fn run_constraint_snapshots(print: bool) -> bool {
+    let snapshots = current_snapshots();
+    if print {
+        print!("{}", format_snapshots(&snapshots));
+        return true;
+    }
+    println!(" Constraint snapshots, {}", PROVENANCE);
+    let cases = canonical_cases();
+    for case in &cases {
+        println!("   {}", case);
+    }
+    let reports = check_snapshots(&snapshots, EXPECTED_SNAPSHOTS);
+    for report in &reports {
+        println!("   {}", report);
+    }
+    cases.iter().all(|case| case.passed()) && reports.iter().all(|report| report.passed())
+}
+
+// `soak --minutes M`: the prove/verify loop for M minutes, one table row per
+// minute. Returns whether memory stayed flat once warmed up; a platform with
+// nothing to measure fails rather than passing unmeasured.
+pub fn run_soak(minutes: u64) -> bool {
+    let config = SoakConfig {
+        duration: Duration::from_secs(60 * minutes),
+        warmup_samples: 2,
+        max_slope: 1024.0 * 1024.0,
+    };
+    let source = match MemorySource::detect() {
+        Ok(source) => source,
+        Err(e) => {
+            println!(" Soak failed: {}", e);
+            return false;
+        }
+    };
+    println!(" Soak: prove/verify loop for {} minutes", minutes);
+    println!("   {:>6} {:>10} {:>15}", "minute", "iterations", format!("{} (KiB)", source));
+    let print_sample = |sample: &SoakSample| {
+        println!("   {:>6} {:>10} {:>15}", sample.minute, sample.iterations, sample.bytes / 1024);
+    };
+    match soak(&config, print_sample) {
+        Ok(samples) => {
+            println!(" Soak passed ({} samples)", samples.len());
+            true
+        }
+        Err(e) => {
+            println!(" Soak failed: {}", e);
+            false
+        }
+    }
+}
+
+// `export-vectors [--out DIR]`: the test vectors of every security level, one
+// JSON file each
+#[cfg(feature = "transcript-log")]
+pub fn run_export_vectors(out: &Path) -> Result<(), String> {
+    use crate::vectors::write_vectors;
+
+    println!(" Exporting test vectors to {}", out.display());
+    let written = write_vectors(out).map_err(|e| e.to_string())?;
+    for path in &written {
+        println!("   {}", path.display());
+    }
+    Ok(())
+}
+
+// `regen-fixtures [--apply]`: rebuilds every fixture and lists what changed.
+// A dry run that finds changes is an error, so CI can require the checked-in
+// fixtures to match the build.
+#[cfg(feature = "regen-fixtures")]
+pub fn run_regen_fixtures(apply: bool) -> Result<(), String> {
+    use crate::fixtures::{fixtures_dir, regen_fixtures};
+
+    let dir = fixtures_dir();
+    let mode = if apply { "" } else { " (dry run)" };
+    println!(" Regenerating fixtures in {}{}", dir.display(), mode);
+    let changes = regen_fixtures(&dir, apply).map_err(|e| e.to_string())?;
+    if changes.is_empty() {
+        println!("   no changes");
+        return Ok(());
+    }
+    for change in &changes {
+        println!("   {}", change);
+    }
+    if !apply {
+        return Err(format!("{} fixtures out of date; re-run with --apply to write them", changes.len()));
+    }
+    println!("   Applied {} changes", changes.len());
+    Ok(())
+}
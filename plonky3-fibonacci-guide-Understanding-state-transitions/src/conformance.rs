@@ -0,0 +1,469 @@
+// The `conformance` command: every example registered in report::EXAMPLES is
+// proved and verified, linted, run against an attack corpus and checked
+// against the default config's degree bound. Registering an example is all it
+// takes to be covered.
+//
+// The corpus has three parts. Column flips and padding attacks are built from
+// the honest trace for any AIR; semantic attacks, which need to know what the
+// example claims, are written per example. Attacks are checked with
+// validate_trace rather than by proving, which would only panic in the
+// prover's debug constraint check.
+use core::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use p3_air::{Air, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::air_id::{Identified, ProvableComputation, prove_identified, verify_identified};
+use crate::big_fibonacci::{BigFibonacciAir, NUM_LIMBS, generate_big_fibonacci_trace};
+use crate::coefficients::{CoefficientAir, generate_coefficient_trace, recurrence_final};
+use crate::cost::{CostParams, SecurityLevel};
+use crate::counter::{CounterAir, generate_counter_trace};
+use crate::dataset::{AMOUNT_BITS, DatasetAir, SAMPLE_DATASET, generate_dataset_trace, parse_dataset};
+use crate::indexed::{IndexedFibonacciAir, generate_indexed_fibonacci_trace};
+use crate::last_row::{LastRowBinding, LastRowDemoAir, PaddingStrategy, generate_last_row_trace};
+use crate::linear_recurrence::{RecurrenceAir, generate_linear_recurrence_trace};
+use crate::lint::{AirLint, lint_air};
+use crate::lucas::{LucasAir, generate_lucas_trace};
+use crate::multi::{MultiFibonacciAir, generate_multi_fibonacci_trace, sample_starts};
+use crate::parity::{ParityAir, generate_parity_trace};
+use crate::report::{EXAMPLES, constraint_summary, panic_message};
+use crate::trace::ColumnNames;
+use crate::validate::{Attack, ConstraintChecker, run_attacks, validate_trace};
+use crate::{FibonacciAir, StarkAir, Val, create_default_config, generate_fibonacci_trace};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub example: &'static str,
+    // One entry per failed check, naming the check or the attack
+    pub failures: Vec<String>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed() {
+            return write!(f, "PASS {}", self.example);
+        }
+        write!(f, "FAIL {}: {}", self.example, self.failures.join("; "))
+    }
+}
+
+fn not_generated(example: &'static str, e: impl fmt::Display) -> ConformanceReport {
+    ConformanceReport { example, failures: vec![format!("trace: {}", e)] }
+}
+
+// `public_values` with one more added to the value at `index`
+fn off_by_one(public_values: &[Val], index: usize) -> Vec<Val> {
+    let mut tampered = public_values.to_vec();
+    tampered[index] += Val::ONE;
+    tampered
+}
+
+// The log quotient degree the prover derives from the largest constraint
+// degree, as in p3-uni-stark: constraints below degree 2 still get a quotient
+// of degree 1
+fn log_quotient_degree(max_degree: usize) -> usize {
+    (max_degree.max(2) - 1).next_power_of_two().trailing_zeros() as usize
+}
+
+// Runs every check on the honest `trace` of `air`. `semantic` holds the
+// example's own attacks; the honest trace is added to them, and the column
+// flips and padding attacks are generated here.
+pub fn check_conformance<A>(
+    example: &'static str,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    real_rows: usize,
+    public_values: &[Val],
+    semantic: Vec<Attack>,
+) -> ConformanceReport
+where
+    A: ProvableComputation + StarkAir + AirLint + ColumnNames + BaseAir<Val>,
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+    for<'a> Identified<'a, A>: StarkAir,
+{
+    let mut failures = Vec::new();
+
+    // The symbolic degree against what the default config's blowup can hold
+    let summary = constraint_summary(air);
+    let needed = log_quotient_degree(summary.max_degree);
+    if let Err(e) = CostParams::test().check(trace.height(), needed) {
+        failures.push(format!("degree {}: {}", summary.max_degree, e));
+    }
+
+    let lint = lint_air(air);
+    if !lint.is_clean() {
+        failures.push(format!("lint: {}", lint));
+    }
+
+    // Adding one to any constrained cell of a real row must break a constraint
+    let (height, width) = (trace.height(), trace.width());
+    if real_rows > 0 {
+        let row = real_rows / 2;
+        for column in (0..width).filter(|c| !air.free_columns().contains(c)) {
+            let mut flipped = trace.clone();
+            flipped.values[row * width + column] += Val::ONE;
+            if validate_trace(air, &flipped, public_values).is_ok() {
+                let label = air.column_names().get(column).map_or(column.to_string(), |name| name.to_string());
+                failures.push(format!("attack \"flip column {} on row {}\" was accepted", label, row));
+            }
+        }
+    }
+
+    let mut attacks = vec![Attack {
+        name: "honest",
+        trace: trace.clone(),
+        public_values: public_values.to_vec(),
+        valid: true,
+    }];
+
+    // Padding must stay padding, and the real rows must stay a prefix
+    if let Some(is_real) = air.column_names().iter().position(|&name| name == "is_real") {
+        if real_rows < height {
+            let mut revived = trace.clone();
+            revived.values[(height - 1) * width + is_real] = Val::ONE;
+            attacks.push(Attack {
+                name: "padding row marked real",
+                trace: revived,
+                public_values: public_values.to_vec(),
+                valid: false,
+            });
+        }
+        // A real row after padding, away from the last row so the prefix
+        // constraint rather than the last-row one has to catch it
+        if real_rows + 2 < height {
+            let mut resumed = trace.clone();
+            resumed.values[((real_rows + height) / 2) * width + is_real] = Val::ONE;
+            attacks.push(Attack {
+                name: "real row after padding",
+                trace: resumed,
+                public_values: public_values.to_vec(),
+                valid: false,
+            });
+        }
+        if real_rows >= 3 {
+            let mut holed = trace.clone();
+            holed.values[(real_rows / 2) * width + is_real] = Val::ZERO;
+            attacks.push(Attack {
+                name: "hole in the real rows",
+                trace: holed,
+                public_values: public_values.to_vec(),
+                valid: false,
+            });
+        }
+    }
+    attacks.extend(semantic);
+
+    let report = run_attacks(example, air, attacks);
+    if let Some(msg) = report.unfinished {
+        failures.push(format!("eval panicked: {}", msg));
+    }
+    for name in report.slipped_through {
+        failures.push(format!("attack \"{}\" was accepted", name));
+    }
+    for name in report.wrongly_rejected {
+        failures.push(format!("honest trace \"{}\" was rejected", name));
+    }
+
+    // Proving an invalid trace panics in the debug check, so only prove once
+    // the honest trace is known to satisfy the constraints
+    if validate_trace(air, &trace, public_values).is_ok() {
+        let config = create_default_config(SecurityLevel::Test);
+        let proof = prove_identified(&config, air, trace, public_values);
+        if let Err(e) = verify_identified(&config, air, &proof, public_values) {
+            failures.push(format!("honest proof: {}", e));
+        }
+    }
+
+    ConformanceReport { example, failures }
+}
+
+pub fn check_fibonacci(example: &'static str) -> ConformanceReport {
+    let trace = match generate_fibonacci_trace::<Val>(100) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    let semantic = vec![
+        Attack {
+            name: "result off by one",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 2),
+            valid: false,
+        },
+        Attack {
+            name: "one more step claimed",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 3),
+            valid: false,
+        },
+    ];
+    check_conformance(example, &FibonacciAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_coefficients(example: &'static str) -> ConformanceReport {
+    let (a0, b0, num_rows) = (Val::ZERO, Val::ONE, 256);
+    let trace = match generate_coefficient_trace::<Val>(a0, b0, 1, 1, num_rows) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    // The Fibonacci trace offered as proof of the Pell numbers' final value
+    let mut pell = trace.public_values.clone();
+    pell[2] = recurrence_final(a0, b0, 2, 1, num_rows);
+    let semantic = vec![Attack {
+        name: "Pell final value",
+        trace: trace.matrix.clone(),
+        public_values: pell,
+        valid: false,
+    }];
+    check_conformance(example, &CoefficientAir, trace.matrix, trace.real_rows, &trace.public_values, semantic)
+}
+
+pub fn check_counter(example: &'static str) -> ConformanceReport {
+    let trace = match generate_counter_trace::<Val>(256) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    let semantic = vec![Attack {
+        name: "count one past the last row",
+        trace: trace.matrix.clone(),
+        public_values: off_by_one(pis, 0),
+        valid: false,
+    }];
+    check_conformance(example, &CounterAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_parity(example: &'static str) -> ConformanceReport {
+    let trace = match generate_parity_trace::<Val>(256) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    let semantic = vec![Attack {
+        name: "final parity flipped",
+        trace: trace.matrix.clone(),
+        public_values: vec![Val::ONE - pis[0]],
+        valid: false,
+    }];
+    check_conformance(example, &ParityAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_last_row(example: &'static str) -> ConformanceReport {
+    let air = LastRowDemoAir { binding: LastRowBinding::LastReal };
+    let trace = match generate_last_row_trace::<Val>(100, PaddingStrategy::Zero) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    let semantic = vec![Attack {
+        name: "count includes a padding row",
+        trace: trace.matrix.clone(),
+        public_values: off_by_one(pis, 0),
+        valid: false,
+    }];
+    check_conformance(example, &air, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_dataset(example: &'static str) -> ConformanceReport {
+    let trace = match parse_dataset(SAMPLE_DATASET, "amount").and_then(|amounts| generate_dataset_trace(&amounts)) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    // An amount moved between two rows keeps the total, but not the running sum
+    let mut moved = trace.matrix.clone();
+    let width = moved.width();
+    moved.values[width + 1] += Val::ONE;
+    moved.values[2 * width + 1] -= Val::ONE;
+    // The same move paid for with a negative amount, every other bit column
+    // made to agree, so the range check alone is left to catch it
+    // (row 1's sum, row 2's amount) rebuilt with the negative amount
+    let mut negative = trace.matrix.clone();
+    let (amount_bits, sum_bits) = (4, 4 + AMOUNT_BITS);
+    negative.values[width + 1] = Val::NEG_ONE;
+    negative.values[width + 2] -= Val::ONE;
+    negative.values[2 * width + 1] += Val::ONE;
+    for (cell, bits) in [(width + 2, width + sum_bits), (2 * width + 1, 2 * width + amount_bits)] {
+        let value = negative.values[cell].as_canonical_u64();
+        for k in 0..AMOUNT_BITS {
+            negative.values[bits + k] = Val::from_bool((value >> k) & 1 == 1);
+        }
+    }
+    let semantic = vec![
+        Attack {
+            name: "sum off by one",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 0),
+            valid: false,
+        },
+        Attack {
+            name: "one more entry claimed",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 1),
+            valid: false,
+        },
+        Attack {
+            name: "amount moved between rows",
+            trace: moved,
+            public_values: pis.clone(),
+            valid: false,
+        },
+        Attack {
+            name: "negative amount",
+            trace: negative,
+            public_values: pis.clone(),
+            valid: false,
+        },
+    ];
+    check_conformance(example, &DatasetAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_tribonacci(example: &'static str) -> ConformanceReport {
+    let trace = match generate_linear_recurrence_trace::<Val, 3>([Val::ZERO, Val::ZERO, Val::ONE], 20) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    let semantic = vec![
+        Attack {
+            name: "result off by one",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 3),
+            valid: false,
+        },
+        Attack {
+            name: "one more step claimed",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 4),
+            valid: false,
+        },
+    ];
+    check_conformance(example, &RecurrenceAir::<3>, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_lucas(example: &'static str) -> ConformanceReport {
+    let trace = match generate_lucas_trace::<Val>(Val::TWO, Val::NEG_ONE, Val::ZERO, Val::ONE, 20) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    // The Pell trace offered under P = 3
+    let semantic = vec![
+        Attack {
+            name: "P changed",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 0),
+            valid: false,
+        },
+        Attack {
+            name: "result off by one",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, 4),
+            valid: false,
+        },
+    ];
+    check_conformance(example, &LucasAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_multi_fibonacci(example: &'static str) -> ConformanceReport {
+    let air = MultiFibonacciAir { num_sequences: 8 };
+    let trace = match generate_multi_fibonacci_trace(&sample_starts(air.num_sequences), 256) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    // Two sequences' columns swapped, claims unchanged
+    let mut swapped = trace.matrix.clone();
+    for row in swapped.values.chunks_exact_mut(swapped.width) {
+        row.swap(0, 2);
+        row.swap(1, 3);
+    }
+    let semantic = vec![
+        Attack {
+            name: "last sequence's result off by one",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, pis.len() - 1),
+            valid: false,
+        },
+        Attack {
+            name: "two sequences swapped",
+            trace: swapped,
+            public_values: pis.clone(),
+            valid: false,
+        },
+    ];
+    check_conformance(example, &air, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_big_fibonacci(example: &'static str) -> ConformanceReport {
+    let trace = match generate_big_fibonacci_trace::<Val>(100) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    // F(100) mod p as the low limb: what FibonacciAir proves
+    let mut reduced = vec![Val::ZERO; NUM_LIMBS];
+    match generate_fibonacci_trace::<Val>(100) {
+        Ok(trace) => reduced[0] = trace.meta.final_value,
+        Err(e) => return not_generated(example, e),
+    }
+    reduced.push(pis[NUM_LIMBS]);
+    let semantic = vec![
+        Attack {
+            name: "top limb off by one",
+            trace: trace.matrix.clone(),
+            public_values: off_by_one(pis, NUM_LIMBS - 1),
+            valid: false,
+        },
+        Attack {
+            name: "result reduced mod p",
+            trace: trace.matrix.clone(),
+            public_values: reduced,
+            valid: false,
+        },
+    ];
+    check_conformance(example, &BigFibonacciAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+pub fn check_indexed_fibonacci(example: &'static str) -> ConformanceReport {
+    let trace = match generate_indexed_fibonacci_trace::<Val>(100) {
+        Ok(trace) => trace,
+        Err(e) => return not_generated(example, e),
+    };
+    let pis = &trace.public_values;
+    // Row 6 claiming row 5's index
+    let mut duplicated = trace.matrix.clone();
+    let width = duplicated.width();
+    duplicated.values[6 * width + 4] = duplicated.values[5 * width + 4];
+    let semantic = vec![Attack {
+        name: "index duplicated",
+        trace: duplicated,
+        public_values: pis.clone(),
+        valid: false,
+    }];
+    check_conformance(example, &IndexedFibonacciAir, trace.matrix, trace.real_rows, pis, semantic)
+}
+
+// Checks every registered example; a panic is reported as that example's failure
+pub fn run_conformance() -> Vec<ConformanceReport> {
+    EXAMPLES
+        .iter()
+        .map(|example| {
+            panic::catch_unwind(AssertUnwindSafe(|| (example.conformance)(example.name))).unwrap_or_else(
+                |payload| ConformanceReport {
+                    example: example.name,
+                    failures: vec![format!("panicked: {}", panic_message(payload.as_ref()))],
+                },
+            )
+        })
+        .collect()
+}
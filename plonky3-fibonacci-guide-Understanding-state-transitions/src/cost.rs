@@ -0,0 +1,526 @@
+use core::fmt;
+use core::hint::black_box;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use p3_challenger::GrindingChallenger;
+use p3_dft::TwoAdicSubgroupDft;
+use p3_field::{Field, PackedValue, PrimeCharacteristicRing, TwoAdicField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_symmetric::Permutation;
+use serde::{Deserialize, Serialize};
+use stark_config::{FriChoice, LOG_FINAL_POLY_LEN};
+
+// Named FRI parameter sets, shared with the arithmetic example
+pub use stark_config::SecurityLevel;
+
+use crate::trace::{DEFAULT_MIN_HEIGHT, SizingError, TraceOptions, trace_height, trace_len};
+use crate::{Challenger, DEFAULT_CONFIG_SEED, Dft, MAX_POW_BITS, Perm, Val, constants_rng};
+
+// Estimates are stated as [estimate / ERROR_FACTOR, estimate * ERROR_FACTOR]
+pub const ERROR_FACTOR: f64 = 4.0;
+
+// Bytes per base field element and per Merkle digest (8 BabyBear elements)
+const FIELD_BYTES: usize = 4;
+const DIGEST_BYTES: usize = 8 * FIELD_BYTES;
+
+// Sponge rate of MyHash: elements absorbed per permutation
+const HASH_RATE: usize = 8;
+
+// Quotient chunks are committed over the extension field (degree 4)
+const EXTENSION_DEGREE: usize = 4;
+
+// The FRI parameters that drive the cost of a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostParams {
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    // Grinding before the query indices are sampled
+    pub pow_bits: usize,
+    pub min_height: usize,
+}
+
+impl CostParams {
+    // SecurityLevel::Test: two queries at a blowup of 2, and the generators'
+    // DEFAULT_MIN_HEIGHT
+    pub const fn test() -> Self {
+        Self::at(SecurityLevel::Test)
+    }
+
+    // What test_config proves under: FriChoice::MINIMAL, padding only to
+    // MIN_TRACE_HEIGHT. Insecure; for tests only.
+    pub const fn minimal() -> Self {
+        let fri = FriChoice::MINIMAL;
+        let (log_blowup, num_queries, pow_bits) = (fri.log_blowup, fri.num_queries, fri.pow_bits);
+        Self { log_blowup, num_queries, pow_bits, min_height: MIN_TRACE_HEIGHT }
+    }
+
+    // The FRI parameters of `level`, padding to DEFAULT_MIN_HEIGHT
+    pub const fn at(level: SecurityLevel) -> Self {
+        let fri = level.fri_choice();
+        let (log_blowup, num_queries, pow_bits) = (fri.log_blowup, fri.num_queries, fri.pow_bits);
+        Self { log_blowup, num_queries, pow_bits, min_height: DEFAULT_MIN_HEIGHT }
+    }
+
+    // Conjectured bits of security: each query is worth log_blowup bits and
+    // grinding adds pow_bits. The degree-4 extension (124 bits) caps it
+    // further out than any SecurityLevel reaches.
+    pub const fn conjectured_security_bits(&self) -> usize {
+        self.log_blowup * self.num_queries + self.pow_bits
+    }
+
+    // These FRI parameters padding as `options` asks. A height past the
+    // field's two-adic subgroup is refused here; check refuses one below
+    // MIN_TRACE_HEIGHT.
+    pub fn with_trace_options(self, options: TraceOptions) -> Result<Self, ParamsError> {
+        let log_lde_height = options.min_log_height.saturating_add(self.log_blowup);
+        match options.min_height() {
+            Some(min_height) if log_lde_height <= Val::TWO_ADICITY => Ok(Self { min_height, ..self }),
+            _ => Err(ParamsError::TooLarge { log_lde_height, max: Val::TWO_ADICITY }),
+        }
+    }
+
+    // The FRI half of these parameters, as the shared config takes them
+    pub const fn fri_choice(&self) -> FriChoice {
+        FriChoice { log_blowup: self.log_blowup, num_queries: self.num_queries, pow_bits: self.pow_bits }
+    }
+}
+
+// FRI folds down to a final polynomial of 2^LOG_FINAL_POLY_LEN coefficients,
+// so a shorter trace leaves it nothing to fold
+pub const MIN_TRACE_HEIGHT: usize = 1 << LOG_FINAL_POLY_LEN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamsError {
+    // The LDE must be at least as large as the quotient domain
+    BlowupTooSmall { log_blowup: usize, min: usize },
+    NoQueries,
+    MinHeightNotPowerOfTwo(usize),
+    MinHeightTooSmall { min_height: usize, min: usize },
+    // The LDE would need a larger two-adic subgroup than BabyBear has
+    TooLarge { log_lde_height: usize, max: usize },
+    // More grinding than a ConfigDescriptor may ask for
+    PowBitsTooLarge { pow_bits: usize, max: usize },
+    Sizing(SizingError),
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlowupTooSmall { log_blowup, min } => {
+                write!(f, "log blowup {} is below the AIR's log quotient degree of {}", log_blowup, min)
+            }
+            Self::NoQueries => write!(f, "FRI needs at least one query"),
+            Self::MinHeightNotPowerOfTwo(height) => write!(f, "minimum height {} is not a power of two", height),
+            Self::MinHeightTooSmall { min_height, min } => {
+                write!(f, "minimum height {} is below the smallest provable height of {}", min_height, min)
+            }
+            Self::TooLarge { log_lde_height, max } => {
+                write!(f, "an LDE of 2^{} rows exceeds the field's 2^{} subgroup", log_lde_height, max)
+            }
+            Self::PowBitsTooLarge { pow_bits, max } => {
+                write!(f, "{} proof-of-work bits is above the limit of {}", pow_bits, max)
+            }
+            Self::Sizing(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+impl From<SizingError> for ParamsError {
+    fn from(e: SizingError) -> Self {
+        Self::Sizing(e)
+    }
+}
+
+impl CostParams {
+    // Rejects parameters the prover would only panic on, deep inside FRI, for
+    // a trace of `rows` rows whose AIR has the given log quotient degree.
+    // Returns the padded height.
+    pub fn check(&self, rows: usize, log_quotient_degree: usize) -> Result<usize, ParamsError> {
+        if self.log_blowup < log_quotient_degree {
+            let min = log_quotient_degree;
+            return Err(ParamsError::BlowupTooSmall { log_blowup: self.log_blowup, min });
+        }
+        if self.num_queries == 0 {
+            return Err(ParamsError::NoQueries);
+        }
+        if self.pow_bits > MAX_POW_BITS {
+            return Err(ParamsError::PowBitsTooLarge { pow_bits: self.pow_bits, max: MAX_POW_BITS });
+        }
+        if !self.min_height.is_power_of_two() {
+            return Err(ParamsError::MinHeightNotPowerOfTwo(self.min_height));
+        }
+        if self.min_height < MIN_TRACE_HEIGHT {
+            return Err(ParamsError::MinHeightTooSmall { min_height: self.min_height, min: MIN_TRACE_HEIGHT });
+        }
+        let height = trace_height(rows, self.min_height)?;
+        let log_lde_height = (height.trailing_zeros() as usize).saturating_add(self.log_blowup);
+        if log_lde_height > Val::TWO_ADICITY {
+            return Err(ParamsError::TooLarge { log_lde_height, max: Val::TWO_ADICITY });
+        }
+        Ok(height)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimatedCost {
+    // Expected prover time, widened by ERROR_FACTOR on both sides
+    pub time_range: (Duration, Duration),
+    pub peak_mem_bytes: usize,
+    pub proof_bytes: usize,
+}
+
+// Per-operation timings measured on this machine by `calibrate`, in
+// nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub field_mul_ns: f64,
+    // Per element, multiplying whole packed vectors
+    pub packed_mul_ns: f64,
+    pub permutation_ns: f64,
+    // Per element per butterfly layer of a DFT under Dft
+    pub dft_ns: f64,
+}
+
+impl Calibration {
+    // Used when no calibration file has been written: slower than any
+    // machine the examples are likely to run on, so estimates err long
+    pub const CONSERVATIVE: Self =
+        Self { field_mul_ns: 5.0, packed_mul_ns: 2.0, permutation_ns: 5_000.0, dft_ns: 10.0 };
+
+    // Rejects constants no measurement could have produced, e.g. from a
+    // hand-edited file
+    pub fn check(&self) -> Result<(), CalibrationError> {
+        let constants = [
+            ("field_mul_ns", self.field_mul_ns),
+            ("packed_mul_ns", self.packed_mul_ns),
+            ("permutation_ns", self.permutation_ns),
+            ("dft_ns", self.dft_ns),
+        ];
+        if let Some(&(name, value)) = constants.iter().find(|(_, value)| !(value.is_finite() && *value > 0.0)) {
+            return Err(CalibrationError::NotPositive { name, value });
+        }
+        // A permutation is dozens of rounds of multiplications
+        if self.packed_mul_ns >= self.permutation_ns {
+            return Err(CalibrationError::Unordered);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let per_second = |ns: f64| 1e9 / ns;
+        let mul_rate = per_second(self.field_mul_ns) / 1e6;
+        writeln!(f, "   BabyBear multiply: {:.2} ns ({:.0} M/s)", self.field_mul_ns, mul_rate)?;
+        writeln!(
+            f,
+            "   Packed multiply, {} lanes: {:.2} ns per element ({:.0} M/s)",
+            <Val as Field>::Packing::WIDTH,
+            self.packed_mul_ns,
+            per_second(self.packed_mul_ns) / 1e6
+        )?;
+        writeln!(
+            f,
+            "   Poseidon2 permutation: {:.0} ns ({:.0} /s)",
+            self.permutation_ns,
+            per_second(self.permutation_ns)
+        )?;
+        write!(f, "   DFT: {:.2} ns per element per layer", self.dft_ns)
+    }
+}
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    NotPositive { name: &'static str, value: f64 },
+    // Packed multiplication measured no faster than a whole permutation
+    Unordered,
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Json(e) => write!(f, "not a calibration file: {}", e),
+            Self::NotPositive { name, value } => write!(f, "{} is {}, not a positive time", name, value),
+            Self::Unordered => write!(f, "a packed multiply measured slower than a permutation"),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+impl From<io::Error> for CalibrationError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CalibrationError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+// Written by `calibrate`, read by estimate_proving_cost
+pub const CALIBRATION_PATH: &str = "calibration.json";
+
+pub fn save_calibration(path: &Path, calibration: &Calibration) -> Result<(), CalibrationError> {
+    let mut json = serde_json::to_string_pretty(calibration)?;
+    json.push('\n');
+    Ok(fs::write(path, json)?)
+}
+
+pub fn load_calibration(path: &Path) -> Result<Calibration, CalibrationError> {
+    let calibration: Calibration = serde_json::from_str(&fs::read_to_string(path)?)?;
+    calibration.check()?;
+    Ok(calibration)
+}
+
+static CALIBRATION: OnceLock<Calibration> = OnceLock::new();
+
+// CALIBRATION_PATH, read once per process; Calibration::CONSERVATIVE if it
+// is missing or does not load
+fn calibration() -> Calibration {
+    *CALIBRATION.get_or_init(|| load_calibration(Path::new(CALIBRATION_PATH)).unwrap_or(Calibration::CONSERVATIVE))
+}
+
+fn nanos_per(elapsed: Duration, ops: u64) -> f64 {
+    elapsed.as_secs_f64() * 1e9 / ops as f64
+}
+
+// Times each operation the estimator counts. `quick` does 1/16 of the work,
+// enough to check the numbers are sane but noisier.
+pub fn calibrate(quick: bool) -> Calibration {
+    let shift = if quick { 4 } else { 0 };
+    let muls: u64 = 1 << (22 - shift);
+    let perms: u64 = 1 << (14 - shift);
+    let log_dft_height = 14 - shift;
+    const DFT_WIDTH: usize = 8;
+
+    let x = Val::from_u64(7);
+    let mut acc = Val::from_u64(3);
+    let start = Instant::now();
+    for _ in 0..muls {
+        acc = black_box(acc * x);
+    }
+    black_box(acc);
+    let field_mul_ns = nanos_per(start.elapsed(), muls);
+
+    let lanes = <Val as Field>::Packing::WIDTH as u64;
+    let packed_x = <Val as Field>::Packing::from(x);
+    let mut packed_acc = <Val as Field>::Packing::from(Val::from_u64(3));
+    let start = Instant::now();
+    for _ in 0..muls / lanes {
+        packed_acc = black_box(packed_acc * packed_x);
+    }
+    black_box(packed_acc);
+    let packed_mul_ns = nanos_per(start.elapsed(), muls / lanes * lanes);
+
+    let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let mut state = [Val::ZERO; 16];
+    let start = Instant::now();
+    for _ in 0..perms {
+        perm.permute_mut(black_box(&mut state));
+    }
+    black_box(state);
+    let permutation_ns = nanos_per(start.elapsed(), perms);
+
+    let height = 1 << log_dft_height;
+    let values = (0..height * DFT_WIDTH).map(Val::from_usize).collect();
+    let matrix = RowMajorMatrix::new(values, DFT_WIDTH);
+    let start = Instant::now();
+    black_box(Dft::default().dft_batch(matrix));
+    let dft_ns = nanos_per(start.elapsed(), (height * DFT_WIDTH * log_dft_height) as u64);
+
+    Calibration { field_mul_ns, packed_mul_ns, permutation_ns, dft_ns }
+}
+
+// Time for one proof-of-work search of `pow_bits` bits on a fresh transcript.
+// The prover grinds once, after the FRI commit phase, so this is what
+// pow_bits adds to proving; it doubles per bit, and one search can run well
+// off the 2^pow_bits average.
+pub fn grinding_time(pow_bits: usize) -> Duration {
+    let perm = Perm::new_from_rng_128(&mut constants_rng(DEFAULT_CONFIG_SEED));
+    let mut challenger = Challenger::new(perm);
+    let start = Instant::now();
+    black_box(challenger.grind(pow_bits));
+    start.elapsed()
+}
+
+// Predicts prover time, peak memory and proof size for a trace of `steps` rows
+// and `width` columns before any of it is generated. The model counts field
+// multiplications in the LDE/quotient DFTs and permutations in the Merkle
+// commitments, timed by the calibration in CALIBRATION_PATH; everything else
+// is treated as noise covered by ERROR_FACTOR.
+pub fn estimate_proving_cost(
+    steps: usize,
+    width: usize,
+    params: &CostParams,
+) -> Result<EstimatedCost, SizingError> {
+    estimate_proving_cost_with(steps, width, params, &calibration())
+}
+
+// estimate_proving_cost under the given per-operation timings
+pub fn estimate_proving_cost_with(
+    steps: usize,
+    width: usize,
+    params: &CostParams,
+    calibration: &Calibration,
+) -> Result<EstimatedCost, SizingError> {
+    let height = trace_height(steps, params.min_height)?;
+    let log_height = height.trailing_zeros() as usize;
+    let log_lde_height = log_height + params.log_blowup;
+    let lde_height = trace_len(height, 1 << params.log_blowup)?;
+
+    // Trace LDE plus the quotient LDE over the extension field
+    let committed_width = width + EXTENSION_DEGREE;
+    // Byte sizes can pass 2^32 on 32-bit targets; this bounds the peak memory
+    // sum below, so the plain arithmetic after it cannot wrap
+    trace_len(lde_height, 2 * committed_width * FIELD_BYTES + 2 * DIGEST_BYTES)?;
+    let dft_muls = (committed_width * lde_height) as u64 * log_lde_height as u64;
+
+    // Leaf hashing absorbs HASH_RATE elements per permutation, and a binary tree
+    // over lde_height leaves needs about lde_height compressions
+    let leaf_perms = lde_height * committed_width.div_ceil(HASH_RATE);
+    let merkle_perms = leaf_perms + 2 * lde_height;
+
+    let estimate_ns = calibration.dft_ns * dft_muls as f64 + calibration.permutation_ns * merkle_perms as f64;
+    let estimate = Duration::from_secs_f64(estimate_ns * 1e-9);
+    let time_range = (estimate.div_f64(ERROR_FACTOR), estimate.mul_f64(ERROR_FACTOR));
+
+    // The trace, its LDE and the Merkle tree over it are alive at the same time
+    let trace_bytes = height * width * FIELD_BYTES;
+    let lde_bytes = lde_height * committed_width * FIELD_BYTES;
+    let tree_bytes = 2 * lde_height * DIGEST_BYTES;
+    let peak_mem_bytes = trace_bytes + lde_bytes + tree_bytes;
+
+    // Each query opens one row plus an authentication path per committed matrix,
+    // and FRI adds a folded path per round
+    let path_bytes = log_lde_height * DIGEST_BYTES;
+    let fri_round_bytes = path_bytes + 2 * EXTENSION_DEGREE * FIELD_BYTES;
+    let per_query = committed_width * FIELD_BYTES + 2 * path_bytes + log_lde_height * fri_round_bytes;
+    let proof_bytes = params.num_queries * per_query;
+
+    Ok(EstimatedCost { time_range, peak_mem_bytes, proof_bytes })
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_uni_stark::{prove, verify};
+
+    use super::*;
+    use crate::proof_file::encoded_size;
+    use crate::{FIBONACCI_LOG_QUOTIENT_DEGREE, FibonacciAir, create_config, generate_fibonacci_trace};
+
+    #[test]
+    fn every_level_proves_a_hundred_steps() {
+        for level in SecurityLevel::ALL {
+            let params = CostParams::at(level);
+            assert_eq!(params.fri_choice(), level.fri_choice());
+            assert_eq!(params.conjectured_security_bits(), level.conjectured_security_bits());
+            assert_eq!(params.check(101, FIBONACCI_LOG_QUOTIENT_DEGREE), Ok(DEFAULT_MIN_HEIGHT));
+        }
+    }
+
+    #[test]
+    fn check_refuses_parameters_fri_would_panic_on() {
+        let lqd = FIBONACCI_LOG_QUOTIENT_DEGREE;
+        let no_blowup = CostParams { log_blowup: 0, ..CostParams::test() };
+        assert_eq!(no_blowup.check(100, lqd), Err(ParamsError::BlowupTooSmall { log_blowup: 0, min: lqd }));
+        let no_queries = CostParams { num_queries: 0, ..CostParams::test() };
+        assert_eq!(no_queries.check(100, lqd), Err(ParamsError::NoQueries));
+        let odd_height = CostParams { min_height: 100, ..CostParams::test() };
+        assert_eq!(odd_height.check(100, lqd), Err(ParamsError::MinHeightNotPowerOfTwo(100)));
+        let huge = CostParams { log_blowup: 16, ..CostParams::test() };
+        assert!(matches!(huge.check(1 << 12, lqd), Err(ParamsError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn the_largest_blowup_that_fits_proves_and_one_more_is_refused() {
+        let lqd = FIBONACCI_LOG_QUOTIENT_DEGREE;
+        // 2^12 rows: a log blowup of 15 reaches BabyBear's 2^27 subgroup exactly
+        let fits = CostParams { log_blowup: Val::TWO_ADICITY - 12, ..CostParams::test() };
+        assert_eq!(fits.check(1 << 12, lqd), Ok(1 << 12));
+        let past = CostParams { log_blowup: Val::TWO_ADICITY - 11, ..CostParams::test() };
+        let too_large = ParamsError::TooLarge { log_lde_height: Val::TWO_ADICITY + 1, max: Val::TWO_ADICITY };
+        assert_eq!(past.check(1 << 12, lqd), Err(too_large));
+        let huge = CostParams { log_blowup: usize::MAX, ..CostParams::test() };
+        assert!(huge.check(1 << 12, lqd).is_err());
+    }
+
+    #[test]
+    fn a_wider_blowup_verifies_with_longer_paths() {
+        let trace = generate_fibonacci_trace::<Val>(100).unwrap();
+        let sizes = [1, 3].map(|log_blowup| {
+            let params = CostParams { log_blowup, ..CostParams::test() };
+            let config = create_config(DEFAULT_CONFIG_SEED, &params);
+            let proof = prove(&config, &FibonacciAir, trace.matrix.clone(), &trace.public_values);
+            assert!(verify(&config, &FibonacciAir, &proof, &trace.public_values).is_ok(), "log blowup {}", log_blowup);
+            encoded_size(&proof).unwrap()
+        });
+        assert!(sizes[0] < sizes[1], "{:?}", sizes);
+    }
+
+    #[test]
+    fn a_witness_ground_to_8_bits_satisfies_8_and_fewer_but_not_20() {
+        let trace = generate_fibonacci_trace::<Val>(100).unwrap();
+        let ground = CostParams { pow_bits: 8, ..CostParams::test() };
+        let config = create_config(DEFAULT_CONFIG_SEED, &ground);
+        let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+        for (pow_bits, accepted) in [(8, true), (4, true), (20, false)] {
+            let config = create_config(DEFAULT_CONFIG_SEED, &CostParams { pow_bits, ..ground });
+            let verified = verify(&config, &FibonacciAir, &proof, &trace.public_values);
+            assert_eq!(verified.is_ok(), accepted, "verifier expecting {} bits", pow_bits);
+        }
+        let too_many = CostParams { pow_bits: MAX_POW_BITS + 1, ..ground };
+        let refused = ParamsError::PowBitsTooLarge { pow_bits: MAX_POW_BITS + 1, max: MAX_POW_BITS };
+        assert_eq!(too_many.check(100, FIBONACCI_LOG_QUOTIENT_DEGREE), Err(refused));
+    }
+
+    #[test]
+    fn a_quick_calibration_is_positive_ordered_and_saved_intact() {
+        let calibration = calibrate(true);
+        assert!(calibration.check().is_ok(), "{}", calibration);
+        assert!(calibration.field_mul_ns < calibration.permutation_ns, "{}", calibration);
+
+        let path = std::env::temp_dir().join(format!("fibonacci-calibration-{}.json", std::process::id()));
+        save_calibration(&path, &calibration).unwrap();
+        let loaded = load_calibration(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap(), calibration);
+    }
+
+    #[test]
+    fn the_estimate_follows_the_calibration() {
+        let params = CostParams::test();
+        let measured = calibrate(true);
+        let (dft_ns, permutation_ns) = (2.0 * measured.dft_ns, 2.0 * measured.permutation_ns);
+        let slower = Calibration { dft_ns, permutation_ns, ..measured };
+        let fast = estimate_proving_cost_with(1 << 12, 4, &params, &measured).unwrap();
+        let slow = estimate_proving_cost_with(1 << 12, 4, &params, &slower).unwrap();
+        let ratio = slow.time_range.1.as_secs_f64() / fast.time_range.1.as_secs_f64();
+        assert!((ratio - 2.0).abs() < 1e-3, "ratio {}", ratio);
+        assert_eq!((slow.peak_mem_bytes, slow.proof_bytes), (fast.peak_mem_bytes, fast.proof_bytes));
+        assert!(fast.time_range.0 < fast.time_range.1);
+    }
+
+    #[test]
+    fn impossible_constants_are_refused() {
+        let zero = Calibration { dft_ns: 0.0, ..Calibration::CONSERVATIVE };
+        assert!(matches!(zero.check(), Err(CalibrationError::NotPositive { name: "dft_ns", .. })));
+        let nan = Calibration { field_mul_ns: f64::NAN, ..Calibration::CONSERVATIVE };
+        assert!(matches!(nan.check(), Err(CalibrationError::NotPositive { name: "field_mul_ns", .. })));
+        let unordered = Calibration { packed_mul_ns: 10_000.0, ..Calibration::CONSERVATIVE };
+        assert!(matches!(unordered.check(), Err(CalibrationError::Unordered)));
+        assert!(Calibration::CONSERVATIVE.check().is_ok());
+    }
+}
@@ -0,0 +1,124 @@
+// The narrowest possible AIR: one column counting 0, 1, 2, ... with the last
+// count public. Width 1 is an edge case for everything that assumes a row
+// struct of several fields, so the row here is a transparent newtype over F.
+use core::borrow::Borrow;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing};
+use p3_matrix::Matrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::last_row::bind_physical_last_row;
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, GeneratedTrace, SizingError, generate_rows, trace_height};
+
+const NUM_COUNTER_COLS: usize = 1;
+
+pub const COUNTER_AIR_ID: AirId = AirId::new("counter", 1, 0, 0);
+
+#[derive(Debug, Clone)]
+pub struct CounterAir;
+
+// Same layout as a bare F, so a width-1 row slice borrows as one and
+// generate_rows sees a one-column row
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy)]
+pub struct CounterRow<F>(pub F);
+
+impl<F> Borrow<CounterRow<F>> for [F] {
+    fn borrow(&self) -> &CounterRow<F> {
+        debug_assert_eq!(self.len(), NUM_COUNTER_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<CounterRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        NUM_COUNTER_COLS
+    }
+}
+
+impl AirLint for CounterAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl ProvableComputation for CounterAir {
+    fn air_id(&self) -> AirId {
+        COUNTER_AIR_ID
+    }
+}
+
+impl ColumnNames for CounterAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["x"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &CounterRow<AB::Var> = (*local).borrow();
+        let next: &CounterRow<AB::Var> = (*next).borrow();
+        let count: AB::Expr = builder.public_values()[0].into();
+
+        builder.when_first_row().assert_zero(local.0.clone());
+        builder.when_transition().assert_eq(next.0.clone(), local.0.clone() + AB::Expr::ONE);
+        // Every row counts, so the physical last row is the last step
+        bind_physical_last_row(builder, local.0.clone().into(), count);
+    }
+}
+
+// Counts on every row of the padded height for `rows` (there is no padding to
+// exempt), so the public count is height - 1. Heights of 4096 and up go
+// through the parallel path of generate_rows.
+pub fn generate_counter_trace<F: Field>(rows: usize) -> Result<GeneratedTrace<(), F>, SizingError> {
+    let height = trace_height(rows, 256)?;
+    let matrix = generate_rows(height, |range| range.map(|i| CounterRow(F::from_usize(i))))?;
+    Ok(GeneratedTrace {
+        matrix,
+        real_rows: height,
+        public_values: vec![F::from_usize(height - 1)],
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Val;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::lint::lint_air;
+    use crate::test_config;
+    use crate::validate::validate_trace;
+
+    #[test]
+    fn a_width_one_trace_proves_and_lints_clean() {
+        let trace = generate_counter_trace::<Val>(100).unwrap();
+        assert_eq!((trace.matrix.width(), trace.matrix.height()), (1, 256));
+        assert_eq!(trace.public_values, [Val::from_u8(255)]);
+        assert!(lint_air(&CounterAir).is_clean());
+        let proof = prove_identified(&test_config(), &CounterAir, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&test_config(), &CounterAir, &proof, &trace.public_values), Ok(None));
+    }
+
+    #[test]
+    fn the_parallel_path_counts_every_row() {
+        let trace = generate_counter_trace::<Val>(5000).unwrap();
+        assert_eq!(trace.matrix.height(), 8192);
+        assert!(trace.matrix.values.iter().enumerate().all(|(i, &x)| x == Val::from_usize(i)));
+        assert_eq!(validate_trace(&CounterAir, &trace.matrix, &trace.public_values), Ok(()));
+    }
+
+    #[test]
+    fn a_wrong_count_is_refused() {
+        let trace = generate_counter_trace::<Val>(100).unwrap();
+        assert!(validate_trace(&CounterAir, &trace.matrix, &[Val::from_u8(254)]).is_err());
+    }
+}
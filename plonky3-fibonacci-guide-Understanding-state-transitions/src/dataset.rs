@@ -0,0 +1,426 @@
+// A claim about a dataset: "the amount column of the dataset committed to as C
+// has N entries summing to S". The AIR keeps a running sum and count over the
+// amounts and binds them to S and N, the public values.
+//
+// C is the proof's own trace commitment: the Merkle root, under the proving
+// config, of the trace the generator builds from the column. The verifier
+// checks the proof's trace commitment is C before running the STARK verifier,
+// so the trace the constraints hold on is the one C names. The trace is a
+// function of the amounts alone, so an auditor holding the CSV recomputes C
+// with commit_dataset, and anyone holding C (and not the data) learns S and N
+// for whatever dataset the data owner published C for.
+//
+// Every amount and every running sum is range-checked to AMOUNT_BITS bits, so
+// no step of the running sum wraps mod p: S is the integer total of N
+// non-negative amounts.
+use core::borrow::Borrow;
+use core::fmt;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_commit::Pcs as CommitmentScheme;
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::StarkGenericConfig;
+
+use crate::air_id::{AirId, AirIdError, IdentifiedProof, ProvableComputation, prove_identified, verify_identified};
+use crate::canonical::{NonCanonicalValue, parse_canonical};
+use crate::journal::{Digest, digest_hex, parse_digest};
+use crate::last_row::{bind_physical_last_row, constrain_real_prefix};
+use crate::lint::AirLint;
+use crate::proof_file::load_described_proof_from_reader;
+use crate::provenance::ProvenanceMismatch;
+use crate::report::panic_message;
+use crate::shape::check_proof_shape;
+use crate::trace::{ColumnNames, GeneratedTrace, SizingError, trace_height, trace_len};
+use crate::{Challenge, Challenger, ConfigDescriptor, MyConfig, Pcs, Val, VerifyError, create_config_from};
+
+// Bits of each range-checked amount and running sum. Two values below
+// 2^AMOUNT_BITS add up to less than p, so no transition wraps.
+pub const AMOUNT_BITS: usize = 29;
+
+// Totals from here up are refused
+pub const MAX_TOTAL: u64 = 1 << AMOUNT_BITS;
+
+pub const NUM_DATASET_COLS: usize = 4 + 2 * AMOUNT_BITS;
+
+// Public values: [S, N]
+pub const NUM_DATASET_PUBLIC_VALUES: usize = 2;
+
+// The largest constraint has degree 3, a transition selector on the real
+// prefix check
+pub const DATASET_LOG_QUOTIENT_DEGREE: usize = 1;
+
+pub const DATASET_AIR_ID: AirId = AirId::new("dataset-sum", 2, 0, 0);
+
+// Twelve transactions with an amount column in cents, totalling 32164
+pub const SAMPLE_DATASET: &str = include_str!("../fixtures/transactions.csv");
+
+#[derive(Debug, Clone)]
+pub struct DatasetAir;
+
+// One entry of the column per real row. Padding rows hold amount 0 and carry
+// sum and count down unchanged, so the physical last row holds both totals.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct DatasetRow<F> {
+    pub is_real: F,
+    pub amount: F,
+    // Amounts up to and including this row
+    pub sum: F,
+    // Real rows up to and including this row
+    pub count: F,
+    // Little-endian bits of amount and of sum
+    pub amount_bits: [F; AMOUNT_BITS],
+    pub sum_bits: [F; AMOUNT_BITS],
+}
+
+impl<F> Borrow<DatasetRow<F>> for [F] {
+    fn borrow(&self) -> &DatasetRow<F> {
+        debug_assert_eq!(self.len(), NUM_DATASET_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<DatasetRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for DatasetAir {
+    fn width(&self) -> usize {
+        NUM_DATASET_COLS
+    }
+}
+
+impl AirLint for DatasetAir {
+    fn num_public_values(&self) -> usize {
+        NUM_DATASET_PUBLIC_VALUES
+    }
+}
+
+impl ProvableComputation for DatasetAir {
+    fn air_id(&self) -> AirId {
+        DATASET_AIR_ID
+    }
+}
+
+impl ColumnNames for DatasetAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["is_real", "amount", "sum", "count"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for DatasetAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &DatasetRow<AB::Var> = (*local).borrow();
+        let next: &DatasetRow<AB::Var> = (*next).borrow();
+        let pis = builder.public_values();
+        let (sum, count): (AB::Expr, AB::Expr) = (pis[0].into(), pis[1].into());
+
+        // The dataset is a prefix of at least one real row, and padding adds nothing
+        constrain_real_prefix(builder, local.is_real.clone().into(), next.is_real.clone().into());
+        builder.assert_zero((AB::Expr::ONE - local.is_real.clone()) * local.amount.clone());
+
+        // Both values are what their bits say, so neither is p minus something
+        for (value, bits) in [(&local.amount, &local.amount_bits), (&local.sum, &local.sum_bits)] {
+            let mut recomposed = AB::Expr::ZERO;
+            for (k, bit) in bits.iter().enumerate() {
+                builder.assert_bool(bit.clone());
+                recomposed += AB::Expr::from_u64(1 << k) * bit.clone();
+            }
+            builder.assert_eq(value.clone(), recomposed);
+        }
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local.sum.clone(), local.amount.clone());
+        when_first_row.assert_eq(local.count.clone(), local.is_real.clone());
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(next.sum.clone(), local.sum.clone() + next.amount.clone());
+        when_transition.assert_eq(next.count.clone(), local.count.clone() + next.is_real.clone());
+
+        // Padding carries both totals down, so the physical last row holds them
+        bind_physical_last_row(builder, local.sum.clone().into(), sum);
+        bind_physical_last_row(builder, local.count.clone().into(), count);
+    }
+}
+
+#[derive(Debug)]
+pub enum DatasetError {
+    Io(io::Error),
+    // The header has no column by this name
+    NoSuchColumn(String),
+    // A line with a different number of fields than the header
+    Malformed { line: usize },
+    // A field in the amount column that is not a non-negative integer
+    NotAnAmount { line: usize, text: String },
+    // An amount of p or more, which no field element can stand for
+    NonCanonical { line: usize, error: NonCanonicalValue },
+    // The running total reaches MAX_TOTAL at this entry (1-based), past the range check
+    SumOverflow { entry: usize },
+    Sizing(SizingError),
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not read dataset: {}", e),
+            Self::NoSuchColumn(name) => write!(f, "no column named {:?}", name),
+            Self::Malformed { line } => write!(f, "line {} does not have one field per column", line),
+            Self::NotAnAmount { line, text } => write!(f, "line {}: {:?} is not a whole amount", line, text),
+            Self::NonCanonical { line, error } => write!(f, "line {}: amount {}", line, error),
+            Self::SumOverflow { entry } => {
+                let bits = AMOUNT_BITS;
+                write!(f, "the total reaches {} at entry {}, past the {}-bit range check", MAX_TOTAL, entry, bits)
+            }
+            Self::Sizing(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DatasetError {}
+
+impl From<io::Error> for DatasetError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<SizingError> for DatasetError {
+    fn from(e: SizingError) -> Self {
+        Self::Sizing(e)
+    }
+}
+
+// The `column` field of every line after the header of a comma-separated
+// `text`. Fields are trimmed; quoting is not supported. Amounts are whole
+// numbers (cents, not dollars) whose total stays below MAX_TOTAL, the bound
+// the AIR range-checks.
+pub fn parse_dataset(text: &str, column: &str) -> Result<Vec<u64>, DatasetError> {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Err(DatasetError::NoSuchColumn(column.to_string()));
+    };
+    let header: Vec<&str> = header.split(',').map(str::trim).collect();
+    let index = header
+        .iter()
+        .position(|&name| name == column)
+        .ok_or_else(|| DatasetError::NoSuchColumn(column.to_string()))?;
+
+    let mut amounts = Vec::new();
+    for (i, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != header.len() {
+            return Err(DatasetError::Malformed { line: i + 1 });
+        }
+        let amount: u64 = fields[index]
+            .parse()
+            .map_err(|_| DatasetError::NotAnAmount { line: i + 1, text: fields[index].to_string() })?;
+        parse_canonical(amount).map_err(|error| DatasetError::NonCanonical { line: i + 1, error })?;
+        amounts.push(amount);
+    }
+    field_total(&amounts)?;
+    Ok(amounts)
+}
+
+// The integer total of `amounts`, which must stay below MAX_TOTAL
+fn field_total(amounts: &[u64]) -> Result<u64, DatasetError> {
+    let mut total = 0u64;
+    for (i, &amount) in amounts.iter().enumerate() {
+        total = total.saturating_add(amount);
+        if total >= MAX_TOTAL {
+            return Err(DatasetError::SumOverflow { entry: i + 1 });
+        }
+    }
+    Ok(total)
+}
+
+pub fn load_dataset(path: &Path, column: &str) -> Result<Vec<u64>, DatasetError> {
+    parse_dataset(&std::fs::read_to_string(path)?, column)
+}
+
+// C for `amounts` under `config`: the trace commitment prove_dataset's proof
+// carries, recomputed without proving. A dataset and the same one with zero
+// amounts appended have different counts, so they commit differently.
+pub fn commit_dataset(config: &MyConfig, amounts: &[u64]) -> Result<Digest, DatasetError> {
+    let trace = generate_dataset_trace(amounts)?;
+    // As the prover commits the main trace
+    let pcs = config.pcs();
+    let height = trace.matrix.height();
+    let domain = <Pcs as CommitmentScheme<Challenge, Challenger>>::natural_domain_for_degree(pcs, height);
+    let (commitment, _) = <Pcs as CommitmentScheme<Challenge, Challenger>>::commit(pcs, vec![(domain, trace.matrix)]);
+    Ok(commitment.into())
+}
+
+// What a dataset proof claims: S and N are its public values, C its trace
+// commitment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatasetClaim {
+    pub commitment: Digest,
+    pub sum: u64,
+    pub count: usize,
+}
+
+impl DatasetClaim {
+    pub fn public_values(&self) -> Vec<Val> {
+        vec![Val::from_u64(self.sum), Val::from_usize(self.count)]
+    }
+}
+
+impl fmt::Display for DatasetClaim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} entries summing to {}, commitment {}", self.count, self.sum, format_commitment(&self.commitment))
+    }
+}
+
+pub fn format_commitment(commitment: &Digest) -> String {
+    digest_hex(commitment)
+}
+
+// The 64 hex digits format_commitment writes, or None
+pub fn parse_commitment(hex: &str) -> Option<Digest> {
+    parse_digest(hex)
+}
+
+// Trace of the column padded to max(256, next power of two), with the public
+// values [S, N]; meta is (S, N). An empty column has no first real row and is
+// refused.
+pub fn generate_dataset_trace(amounts: &[u64]) -> Result<GeneratedTrace<(u64, usize)>, DatasetError> {
+    if amounts.is_empty() {
+        return Err(SizingError::NoRows.into());
+    }
+    let totals = (field_total(amounts)?, amounts.len());
+    let height = trace_height(amounts.len(), 256)?;
+    let len = trace_len(height, NUM_DATASET_COLS)?;
+    let mut trace = RowMajorMatrix::new(Val::zero_vec(len), NUM_DATASET_COLS);
+
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<DatasetRow<Val>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), height);
+
+    // field_total bounds every running sum, and so every amount, by MAX_TOTAL
+    let bits = |value: u64| core::array::from_fn(|k| Val::from_bool((value >> k) & 1 == 1));
+    let (mut sum, mut count) = (0u64, 0usize);
+    for (i, row) in rows.iter_mut().enumerate() {
+        let amount = amounts.get(i).copied().unwrap_or(0);
+        sum += amount;
+        count += usize::from(i < amounts.len());
+        *row = DatasetRow {
+            is_real: Val::from_bool(i < amounts.len()),
+            amount: Val::from_u64(amount),
+            sum: Val::from_u64(sum),
+            count: Val::from_usize(count),
+            amount_bits: bits(amount),
+            sum_bits: bits(sum),
+        };
+    }
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: amounts.len(),
+        public_values: vec![Val::from_u64(totals.0), Val::from_usize(totals.1)],
+        meta: totals,
+    })
+}
+
+// The claim, C included, and its proof
+pub fn prove_dataset(config: &MyConfig, amounts: &[u64]) -> Result<(DatasetClaim, IdentifiedProof), DatasetError> {
+    let trace = generate_dataset_trace(amounts)?;
+    let proof = prove_identified(config, &DatasetAir, trace.matrix, &trace.public_values);
+    let (sum, count) = trace.meta;
+    let claim = DatasetClaim { commitment: proof.proof.commitments.trace.clone().into(), sum, count };
+    Ok((claim, proof))
+}
+
+// A proof of another trace, however valid, says nothing about the dataset C
+// names, so it is refused before the STARK verifier runs. Returns the
+// provenance mismatch as verify_identified does.
+pub fn verify_dataset(
+    config: &MyConfig,
+    claim: &DatasetClaim,
+    proof: &IdentifiedProof,
+) -> Result<Option<ProvenanceMismatch>, AirIdError> {
+    let committed: Digest = proof.proof.commitments.trace.clone().into();
+    if committed != claim.commitment {
+        return Err(AirIdError::Verification(format!(
+            "the proof commits to dataset {}, not {}",
+            format_commitment(&committed),
+            format_commitment(&claim.commitment)
+        )));
+    }
+    verify_identified(config, &DatasetAir, proof, &claim.public_values())
+}
+
+// verify_dataset for untrusted bytes in proof_file's encoding, under the
+// config their own descriptor describes; see verify_described
+pub fn verify_dataset_bytes(bytes: &[u8], claim: &DatasetClaim) -> Result<ConfigDescriptor, VerifyError> {
+    let (descriptor, proof): (ConfigDescriptor, IdentifiedProof) = load_described_proof_from_reader(bytes)?;
+    descriptor.check(DATASET_LOG_QUOTIENT_DEGREE)?;
+    check_proof_shape(&proof.proof, NUM_DATASET_COLS, DATASET_LOG_QUOTIENT_DEGREE, &descriptor.params())?;
+    let verify = || verify_dataset(&create_config_from(&descriptor), claim, &proof);
+    panic::catch_unwind(AssertUnwindSafe(verify))
+        .map_err(|payload| VerifyError::Panicked(panic_message(payload.as_ref())))?
+        .map_err(VerifyError::Proof)?;
+    Ok(descriptor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_config;
+    use crate::validate::validate_trace;
+
+    fn sample() -> Vec<u64> {
+        parse_dataset(SAMPLE_DATASET, "amount").unwrap()
+    }
+
+    #[test]
+    fn the_honest_claim_verifies_and_commits_as_the_csv_does() {
+        let (claim, proof) = prove_dataset(&test_config(), &sample()).unwrap();
+        assert_eq!((claim.sum, claim.count), (32164, 12));
+        assert_eq!(verify_dataset(&test_config(), &claim, &proof), Ok(None));
+        assert_eq!(commit_dataset(&test_config(), &sample()).unwrap(), claim.commitment);
+    }
+
+    #[test]
+    fn another_dataset_commits_differently_and_its_c_is_refused() {
+        let (claim, proof) = prove_dataset(&test_config(), &sample()).unwrap();
+        let edited = parse_dataset(&SAMPLE_DATASET.replacen(",735", ",835", 1), "amount").unwrap();
+        let appended = [sample(), vec![0]].concat();
+        for other in [edited, appended] {
+            let commitment = commit_dataset(&test_config(), &other).unwrap();
+            assert_ne!(commitment, claim.commitment);
+            let misnamed = DatasetClaim { commitment, ..claim };
+            assert!(verify_dataset(&test_config(), &misnamed, &proof).is_err());
+        }
+        let overstated = DatasetClaim { sum: claim.sum + 1, ..claim };
+        assert!(verify_dataset(&test_config(), &overstated, &proof).is_err());
+    }
+
+    #[test]
+    fn a_wrapping_amount_fails_the_range_check() {
+        let trace = generate_dataset_trace(&[5, 7]).unwrap();
+        let mut wrapped = trace.matrix.clone();
+        let width = wrapped.width();
+        // Row 0 pays -1 and row 1 one more, keeping S; only the bits disagree
+        wrapped.values[1] = Val::NEG_ONE;
+        wrapped.values[2] = Val::NEG_ONE;
+        wrapped.values[width + 1] = Val::from_u8(13);
+        assert!(validate_trace(&DatasetAir, &wrapped, &trace.public_values).is_err());
+        assert_eq!(validate_trace(&DatasetAir, &trace.matrix, &trace.public_values), Ok(()));
+    }
+
+    #[test]
+    fn totals_past_the_range_check_are_refused() {
+        assert!(matches!(generate_dataset_trace(&[MAX_TOTAL - 1, 1]), Err(DatasetError::SumOverflow { entry: 2 })));
+        assert!(generate_dataset_trace(&[MAX_TOTAL - 1]).is_ok());
+        assert!(matches!(parse_dataset("amount\n", "amount").map(|a| generate_dataset_trace(&a)), Ok(Err(_))));
+    }
+}
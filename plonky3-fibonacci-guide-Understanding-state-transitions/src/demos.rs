@@ -0,0 +1,901 @@
+// The binary's stand-alone demos, one per flag: each proves or checks one
+// thing and prints what happened, and none needs the main proof
+use core::fmt::Display;
+use core::num::NonZeroUsize;
+use core::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Instant;
+
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_uni_stark::{prove, verify};
+use stark_config::FriChoice;
+
+use crate::air_id::{IdentifiedProof, prove_identified, verify_identified};
+use crate::big_fibonacci::{
+    BigFibonacciAir, LIMB_BITS, MAX_BIG_STEPS, NUM_LIMBS, generate_big_fibonacci_trace, limbs_to_biguint,
+};
+use crate::blake3::compare_commit_times;
+use crate::canonical::{Reduction, parse_with};
+use crate::challenger::compare_challengers;
+use crate::claims::{FibonacciClaims, parse_public_values, parse_public_values_with};
+use crate::coefficients::{CoefficientAir, generate_reachable_trace, prove_reachable, recurrence_final};
+use crate::cost::{CostParams, SecurityLevel, estimate_proving_cost, grinding_time};
+use crate::dataset::{
+    DatasetAir, DatasetClaim, SAMPLE_DATASET, commit_dataset, generate_dataset_trace, parse_dataset, prove_dataset,
+    verify_dataset,
+};
+use crate::diff::{diff_trace_files, diff_traces};
+use crate::domain::{prove_fibonacci_in, verify_fibonacci_in};
+use crate::dual::{DualProof, ProofArtifact, prove_dual, verify_dual};
+use crate::exact::{check_trace_matches_reference, fibonacci_value, fibonacci_value_exact};
+use crate::indexed::{IndexedFibonacciAir, generate_indexed_fibonacci_trace};
+use crate::last_row::{PaddingStrategy, generate_last_row_trace};
+use crate::linear_recurrence::{RecurrenceAir, generate_linear_recurrence_trace};
+use crate::lucas::{LucasAir, generate_lucas_trace};
+use crate::multi::{MultiFibonacciAir, generate_multi_fibonacci_trace, sample_starts};
+use crate::parity::generate_parity_trace;
+use crate::proof_file::{ConfigFingerprint, encode_proof, encoded_size, load_proof_from_reader};
+use crate::provenance::{PROVENANCE, check_provenance};
+use crate::segments::{prove_segment, prove_segments, verify_chain};
+use crate::sequence::check_sequence;
+use crate::trace::{generate_rows, generate_rows_serial, trace_height};
+use crate::validate::{RowKind, evaluate_constraints, validate_trace};
+use crate::zk::{prove_fibonacci_with, verify_fibonacci_proof};
+use crate::{
+    ConfigDescriptor, DEFAULT_CONFIG_SEED, FIBONACCI_LOG_QUOTIENT_DEGREE, FibonacciAir, NUM_FIBONACCI_COLS, Val,
+    create_config, create_config_with_seed, create_default_config, generate_fibonacci_trace, generate_recurrence_trace,
+    prove_fibonacci, verify_bytes, verify_fibonacci,
+};
+
+// Times serial vs parallel generation of a synthetic 2^22 x 16 trace
+pub fn run_trace_benchmark() {
+    const HEIGHT: usize = 1 << 22;
+    const WIDTH: usize = 16;
+    let row = |range: Range<usize>| {
+        range.map(|i| core::array::from_fn::<Val, WIDTH, _>(|j| Val::from_usize(i * WIDTH + j)))
+    };
+
+    println!(" Trace generation, {} x {}:", HEIGHT, WIDTH);
+    let start = Instant::now();
+    let serial = generate_rows_serial(HEIGHT, row).expect("2^22 x 16 fits in memory");
+    println!("   zero fill + serial writes: {:?}", start.elapsed());
+    let start = Instant::now();
+    let parallel = generate_rows(HEIGHT, row).expect("2^22 x 16 fits in memory");
+    println!("   uninitialized + parallel:  {:?}", start.elapsed());
+    println!("   serial vs parallel: {}", diff_traces(&serial, &parallel, 5));
+
+    println!(" Merkle commitment, {} x {}:", HEIGHT, WIDTH);
+    for (hash, time) in compare_commit_times(&serial) {
+        println!("   {}: {:?}", hash, time);
+    }
+}
+
+// --tribonacci: RecurrenceAir at orders 3 and 4 from [0, ..., 0, 1]. T(20)
+// is checked against 35890, worked out by hand. Order 1 does not compile.
+pub fn run_tribonacci_demo() {
+    fn prove_order<const K: usize>(name: &str, num_steps: usize, expected: Option<u64>) {
+        let mut start = [Val::ZERO; K];
+        start[K - 1] = Val::ONE;
+        let trace = match generate_linear_recurrence_trace(start, num_steps) {
+            Ok(trace) => trace,
+            Err(e) => {
+                println!("   {}: {}", name, e);
+                return;
+            }
+        };
+        let result = trace.public_values[K];
+        if let Some(expected) = expected {
+            let verdict = if result == Val::from_u64(expected) { "matches" } else { "does not match" };
+            println!("   {}({}) = {}, {} the hand-computed {}", name, num_steps, result, verdict, expected);
+        }
+        let config = create_default_config(SecurityLevel::Test);
+        let air = RecurrenceAir::<K>;
+        let proof = prove_identified(&config, &air, trace.matrix, &trace.public_values);
+        match verify_identified(&config, &air, &proof, &trace.public_values) {
+            Ok(_) => println!("   {}({}) = {}: proved and verified", name, num_steps, result),
+            Err(e) => println!("   {}({}): verification failed: {}", name, num_steps, e),
+        }
+    }
+
+    println!(" Order-K recurrences from [0, ..., 0, 1]:");
+    prove_order::<3>("T", 20, Some(35890));
+    prove_order::<4>("Q", 20, None);
+}
+
+// --lucas: LucasAir for the Pell numbers (P = 2, Q = -1), where P(10) = 2378,
+// and for P = 3, Q = 2, where U(20) = 2^20 - 1. The Pell proof is then offered
+// under P = 3, which the verifier must reject.
+pub fn run_lucas_demo() {
+    let config = create_default_config(SecurityLevel::Test);
+    let cases = [
+        ("Pell", Val::TWO, Val::NEG_ONE, 10, 2378),
+        ("P = 3, Q = 2", Val::from_u64(3), Val::TWO, 20, (1 << 20) - 1),
+    ];
+    println!(" Lucas sequences U(n) = P * U(n-1) - Q * U(n-2) from (0, 1):");
+    let mut pell = None;
+    for (name, p, q, num_steps, expected) in cases {
+        let trace = match generate_lucas_trace(p, q, Val::ZERO, Val::ONE, num_steps) {
+            Ok(trace) => trace,
+            Err(e) => {
+                println!("   {}: {}", name, e);
+                return;
+            }
+        };
+        let result = trace.public_values[4];
+        let verdict = if result == Val::from_u64(expected) { "matches" } else { "does not match" };
+        println!("   {}: U({}) = {}, {} {}", name, num_steps, result, verdict, expected);
+        let proof = prove_identified(&config, &LucasAir, trace.matrix, &trace.public_values);
+        match verify_identified(&config, &LucasAir, &proof, &trace.public_values) {
+            Ok(_) => println!("   {}: proved and verified", name),
+            Err(e) => println!("   {}: verification failed: {}", name, e),
+        }
+        pell.get_or_insert((proof, trace.public_values));
+    }
+
+    let Some((proof, mut public_values)) = pell else {
+        return;
+    };
+    public_values[0] = Val::from_u64(3);
+    match verify_identified(&config, &LucasAir, &proof, &public_values) {
+        Ok(_) => println!("   Pell proof claimed with P = 3: unexpectedly accepted"),
+        Err(e) => println!("   Pell proof claimed with P = 3: rejected ({})", e),
+    }
+}
+
+// --multi: 8 sequences of 1024 steps in one MultiFibonacciAir proof. Each
+// final value read off the public values is checked against the same start
+// run through generate_recurrence_trace on its own.
+pub fn run_multi_demo() {
+    let (num_sequences, num_steps) = (8, 1024);
+    let air = MultiFibonacciAir { num_sequences };
+    let starts = sample_starts::<Val>(num_sequences);
+    let trace = match generate_multi_fibonacci_trace(&starts, num_steps) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    println!(" {} sequences of {} steps, one {}-column trace:", num_sequences, num_steps, 2 * num_sequences);
+    let config = create_default_config(SecurityLevel::Test);
+    let start = Instant::now();
+    let proof = prove_identified(&config, &air, trace.matrix, &trace.public_values);
+    println!("   Proved in {:?}", start.elapsed());
+    if let Err(e) = verify_identified(&config, &air, &proof, &trace.public_values) {
+        println!("   Verification failed: {}", e);
+        return;
+    }
+    for (claim, (a0, b0)) in trace.public_values.chunks_exact(3).zip(starts) {
+        let single = generate_recurrence_trace(a0, b0, num_steps).map(|trace| trace.meta.final_value);
+        let verdict = match single {
+            Ok(expected) if expected == claim[2] => "matches".to_string(),
+            Ok(expected) => format!("does not match {}", expected),
+            Err(e) => e.to_string(),
+        };
+        let (final_value, steps) = (claim[2], num_steps);
+        println!("   From ({}, {}): {} after {} steps, {} the one-sequence trace", a0, b0, final_value, steps, verdict);
+    }
+}
+
+// --big: F(100) proved over the integers in BigFibonacciAir's 16-bit limbs.
+// The limbs in the public values are reassembled and compared against the
+// BigUint reference, and against F(100) = 354224848179261915075 as published.
+pub fn run_big_fibonacci_demo() {
+    let num_steps = 100;
+    let trace = match generate_big_fibonacci_trace::<Val>(num_steps) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    println!(" F({}) in {} limbs of {} bits:", num_steps, NUM_LIMBS, LIMB_BITS);
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove_identified(&config, &BigFibonacciAir, trace.matrix, &trace.public_values);
+    match verify_identified(&config, &BigFibonacciAir, &proof, &trace.public_values) {
+        Ok(_) => println!("   Proof verified"),
+        Err(e) => {
+            println!("   Verification failed: {}", e);
+            return;
+        }
+    }
+    let proven = limbs_to_biguint(&trace.public_values[..NUM_LIMBS]);
+    println!("   Reassembled from the public limbs: {}", proven);
+    println!("   Matches the BigUint reference: {}", proven == fibonacci_value_exact(num_steps));
+    println!("   Matches 354224848179261915075: {}", proven.to_string() == "354224848179261915075");
+    match generate_big_fibonacci_trace::<Val>(MAX_BIG_STEPS + 1) {
+        Ok(_) => println!("   {} steps: unexpectedly generated", MAX_BIG_STEPS + 1),
+        Err(e) => println!("   {} steps: {}", MAX_BIG_STEPS + 1, e),
+    }
+}
+
+// --reference-check: a 1000-step trace against the BigUint reference reduced
+// mod p, row by row, and fibonacci_value refusing values past F(1000)
+pub fn run_reference_check() {
+    let num_steps = 1000;
+    let trace = match generate_fibonacci_trace::<Val>(num_steps) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    println!(" Reference check, {} steps in {} rows:", num_steps, trace.matrix.height());
+    match check_trace_matches_reference(&trace.matrix, num_steps) {
+        Ok(()) => println!("   Every real row matches the reference"),
+        Err(e) => println!("   {}", e),
+    }
+    for n in [num_steps, num_steps + 1, trace.matrix.height()] {
+        match fibonacci_value(&trace.matrix, n) {
+            Ok(value) => println!("   F({}) = {} (mod p)", n, value),
+            Err(e) => println!("   F({}): {}", n, e),
+        }
+    }
+}
+
+// --index-check: IndexedFibonacciAir proves and verifies its honest trace, and
+// a trace with one index repeated fails the constraint check
+pub fn run_index_check() {
+    let trace = match generate_indexed_fibonacci_trace::<Val>(100) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    println!(" Row index column, {} rows:", trace.matrix.height());
+    let mut duplicated = trace.matrix.clone();
+    let width = duplicated.width();
+    duplicated.values[6 * width + 4] = duplicated.values[5 * width + 4];
+    match validate_trace(&IndexedFibonacciAir, &duplicated, &trace.public_values) {
+        Ok(()) => println!("   Row 6 with row 5's index: all constraints hold"),
+        Err(violation) => println!("   Row 6 with row 5's index: {}", violation),
+    }
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove_identified(&config, &IndexedFibonacciAir, trace.matrix, &trace.public_values);
+    match verify_identified(&config, &IndexedFibonacciAir, &proof, &trace.public_values) {
+        Ok(_) => println!("   Honest trace: proof verified"),
+        Err(e) => println!("   Honest trace: verification failed: {}", e),
+    }
+}
+
+// --segments: 1000 steps as segments of 300, the last one 100; the honest
+// chain verifies, a middle segment one step short breaks the link into the
+// next, and a wrong end pair is caught
+pub fn run_segments_demo() {
+    let (num_steps, segment_len) = (1000, NonZeroUsize::new(300).expect("300 is not zero"));
+    let end = match (generate_fibonacci_trace::<Val>(num_steps), generate_fibonacci_trace::<Val>(num_steps + 1)) {
+        (Ok(a), Ok(b)) => (a.meta.final_value, b.meta.final_value),
+        (Err(e), _) | (_, Err(e)) => {
+            println!(" Could not generate the reference traces: {}", e);
+            return;
+        }
+    };
+    let mut segments = match prove_segments(num_steps, segment_len) {
+        Ok(segments) => segments,
+        Err(e) => {
+            println!(" Could not generate the segments: {}", e);
+            return;
+        }
+    };
+    println!(" {} steps in segments of {}:", num_steps, segment_len);
+    for (index, segment) in segments.iter().enumerate() {
+        match (segment.num_steps(), segment.start(), segment.end()) {
+            (Some(steps), Some(from), Some(to)) => {
+                println!("   Segment {}: {} steps, {:?} -> {:?}", index, steps, from, to)
+            }
+            _ => println!("   Segment {}: malformed public values", index),
+        }
+    }
+    let start = (Val::ZERO, Val::ONE);
+    match verify_chain(&segments, start, end) {
+        Ok(steps) => println!("   Honest chain: verified, {} steps", steps),
+        Err(e) => println!("   Honest chain: rejected: {}", e),
+    }
+    let wrong_end = (end.0, end.1 + Val::ONE);
+    match verify_chain(&segments, start, wrong_end) {
+        Ok(steps) => println!("   Wrong end pair: verified, {} steps", steps),
+        Err(e) => println!("   Wrong end pair: rejected: {}", e),
+    }
+    // Segment 0 ends where segment 1 starts
+    let Some(resume) = segments[0].end() else {
+        println!("   Segment 0: malformed public values");
+        return;
+    };
+    match prove_segment(resume, segment_len.get() - 1) {
+        Ok(short) => segments[1] = short,
+        Err(e) => {
+            println!(" Could not generate the short segment: {}", e);
+            return;
+        }
+    }
+    match verify_chain(&segments, start, end) {
+        Ok(steps) => println!("   Segment 1 one step short: verified, {} steps", steps),
+        Err(e) => println!("   Segment 1 one step short: rejected: {}", e),
+    }
+}
+
+// The sample dataset proved, then one amount edited: in the CSV it commits to
+// another C, and in the trace the running sum breaks
+pub fn run_dataset_demo() {
+    println!(" Dataset claim, sample transactions:");
+    let amounts = match parse_dataset(SAMPLE_DATASET, "amount") {
+        Ok(amounts) => amounts,
+        Err(e) => {
+            println!("   Sample dataset: {}", e);
+            return;
+        }
+    };
+    let config = create_default_config(SecurityLevel::Test);
+    let (claim, proof) = match prove_dataset(&config, &amounts) {
+        Ok(proved) => proved,
+        Err(e) => {
+            println!("   Could not prove the sample: {}", e);
+            return;
+        }
+    };
+    println!("   Claim: {}", claim);
+    match verify_dataset(&config, &claim, &proof) {
+        Ok(_) => println!("   Honest proof: verified"),
+        Err(e) => println!("   Honest proof: unexpectedly rejected: {}", e),
+    }
+    let overstated = DatasetClaim { sum: claim.sum + 1, ..claim };
+    match verify_dataset(&config, &overstated, &proof) {
+        Ok(_) => println!("   Sum claimed as {}: unexpectedly verified", overstated.sum),
+        Err(_) => println!("   Sum claimed as {}: rejected", overstated.sum),
+    }
+
+    // The CSV with the fourth amount raised by 100 cents
+    let edited = SAMPLE_DATASET.replacen(",735", ",835", 1);
+    match parse_dataset(&edited, "amount").and_then(|edited| commit_dataset(&config, &edited)) {
+        Ok(commitment) if commitment != claim.commitment => {
+            println!("   Amount 735 edited to 835 in the CSV: commitment no longer matches the claim")
+        }
+        Ok(_) => println!("   Amount 735 edited to 835 in the CSV: unexpectedly commits the same"),
+        Err(e) => println!("   Edited dataset: {}", e),
+    }
+
+    // The same edit made in the trace, keeping the claimed sum
+    let trace = match generate_dataset_trace(&amounts) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   Sample trace: {}", e);
+            return;
+        }
+    };
+    let mut edited = trace.matrix.clone();
+    edited.values[3 * edited.width() + 1] += Val::from_u32(100);
+    match validate_trace(&DatasetAir, &edited, &trace.public_values) {
+        Ok(()) => println!("   Amount 735 edited to 835 in the trace: unexpectedly valid"),
+        Err(violation) => println!("   Amount 735 edited to 835 in the trace: {}", violation),
+    }
+}
+
+// Proves one CoefficientAir statement under both configs, then mixes the
+// parts into a pair with a corrupted proof and a pair claiming different values
+pub fn run_dual_demo() {
+    println!(" Dual proof: Poseidon2 duplex and Keccak challenger configs");
+    let (a0, b0) = (Val::ZERO, Val::ONE);
+    let num_rows = 256;
+    let dual_for = |final_value| match generate_reachable_trace(a0, b0, final_value, num_rows) {
+        Ok(trace) => Some(prove_dual(&CoefficientAir, trace.matrix, &trace.public_values)),
+        Err(e) => {
+            println!("   {}", e);
+            None
+        }
+    };
+    let (Some(honest), Some(other)) = (
+        dual_for(recurrence_final(a0, b0, 1, 1, num_rows)),
+        dual_for(recurrence_final(a0, b0, 2, 1, num_rows)),
+    ) else {
+        return;
+    };
+
+    match verify_dual(&CoefficientAir, &honest) {
+        Ok(()) => println!("   Both proofs verified with equal public values"),
+        Err(e) => println!("   Honest pair rejected: {}", e),
+    }
+    match (honest.a.fibonacci_claims(), honest.b.fibonacci_claims()) {
+        (Ok(a), Ok(b)) if a == b => println!("   Both decode to the same claims: {}", a),
+        (a, b) => println!("   Claims diverge across configs: {:?} vs {:?}", a, b),
+    }
+
+    let honest_values = honest.a.public_values.clone();
+    let corrupted = DualProof {
+        a: honest.a,
+        b: ProofArtifact { proof: other.b.proof, public_values: honest_values.clone() },
+    };
+    match verify_dual(&CoefficientAir, &corrupted) {
+        Ok(()) => println!("   Corrupted pair unexpectedly accepted"),
+        Err(e) => println!("   Corrupted pair: {}", e),
+    }
+
+    let mismatched = DualProof {
+        a: other.a,
+        b: ProofArtifact { proof: honest.b.proof, public_values: honest_values },
+    };
+    match verify_dual(&CoefficientAir, &mismatched) {
+        Ok(()) => println!("   Mismatched pair unexpectedly accepted"),
+        Err(e) => println!("   Mismatched pair: {}", e),
+    }
+}
+
+// Drives each library entry point down its error paths. Every bad input must
+// come back as an Err; the binary installs a panic hook around this that
+// counts every panic, including those a verifier catches itself, and the
+// count should stay at zero.
+pub fn run_panic_check() {
+    // An honest proof as verify_bytes receives it, and copies of it altered
+    // the ways a hostile sender might
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    let fingerprint = ConfigFingerprint::of(&descriptor);
+    let (proof, public_values) = prove_fibonacci(8).expect("8 steps fit in a trace");
+    let bytes = encode_proof(&descriptor, &proof).expect("an honest proof encodes");
+    let tampered = |tamper: fn(&mut IdentifiedProof)| -> Vec<u8> {
+        let mut copy = load_proof_from_reader(bytes.as_slice(), fingerprint).expect("an honest proof decodes");
+        tamper(&mut copy);
+        encode_proof(&descriptor, &copy).expect("a decoded proof encodes")
+    };
+    let mut flipped = bytes.clone();
+    // The proof ends the encoding and starts with the trace commitment
+    flipped[bytes.len() - encoded_size(&proof.proof).unwrap_or(0)] ^= 1;
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    let malformed = [
+        ("proof bytes with a bit flipped in the trace commitment", flipped),
+        ("proof bytes with a FRI query dropped", tampered(|p| {
+            p.proof.opening_proof.query_proofs.pop();
+        })),
+        ("proof bytes with a query missing a FRI round", tampered(|p| {
+            p.proof.opening_proof.query_proofs[0].commit_phase_openings.pop();
+        })),
+        ("proof bytes with a query's trace Merkle path cut short", tampered(|p| {
+            p.proof.opening_proof.query_proofs[0].input_proof[0].opening_proof.pop();
+        })),
+        ("proof bytes with an opened trace column missing", tampered(|p| {
+            p.proof.opened_values.trace_local.pop();
+        })),
+        ("proof bytes claiming 2^40 rows", tampered(|p| p.proof.degree_bits = 40)),
+        ("proof bytes with trailing data", trailing),
+    ];
+    println!(" Untouched proof bytes: {:?}", verify_bytes(&bytes, &public_values));
+
+    let mut cases: Vec<(&str, Box<dyn Fn() -> bool>)> = vec![
+        ("fibonacci trace of usize::MAX steps", Box::new(|| {
+            generate_fibonacci_trace::<Val>(usize::MAX).is_err()
+        })),
+        ("trace height of usize::MAX rows", Box::new(|| trace_height(usize::MAX, 256).is_err())),
+        ("last-row trace with no real rows", Box::new(|| {
+            generate_last_row_trace::<Val>(0, PaddingStrategy::Zero).is_err()
+        })),
+        ("parity trace of 100 rows", Box::new(|| generate_parity_trace::<Val>(100).is_err())),
+        ("coefficient trace of 100 rows", Box::new(|| {
+            generate_reachable_trace(Val::ZERO, Val::ONE, Val::ONE, 100).is_err()
+        })),
+        ("unreachable coefficient target", Box::new(|| {
+            prove_reachable(Val::ZERO, Val::ZERO, Val::ONE, 256).is_err()
+        })),
+        ("cost of usize::MAX steps", Box::new(|| {
+            estimate_proving_cost(usize::MAX, NUM_FIBONACCI_COLS, &CostParams::test()).is_err()
+        })),
+        ("missing sequence file", Box::new(|| {
+            let claims = FibonacciClaims::empty(Val::ZERO, Val::ONE);
+            check_sequence(Path::new("/nonexistent/sequence.csv"), &claims).is_err()
+        })),
+    ];
+    for (name, bad) in malformed {
+        let public_values = public_values.clone();
+        cases.push((name, Box::new(move || verify_bytes(&bad, &public_values).is_err())));
+    }
+
+    println!(" Panic check:");
+    for (name, case) in &cases {
+        match panic::catch_unwind(AssertUnwindSafe(case)) {
+            Ok(true) => println!("   {}: error returned", name),
+            Ok(false) => println!("   {}: unexpectedly accepted", name),
+            Err(_) => println!("   {}: panicked", name),
+        }
+    }
+}
+
+// --check-row LOCAL NEXT [first|middle|last] [RESULT]: FibonacciAir's
+// constraints on one window given as two CSV rows, e.g. --check-row 1,2,1,1
+// 2,3,1,2 middle. The public values are (0, 1, RESULT, local.count), RESULT
+// defaulting to local.a so the last-row check only fails on a supplied claim.
+// Values of p or more are rejected unless --allow-reduction is given.
+pub fn run_check_row(args: &[String], reduction: Reduction) -> Result<(), String> {
+    let parse_value = |text: &str| -> Result<Val, String> {
+        let value = text.trim().parse::<u64>().map_err(|_| format!("{:?} is not an integer", text))?;
+        parse_with(value, reduction).map_err(|e| e.to_string())
+    };
+    let parse_row = |row: Option<&String>| -> Result<Vec<Val>, String> {
+        let row = row.ok_or("--check-row takes two rows, e.g. 1,2,1,1 2,3,1,2")?;
+        row.split(',').map(parse_value).collect()
+    };
+    let mut args = args.iter();
+    let (local, next) = (parse_row(args.next())?, parse_row(args.next())?);
+    if local.len() != NUM_FIBONACCI_COLS || next.len() != NUM_FIBONACCI_COLS {
+        return Err(format!("FibonacciAir rows have {} columns", NUM_FIBONACCI_COLS));
+    }
+    let row_kind = match args.next().map(String::as_str) {
+        None | Some("middle") => RowKind::Middle,
+        Some("first") => RowKind::First,
+        Some("last") => RowKind::Last,
+        Some(other) => return Err(format!("Unknown row kind {:?}; expected first, middle or last", other)),
+    };
+    let result = match args.next() {
+        None => local[0],
+        Some(text) => parse_value(text).map_err(|e| format!("RESULT: {}", e))?,
+    };
+
+    println!(" FibonacciAir on {:?} -> {:?} ({:?} row):", local, next, row_kind);
+    let public_values = [Val::ZERO, Val::ONE, result, local[3]];
+    let values = evaluate_constraints(&FibonacciAir, [&local, &next], &public_values, row_kind);
+    for (constraint, value) in values.iter().enumerate() {
+        let verdict = if *value == Val::ZERO { "holds" } else { "violated" };
+        println!("   constraint {}: {} ({})", constraint, value, verdict);
+    }
+    Ok(())
+}
+
+// --canonical-check: p - 1, p and 2^63 through the public-value, dataset,
+// sequence-file and trace-file parsers. p - 1 is accepted everywhere (the
+// sequence file then fails the recurrence); the other two are reported as
+// non-canonical rather than quietly reduced mod p.
+pub fn run_canonical_check() {
+    fn outcome<T, E: Display>(result: Result<T, E>) -> String {
+        match result {
+            Ok(_) => "accepted".to_string(),
+            Err(e) => e.to_string(),
+        }
+    }
+    let scratch = |name: &str| std::env::temp_dir().join(format!("fibonacci-{}-{}.csv", name, std::process::id()));
+    let (sequence_path, trace_path) = (scratch("canonical-sequence"), scratch("canonical-trace"));
+
+    println!(" Canonical check (p = {}):", Val::ORDER_U64);
+    for value in [Val::ORDER_U64 - 1, Val::ORDER_U64, 1 << 63] {
+        println!("   {}:", value);
+        let public_values = format!("0,1,{},8", value);
+        println!("     --public-values: {}", outcome(parse_public_values(&public_values)));
+        let reduced = parse_public_values_with(&public_values, Reduction::Allow);
+        println!("     --public-values --allow-reduction: {}", outcome(reduced));
+        let dataset = format!("amount\n{}\n", value);
+        println!("     dataset amount: {}", outcome(parse_dataset(&dataset, "amount")));
+
+        let empty = FibonacciClaims::empty(Val::ZERO, Val::ONE);
+        let sequence = std::fs::write(&sequence_path, format!("index,value\n0,{}\n", value))
+            .map_err(|e| e.to_string())
+            .and_then(|()| check_sequence(&sequence_path, &empty).map_err(|e| e.to_string()));
+        println!("     sequence file: {}", outcome(sequence));
+        let trace = std::fs::write(&trace_path, format!("{},1,1,0\n", value))
+            .map_err(|e| e.to_string())
+            .and_then(|()| diff_trace_files(&trace_path, &trace_path, 1).map_err(|e| e.to_string()));
+        println!("     trace file: {}", outcome(trace));
+    }
+    let _ = std::fs::remove_file(&sequence_path);
+    let _ = std::fs::remove_file(&trace_path);
+}
+
+// --security-levels: the main proof's statement at every SecurityLevel, with
+// the FRI parameters, proving time and proof size of each
+pub fn run_security_levels(num_steps: usize) {
+    println!(" Security levels, {} steps:", num_steps);
+    let trace = match generate_fibonacci_trace::<Val>(num_steps) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    for level in SecurityLevel::ALL {
+        let params = CostParams::at(level);
+        println!(
+            "   {}: log_blowup {}, {} queries, {} pow bits, ~{} bits conjectured",
+            level,
+            params.log_blowup,
+            params.num_queries,
+            params.pow_bits,
+            params.conjectured_security_bits()
+        );
+        let config = create_config_with_seed(DEFAULT_CONFIG_SEED, level);
+        let start = Instant::now();
+        let proof = prove(&config, &FibonacciAir, trace.matrix.clone(), &trace.public_values);
+        let proving = start.elapsed();
+        let verified = verify(&config, &FibonacciAir, &proof, &trace.public_values);
+        match (verified, encoded_size(&proof)) {
+            (Ok(()), Ok(size)) => println!("     prove {:?}, {} bytes, verified", proving, size),
+            (Err(e), _) => println!("     verification failed: {:?}", e),
+            (_, Err(e)) => println!("     could not size the proof: {}", e),
+        }
+    }
+    let (test, bits100) = (CostParams::test(), CostParams::at(SecurityLevel::Bits100));
+    println!("   100-bit level opens more queries than test: {}", bits100.num_queries > test.num_queries);
+}
+
+// (log_blowup, num_queries) pairs for --compare-params: two query counts per
+// blowup, then a blowup whose LDE no longer fits in BabyBear's 2^27 subgroup
+const COMPARED_PARAMS: [(usize, usize); 7] = [(1, 16), (1, 64), (2, 16), (2, 32), (3, 11), (3, 22), (16, 8)];
+
+// --compare-params: one 2^12-row trace proved under each COMPARED_PARAMS
+// pair, as a table of proving time, verification time and proof size.
+// Parameters CostParams::check refuses get a row with the reason instead.
+pub fn run_compare_params() {
+    // 2^12 - 1 steps and the padding row holding the result fill 2^12 rows
+    let steps = (1 << 12) - 1;
+    let trace = match generate_fibonacci_trace::<Val>(steps) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    println!(" FRI parameter comparison, 2^12-row Fibonacci trace:");
+    println!("   {:>7} {:>7} {:>12} {:>12} {:>12}", "blowup", "queries", "prove", "verify", "proof bytes");
+    for (log_blowup, num_queries) in COMPARED_PARAMS {
+        let params = CostParams { log_blowup, num_queries, ..CostParams::test() };
+        let columns = format!("   {:>7} {:>7}", 1usize << log_blowup, num_queries);
+        if let Err(e) = params.check(trace.matrix.height(), FIBONACCI_LOG_QUOTIENT_DEGREE) {
+            println!("{} rejected: {}", columns, e);
+            continue;
+        }
+        let config = create_config(DEFAULT_CONFIG_SEED, &params);
+        let start = Instant::now();
+        let proof = prove(&config, &FibonacciAir, trace.matrix.clone(), &trace.public_values);
+        let proving = start.elapsed();
+        let start = Instant::now();
+        let verified = verify(&config, &FibonacciAir, &proof, &trace.public_values);
+        let verifying = start.elapsed();
+        match (verified, encoded_size(&proof)) {
+            (Ok(()), Ok(size)) => println!(
+                "{} {:>12} {:>12} {:>12}",
+                columns,
+                format!("{:.1?}", proving),
+                format!("{:.1?}", verifying),
+                size
+            ),
+            (Err(e), _) => println!("{} verification failed: {:?}", columns, e),
+            (_, Err(e)) => println!("{} could not size the proof: {}", columns, e),
+        }
+    }
+}
+
+// --pow-check: a proof ground to 8 bits, verified under configs that expect 8,
+// 20 and 4 bits. The verifier checks the witness against its own pow_bits,
+// which the transcript does not bind: a witness good for 8 bits is good for
+// fewer, so only a verifier expecting more rejects it.
+pub fn run_pow_check() {
+    println!(" Proof-of-work grinding:");
+    let trace = match generate_fibonacci_trace::<Val>(100) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    let ground = CostParams { pow_bits: 8, ..CostParams::test() };
+    let start = Instant::now();
+    let proof = prove(&create_config(DEFAULT_CONFIG_SEED, &ground), &FibonacciAir, trace.matrix, &trace.public_values);
+    println!("   Proved with 8 bits in {:?}; grinding alone: ~{:?}", start.elapsed(), grinding_time(8));
+    for pow_bits in [8, 20, 4] {
+        let config = create_config(DEFAULT_CONFIG_SEED, &CostParams { pow_bits, ..ground });
+        match verify(&config, &FibonacciAir, &proof, &trace.public_values) {
+            Ok(()) => println!("   Verifier expecting {} bits: verified", pow_bits),
+            Err(e) => println!("   Verifier expecting {} bits: rejected ({:?})", pow_bits, e),
+        }
+    }
+}
+
+// --zk: two hiding proofs of the same trace under fresh blinding, which share
+// no trace commitment, next to two plain proofs, which encode byte for byte
+// the same
+pub fn run_zk_demo(num_steps: usize) {
+    println!(" Zero-knowledge proofs:");
+    let blinded = prove_fibonacci_with(num_steps, true)
+        .and_then(|first| Ok((first, prove_fibonacci_with(num_steps, true)?)));
+    let ((first, public_values), (second, _)) = match blinded {
+        Ok(proofs) => proofs,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    for (name, proof) in [("First", &first), ("Second", &second)] {
+        match verify_fibonacci_proof(proof, &public_values) {
+            Ok(()) => println!("   {} blinded proof: verified", name),
+            Err(e) => println!("   {} blinded proof: verification failed: {}", name, e),
+        }
+    }
+    println!("   Blinded trace commitments differ: {}", first.trace_commitment() != second.trace_commitment());
+
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    let plain = || {
+        let trace = generate_fibonacci_trace::<Val>(num_steps).map_err(|e| e.to_string())?;
+        let config = create_default_config(SecurityLevel::Test);
+        let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+        encode_proof(&descriptor, &proof).map_err(|e| e.to_string())
+    };
+    match plain().and_then(|first| Ok((first, plain()?))) {
+        Ok((first, second)) => println!("   Plain proofs byte-identical: {}", first == second),
+        Err(e) => println!("   Plain proofs: {}", e),
+    }
+}
+
+// --shared-config-check: a prove_fibonacci proof verified under configs built
+// by the shared stark_config constructor, which the arithmetic example uses
+// too. The same seed rebuilds the prover's config; any other seed does not.
+pub fn run_shared_config_check() {
+    println!(" Shared config:");
+    let (proof, public_values) = match prove_fibonacci(20) {
+        Ok(proven) => proven,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    for seed in [DEFAULT_CONFIG_SEED, DEFAULT_CONFIG_SEED + 1] {
+        let config = stark_config::create_config(seed, FriChoice::TEST);
+        match verify_identified(&config, &FibonacciAir, &proof, &public_values) {
+            Ok(_) => println!("   stark_config::create_config, seed {}: verified", seed),
+            Err(e) => println!("   stark_config::create_config, seed {}: rejected ({})", seed, e),
+        }
+    }
+}
+
+// --domain-check: proofs bound to a domain string, verified under their own
+// domain, other domains and none. Only the matching domain accepts.
+pub fn run_domain_check() {
+    println!(" Fiat-Shamir domain separation:");
+    for domain in ["fib-v1", ""] {
+        let (proof, public_values) = match prove_fibonacci_in(domain, 20) {
+            Ok(proven) => proven,
+            Err(e) => {
+                println!("   Could not generate the trace: {}", e);
+                return;
+            }
+        };
+        for verifier in ["fib-v1", "fib-v2", ""] {
+            match verify_fibonacci_in(verifier, &proof, &public_values) {
+                Ok(()) => println!("   Proved under {:?}, verified under {:?}: accepted", domain, verifier),
+                Err(e) => println!("   Proved under {:?}, verified under {:?}: rejected ({})", domain, verifier, e),
+            }
+        }
+        match verify_fibonacci(&proof, &public_values) {
+            Ok(()) => println!("   Proved under {:?}, verified with no domain: accepted", domain),
+            Err(e) => println!("   Proved under {:?}, verified with no domain: rejected ({})", domain, e),
+        }
+    }
+}
+
+// --stage N: stage N of the tutorial against every case, what it should make
+// of each and whether it does, then a proof of the honest trace
+#[cfg(feature = "tutorial")]
+pub fn run_stage(stage: &str) {
+    use crate::stages::{NUM_STAGES, check_stage, prove_stage, stage_cases};
+
+    let cases = match stage_cases() {
+        Ok(cases) => cases,
+        Err(e) => {
+            println!(" Could not generate the cases: {}", e);
+            return;
+        }
+    };
+    let Some((stage, report)) = stage.parse().ok().and_then(|n| Some((n, check_stage(n, &cases)?))) else {
+        println!(" --stage takes a stage from 1 to {}", NUM_STAGES);
+        return;
+    };
+    println!(" {}:", report.name);
+    for case in &cases {
+        let verdict = if case.accepted_by[stage - 1] { "accept" } else { "reject" };
+        println!("   should {}: {}", verdict, case.name);
+    }
+    println!("   {}", report);
+    match prove_stage(stage) {
+        Some(Ok(())) => println!("   Honest trace proved and verified"),
+        Some(Err(e)) => println!("   Honest trace failed to verify: {}", e),
+        None => println!("   No honest trace satisfies this stage, so there is nothing to prove"),
+    }
+}
+
+// Logs the prove and verify transcripts of a small proof to `path`, then
+// shows where a prover with other Poseidon2 constants first departs from them
+#[cfg(feature = "transcript-log")]
+pub fn run_transcript_log(path: &Path) {
+    use crate::transcript::{compare_transcripts, prove_logged, verify_logged, write_transcript_logs};
+
+    let trace = match generate_fibonacci_trace::<Val>(8) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    let pis = &trace.public_values;
+    let (proof, prove_log) = prove_logged(DEFAULT_CONFIG_SEED, &FibonacciAir, trace.matrix.clone(), pis);
+    let (verified, verify_log) = verify_logged(DEFAULT_CONFIG_SEED, &FibonacciAir, &proof, pis);
+    println!(
+        " Transcript log: {} prove entries, {} verify entries",
+        prove_log.entries.len(),
+        verify_log.entries.len()
+    );
+    if let Err(e) = verified {
+        println!("   Verification failed: {}", e);
+    }
+    match compare_transcripts(&prove_log, &verify_log) {
+        None => println!("   Verifier replayed the prover's transcript exactly"),
+        Some(divergence) => println!("   Prove and verify diverge at {}", divergence),
+    }
+
+    let (_, other_log) = prove_logged(1, &FibonacciAir, trace.matrix, pis);
+    match compare_transcripts(&prove_log, &other_log) {
+        None => println!("   Seed 1 unexpectedly produced the same transcript"),
+        Some(divergence) => println!("   Seed 42 vs seed 1 first differ at {}", divergence),
+    }
+
+    match write_transcript_logs(path, &prove_log, &verify_log) {
+        Ok(()) => println!("   Wrote both logs to {}", path.display()),
+        Err(e) => println!("   Could not write {}: {}", path.display(), e),
+    }
+}
+
+// --diff-traces A B: the first rows where two trace files differ
+pub fn run_diff_traces(a: &Path, b: &Path) {
+    match diff_trace_files(a, b, 10) {
+        Ok(diff) => println!(" {}", diff),
+        Err(e) => println!(" Could not diff {} and {}: {}", a.display(), b.display(), e),
+    }
+}
+
+// --provenance: this build's Plonky3 revision and features, and what a proof
+// from another build would trigger
+pub fn run_provenance() {
+    println!(" Built with {}", PROVENANCE);
+    println!("   Digest: {:016x}", PROVENANCE.digest());
+    // A doctored digest stands in for a proof from another build
+    let doctored = PROVENANCE.digest() ^ 1;
+    if let Err(mismatch) = check_provenance(doctored) {
+        println!("   Doctored digest flagged: {}", mismatch);
+        println!("   Verification would still proceed");
+    }
+}
+
+// --compare-fields: the same 2^12-row trace proved over BabyBear and KoalaBear
+#[cfg(feature = "koala-bear")]
+pub fn run_compare_fields() {
+    // 2^12 - 1 steps and the padding row holding the result fill 2^12 rows
+    println!(" Field comparison, 2^12-row Fibonacci trace:");
+    match crate::koala_bear::compare_fields((1 << 12) - 1) {
+        Ok(timings) => {
+            for (field, prove_time) in timings {
+                println!("   {}: prove {:?}", field, prove_time);
+            }
+        }
+        Err(e) => println!("   {}", e),
+    }
+}
+
+// --compare-challengers: proving time under the duplex and Keccak challengers
+pub fn run_compare_challengers() {
+    println!(" Challenger comparison, 2^16-step Fibonacci trace:");
+    match compare_challengers(1 << 16) {
+        Ok(timings) => {
+            for timing in timings {
+                println!("   {}", timing);
+            }
+        }
+        Err(e) => println!("   {}", e),
+    }
+}
+
+// --exercises: every exercise slot against its cases. Returns whether all
+// of them passed.
+#[cfg(feature = "exercises")]
+pub fn run_exercises() -> bool {
+    println!(" Exercises:");
+    let reports = crate::exercises::run_exercises();
+    for report in &reports {
+        println!("   {}", report);
+    }
+    reports.iter().all(|report| report.passed())
+}
@@ -0,0 +1,258 @@
+use core::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use p3_field::PrimeField64;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::Val;
+use crate::canonical::{NonCanonicalValue, parse_canonical};
+
+// Streams a trace as CSV, one row per line of canonical values
+pub fn write_trace(path: &Path, trace: &RowMajorMatrix<Val>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    for row in trace.values.chunks(trace.width()) {
+        let line: Vec<String> = row.iter().map(|v| v.as_canonical_u64().to_string()).collect();
+        writeln!(out, "{}", line.join(","))?;
+    }
+    out.flush()
+}
+
+// One differing cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub row: usize,
+    pub col: usize,
+    pub a: Val,
+    pub b: Val,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceDiff {
+    // (height, width) of each side; widths are those of the first row
+    pub dims_a: (usize, usize),
+    pub dims_b: (usize, usize),
+    // The first `limit` differences in row-major order
+    pub first: Vec<DiffEntry>,
+    // Differences per column over the rows both traces have
+    pub column_counts: Vec<usize>,
+}
+
+impl TraceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.dims_a == self.dims_b && self.column_counts.iter().all(|&count| count == 0)
+    }
+
+    pub fn total(&self) -> usize {
+        self.column_counts.iter().sum()
+    }
+}
+
+impl fmt::Display for TraceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "traces are identical ({} x {})", self.dims_a.0, self.dims_a.1);
+        }
+        if self.dims_a != self.dims_b {
+            writeln!(
+                f,
+                "dimensions differ: {} x {} vs {} x {}",
+                self.dims_a.0, self.dims_a.1, self.dims_b.0, self.dims_b.1
+            )?;
+        }
+        write!(f, "{} differing cells in the shared rows", self.total())?;
+        for entry in &self.first {
+            write!(f, "\n   row {}, col {}: {} vs {}", entry.row, entry.col, entry.a, entry.b)?;
+        }
+        for (col, count) in self.column_counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+            write!(f, "\n   col {}: {} differences", col, count)?;
+        }
+        Ok(())
+    }
+}
+
+// Diffs two row streams, holding only one row of each at a time
+pub fn diff_rows<A, B, R, S>(a: A, b: B, limit: usize) -> TraceDiff
+where
+    A: Iterator<Item = R>,
+    B: Iterator<Item = S>,
+    R: AsRef<[Val]>,
+    S: AsRef<[Val]>,
+{
+    let mut diff = TraceDiff::default();
+    let (mut a, mut b) = (a.fuse(), b.fuse());
+    let count = |dims: &mut (usize, usize), row: &[Val]| {
+        if dims.0 == 0 {
+            dims.1 = row.len();
+        }
+        dims.0 += 1;
+    };
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => break,
+            (Some(row_a), None) => count(&mut diff.dims_a, row_a.as_ref()),
+            (None, Some(row_b)) => count(&mut diff.dims_b, row_b.as_ref()),
+            (Some(row_a), Some(row_b)) => {
+                let (row_a, row_b) = (row_a.as_ref(), row_b.as_ref());
+                let row = diff.dims_a.0;
+                count(&mut diff.dims_a, row_a);
+                count(&mut diff.dims_b, row_b);
+                let width = row_a.len().max(row_b.len());
+                if diff.column_counts.len() < width {
+                    diff.column_counts.resize(width, 0);
+                }
+                for (col, (&va, &vb)) in row_a.iter().zip(row_b).enumerate() {
+                    if va != vb {
+                        diff.column_counts[col] += 1;
+                        if diff.first.len() < limit {
+                            diff.first.push(DiffEntry { row, col, a: va, b: vb });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    diff
+}
+
+pub fn diff_traces(a: &RowMajorMatrix<Val>, b: &RowMajorMatrix<Val>, limit: usize) -> TraceDiff {
+    diff_rows(a.values.chunks(a.width()), b.values.chunks(b.width()), limit)
+}
+
+#[derive(Debug)]
+pub enum DiffError {
+    Io(io::Error),
+    // A line that is not comma-separated integers
+    Malformed { path: String, line: usize },
+    // An integer on this line of p or more
+    NonCanonical { path: String, line: usize, error: NonCanonicalValue },
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not read trace: {}", e),
+            Self::Malformed { path, line } => write!(f, "{}: malformed trace row on line {}", path, line),
+            Self::NonCanonical { path, line, error } => write!(f, "{}: line {}: {}", path, line, error),
+        }
+    }
+}
+
+impl std::error::Error for DiffError {}
+
+impl From<io::Error> for DiffError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn parse_row(path: &Path, line: usize, text: &str) -> Result<Vec<Val>, DiffError> {
+    let path = || path.display().to_string();
+    text.split(',')
+        .map(|value| {
+            let value = value.parse::<u64>().map_err(|_| DiffError::Malformed { path: path(), line })?;
+            parse_canonical(value).map_err(|error| DiffError::NonCanonical { path: path(), line, error })
+        })
+        .collect()
+}
+
+// Streams rows of a trace file, stopping at the first bad line and leaving
+// the error in `error`
+fn read_rows<'a>(
+    path: &'a Path,
+    error: &'a mut Option<DiffError>,
+) -> Result<impl Iterator<Item = Vec<Val>> + 'a, DiffError> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(reader.lines().enumerate().map_while(move |(index, line)| {
+        let parsed = match line {
+            Ok(line) => parse_row(path, index + 1, &line),
+            Err(e) => Err(DiffError::Io(e)),
+        };
+        parsed.map_err(|e| *error = Some(e)).ok()
+    }))
+}
+
+// Diffs two trace files written by write_trace line by line, so memory stays
+// bounded by one row of each whatever the file sizes
+pub fn diff_trace_files(a: &Path, b: &Path, limit: usize) -> Result<TraceDiff, DiffError> {
+    let (mut error_a, mut error_b) = (None, None);
+    let diff = diff_rows(read_rows(a, &mut error_a)?, read_rows(b, &mut error_b)?, limit);
+    match error_a.or(error_b) {
+        Some(e) => Err(e),
+        None => Ok(diff),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::{generate_fibonacci_trace, generate_recurrence_trace};
+
+    fn scratch(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("fibonacci-diff-{}-{}.csv", name, std::process::id()))
+    }
+
+    #[test]
+    fn identical_traces_have_an_empty_diff() {
+        let trace = generate_fibonacci_trace::<Val>(20).unwrap().matrix;
+        let diff = diff_traces(&trace, &trace, 5);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "traces are identical (256 x 4)");
+    }
+
+    #[test]
+    fn one_edited_cell_is_located() {
+        let a = generate_fibonacci_trace::<Val>(20).unwrap().matrix;
+        let mut b = a.clone();
+        b.values[7 * 4 + 1] += Val::ONE;
+        let diff = diff_traces(&a, &b, 5);
+        assert_eq!(diff.first, [DiffEntry { row: 7, col: 1, a: a.values[29], b: b.values[29] }]);
+        assert_eq!(diff.column_counts, [0, 1, 0, 0]);
+        assert_eq!(diff.total(), 1);
+    }
+
+    #[test]
+    fn another_start_diverges_from_row_zero_and_the_limit_caps_samples() {
+        let a = generate_fibonacci_trace::<Val>(20).unwrap().matrix;
+        let b = generate_recurrence_trace(Val::TWO, Val::ONE, 20).unwrap().matrix;
+        let diff = diff_traces(&a, &b, 3);
+        assert_eq!(diff.first.len(), 3);
+        assert_eq!((diff.first[0].row, diff.first[0].col), (0, 0));
+        assert!(diff.total() > 3);
+    }
+
+    #[test]
+    fn differing_heights_count_only_the_shared_rows() {
+        let a = generate_fibonacci_trace::<Val>(20).unwrap().matrix;
+        let b = generate_fibonacci_trace::<Val>(300).unwrap().matrix;
+        let diff = diff_traces(&a, &b, 0);
+        assert_eq!((diff.dims_a, diff.dims_b), ((256, 4), (512, 4)));
+        assert!(!diff.is_empty());
+        assert!(diff.first.is_empty());
+    }
+
+    #[test]
+    fn trace_files_round_trip_and_bad_lines_are_errors() {
+        let (a, b, bad) = (scratch("a"), scratch("b"), scratch("bad"));
+        let trace = generate_fibonacci_trace::<Val>(20).unwrap().matrix;
+        write_trace(&a, &trace).unwrap();
+        write_trace(&b, &trace).unwrap();
+        let same = diff_trace_files(&a, &b, 5);
+
+        std::fs::write(&bad, "0,1,1,1\n1,x,1,2\n").unwrap();
+        let malformed = diff_trace_files(&a, &bad, 5);
+        std::fs::write(&bad, format!("0,1,1,1\n{},1,1,2\n", Val::ORDER_U64)).unwrap();
+        let non_canonical = diff_trace_files(&a, &bad, 5);
+        for path in [&a, &b, &bad] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(same.unwrap().is_empty());
+        assert!(matches!(malformed, Err(DiffError::Malformed { line: 2, .. })), "{:?}", malformed);
+        assert!(matches!(non_canonical, Err(DiffError::NonCanonical { line: 2, .. })), "{:?}", non_canonical);
+    }
+}
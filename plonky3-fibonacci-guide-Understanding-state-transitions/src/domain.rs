@@ -0,0 +1,72 @@
+// Fiat-Shamir domain separation. Every config built from the same descriptor
+// starts its transcript in the same state, so nothing but the public values
+// ties a proof to the application that asked for it. A domain string, hashed
+// to a digest and observed by the challenger before anything else, gives each
+// application its own transcript: a proof made under one domain draws
+// different challenges under any other and fails to verify there.
+//
+// The digest hashes the string's byte length and then its bytes, so the
+// empty domain is a domain of its own, distinct from having none at all.
+use p3_field::PrimeCharacteristicRing;
+use p3_symmetric::CryptographicHasher;
+
+use crate::air_id::IdentifiedProof;
+use crate::claims::FibonacciClaims;
+use crate::cost::CostParams;
+use crate::journal::{Digest, hasher};
+use crate::trace::SizingError;
+use crate::{
+    ConfigDescriptor, DEFAULT_CONFIG_SEED, MyConfig, PublicValues, Val, VerifyError, create_config_with_prefix,
+    generate_fibonacci_trace, prove_fibonacci_trace, verify_sequence,
+};
+
+pub fn domain_digest(domain: &str) -> Digest {
+    let input = core::iter::once(Val::from_usize(domain.len())).chain(domain.bytes().map(Val::from_u8));
+    hasher().hash_iter(input)
+}
+
+// create_default_config with the challenger bound to `domain`
+pub fn create_domain_config(domain: &str) -> MyConfig {
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    create_config_with_prefix(&descriptor, &domain_digest(domain))
+}
+
+// prove_fibonacci under `domain`
+pub fn prove_fibonacci_in(domain: &str, num_steps: usize) -> Result<(IdentifiedProof, PublicValues), SizingError> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps)?;
+    Ok(prove_fibonacci_trace(&create_domain_config(domain), trace, &mut ()))
+}
+
+// verify_fibonacci under `domain`; a proof made under any other domain, or
+// under none, is rejected
+pub fn verify_fibonacci_in(domain: &str, proof: &IdentifiedProof, public_values: &[Val]) -> Result<(), VerifyError> {
+    let claims = FibonacciClaims::from_public_values(public_values)?;
+    verify_sequence(&create_domain_config(domain), proof, &claims).map_err(VerifyError::Proof)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_fibonacci;
+
+    #[test]
+    fn only_the_proving_domain_verifies() {
+        for domain in ["fib-v1", ""] {
+            let (proof, public_values) = prove_fibonacci_in(domain, 20).unwrap();
+            for verifier in ["fib-v1", "fib-v2", ""] {
+                let verified = verify_fibonacci_in(verifier, &proof, &public_values);
+                let case = format!("proved under {:?}, verified under {:?}", domain, verifier);
+                assert_eq!(verified.is_ok(), verifier == domain, "{}", case);
+            }
+            assert!(verify_fibonacci(&proof, &public_values).is_err(), "proved under {:?}", domain);
+        }
+    }
+
+    #[test]
+    fn the_empty_domain_has_its_own_digest() {
+        assert_ne!(domain_digest(""), domain_digest("\0"));
+        assert_ne!(domain_digest("fib-v1"), domain_digest("fib-v2"));
+        assert_eq!(domain_digest("fib-v1"), domain_digest("fib-v1"));
+    }
+}
@@ -0,0 +1,148 @@
+// Defense in depth: the same statement proven under the Poseidon2 duplex
+// config and the Keccak challenger config, accepted only if both proofs
+// verify and claim the same public values. The two configs are different
+// types, so a DualProof cannot hold two proofs from the same config.
+use core::fmt;
+
+use p3_air::Air;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{
+    DebugConstraintBuilder, Proof, ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder,
+    VerifierConstraintFolder, prove, verify,
+};
+
+use crate::challenger::{KeccakChallengerConfig, create_keccak_challenger_config};
+use crate::claims::{ClaimsError, FibonacciClaims};
+use crate::cost::SecurityLevel;
+use crate::{MyConfig, Val, create_default_config};
+
+pub const DUPLEX_NAME: &str = "Poseidon2 duplex";
+pub const KECCAK_NAME: &str = "Keccak challenger";
+
+// A proof with the public values it claims
+pub struct ProofArtifact<SC: StarkGenericConfig> {
+    pub proof: Proof<SC>,
+    pub public_values: Vec<Val>,
+}
+
+impl<SC: StarkGenericConfig> ProofArtifact<SC> {
+    // For CoefficientAir proofs, whose step count is the proof's row count;
+    // decodes identically under either config
+    pub fn fibonacci_claims(&self) -> Result<FibonacciClaims, ClaimsError> {
+        FibonacciClaims::from_coefficient_proof(&self.public_values, &self.proof)
+    }
+}
+
+pub struct DualProof {
+    pub a: ProofArtifact<MyConfig>,
+    pub b: ProofArtifact<KeccakChallengerConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DualError {
+    // The two proofs claim different statements
+    PublicValueMismatch { a: Vec<Val>, b: Vec<Val> },
+    // One proof failed under its own config
+    Verification { config: &'static str, msg: String },
+}
+
+impl fmt::Display for DualError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PublicValueMismatch { a, b } => write!(
+                f,
+                "{} proof claims {:?} but {} proof claims {:?}",
+                DUPLEX_NAME, a, KECCAK_NAME, b
+            ),
+            Self::Verification { config, msg } => write!(f, "{} proof rejected: {}", config, msg),
+        }
+    }
+}
+
+impl std::error::Error for DualError {}
+
+pub fn prove_dual<A>(air: &A, trace: RowMajorMatrix<Val>, public_values: &Vec<Val>) -> DualProof
+where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<ProverConstraintFolder<'a, KeccakChallengerConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val>>,
+{
+    let a = prove(&create_default_config(SecurityLevel::Test), air, trace.clone(), public_values);
+    let b = prove(&create_keccak_challenger_config(), air, trace, public_values);
+    DualProof {
+        a: ProofArtifact { proof: a, public_values: public_values.clone() },
+        b: ProofArtifact { proof: b, public_values: public_values.clone() },
+    }
+}
+
+// Checks the public values agree before spending time on either proof, then
+// verifies each under its own config
+pub fn verify_dual<A>(air: &A, dual: &DualProof) -> Result<(), DualError>
+where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<VerifierConstraintFolder<'a, KeccakChallengerConfig>>,
+{
+    if dual.a.public_values != dual.b.public_values {
+        return Err(DualError::PublicValueMismatch {
+            a: dual.a.public_values.clone(),
+            b: dual.b.public_values.clone(),
+        });
+    }
+    let duplex = create_default_config(SecurityLevel::Test);
+    verify(&duplex, air, &dual.a.proof, &dual.a.public_values).map_err(|e| {
+        DualError::Verification { config: DUPLEX_NAME, msg: format!("{:?}", e) }
+    })?;
+    verify(&create_keccak_challenger_config(), air, &dual.b.proof, &dual.b.public_values).map_err(
+        |e| DualError::Verification { config: KECCAK_NAME, msg: format!("{:?}", e) },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::coefficients::{CoefficientAir, generate_coefficient_trace, recurrence_final};
+    use crate::{FibonacciAir, generate_fibonacci_trace};
+
+    fn dual() -> DualProof {
+        let trace = generate_fibonacci_trace::<Val>(20).unwrap();
+        prove_dual(&FibonacciAir, trace.matrix, &trace.public_values)
+    }
+
+    #[test]
+    fn both_proofs_of_one_statement_verify() {
+        assert_eq!(verify_dual(&FibonacciAir, &dual()), Ok(()));
+    }
+
+    #[test]
+    fn proofs_claiming_different_statements_are_refused_unverified() {
+        let mut dual = dual();
+        dual.b.public_values[2] += Val::ONE;
+        assert_eq!(
+            verify_dual(&FibonacciAir, &dual),
+            Err(DualError::PublicValueMismatch { a: dual.a.public_values.clone(), b: dual.b.public_values.clone() })
+        );
+    }
+
+    #[test]
+    fn a_shared_wrong_claim_fails_the_first_verifier() {
+        let mut dual = dual();
+        dual.a.public_values[2] += Val::ONE;
+        dual.b.public_values[2] += Val::ONE;
+        let rejected = verify_dual(&FibonacciAir, &dual);
+        assert!(matches!(rejected, Err(DualError::Verification { config: DUPLEX_NAME, .. })), "{:?}", rejected);
+    }
+
+    #[test]
+    fn claims_decode_equal_under_both_configs() {
+        let trace = generate_coefficient_trace(Val::ZERO, Val::ONE, 1, 1, 256).unwrap();
+        let dual = prove_dual(&CoefficientAir, trace.matrix, &trace.public_values);
+        let (a, b) = (dual.a.fibonacci_claims().unwrap(), dual.b.fibonacci_claims().unwrap());
+        assert_eq!(a, b);
+        assert_eq!(a.num_steps, 256);
+        assert_eq!(a.result, recurrence_final(Val::ZERO, Val::ONE, 1, 1, 256));
+    }
+}
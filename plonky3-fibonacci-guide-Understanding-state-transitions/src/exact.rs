@@ -0,0 +1,201 @@
+// Fibonacci numbers as users read them. The trace only holds F(n) mod p,
+// which for n > 46 is not F(n), so a printed value is either the exact
+// integer next to the field element or explicitly marked "(mod p)".
+use core::fmt;
+
+use num_bigint::BigUint;
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::trace::{TraceView, TraceViewError};
+use crate::{FibonacciAir, Val};
+
+// Largest n for which the exact integer is computed; F(100_000) has about
+// 21_000 digits
+pub const DEFAULT_EXACT_BOUND: usize = 100_000;
+
+// F(n) as an integer, or None above `bound`
+pub fn fibonacci_exact(n: usize, bound: usize) -> Option<BigUint> {
+    (n <= bound).then(|| fibonacci_value_exact(n))
+}
+
+// F(n) as an integer, however long it takes
+pub fn fibonacci_value_exact(n: usize) -> BigUint {
+    let (mut a, mut b) = (BigUint::ZERO, BigUint::from(1u8));
+    for _ in 0..n {
+        let next = &a + &b;
+        a = core::mem::replace(&mut b, next);
+    }
+    a
+}
+
+// F(n) with its field element, printed as "F(n) = exact (field: x)" or
+// "F(n) = x (mod p)", never as a bare reduced value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FibonacciValue {
+    pub n: usize,
+    pub reduced: Val,
+    pub exact: Option<BigUint>,
+}
+
+impl FibonacciValue {
+    pub fn new(n: usize, reduced: Val, bound: usize) -> Self {
+        Self { n, reduced, exact: fibonacci_exact(n, bound) }
+    }
+}
+
+impl fmt::Display for FibonacciValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.exact {
+            Some(exact) if *exact == BigUint::from(self.reduced.as_canonical_u64()) => {
+                write!(f, "F({}) = {}", self.n, exact)
+            }
+            Some(exact) => write!(f, "F({}) = {} (field: {})", self.n, exact, self.reduced),
+            None => write!(f, "F({}) = {} (mod p)", self.n, self.reduced),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FibonacciValueError {
+    // n is past F(num_steps), the last value the trace computes; the padding
+    // below repeats F(num_steps) and says nothing about F(n)
+    PastEnd { n: usize, num_steps: usize },
+    // The trace computes `found` steps, not the `expected` ones
+    StepCount { expected: usize, found: usize },
+    // The trace's F(n) is not the reference F(n) mod p
+    Mismatch { n: usize, trace: Val, reference: Val },
+    // Not a FibonacciAir trace
+    View(TraceViewError),
+}
+
+impl fmt::Display for FibonacciValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PastEnd { n, num_steps } => write!(f, "F({}) is past the trace's last value F({})", n, num_steps),
+            Self::StepCount { expected, found } => write!(f, "expected {} steps, the trace has {}", expected, found),
+            Self::Mismatch { n, trace, reference } => {
+                write!(f, "the trace has F({}) = {} but the reference is {} (mod p)", n, trace, reference)
+            }
+            Self::View(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for FibonacciValueError {}
+
+impl From<TraceViewError> for FibonacciValueError {
+    fn from(e: TraceViewError) -> Self {
+        Self::View(e)
+    }
+}
+
+// The step count a FibonacciAir trace computes, off its last row's count
+fn trace_steps(view: &TraceView<'_>) -> Result<usize, FibonacciValueError> {
+    let last = view.height().saturating_sub(1);
+    Ok(view.get_named(last, "count")?.as_canonical_u64() as usize)
+}
+
+// F(n) mod p read off a FibonacciAir trace: row n's a column, or the first
+// padding row's for n = num_steps. Past that is an error rather than a read
+// of the padding.
+pub fn fibonacci_value(trace: &RowMajorMatrix<Val>, n: usize) -> Result<Val, FibonacciValueError> {
+    let view = TraceView::named(trace, &FibonacciAir);
+    let num_steps = trace_steps(&view)?;
+    if n > num_steps {
+        return Err(FibonacciValueError::PastEnd { n, num_steps });
+    }
+    Ok(view.get_named(n, "a")?)
+}
+
+// Recomputes F(0), ..., F(num_steps + 1) with BigUint, reduces each mod p and
+// compares them against every real row's (a, b) and the first padding row's a
+pub fn check_trace_matches_reference(trace: &RowMajorMatrix<Val>, num_steps: usize) -> Result<(), FibonacciValueError> {
+    let view = TraceView::named(trace, &FibonacciAir);
+    let found = trace_steps(&view)?;
+    if found != num_steps {
+        return Err(FibonacciValueError::StepCount { expected: num_steps, found });
+    }
+    let order = BigUint::from(Val::ORDER_U64);
+    let check = |row: usize, column: &str, n: usize, value: &BigUint| -> Result<(), FibonacciValueError> {
+        let reduced = u64::try_from(value % &order).expect("reduced below p");
+        let (trace, reference) = (view.get_named(row, column)?, Val::from_u64(reduced));
+        if trace != reference {
+            return Err(FibonacciValueError::Mismatch { n, trace, reference });
+        }
+        Ok(())
+    };
+
+    // Row i holds (F(i), F(i + 1))
+    let (mut a, mut b) = (BigUint::ZERO, BigUint::from(1u8));
+    for row in 0..=num_steps {
+        check(row, "a", row, &a)?;
+        if row < num_steps {
+            check(row, "b", row + 1, &b)?;
+        }
+        let next = &a + &b;
+        a = core::mem::replace(&mut b, next);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_matrix::Matrix;
+    use test_support::oracle::{fibonacci_big, fibonacci_mod};
+
+    use super::*;
+    use crate::{NUM_FIBONACCI_COLS, generate_fibonacci_trace};
+
+    #[test]
+    fn exact_values_are_the_integers_not_their_residues() {
+        assert_eq!(fibonacci_value_exact(0), BigUint::ZERO);
+        assert_eq!(fibonacci_value_exact(10), BigUint::from(55u8));
+        // F(47) = 2971215073 is the first value past p
+        assert_eq!(fibonacci_value_exact(47), BigUint::from(2_971_215_073u64));
+        assert!(fibonacci_value_exact(47) > BigUint::from(Val::ORDER_U64));
+        assert_eq!(fibonacci_value_exact(1000), fibonacci_big(1000));
+    }
+
+    #[test]
+    fn the_bound_is_inclusive() {
+        assert_eq!(fibonacci_exact(100, 100), Some(fibonacci_big(100)));
+        assert_eq!(fibonacci_exact(101, 100), None);
+    }
+
+    #[test]
+    fn printed_values_are_exact_or_marked_mod_p() {
+        assert_eq!(FibonacciValue::new(10, Val::from_u8(55), 100).to_string(), "F(10) = 55");
+        let reduced = fibonacci_mod::<Val>(50);
+        let expected = format!("F(50) = 12586269025 (field: {})", reduced);
+        assert_eq!(FibonacciValue::new(50, reduced, 100).to_string(), expected);
+        assert_eq!(FibonacciValue::new(50, reduced, 40).to_string(), format!("F(50) = {} (mod p)", reduced));
+    }
+
+    #[test]
+    fn the_trace_matches_the_reduced_reference_up_to_1000() {
+        for num_steps in [1, 100, 1000] {
+            let trace = generate_fibonacci_trace::<Val>(num_steps).unwrap();
+            assert_eq!(check_trace_matches_reference(&trace.matrix, num_steps), Ok(()), "{} steps", num_steps);
+            for n in 0..=num_steps {
+                assert_eq!(fibonacci_value(&trace.matrix, n), Ok(fibonacci_mod(n as u64)), "F({})", n);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_past_the_last_step_are_errors() {
+        let num_steps = 1000;
+        let trace = generate_fibonacci_trace::<Val>(num_steps).unwrap();
+        for n in [num_steps + 1, trace.matrix.height(), usize::MAX] {
+            assert_eq!(fibonacci_value(&trace.matrix, n), Err(FibonacciValueError::PastEnd { n, num_steps }));
+        }
+        let wrong_count = check_trace_matches_reference(&trace.matrix, 999);
+        assert_eq!(wrong_count, Err(FibonacciValueError::StepCount { expected: 999, found: 1000 }));
+
+        let mut edited = trace.matrix.clone();
+        edited.values[500 * NUM_FIBONACCI_COLS] += Val::ONE;
+        let mismatch = check_trace_matches_reference(&edited, num_steps);
+        assert!(matches!(mismatch, Err(FibonacciValueError::Mismatch { n: 500, .. })), "{:?}", mismatch);
+    }
+}
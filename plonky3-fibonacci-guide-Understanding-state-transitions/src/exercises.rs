@@ -0,0 +1,210 @@
+// Fill-in-the-constraint exercises. Each skeleton AIR has a constraint slot
+// that is todo!() until the learner writes it; `--exercises` (built with
+// --features exercises) runs every exercise against its attack corpus.
+// Reference solutions are compiled in with --features solutions.
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::Val;
+use crate::last_row::{bind_last_real_row, constrain_real_prefix};
+use crate::validate::{Attack, AttackReport, run_attacks};
+
+// Exercise 1: x counts up by one from a public start value.
+// Slot: bind the first row's x to the public start.
+#[derive(Debug, Clone)]
+pub struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+#[allow(unused_variables)]
+fn counter_boundary<AB: AirBuilder>(builder: &mut AB, x: AB::Expr, start: AB::Expr) {
+    #[cfg(feature = "solutions")]
+    builder.when_first_row().assert_eq(x, start);
+    #[cfg(not(feature = "solutions"))]
+    todo!("exercise 1: constrain x on the first row to the public start value");
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let start: AB::Expr = builder.public_values()[0].into();
+        let x: AB::Expr = local[0].clone().into();
+
+        builder.when_transition().assert_eq(next[0].clone(), x.clone() + AB::Expr::ONE);
+        counter_boundary(builder, x, start);
+    }
+}
+
+// Exercise 2: the same counter over real rows only, padded with zero rows,
+// with the count bound on the last real row.
+// Slot: gate the counting rule so padding rows are exempt from it.
+#[derive(Debug, Clone)]
+pub struct PaddedCounterAir;
+
+#[repr(C)]
+pub struct PaddedCounterRow<F> {
+    pub is_real: F,
+    pub x: F,
+}
+
+impl<F> Borrow<PaddedCounterRow<F>> for [F] {
+    fn borrow(&self) -> &PaddedCounterRow<F> {
+        debug_assert_eq!(self.len(), 2);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<PaddedCounterRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for PaddedCounterAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+#[allow(unused_variables)]
+fn padded_counter_step<AB: AirBuilder>(
+    builder: &mut AB,
+    next_is_real: AB::Expr,
+    x: AB::Expr,
+    next_x: AB::Expr,
+) {
+    #[cfg(feature = "solutions")]
+    builder.when_transition().assert_zero(next_is_real * (next_x - x - AB::Expr::ONE));
+    #[cfg(not(feature = "solutions"))]
+    todo!("exercise 2: require next.x = x + 1, but only when the next row is real");
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for PaddedCounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &PaddedCounterRow<AB::Var> = (*local).borrow();
+        let next: &PaddedCounterRow<AB::Var> = (*next).borrow();
+        let count: AB::Expr = builder.public_values()[0].into();
+
+        let is_real: AB::Expr = local.is_real.clone().into();
+        let next_is_real: AB::Expr = next.is_real.clone().into();
+        let x: AB::Expr = local.x.clone().into();
+        constrain_real_prefix(builder, is_real.clone(), next_is_real.clone());
+        builder.when_first_row().assert_one(x.clone());
+        padded_counter_step(builder, next_is_real.clone(), x.clone(), next.x.clone().into());
+        bind_last_real_row(builder, is_real, next_is_real, x, count);
+    }
+}
+
+const HEIGHT: usize = 256;
+
+fn column(values: impl Iterator<Item = u64>) -> RowMajorMatrix<Val> {
+    RowMajorMatrix::new(values.map(Val::from_u64).collect(), 1)
+}
+
+fn counter_attacks() -> Vec<Attack> {
+    let counting = |start: u64| column((0..HEIGHT as u64).map(move |i| start + i));
+    vec![
+        Attack { name: "honest", trace: counting(7), public_values: vec![Val::from_u64(7)], valid: true },
+        Attack {
+            name: "shifted start",
+            trace: counting(12),
+            public_values: vec![Val::from_u64(7)],
+            valid: false,
+        },
+    ]
+}
+
+fn padded_counter_trace(real_rows: usize, tamper_row: Option<usize>) -> RowMajorMatrix<Val> {
+    let mut values = Val::zero_vec(HEIGHT * 2);
+    for i in 0..real_rows {
+        values[2 * i] = Val::ONE;
+        values[2 * i + 1] = Val::from_usize(i + 1);
+    }
+    if let Some(row) = tamper_row {
+        values[2 * row + 1] += Val::ONE;
+    }
+    RowMajorMatrix::new(values, 2)
+}
+
+fn padded_counter_attacks() -> Vec<Attack> {
+    let count = vec![Val::from_u64(100)];
+    vec![
+        Attack {
+            name: "honest, zero padded",
+            trace: padded_counter_trace(100, None),
+            public_values: count.clone(),
+            valid: true,
+        },
+        Attack {
+            name: "skipped step in real rows",
+            trace: padded_counter_trace(100, Some(50)),
+            public_values: count,
+            valid: false,
+        },
+    ]
+}
+
+// Runs every exercise and returns one report each
+pub fn run_exercises() -> Vec<AttackReport> {
+    vec![
+        run_attacks("1. boundary constraint", &CounterAir, counter_attacks()),
+        run_attacks("2. padding selector", &PaddedCounterAir, padded_counter_attacks()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "solutions")]
+    #[test]
+    fn the_reference_solutions_pass_every_exercise() {
+        for report in run_exercises() {
+            assert!(report.passed(), "{}", report);
+        }
+    }
+
+    // Without the solutions each slot is still todo!(), which the harness
+    // reports instead of unwinding through it
+    #[cfg(not(feature = "solutions"))]
+    #[test]
+    fn an_empty_slot_is_reported_unfinished() {
+        let reports = run_exercises();
+        assert_eq!(reports.len(), 2);
+        for (report, slot) in reports.iter().zip(["exercise 1", "exercise 2"]) {
+            let unfinished = report.unfinished.as_deref().unwrap_or_else(|| panic!("{}", report));
+            assert!(unfinished.contains(slot), "{}", report);
+            assert!(!report.passed());
+        }
+    }
+
+    #[test]
+    fn each_corpus_has_an_honest_trace_and_an_attack() {
+        for attacks in [counter_attacks(), padded_counter_attacks()] {
+            assert!(attacks.iter().any(|attack| attack.valid));
+            assert!(attacks.iter().any(|attack| !attack.valid));
+            assert!(attacks.iter().all(|attack| attack.trace.height() == HEIGHT));
+        }
+    }
+
+    #[test]
+    fn the_tampered_padded_trace_differs_in_one_real_row() {
+        let honest = padded_counter_trace(100, None);
+        let tampered = padded_counter_trace(100, Some(50));
+        let row = |trace: &RowMajorMatrix<Val>, i: usize| trace.values[2 * i..2 * i + 2].to_vec();
+        let differing: Vec<_> = (0..HEIGHT).filter(|&i| row(&honest, i) != row(&tampered, i)).collect();
+        assert_eq!(differing, [50]);
+        assert_eq!(honest.values[2 * 99..2 * 101], [Val::ONE, Val::from_u64(100), Val::ZERO, Val::ZERO]);
+    }
+}
@@ -0,0 +1,176 @@
+// Rebuilds every checked-in fixture through the code paths that read or
+// produce it elsewhere: the self-test for fingerprints, the snapshot module for
+// constraints, write_trace and write_sequence for the file formats, and
+// vectors.rs for the test vectors. Everything
+// is generated into a scratch directory first, so a failing generator leaves
+// fixtures/ untouched, and nothing is copied over without `apply`.
+use core::fmt;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::diff::write_trace;
+use crate::selftest::{format_fingerprints, self_test};
+use crate::sequence::{sequence_values, write_sequence};
+use crate::snapshot::{current_snapshots, format_snapshots};
+use crate::trace::SizingError;
+use crate::vectors::{VectorError, write_vectors};
+use crate::{Val, generate_fibonacci_trace};
+
+// Steps in the trace and sequence fixtures
+pub const FIXTURE_STEPS: usize = 8;
+
+// fixtures/ next to Cargo.toml
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    // Same length, different bytes: a hash, seed or transcript change
+    Contents,
+    // A different length: a trace shape or file format change
+    Size { old: u64, new: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureChange {
+    pub name: String,
+    pub kind: ChangeKind,
+}
+
+impl fmt::Display for FixtureChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ChangeKind::Added => write!(f, "{}: added", self.name),
+            ChangeKind::Removed => write!(f, "{}: no longer generated", self.name),
+            ChangeKind::Contents => {
+                write!(f, "{}: same size, new contents (hash, seed or transcript change)", self.name)
+            }
+            ChangeKind::Size { old, new } => {
+                write!(f, "{}: {} -> {} bytes (shape or format change)", self.name, old, new)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FixtureError {
+    Io(io::Error),
+    Trace(SizingError),
+    // A failing self-test has no fingerprint worth recording
+    SelfTest(String),
+    Vectors(VectorError),
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not write fixtures: {}", e),
+            Self::Trace(e) => write!(f, "fixture trace: {}", e),
+            Self::SelfTest(report) => write!(f, "self-test failed, fixtures left alone: {}", report),
+            Self::Vectors(e) => write!(f, "test vectors: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+impl From<io::Error> for FixtureError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn write_fixtures(dir: &Path) -> Result<(), FixtureError> {
+    fs::create_dir_all(dir)?;
+
+    let reports = self_test(&[]);
+    if let Some(failed) = reports.iter().find(|report| !report.passed()) {
+        return Err(FixtureError::SelfTest(failed.to_string()));
+    }
+    fs::write(dir.join("fingerprints.txt"), format_fingerprints(&reports))?;
+    fs::write(dir.join("constraints.txt"), format_snapshots(&current_snapshots()))?;
+
+    let trace = generate_fibonacci_trace::<Val>(FIXTURE_STEPS).map_err(FixtureError::Trace)?;
+    write_trace(&dir.join("trace.csv"), &trace.matrix)?;
+    write_sequence(&dir.join("sequence.csv"), sequence_values(&trace.matrix, trace.real_rows))?;
+    write_vectors(dir).map_err(FixtureError::Vectors)?;
+    Ok(())
+}
+
+// File names in `dir`, none if it does not exist yet
+fn file_names(dir: &Path) -> io::Result<BTreeSet<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(e) => return Err(e),
+    };
+    let mut names = BTreeSet::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            names.insert(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    Ok(names)
+}
+
+fn compare(fresh: &Path, checked_in: &Path) -> io::Result<Vec<FixtureChange>> {
+    let (new_names, old_names) = (file_names(fresh)?, file_names(checked_in)?);
+    let mut changes = Vec::new();
+    for name in new_names.union(&old_names) {
+        let kind = match (old_names.contains(name), new_names.contains(name)) {
+            (false, _) => ChangeKind::Added,
+            (_, false) => ChangeKind::Removed,
+            _ => {
+                let (old, new) = (fs::read(checked_in.join(name))?, fs::read(fresh.join(name))?);
+                if old == new {
+                    continue;
+                }
+                if old.len() == new.len() {
+                    ChangeKind::Contents
+                } else {
+                    ChangeKind::Size { old: old.len() as u64, new: new.len() as u64 }
+                }
+            }
+        };
+        changes.push(FixtureChange { name: name.clone(), kind });
+    }
+    Ok(changes)
+}
+
+// Stages every new file beside its target before renaming any into place
+fn apply_changes(fresh: &Path, checked_in: &Path, changes: &[FixtureChange]) -> io::Result<()> {
+    fs::create_dir_all(checked_in)?;
+    let staged = |name: &str| checked_in.join(format!(".{}.new", name));
+    for change in changes.iter().filter(|change| change.kind != ChangeKind::Removed) {
+        fs::copy(fresh.join(&change.name), staged(&change.name))?;
+    }
+    for change in changes {
+        match change.kind {
+            ChangeKind::Removed => fs::remove_file(checked_in.join(&change.name))?,
+            _ => fs::rename(staged(&change.name), checked_in.join(&change.name))?,
+        }
+    }
+    Ok(())
+}
+
+// What regenerating would change in `checked_in`, applied when `apply` is set.
+// An empty list means the fixtures match the current build.
+pub fn regen_fixtures(checked_in: &Path, apply: bool) -> Result<Vec<FixtureChange>, FixtureError> {
+    let scratch = std::env::temp_dir().join(format!("fibonacci-fixtures-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&scratch);
+    let result = write_fixtures(&scratch).and_then(|()| {
+        let changes = compare(&scratch, checked_in)?;
+        if apply {
+            apply_changes(&scratch, checked_in, &changes)?;
+        }
+        Ok(changes)
+    });
+    let _ = fs::remove_dir_all(&scratch);
+    result
+}
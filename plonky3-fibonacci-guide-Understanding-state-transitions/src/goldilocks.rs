@@ -0,0 +1,102 @@
+// FibonacciAir over Goldilocks (p = 2^64 - 2^32 + 1) instead of BabyBear. The
+// AIR and trace generator are already generic over the field; what changes is
+// the config: Poseidon2 over 8 Goldilocks elements, 4-element digests, and a
+// degree-2 extension, which is as large as BabyBear's degree-4 one.
+//
+// Everything else in the crate (proof files, AIR ids, the journal) stays on
+// BabyBear; this module proves and verifies plain p3 proofs.
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::Field;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::TwoAdicFriPcs;
+use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::cost::SecurityLevel;
+use crate::trace::SizingError;
+use crate::{DEFAULT_CONFIG_SEED, FibonacciAir, constants_rng, fri_params_for, generate_fibonacci_trace};
+
+pub type GoldilocksPerm = Poseidon2Goldilocks<8>;
+pub type GoldilocksHash = PaddingFreeSponge<GoldilocksPerm, 8, 4, 4>;
+pub type GoldilocksCompress = TruncatedPermutation<GoldilocksPerm, 2, 4, 8>;
+pub type GoldilocksValMmcs = MerkleTreeMmcs<
+    <Goldilocks as Field>::Packing,
+    <Goldilocks as Field>::Packing,
+    GoldilocksHash,
+    GoldilocksCompress,
+    4,
+>;
+pub type GoldilocksChallenge = BinomialExtensionField<Goldilocks, 2>;
+pub type GoldilocksChallengeMmcs = ExtensionMmcs<Goldilocks, GoldilocksChallenge, GoldilocksValMmcs>;
+pub type GoldilocksChallenger = DuplexChallenger<Goldilocks, GoldilocksPerm, 8, 4>;
+pub type GoldilocksDft = Radix2DitParallel<Goldilocks>;
+pub type GoldilocksPcs = TwoAdicFriPcs<Goldilocks, GoldilocksDft, GoldilocksValMmcs, GoldilocksChallengeMmcs>;
+pub type GoldilocksConfig = StarkConfig<GoldilocksPcs, GoldilocksChallenge, GoldilocksChallenger>;
+
+// create_config_with_seed for Goldilocks: same seed handling and FRI parameters
+pub fn create_goldilocks_config(seed: u64) -> GoldilocksConfig {
+    let mut rng = constants_rng(seed);
+    let perm = GoldilocksPerm::new_from_rng_128(&mut rng);
+    let hash = GoldilocksHash::new(perm.clone());
+    let compress = GoldilocksCompress::new(perm.clone());
+    let val_mmcs = GoldilocksValMmcs::new(hash, compress);
+    let challenge_mmcs = GoldilocksChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = GoldilocksPcs::new(GoldilocksDft::default(), val_mmcs, fri_params);
+    GoldilocksConfig::new(pcs, GoldilocksChallenger::new(perm))
+}
+
+// prove_fibonacci over Goldilocks, returning the public values
+// [F(0), F(1), F(num_steps), num_steps]
+pub fn prove_fibonacci_goldilocks(
+    num_steps: usize,
+) -> Result<(Proof<GoldilocksConfig>, Vec<Goldilocks>), SizingError> {
+    let trace = generate_fibonacci_trace::<Goldilocks>(num_steps)?;
+    let config = create_goldilocks_config(DEFAULT_CONFIG_SEED);
+    let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_fibonacci_goldilocks(
+    proof: &Proof<GoldilocksConfig>,
+    public_values: &Vec<Goldilocks>,
+) -> Result<(), String> {
+    let config = create_goldilocks_config(DEFAULT_CONFIG_SEED);
+    verify(&config, &FibonacciAir, proof, public_values).map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::{PrimeCharacteristicRing, PrimeField64};
+
+    use super::*;
+    use crate::prove_fibonacci;
+
+    // F(93), the largest Fibonacci number below 2^64
+    const F_93: u64 = 12_200_160_415_121_876_738;
+
+    #[test]
+    fn f_93_is_exact_over_goldilocks_and_reduced_over_baby_bear() {
+        let (proof, public_values) = prove_fibonacci_goldilocks(93).unwrap();
+        assert_eq!(verify_fibonacci_goldilocks(&proof, &public_values), Ok(()));
+        assert_eq!(public_values[2].as_canonical_u64(), F_93);
+
+        let baby_bear = prove_fibonacci(93).unwrap().1[2].as_canonical_u64();
+        assert_eq!(baby_bear, F_93 % 2_013_265_921);
+        assert_ne!(baby_bear, F_93);
+    }
+
+    #[test]
+    fn a_wrong_claim_or_seed_is_refused() {
+        let (proof, public_values) = prove_fibonacci_goldilocks(93).unwrap();
+        let mut wrong = public_values.clone();
+        wrong[2] += Goldilocks::ONE;
+        assert!(verify_fibonacci_goldilocks(&proof, &wrong).is_err());
+        let other_seed = create_goldilocks_config(DEFAULT_CONFIG_SEED + 1);
+        assert!(verify(&other_seed, &FibonacciAir, &proof, &public_values).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+// FibonacciAir with a row index appended: idx is 0 on the first row and goes
+// up by one on every transition, padding included, so each row carries its
+// own position and a trace dump or failure report can name rows by it. The
+// Fibonacci constraints are FibonacciAir's own; FibonacciAir itself stays
+// without the column.
+use core::borrow::Borrow;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, FibonacciMeta, GeneratedTrace, SizingError};
+use crate::{
+    FibonacciTraceRow, NUM_FIBONACCI_COLS, NUM_FIBONACCI_PUBLIC_VALUES, eval_fibonacci, generate_fibonacci_trace,
+};
+
+const NUM_INDEXED_COLS: usize = NUM_FIBONACCI_COLS + 1;
+
+pub const INDEXED_FIBONACCI_AIR_ID: AirId = AirId::new("indexed_fibonacci", 1, 0, 0);
+
+#[derive(Debug, Clone)]
+pub struct IndexedFibonacciAir;
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct IndexedFibonacciRow<F> {
+    pub row: FibonacciTraceRow<F>,
+    pub idx: F,
+}
+
+impl<F> Borrow<IndexedFibonacciRow<F>> for [F] {
+    fn borrow(&self) -> &IndexedFibonacciRow<F> {
+        debug_assert_eq!(self.len(), NUM_INDEXED_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<IndexedFibonacciRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for IndexedFibonacciAir {
+    fn width(&self) -> usize {
+        NUM_INDEXED_COLS
+    }
+}
+
+impl AirLint for IndexedFibonacciAir {
+    fn num_public_values(&self) -> usize {
+        NUM_FIBONACCI_PUBLIC_VALUES
+    }
+}
+
+impl ProvableComputation for IndexedFibonacciAir {
+    fn air_id(&self) -> AirId {
+        INDEXED_FIBONACCI_AIR_ID
+    }
+}
+
+impl ColumnNames for IndexedFibonacciAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["a", "b", "is_real", "count", "idx"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for IndexedFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &IndexedFibonacciRow<AB::Var> = (*local).borrow();
+        let next: &IndexedFibonacciRow<AB::Var> = (*next).borrow();
+
+        eval_fibonacci(builder, &local.row, &next.row);
+
+        builder.when_first_row().assert_zero(local.idx.clone());
+        builder
+            .when_transition()
+            .assert_eq(next.idx.clone(), local.idx.clone() + AB::Expr::ONE);
+    }
+}
+
+// generate_fibonacci_trace with each row's index appended
+pub fn generate_indexed_fibonacci_trace<F: Field>(
+    num_steps: usize,
+) -> Result<GeneratedTrace<FibonacciMeta<F>, F>, SizingError> {
+    let trace = generate_fibonacci_trace::<F>(num_steps)?;
+    let values = trace
+        .matrix
+        .values
+        .chunks_exact(NUM_FIBONACCI_COLS)
+        .enumerate()
+        .flat_map(|(i, row)| row.iter().copied().chain([F::from_usize(i)]))
+        .collect();
+    Ok(GeneratedTrace { matrix: RowMajorMatrix::new(values, NUM_INDEXED_COLS), ..trace })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::cost::SecurityLevel;
+    use crate::validate::validate_trace;
+    use crate::{Val, create_default_config};
+
+    #[test]
+    fn each_row_holds_its_index() {
+        let trace = generate_indexed_fibonacci_trace::<Val>(20).unwrap();
+        for (i, row) in trace.matrix.values.chunks_exact(NUM_INDEXED_COLS).enumerate() {
+            assert_eq!(row[NUM_FIBONACCI_COLS], Val::from_usize(i));
+        }
+        assert_eq!(validate_trace(&IndexedFibonacciAir, &trace.matrix, &trace.public_values), Ok(()));
+    }
+
+    #[test]
+    fn a_skipped_index_is_refused() {
+        let mut trace = generate_indexed_fibonacci_trace::<Val>(20).unwrap();
+        trace.matrix.values[30 * NUM_INDEXED_COLS + NUM_FIBONACCI_COLS] += Val::ONE;
+        assert!(validate_trace(&IndexedFibonacciAir, &trace.matrix, &trace.public_values).is_err());
+    }
+
+    #[test]
+    fn a_duplicated_or_shifted_index_is_refused_and_the_honest_trace_proves() {
+        let trace = generate_indexed_fibonacci_trace::<Val>(100).unwrap();
+        let idx = |row: usize| row * NUM_INDEXED_COLS + NUM_FIBONACCI_COLS;
+        let mut duplicated = trace.matrix.clone();
+        duplicated.values[idx(6)] = duplicated.values[idx(5)];
+        assert!(validate_trace(&IndexedFibonacciAir, &duplicated, &trace.public_values).is_err());
+        let mut shifted = trace.matrix.clone();
+        for row in shifted.values.chunks_exact_mut(NUM_INDEXED_COLS) {
+            row[NUM_FIBONACCI_COLS] += Val::ONE;
+        }
+        assert!(validate_trace(&IndexedFibonacciAir, &shifted, &trace.public_values).is_err());
+
+        let config = create_default_config(SecurityLevel::Test);
+        let proof = prove_identified(&config, &IndexedFibonacciAir, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&config, &IndexedFibonacciAir, &proof, &trace.public_values), Ok(None));
+    }
+}
@@ -0,0 +1,228 @@
+// One verify for every form a Fibonacci proof arrives in: a decoded
+// IdentifiedProof, proof_file's bytes, those bytes as hex or armored text, or
+// a file holding them. The CLI, the Python bindings and library callers all
+// go through verify_input, and its error names the stage that failed, so a
+// missing file, a mangled paste and bytes that are not a proof read
+// differently.
+use core::fmt;
+use std::path::Path;
+
+use crate::air_id::IdentifiedProof;
+use crate::claims::FibonacciClaims;
+use crate::cost::CostParams;
+use crate::proof_file::{ARMOR_BEGIN, decode_hex, unarmor};
+use crate::provenance::ProvenanceMismatch;
+use crate::{
+    ConfigDescriptor, DEFAULT_CONFIG_SEED, PublicValues, VerifyError, verify_described_with_provenance,
+    verify_untrusted,
+};
+
+#[derive(Clone, Copy)]
+pub enum ProofInput<'a> {
+    // Checked under the default config, as verify_fibonacci does
+    Proof(&'a IdentifiedProof),
+    // proof_file's encoding, checked under the descriptor saved with it
+    Bytes(&'a [u8]),
+    // The same bytes as hex digits
+    Hex(&'a str),
+    // proof_to_string's output
+    Armored(&'a str),
+    // A file holding proof_file's bytes
+    File(&'a Path),
+}
+
+impl<'a> From<&'a IdentifiedProof> for ProofInput<'a> {
+    fn from(proof: &'a IdentifiedProof) -> Self {
+        Self::Proof(proof)
+    }
+}
+
+impl<'a> From<&'a [u8]> for ProofInput<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for ProofInput<'a> {
+    fn from(bytes: &'a Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+// Armored text is told apart by its header; anything else is taken as hex
+impl<'a> From<&'a str> for ProofInput<'a> {
+    fn from(text: &'a str) -> Self {
+        if text.trim_start().starts_with(ARMOR_BEGIN) { Self::Armored(text) } else { Self::Hex(text) }
+    }
+}
+
+impl<'a> From<&'a Path> for ProofInput<'a> {
+    fn from(path: &'a Path) -> Self {
+        Self::File(path)
+    }
+}
+
+// Where verify_input gave up, in the order the stages run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeStage {
+    // Reading the file
+    File,
+    // Turning hex or armored text into bytes
+    Text,
+    // The envelope: format version, config fingerprint and bincode
+    Envelope,
+    // The claims, the proof's shape, or the verifier itself
+    Verification,
+}
+
+impl fmt::Display for DecodeStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::File => "reading the file",
+            Self::Text => "decoding the text",
+            Self::Envelope => "decoding the proof",
+            Self::Verification => "verifying",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofInputError {
+    Read(String),
+    // Text that is not hex, or armor with a broken header, trailer or checksum
+    Text(String),
+    Verify(VerifyError),
+}
+
+impl ProofInputError {
+    pub fn stage(&self) -> DecodeStage {
+        match self {
+            Self::Read(_) => DecodeStage::File,
+            Self::Text(_) => DecodeStage::Text,
+            Self::Verify(VerifyError::Decode(_) | VerifyError::ConfigMismatch { .. }) => DecodeStage::Envelope,
+            Self::Verify(_) => DecodeStage::Verification,
+        }
+    }
+}
+
+impl fmt::Display for ProofInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(msg) | Self::Text(msg) => write!(f, "{}: {}", self.stage(), msg),
+            Self::Verify(e) => write!(f, "{}: {}", self.stage(), e),
+        }
+    }
+}
+
+impl std::error::Error for ProofInputError {}
+
+impl From<VerifyError> for ProofInputError {
+    fn from(e: VerifyError) -> Self {
+        Self::Verify(e)
+    }
+}
+
+// What verify_input established about a proof that verified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Verified {
+    // The config it was checked under
+    pub descriptor: ConfigDescriptor,
+    // Set when a differently built prover made it; a hint for when a later
+    // proof fails, not a reason to reject this one
+    pub provenance: Option<ProvenanceMismatch>,
+}
+
+// Verifies `input` against [F(0), F(1), F(num_steps), num_steps]
+pub fn verify_input<'a>(
+    input: impl Into<ProofInput<'a>>,
+    public_values: &PublicValues,
+) -> Result<Verified, ProofInputError> {
+    let bytes = match input.into() {
+        ProofInput::Proof(proof) => {
+            let claims = FibonacciClaims::from_public_values(public_values).map_err(VerifyError::from)?;
+            let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+            let provenance = verify_untrusted(&descriptor, proof, &claims)?;
+            return Ok(Verified { descriptor, provenance });
+        }
+        ProofInput::Bytes(bytes) => return verified(bytes, public_values),
+        ProofInput::Hex(hex) => {
+            let digits: String = hex.split_whitespace().collect();
+            decode_hex(&digits).ok_or_else(|| ProofInputError::Text("not hex digits".to_string()))?
+        }
+        ProofInput::Armored(text) => unarmor(text).map_err(|e| ProofInputError::Text(e.to_string()))?.1,
+        ProofInput::File(path) => {
+            std::fs::read(path).map_err(|e| ProofInputError::Read(format!("{}: {}", path.display(), e)))?
+        }
+    };
+    verified(&bytes, public_values)
+}
+
+fn verified(bytes: &[u8], public_values: &PublicValues) -> Result<Verified, ProofInputError> {
+    let (descriptor, provenance) = verify_described_with_provenance(bytes, public_values)?;
+    Ok(Verified { descriptor, provenance })
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::proof_file::{encode_proof, proof_to_string};
+    use crate::provenance::PROVENANCE;
+    use crate::{FIBONACCI_AIR_ID, Val, prove_fibonacci};
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn every_form_of_a_valid_proof_verifies() {
+        let (proof, public_values) = prove_fibonacci(15).unwrap();
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let bytes = encode_proof(&descriptor, &proof).unwrap();
+        let armored = proof_to_string(&FIBONACCI_AIR_ID, &descriptor, &proof).unwrap();
+        let hex = to_hex(&bytes);
+        let path = std::env::temp_dir().join(format!("fibonacci-input-{}.proof", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let verified = Verified { descriptor, provenance: None };
+        assert_eq!(verify_input(&proof, &public_values), Ok(verified));
+        assert_eq!(verify_input(&bytes, &public_values), Ok(verified));
+        assert_eq!(verify_input(hex.as_str(), &public_values), Ok(verified));
+        assert_eq!(verify_input(armored.as_str(), &public_values), Ok(verified));
+        assert_eq!(verify_input(path.as_path(), &public_values), Ok(verified));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn a_proof_from_another_build_verifies_with_its_mismatch() {
+        let (mut proof, public_values) = prove_fibonacci(15).unwrap();
+        let expected = PROVENANCE.digest();
+        proof.provenance = expected ^ 1;
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let bytes = encode_proof(&descriptor, &proof).unwrap();
+        let verified = Verified { descriptor, provenance: Some(ProvenanceMismatch { found: expected ^ 1, expected }) };
+        assert_eq!(verify_input(&proof, &public_values), Ok(verified));
+        assert_eq!(verify_input(&bytes, &public_values), Ok(verified));
+    }
+
+    #[test]
+    fn a_malformed_instance_of_each_form_names_its_stage() {
+        let (proof, public_values) = prove_fibonacci(15).unwrap();
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let bytes = encode_proof(&descriptor, &proof).unwrap();
+        let armored = proof_to_string(&FIBONACCI_AIR_ID, &descriptor, &proof).unwrap();
+        let broken_armor = armored.replacen('=', "=f", 1);
+        let missing = std::env::temp_dir().join("fibonacci-input-missing.proof");
+        let mut wrong_claim = public_values.clone();
+        wrong_claim[2] += Val::ONE;
+
+        let stage = |result: Result<Verified, ProofInputError>| result.unwrap_err().stage();
+        assert_eq!(stage(verify_input(&proof, &wrong_claim)), DecodeStage::Verification);
+        assert_eq!(stage(verify_input(&bytes[..bytes.len() - 1], &public_values)), DecodeStage::Envelope);
+        assert_eq!(stage(verify_input("not hex", &public_values)), DecodeStage::Text);
+        assert_eq!(stage(verify_input(broken_armor.as_str(), &public_values)), DecodeStage::Text);
+        assert_eq!(stage(verify_input(missing.as_path(), &public_values)), DecodeStage::File);
+    }
+}
@@ -0,0 +1,352 @@
+// An append-only log of produced proofs. Each record carries the Poseidon2
+// digest of the record before it, so altering or dropping any record breaks
+// the link into the next one, and the head digest pins the last record. Each
+// proof is saved next to the journal as a proof_file envelope, which
+// verify_journal decodes and verifies again.
+use core::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_symmetric::CryptographicHasher;
+
+use crate::air_id::IdentifiedProof;
+use crate::canonical::parse_canonical;
+use crate::proof_file::{ProofFileError, load_described_proof_from_reader, save_proof};
+use crate::{ConfigDescriptor, DEFAULT_CONFIG_SEED, MyHash, Perm, Val, constants_rng, verify_described};
+
+pub type Digest = [Val; 8];
+
+// prev of the first record
+pub const GENESIS: Digest = [Val::ZERO; 8];
+
+pub(crate) fn hasher() -> MyHash {
+    let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+    MyHash::new(Perm::new_from_rng_128(&mut rng))
+}
+
+pub(crate) fn digest_hex(digest: &Digest) -> String {
+    digest.iter().map(|v| format!("{:08x}", v.as_canonical_u64())).collect()
+}
+
+pub(crate) fn parse_digest(hex: &str) -> Option<Digest> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut digest = GENESIS;
+    for (i, slot) in digest.iter_mut().enumerate() {
+        let value = u64::from_str_radix(hex.get(8 * i..8 * i + 8)?, 16).ok()?;
+        *slot = parse_canonical(value).ok()?;
+    }
+    Some(digest)
+}
+
+// Binds the proof's AIR id, degree and trace and quotient commitments
+pub fn proof_digest(proof: &IdentifiedProof) -> Digest {
+    let trace: [Val; 8] = proof.proof.commitments.trace.clone().into();
+    let quotient: [Val; 8] = proof.proof.commitments.quotient_chunks.clone().into();
+    let input = [proof.air_id, Val::from_usize(proof.proof.degree_bits)]
+        .into_iter()
+        .chain(trace)
+        .chain(quotient);
+    hasher().hash_iter(input)
+}
+
+fn line_digest(line: &str) -> Digest {
+    hasher().hash_iter(line.bytes().map(Val::from_u8))
+}
+
+// One line of the journal:
+//   timestamp,air_id,public values separated by ';',proof digest,proof file,prev digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalRecord {
+    // Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub air_id: Val,
+    pub public_values: Vec<Val>,
+    pub proof_digest: Digest,
+    // The saved proof's file name, relative to the journal's directory
+    pub proof_file: String,
+    pub prev: Digest,
+}
+
+impl JournalRecord {
+    fn to_line(&self) -> String {
+        let public_values: Vec<String> =
+            self.public_values.iter().map(|v| v.as_canonical_u64().to_string()).collect();
+        format!(
+            "{},{},{},{},{},{}",
+            self.timestamp,
+            self.air_id.as_canonical_u64(),
+            public_values.join(";"),
+            digest_hex(&self.proof_digest),
+            self.proof_file,
+            digest_hex(&self.prev)
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split(',').collect();
+        let &[timestamp, air_id, public_values, proof_digest, proof_file, prev] = fields.as_slice() else {
+            return None;
+        };
+        if proof_file.is_empty() || proof_file.contains(['/', '\\']) {
+            return None;
+        }
+        // A journal is only written by append, so a value of p or more means a
+        // corrupt line and is reported as Malformed like any other
+        let element = |s: &str| s.parse::<u64>().ok().and_then(|v| parse_canonical(v).ok());
+        let public_values = if public_values.is_empty() {
+            vec![]
+        } else {
+            public_values.split(';').map(element).collect::<Option<_>>()?
+        };
+        Some(Self {
+            timestamp: timestamp.parse().ok()?,
+            air_id: element(air_id)?,
+            public_values,
+            proof_digest: parse_digest(proof_digest)?,
+            proof_file: proof_file.to_string(),
+            prev: parse_digest(prev)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    // Record `index` (0-based) is not a well-formed record line
+    Malformed { index: usize },
+    // Record `index` does not carry the digest of record index - 1
+    BrokenChain { index: usize },
+    // The chain is intact but ends somewhere other than the pinned head: the
+    // last record was altered, or records were appended or removed after it
+    HeadMismatch { records: usize },
+    // Record `index`'s proof file is missing or does not decode
+    ProofFile { index: usize, error: ProofFileError },
+    // Record `index`'s proof file holds a different proof than it recorded
+    ProofDigestMismatch { index: usize },
+    // Record `index`'s proof no longer verifies against its public values
+    ProofRejected { index: usize, msg: String },
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not access journal: {}", e),
+            Self::Malformed { index } => write!(f, "record {} is malformed", index),
+            Self::BrokenChain { index: 0 } => write!(f, "record 0 does not start from the genesis digest"),
+            Self::BrokenChain { index } => write!(
+                f,
+                "record {} does not link to record {}: one was altered or records between them removed",
+                index,
+                index - 1
+            ),
+            Self::HeadMismatch { records } => {
+                write!(f, "the {} records do not end at the pinned head", records)
+            }
+            Self::ProofFile { index, error } => write!(f, "record {}: proof file: {}", index, error),
+            Self::ProofDigestMismatch { index } => {
+                write!(f, "record {}: proof file holds a different proof than was journaled", index)
+            }
+            Self::ProofRejected { index, msg } => write!(f, "record {}: proof rejected: {}", index, msg),
+        }
+    }
+}
+
+impl std::error::Error for JournalError {}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+// Re-checks every link, returning the record count and the head digest.
+// Links alone cannot show a changed last record; verify_journal also compares
+// the head with one kept elsewhere.
+pub fn journal_head(path: &Path) -> Result<(usize, Digest), JournalError> {
+    let (records, head) = read_records(path)?;
+    Ok((records.len(), head))
+}
+
+fn read_records(path: &Path) -> Result<(Vec<JournalRecord>, Digest), JournalError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut head = GENESIS;
+    let mut records = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let record = JournalRecord::from_line(&line).ok_or(JournalError::Malformed { index })?;
+        if record.prev != head {
+            return Err(JournalError::BrokenChain { index });
+        }
+        head = line_digest(&line);
+        records.push(record);
+    }
+    Ok((records, head))
+}
+
+// The full check: every link, the head against `pinned` (as Journal::head
+// returned it after the last append), then each record's proof file decoded,
+// matched against its digest and verified against its public values under the
+// config saved with it. Returns the record count.
+pub fn verify_journal(path: &Path, pinned: &Digest) -> Result<usize, JournalError> {
+    let (records, head) = read_records(path)?;
+    if head != *pinned {
+        return Err(JournalError::HeadMismatch { records: records.len() });
+    }
+    let dir = path.parent().unwrap_or(Path::new("."));
+    for (index, record) in records.iter().enumerate() {
+        let bytes = std::fs::read(dir.join(&record.proof_file))
+            .map_err(|e| JournalError::ProofFile { index, error: e.into() })?;
+        let (_, proof): (ConfigDescriptor, IdentifiedProof) = load_described_proof_from_reader(bytes.as_slice())
+            .map_err(|error| JournalError::ProofFile { index, error })?;
+        if proof_digest(&proof) != record.proof_digest {
+            return Err(JournalError::ProofDigestMismatch { index });
+        }
+        verify_described(&bytes, &record.public_values)
+            .map_err(|e| JournalError::ProofRejected { index, msg: e.to_string() })?;
+    }
+    Ok(records.len())
+}
+
+pub struct Journal {
+    path: PathBuf,
+    records: usize,
+    head: Digest,
+}
+
+impl Journal {
+    // Opens an existing journal, refusing one whose chain is already broken,
+    // or starts a new one
+    pub fn open(path: &Path) -> Result<Self, JournalError> {
+        let (records, head) = match journal_head(path) {
+            Ok(found) => found,
+            Err(JournalError::Io(e)) if e.kind() == io::ErrorKind::NotFound => (0, GENESIS),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path: path.to_path_buf(), records, head })
+    }
+
+    // The digest of the last record; keep it outside the journal to pass to
+    // verify_journal
+    pub fn head(&self) -> Digest {
+        self.head
+    }
+
+    // Saves the proof under `descriptor` as <journal name>.<record index>.proof
+    // beside the journal, then appends its record. Returns the new head.
+    pub fn append(
+        &mut self,
+        descriptor: &ConfigDescriptor,
+        proof: &IdentifiedProof,
+        public_values: &[Val],
+    ) -> Result<Digest, JournalError> {
+        let journal_name = self.path.file_name().map_or("journal".into(), |name| name.to_string_lossy());
+        let proof_file = format!("{}.{}.proof", journal_name, self.records);
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        save_proof(&dir.join(&proof_file), descriptor, proof)
+            .map_err(|error| JournalError::ProofFile { index: self.records, error })?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let record = JournalRecord {
+            timestamp,
+            air_id: proof.air_id,
+            public_values: public_values.to_vec(),
+            proof_digest: proof_digest(proof),
+            proof_file,
+            prev: self.head,
+        };
+        let line = record.to_line();
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        self.records += 1;
+        self.head = line_digest(&line);
+        Ok(self.head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::cost::CostParams;
+    use crate::prove_fibonacci;
+
+    // A journal of three proofs of F(20) in its own scratch directory, with the
+    // head its writer pinned
+    fn journal(name: &str) -> (PathBuf, Digest) {
+        let dir = std::env::temp_dir().join(format!("fibonacci-journal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("journal.log");
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let (proof, public_values) = prove_fibonacci(20).unwrap();
+        let mut journal = Journal::open(&path).unwrap();
+        for _ in 0..3 {
+            journal.append(&descriptor, &proof, &public_values).unwrap();
+        }
+        (path, journal.head())
+    }
+
+    fn rewrite_last_record(path: &Path, edit: impl FnOnce(&mut JournalRecord)) {
+        let contents = fs::read_to_string(path).unwrap();
+        let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+        let last = lines.len() - 1;
+        let mut record = JournalRecord::from_line(&lines[last]).unwrap();
+        edit(&mut record);
+        lines[last] = record.to_line();
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn an_untouched_journal_verifies_against_its_head() {
+        let (path, head) = journal("intact");
+        let verified = verify_journal(&path, &head);
+        let reopened = Journal::open(&path).map(|journal| journal.head());
+        cleanup(&path);
+        assert_eq!(verified.unwrap(), 3);
+        assert_eq!(reopened.unwrap(), head);
+    }
+
+    #[test]
+    fn an_edited_last_record_misses_the_pinned_head() {
+        let (path, head) = journal("last");
+        rewrite_last_record(&path, |record| record.timestamp += 1);
+        let verified = verify_journal(&path, &head);
+        cleanup(&path);
+        assert!(matches!(verified, Err(JournalError::HeadMismatch { records: 3 })), "{:?}", verified);
+    }
+
+    #[test]
+    fn a_false_claim_fails_even_under_a_matching_head() {
+        let (path, _) = journal("claim");
+        rewrite_last_record(&path, |record| record.public_values[2] += Val::ONE);
+        let (_, head) = journal_head(&path).unwrap();
+        let verified = verify_journal(&path, &head);
+        cleanup(&path);
+        assert!(matches!(verified, Err(JournalError::ProofRejected { index: 2, .. })), "{:?}", verified);
+    }
+
+    #[test]
+    fn a_missing_or_swapped_proof_file_is_reported() {
+        let (path, head) = journal("files");
+        let dir = path.parent().unwrap();
+        let (other, _) = prove_fibonacci(21).unwrap();
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        save_proof(&dir.join("journal.log.1.proof"), &descriptor, &other).unwrap();
+        let swapped = verify_journal(&path, &head);
+        fs::remove_file(dir.join("journal.log.0.proof")).unwrap();
+        let missing = verify_journal(&path, &head);
+        cleanup(&path);
+        assert!(matches!(swapped, Err(JournalError::ProofDigestMismatch { index: 1 })), "{:?}", swapped);
+        assert!(matches!(missing, Err(JournalError::ProofFile { index: 0, .. })), "{:?}", missing);
+    }
+}
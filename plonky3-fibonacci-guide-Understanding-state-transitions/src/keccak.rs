@@ -0,0 +1,49 @@
+// MyConfig with Keccak-256 Merkle trees in place of Poseidon2, for verifiers
+// that can only hash bytes (an EVM contract has a Keccak precompile but no
+// BabyBear Poseidon2). Field elements are serialized to bytes before hashing,
+// digests are 32 bytes, and the challenger is the SerializingChallenger of
+// the challenger module so the transcript is Keccak too. The field, the
+// extension and the FRI parameters are MyConfig's.
+//
+// As with the goldilocks module, proof files, AIR ids and the journal stay on
+// the Poseidon2 config; this module proves and verifies plain p3 proofs.
+use p3_commit::ExtensionMmcs;
+use p3_fri::TwoAdicFriPcs;
+use p3_keccak::Keccak256Hash;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::challenger::KeccakChallenger;
+use crate::cost::SecurityLevel;
+use crate::trace::SizingError;
+use crate::{Challenge, Dft, FibonacciAir, Val, fri_params_for, generate_fibonacci_trace};
+
+pub type KeccakHash = SerializingHasher<Keccak256Hash>;
+pub type KeccakCompress = CompressionFunctionFromHasher<Keccak256Hash, 2, 32>;
+pub type KeccakValMmcs = MerkleTreeMmcs<Val, u8, KeccakHash, KeccakCompress, 32>;
+pub type KeccakChallengeMmcs = ExtensionMmcs<Val, Challenge, KeccakValMmcs>;
+pub type KeccakPcs = TwoAdicFriPcs<Val, Dft, KeccakValMmcs, KeccakChallengeMmcs>;
+pub type KeccakConfig = StarkConfig<KeccakPcs, Challenge, KeccakChallenger>;
+
+// Keccak has no constants to sample, so unlike create_config_with_seed this
+// takes no seed
+pub fn create_keccak_config() -> KeccakConfig {
+    let val_mmcs = KeccakValMmcs::new(KeccakHash::new(Keccak256Hash {}), KeccakCompress::new(Keccak256Hash {}));
+    let challenge_mmcs = KeccakChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = KeccakPcs::new(Dft::default(), val_mmcs, fri_params);
+    KeccakConfig::new(pcs, KeccakChallenger::from_hasher(vec![], Keccak256Hash {}))
+}
+
+// prove_fibonacci under the Keccak config, returning the public values
+// [F(0), F(1), F(num_steps), num_steps]
+pub fn prove_fibonacci_keccak(num_steps: usize) -> Result<(Proof<KeccakConfig>, Vec<Val>), SizingError> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps)?;
+    let proof = prove(&create_keccak_config(), &FibonacciAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_fibonacci_keccak(proof: &Proof<KeccakConfig>, public_values: &Vec<Val>) -> Result<(), String> {
+    verify(&create_keccak_config(), &FibonacciAir, proof, public_values).map_err(|e| format!("{:?}", e))
+}
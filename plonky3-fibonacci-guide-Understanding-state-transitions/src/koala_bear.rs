@@ -0,0 +1,121 @@
+// FibonacciAir over KoalaBear (p = 2^31 - 2^24 + 1) instead of BabyBear. Both
+// are 31-bit fields with a degree-4 extension and a width-16 Poseidon2, so the
+// config is BabyBear's with the field swapped; nothing else in the crate
+// needs to change for the AIR or the trace generator.
+//
+// As with the goldilocks module, proof files, AIR ids and the journal stay on
+// BabyBear; this module proves and verifies plain p3 proofs.
+use std::time::{Duration, Instant};
+
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::Field;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::TwoAdicFriPcs;
+use p3_koala_bear::{KoalaBear, Poseidon2KoalaBear};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::cost::SecurityLevel;
+use crate::trace::SizingError;
+use crate::{
+    DEFAULT_CONFIG_SEED, FibonacciAir, Val, constants_rng, create_default_config, fri_params_for,
+    generate_fibonacci_trace,
+};
+
+pub type KoalaBearPerm = Poseidon2KoalaBear<16>;
+pub type KoalaBearHash = PaddingFreeSponge<KoalaBearPerm, 16, 8, 8>;
+pub type KoalaBearCompress = TruncatedPermutation<KoalaBearPerm, 2, 8, 16>;
+pub type KoalaBearValMmcs = MerkleTreeMmcs<
+    <KoalaBear as Field>::Packing,
+    <KoalaBear as Field>::Packing,
+    KoalaBearHash,
+    KoalaBearCompress,
+    8,
+>;
+pub type KoalaBearChallenge = BinomialExtensionField<KoalaBear, 4>;
+pub type KoalaBearChallengeMmcs = ExtensionMmcs<KoalaBear, KoalaBearChallenge, KoalaBearValMmcs>;
+pub type KoalaBearChallenger = DuplexChallenger<KoalaBear, KoalaBearPerm, 16, 8>;
+pub type KoalaBearDft = Radix2DitParallel<KoalaBear>;
+pub type KoalaBearPcs = TwoAdicFriPcs<KoalaBear, KoalaBearDft, KoalaBearValMmcs, KoalaBearChallengeMmcs>;
+pub type KoalaBearConfig = StarkConfig<KoalaBearPcs, KoalaBearChallenge, KoalaBearChallenger>;
+
+// create_config_with_seed for KoalaBear: same seed handling and FRI parameters
+pub fn create_koala_bear_config(seed: u64) -> KoalaBearConfig {
+    let mut rng = constants_rng(seed);
+    let perm = KoalaBearPerm::new_from_rng_128(&mut rng);
+    let hash = KoalaBearHash::new(perm.clone());
+    let compress = KoalaBearCompress::new(perm.clone());
+    let val_mmcs = KoalaBearValMmcs::new(hash, compress);
+    let challenge_mmcs = KoalaBearChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = KoalaBearPcs::new(KoalaBearDft::default(), val_mmcs, fri_params);
+    KoalaBearConfig::new(pcs, KoalaBearChallenger::new(perm))
+}
+
+// prove_fibonacci over KoalaBear, returning the public values
+// [F(0), F(1), F(num_steps), num_steps]
+pub fn prove_fibonacci_koala_bear(
+    num_steps: usize,
+) -> Result<(Proof<KoalaBearConfig>, Vec<KoalaBear>), SizingError> {
+    let trace = generate_fibonacci_trace::<KoalaBear>(num_steps)?;
+    let config = create_koala_bear_config(DEFAULT_CONFIG_SEED);
+    let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_fibonacci_koala_bear(
+    proof: &Proof<KoalaBearConfig>,
+    public_values: &Vec<KoalaBear>,
+) -> Result<(), String> {
+    let config = create_koala_bear_config(DEFAULT_CONFIG_SEED);
+    verify(&config, &FibonacciAir, proof, public_values).map_err(|e| format!("{:?}", e))
+}
+
+// Proving time for the same num_steps Fibonacci trace on BabyBear and on
+// KoalaBear, trace generation excluded
+pub fn compare_fields(num_steps: usize) -> Result<[(&'static str, Duration); 2], SizingError> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps)?;
+    let config = create_default_config(SecurityLevel::Test);
+    let start = Instant::now();
+    prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+    let baby_bear = start.elapsed();
+
+    let trace = generate_fibonacci_trace::<KoalaBear>(num_steps)?;
+    let config = create_koala_bear_config(DEFAULT_CONFIG_SEED);
+    let start = Instant::now();
+    prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+    let koala_bear = start.elapsed();
+
+    Ok([("BabyBear", baby_bear), ("KoalaBear", koala_bear)])
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::{PrimeCharacteristicRing, PrimeField64};
+
+    use super::*;
+
+    #[test]
+    fn the_koala_bear_proof_round_trips_and_reduces_mod_its_own_prime() {
+        let (proof, public_values) = prove_fibonacci_koala_bear(93).unwrap();
+        assert_eq!(verify_fibonacci_koala_bear(&proof, &public_values), Ok(()));
+        // F(93) < 2^64
+        let f_93: u64 = 12_200_160_415_121_876_738;
+        assert_eq!(public_values[2].as_canonical_u64(), f_93 % KoalaBear::ORDER_U64);
+
+        let mut wrong = public_values.clone();
+        wrong[2] += KoalaBear::ONE;
+        assert!(verify_fibonacci_koala_bear(&proof, &wrong).is_err());
+        let other_seed = create_koala_bear_config(DEFAULT_CONFIG_SEED + 1);
+        assert!(verify(&other_seed, &FibonacciAir, &proof, &public_values).is_err());
+    }
+
+    #[test]
+    fn compare_fields_times_both() {
+        let timings = compare_fields(1 << 8).unwrap();
+        assert_eq!(timings.map(|(field, _)| field), ["BabyBear", "KoalaBear"]);
+    }
+}
@@ -0,0 +1,232 @@
+use core::borrow::Borrow;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, GeneratedTrace, SizingError, trace_height, trace_len};
+
+// when_last_row selects the physical last row, i.e. row height - 1 of the
+// power-of-two trace. Unless the trace is exactly full, that is a padding
+// row, and a value bound there says nothing about the real computation.
+
+// Binds `value` on the physical last row. Only meaningful when the trace is
+// exactly full, or when padding provably carries the value forward unchanged.
+pub fn bind_physical_last_row<AB: AirBuilder>(builder: &mut AB, value: AB::Expr, public: AB::Expr) {
+    builder.when_last_row().assert_eq(value, public);
+}
+
+// Constrains `is_real` to a prefix of ones: boolean, 1 on the first row and
+// never switching back on once a padding row is reached
+pub fn constrain_real_prefix<AB: AirBuilder>(builder: &mut AB, is_real: AB::Expr, next_is_real: AB::Expr) {
+    builder.when_first_row().assert_one(is_real.clone());
+    constrain_possibly_empty_prefix(builder, is_real, next_is_real);
+}
+
+// constrain_real_prefix without the first row: an all-padding trace, standing
+// for an empty computation, is allowed
+pub fn constrain_possibly_empty_prefix<AB: AirBuilder>(
+    builder: &mut AB,
+    is_real: AB::Expr,
+    next_is_real: AB::Expr,
+) {
+    builder.assert_bool(is_real.clone());
+    builder.when_transition().assert_zero(next_is_real * (AB::Expr::ONE - is_real));
+}
+
+// Binds `value` on the last row whose is_real flag is set, wherever the padding
+// starts: the real -> padding boundary, or the physical last row if the trace
+// is exactly full. `is_real` must be constrained with constrain_real_prefix.
+pub fn bind_last_real_row<AB: AirBuilder>(
+    builder: &mut AB,
+    is_real: AB::Expr,
+    next_is_real: AB::Expr,
+    value: AB::Expr,
+    public: AB::Expr,
+) {
+    let diff = value - public;
+    builder
+        .when_transition()
+        .assert_zero(is_real.clone() * (AB::Expr::ONE - next_is_real) * diff.clone());
+    builder.when_last_row().assert_zero(is_real * diff);
+}
+
+// Which row LastRowDemoAir binds its public value to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastRowBinding {
+    Physical,
+    LastReal,
+}
+
+// Counts real rows (x = 1, 2, 3, ...) and binds the count to public value 0.
+// Padding rows leave x unconstrained, so the two bindings diverge on padded traces.
+#[derive(Debug, Clone)]
+pub struct LastRowDemoAir {
+    pub binding: LastRowBinding,
+}
+
+const NUM_LAST_ROW_COLS: usize = 2;
+
+// Both bindings share an id: the binding is chosen by the verifier, not
+// carried in the proof
+pub const LAST_ROW_AIR_ID: AirId = AirId::new("last-row-demo", 1, 0, 0);
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct LastRowDemoRow<F> {
+    pub is_real: F,
+    pub x: F,
+}
+
+impl<F> Borrow<LastRowDemoRow<F>> for [F] {
+    fn borrow(&self) -> &LastRowDemoRow<F> {
+        debug_assert_eq!(self.len(), NUM_LAST_ROW_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<LastRowDemoRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for LastRowDemoAir {
+    fn width(&self) -> usize {
+        NUM_LAST_ROW_COLS
+    }
+}
+
+impl AirLint for LastRowDemoAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl ProvableComputation for LastRowDemoAir {
+    fn air_id(&self) -> AirId {
+        LAST_ROW_AIR_ID
+    }
+}
+
+impl ColumnNames for LastRowDemoAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["is_real", "x"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for LastRowDemoAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &LastRowDemoRow<AB::Var> = (*local).borrow();
+        let next: &LastRowDemoRow<AB::Var> = (*next).borrow();
+        let count: AB::Expr = builder.public_values()[0].into();
+
+        let is_real: AB::Expr = local.is_real.clone().into();
+        let next_is_real: AB::Expr = next.is_real.clone().into();
+        constrain_real_prefix(builder, is_real.clone(), next_is_real.clone());
+
+        builder.when_first_row().assert_one(local.x.clone());
+        builder
+            .when_transition()
+            .assert_zero(next_is_real.clone() * (next.x.clone() - local.x.clone() - AB::Expr::ONE));
+
+        let x: AB::Expr = local.x.clone().into();
+        match self.binding {
+            LastRowBinding::Physical => bind_physical_last_row(builder, x, count),
+            LastRowBinding::LastReal => bind_last_real_row(builder, is_real, next_is_real, x, count),
+        }
+    }
+}
+
+// How a generator fills the rows past the real ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingStrategy {
+    // Zero rows: is_real = 0 and x = 0
+    Zero,
+    // Padding repeats the last real x, so the physical last row holds it too
+    Continue,
+}
+
+// Trace of `real_rows` counted rows padded to max(256, next power of two),
+// with the real row count as the public value
+pub fn generate_last_row_trace<F: PrimeField64>(
+    real_rows: usize,
+    padding: PaddingStrategy,
+) -> Result<GeneratedTrace<(), F>, SizingError> {
+    // The first row must be real for constrain_real_prefix
+    if real_rows == 0 {
+        return Err(SizingError::NoRows);
+    }
+    let n = trace_height(real_rows, 256)?;
+    let len = trace_len(n, NUM_LAST_ROW_COLS)?;
+    let mut trace = RowMajorMatrix::new(F::zero_vec(len), NUM_LAST_ROW_COLS);
+
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<LastRowDemoRow<F>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), n);
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        let real = i < real_rows;
+        row.is_real = F::from_bool(real);
+        row.x = match (real, padding) {
+            (true, _) => F::from_usize(i + 1),
+            (false, PaddingStrategy::Zero) => F::ZERO,
+            (false, PaddingStrategy::Continue) => F::from_usize(real_rows),
+        };
+    }
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows,
+        public_values: vec![F::from_usize(real_rows)],
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Val;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::test_config;
+    use crate::validate::validate_trace;
+
+    fn accepts(binding: LastRowBinding, real_rows: usize, padding: PaddingStrategy, claim: usize) -> bool {
+        let trace = generate_last_row_trace::<Val>(real_rows, padding).unwrap();
+        validate_trace(&LastRowDemoAir { binding }, &trace.matrix, &[Val::from_usize(claim)]).is_ok()
+    }
+
+    #[test]
+    fn the_physical_binding_only_holds_when_padding_carries_the_value() {
+        assert!(!accepts(LastRowBinding::Physical, 100, PaddingStrategy::Zero, 100));
+        assert!(accepts(LastRowBinding::Physical, 100, PaddingStrategy::Continue, 100));
+        assert!(accepts(LastRowBinding::Physical, 256, PaddingStrategy::Zero, 256));
+    }
+
+    #[test]
+    fn the_last_real_binding_holds_whatever_the_padding() {
+        for padding in [PaddingStrategy::Zero, PaddingStrategy::Continue] {
+            assert!(accepts(LastRowBinding::LastReal, 100, padding, 100), "{:?}", padding);
+            assert!(!accepts(LastRowBinding::LastReal, 100, padding, 99), "{:?}", padding);
+        }
+        assert!(accepts(LastRowBinding::LastReal, 256, PaddingStrategy::Zero, 256));
+        assert!(!accepts(LastRowBinding::LastReal, 256, PaddingStrategy::Zero, 255));
+    }
+
+    #[test]
+    fn a_zero_padded_trace_proves_under_the_last_real_binding() {
+        let air = LastRowDemoAir { binding: LastRowBinding::LastReal };
+        let trace = generate_last_row_trace::<Val>(100, PaddingStrategy::Zero).unwrap();
+        let proof = prove_identified(&test_config(), &air, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&test_config(), &air, &proof, &trace.public_values), Ok(None));
+    }
+
+    #[test]
+    fn a_trace_needs_a_real_row() {
+        assert_eq!(generate_last_row_trace::<Val>(0, PaddingStrategy::Zero).err(), Some(SizingError::NoRows));
+    }
+}
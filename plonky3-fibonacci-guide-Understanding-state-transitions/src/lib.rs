@@ -0,0 +1,827 @@
+// The Fibonacci example as a library: FibonacciAir, its trace generator, the
+// shared STARK config and one-call prove/verify entry points, plus the demos
+// and subcommands main.rs dispatches to (commands, demos, walkthrough).
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing};
+use p3_fri::FriParameters;
+use p3_matrix::Matrix;
+use p3_uni_stark::{
+    DebugConstraintBuilder, ProverConstraintFolder, SymbolicAirBuilder,
+    VerifierConstraintFolder,
+};
+use serde::{Deserialize, Serialize};
+use stark_config::FriChoice;
+
+pub mod air_id;
+pub mod big_fibonacci;
+pub mod blake3;
+pub mod cache;
+pub mod canonical;
+pub mod capabilities;
+pub mod challenger;
+pub mod claims;
+pub mod coefficients;
+pub mod commands;
+pub mod conformance;
+pub mod cost;
+pub mod counter;
+pub mod dataset;
+pub mod demos;
+pub mod diff;
+pub mod domain;
+pub mod dual;
+pub mod exact;
+#[cfg(feature = "exercises")]
+pub mod exercises;
+#[cfg(feature = "regen-fixtures")]
+pub mod fixtures;
+#[cfg(feature = "goldilocks")]
+pub mod goldilocks;
+pub mod indexed;
+pub mod input;
+pub mod journal;
+pub mod keccak;
+#[cfg(feature = "koala-bear")]
+pub mod koala_bear;
+pub mod last_row;
+pub mod linear_recurrence;
+pub mod lint;
+pub mod lucas;
+#[cfg(feature = "mersenne-31")]
+pub mod mersenne_31;
+pub mod multi;
+pub mod observer;
+pub mod parity;
+pub mod proof_file;
+pub mod provenance;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod report;
+pub mod segments;
+pub mod selftest;
+pub mod sequence;
+pub mod shape;
+pub mod snapshot;
+pub mod soak;
+#[cfg(feature = "tutorial")]
+pub mod stages;
+pub mod stats;
+pub mod threads;
+pub mod trace;
+#[cfg(feature = "transcript-log")]
+pub mod transcript;
+pub mod validate;
+#[cfg(feature = "transcript-log")]
+pub mod vectors;
+pub mod walkthrough;
+pub mod zk;
+
+use air_id::{
+    AirId, AirIdError, IdentifiedProof, ProvableComputation, prove_identified_observed, verify_identified,
+};
+use claims::{ClaimsError, FibonacciClaims, sequence_claim_values};
+use cost::{CostParams, MIN_TRACE_HEIGHT, ParamsError, SecurityLevel};
+use last_row::{bind_physical_last_row, constrain_possibly_empty_prefix};
+use lint::AirLint;
+use observer::ProofObserver;
+use proof_file::{ConfigFingerprint, ProofFileError, load_described_proof_from_reader, load_proof_from_reader};
+use provenance::ProvenanceMismatch;
+use report::panic_message;
+use shape::{ShapeError, check_proof_shape};
+use trace::{
+    ColumnNames, DEFAULT_MIN_HEIGHT, FibonacciMeta, GeneratedTrace, SizingError, TraceOptions, generate_rows,
+    trace_height,
+};
+
+// Fibonacci trace: 4 columns [a, b, is_real, count], a and b consecutive
+// Fibonacci numbers, is_real cleared on padding rows and count the real rows
+// so far
+pub const NUM_FIBONACCI_COLS: usize = 4;
+
+// Public values: [F(0), F(1), F(num_steps), num_steps]; the first row must
+// start from the pair and the last row must end on the claimed result and
+// step count. num_steps = 0 is the empty computation.
+pub const NUM_FIBONACCI_PUBLIC_VALUES: usize = 4;
+
+// The constraints have degree at most 3 (transition selector, is_real and a
+// linear difference), so the quotient has degree 2
+pub const FIBONACCI_LOG_QUOTIENT_DEGREE: usize = 1;
+
+// Bump on any change to FibonacciAir's constraints
+pub const FIBONACCI_AIR_ID: AirId = AirId::new("fibonacci", 5, 0, 0);
+
+#[derive(Debug, Clone)]
+pub struct FibonacciAir;
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl ProvableComputation for FibonacciAir {
+    fn air_id(&self) -> AirId {
+        FIBONACCI_AIR_ID
+    }
+}
+
+impl AirLint for FibonacciAir {
+    fn num_public_values(&self) -> usize {
+        NUM_FIBONACCI_PUBLIC_VALUES
+    }
+}
+
+impl ColumnNames for FibonacciAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["a", "b", "is_real", "count"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+
+        // Get current row and next row. Every generator emits at least 16 rows
+        // and the provers only call eval on non-empty traces, so both exist.
+        let local_slice = main.row_slice(0).expect("Matrix is empty?");
+        let next_slice = main.row_slice(1).expect("Matrix only has 1 row?");
+
+        let local: &FibonacciTraceRow<AB::Var> = (*local_slice).borrow();
+        let next: &FibonacciTraceRow<AB::Var> = (*next_slice).borrow();
+        eval_fibonacci(builder, local, next);
+    }
+}
+
+// FibonacciAir's constraints on one window, for AIRs whose rows start with a
+// FibonacciTraceRow
+pub(crate) fn eval_fibonacci<AB: AirBuilderWithPublicValues>(
+    builder: &mut AB,
+    local: &FibonacciTraceRow<AB::Var>,
+    next: &FibonacciTraceRow<AB::Var>,
+) {
+    let (local_fib, next_fib) = (&local.fib, &next.fib);
+    let pis = builder.public_values();
+    let (f0, f1, result, num_steps) = (pis[0], pis[1], pis[2], pis[3]);
+
+    // Boundary: the sequence starts from the public pair, so a trace
+    // starting anywhere else cannot satisfy the constraints
+    let mut when_first_row = builder.when_first_row();
+    when_first_row.assert_eq(local_fib.a.clone(), f0);
+    when_first_row.assert_eq(local_fib.b.clone(), f1);
+    when_first_row.assert_eq(local.count.clone(), local.is_real.clone());
+
+    // Output: the first padding row holds (F(num_steps), F(num_steps + 1))
+    // and the rest copy it, so the last row carries the result and the
+    // step count, and a wrong claim cannot be satisfied
+    bind_physical_last_row(builder, local_fib.a.clone().into(), result.into());
+    bind_physical_last_row(builder, local.count.clone().into(), num_steps.into());
+
+    // Real rows form a prefix, possibly empty, ending before the last row,
+    // and count them
+    constrain_possibly_empty_prefix(builder, local.is_real.clone().into(), next.is_real.clone().into());
+    builder.when_last_row().assert_zero(local.is_real.clone());
+    builder
+        .when_transition()
+        .assert_eq(next.count.clone(), local.count.clone() + next.is_real.clone());
+
+    // Constraint 1: Fibonacci recurrence relation, out of every real row
+    // next.b should equal local.a + local.b
+    let mut when_step = builder.when_transition().when(local.is_real.clone());
+    when_step.assert_eq(next_fib.b.clone(), local_fib.a.clone() + local_fib.b.clone());
+
+    // Constraint 2: State propagation
+    // next.a should equal local.b
+    when_step.assert_eq(next_fib.a.clone(), local_fib.b.clone());
+
+    // Padding rows copy the row before them, carrying the result down
+    let mut when_padding = builder.when_transition().when(AB::Expr::ONE - local.is_real.clone());
+    when_padding.assert_eq(next_fib.a.clone(), local_fib.a.clone());
+    when_padding.assert_eq(next_fib.b.clone(), local_fib.b.clone());
+}
+
+// Row structure: [a, b] where a = F(n-1), b = F(n)
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FibonacciRow<F> {
+    pub a: F,  // F(n-1)
+    pub b: F,  // F(n)
+}
+
+impl<F> FibonacciRow<F> {
+    const fn new(a: F, b: F) -> Self {
+        Self { a, b }
+    }
+}
+
+// A FibonacciAir row: the pair, whether it is a real step, and how many real
+// steps there have been up to and including it
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FibonacciTraceRow<F> {
+    pub fib: FibonacciRow<F>,
+    pub is_real: F,
+    pub count: F,
+}
+
+// Memory layout conversion for efficient access
+impl<F> Borrow<FibonacciTraceRow<F>> for [F] {
+    fn borrow(&self) -> &FibonacciTraceRow<F> {
+        debug_assert_eq!(self.len(), NUM_FIBONACCI_COLS);
+        let (prefix, rows, suffix) = unsafe {
+            self.align_to::<FibonacciTraceRow<F>>()
+        };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+// (F(k), F(k + 1)) by fast doubling, so a chunk of rows can start anywhere
+fn fibonacci_pair<F: Field>(k: usize) -> (F, F) {
+    let mut pair = (F::ZERO, F::ONE);
+    for bit in (0..usize::BITS - k.leading_zeros()).rev() {
+        let (a, b) = pair;
+        let even = a * (b.double() - a); // F(2m)
+        let odd = a.square() + b.square(); // F(2m + 1)
+        pair = if (k >> bit) & 1 == 0 { (even, odd) } else { (odd, even + odd) };
+    }
+    pair
+}
+
+// generate_fibonacci_trace_padded to DEFAULT_MIN_HEIGHT rows
+pub fn generate_fibonacci_trace<F: Field>(
+    num_steps: usize,
+) -> Result<GeneratedTrace<FibonacciMeta<F>, F>, SizingError> {
+    generate_fibonacci_trace_padded(num_steps, DEFAULT_MIN_HEIGHT)
+}
+
+// generate_fibonacci_trace padded to at least `min_height` rows
+pub fn generate_fibonacci_trace_padded<F: Field>(
+    num_steps: usize,
+    min_height: usize,
+) -> Result<GeneratedTrace<FibonacciMeta<F>, F>, SizingError> {
+    generate_recurrence_trace_padded(F::ZERO, F::ONE, num_steps, min_height)
+}
+
+// (G(k), G(k + 1)) for the sequence G starting from (a0, b0): G(k) is
+// a0 * F(k - 1) + b0 * F(k), with F(-1) = F(1) - F(0) = 1
+pub(crate) fn recurrence_pair<F: Field>(a0: F, b0: F, k: usize) -> (F, F) {
+    let (f, g) = fibonacci_pair::<F>(k);
+    (a0 * (g - f) + b0 * f, a0 * f + b0 * g)
+}
+
+// The Fibonacci recurrence from the pair (a0, b0) instead of (0, 1), e.g. the
+// Lucas numbers from (2, 1). FibonacciAir takes the pair from the public
+// values, so the constraints are the same for every start.
+pub fn generate_recurrence_trace<F: Field>(
+    a0: F,
+    b0: F,
+    num_steps: usize,
+) -> Result<GeneratedTrace<FibonacciMeta<F>, F>, SizingError> {
+    generate_recurrence_trace_padded(a0, b0, num_steps, DEFAULT_MIN_HEIGHT)
+}
+
+// generate_recurrence_trace padded to at least `min_height` rows
+pub fn generate_recurrence_trace_padded<F: Field>(
+    a0: F,
+    b0: F,
+    num_steps: usize,
+    min_height: usize,
+) -> Result<GeneratedTrace<FibonacciMeta<F>, F>, SizingError> {
+    // Ensure power of 2 for FFT operations, with at least one padding row to
+    // hold the result
+    let rows = num_steps.checked_add(1).ok_or(SizingError::HeightOverflow { rows: num_steps })?;
+    let n = trace_height(rows, min_height)?;
+    let (final_a, final_b) = recurrence_pair(a0, b0, num_steps);
+    let count = F::from_usize(num_steps);
+
+    // Row i holds (G(i), G(i + 1)). Rows from num_steps on are padding with
+    // is_real = 0, all holding (G(num_steps), G(num_steps + 1)). With
+    // num_steps = 0 the whole trace is padding: the empty computation.
+    let trace = generate_rows(n, |range: Range<usize>| {
+        let (mut a, mut b) = recurrence_pair(a0, b0, range.start.min(num_steps));
+        range.map(move |i| {
+            if i >= num_steps {
+                let fib = FibonacciRow::new(final_a, final_b);
+                return FibonacciTraceRow { fib, is_real: F::ZERO, count };
+            }
+            let fib = FibonacciRow::new(a, b);
+            let row = FibonacciTraceRow { fib, is_real: F::ONE, count: F::from_usize(i + 1) };
+            // G(n) = G(n-1) + G(n-2)
+            (a, b) = (b, a + b);
+            row
+        })
+    })?;
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: num_steps,
+        public_values: sequence_claim_values(a0, b0, final_a, count),
+        meta: FibonacciMeta { final_value: final_a },
+    })
+}
+
+// Proves a generated Fibonacci trace against its embedded public values
+pub fn prove_fibonacci_trace(
+    config: &MyConfig,
+    trace: GeneratedTrace<FibonacciMeta>,
+    observer: &mut dyn ProofObserver,
+) -> (IdentifiedProof, PublicValues) {
+    let proof = prove_identified_observed(config, &FibonacciAir, trace.matrix, &trace.public_values, observer);
+    (proof, trace.public_values)
+}
+
+// verify_identified against claims read by name; an all-padding proof is only
+// accepted when `claims` is marked empty
+pub fn verify_sequence(
+    config: &MyConfig,
+    proof: &IdentifiedProof,
+    claims: &FibonacciClaims,
+) -> Result<Option<ProvenanceMismatch>, AirIdError> {
+    verify_identified(config, &FibonacciAir, proof, &claims.to_public_values())
+}
+
+// Public values as passed to prove and verify, in FibonacciAir's FibonacciClaims layout
+pub type PublicValues = Vec<Val>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    // The public values are not [F(0), F(1), F(num_steps), num_steps]
+    Claims(ClaimsError),
+    // Wrong AIR revision, or the STARK verifier rejected the proof
+    Proof(AirIdError),
+    // Bytes that are not a proof at all
+    Decode(String),
+    // A well-formed proof made under a different seed or FRI setup than the
+    // verifier's, refused before it was decoded
+    ConfigMismatch { expected: ConfigFingerprint, found: ConfigFingerprint },
+    // A decoded proof whose lengths do not fit FibonacciAir and the FRI
+    // parameters; rejected before the verifier indexes into it
+    Shape(ShapeError),
+    // The verifier panicked on a proof that passed the shape check
+    Panicked(String),
+    // A saved ConfigDescriptor that no config can be built from
+    Config(ParamsError),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Claims(e) => write!(f, "bad public values: {}", e),
+            Self::Proof(e) => write!(f, "{}", e),
+            Self::Decode(msg) => write!(f, "not a proof: {}", msg),
+            Self::ConfigMismatch { expected, found } => {
+                write!(f, "proof was made under config {} but the verifier's is {}", found, expected)
+            }
+            Self::Shape(e) => write!(f, "malformed proof: {}", e),
+            Self::Panicked(msg) => write!(f, "verifier panicked: {}", msg),
+            Self::Config(e) => write!(f, "unusable config: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<ClaimsError> for VerifyError {
+    fn from(e: ClaimsError) -> Self {
+        Self::Claims(e)
+    }
+}
+
+impl From<AirIdError> for VerifyError {
+    fn from(e: AirIdError) -> Self {
+        Self::Proof(e)
+    }
+}
+
+impl From<ParamsError> for VerifyError {
+    fn from(e: ParamsError) -> Self {
+        Self::Config(e)
+    }
+}
+
+impl From<ShapeError> for VerifyError {
+    fn from(e: ShapeError) -> Self {
+        Self::Shape(e)
+    }
+}
+
+// A config mismatch keeps both fingerprints; every other way of failing to
+// load is a decode error
+impl From<ProofFileError> for VerifyError {
+    fn from(e: ProofFileError) -> Self {
+        match e {
+            ProofFileError::ConfigMismatch { expected, found } => Self::ConfigMismatch { expected, found },
+            e => Self::Decode(e.to_string()),
+        }
+    }
+}
+
+// Proves F(num_steps) from (0, 1) under the default config at the Test
+// level, the parameters verify_bytes expects
+pub fn prove_fibonacci(num_steps: usize) -> Result<(IdentifiedProof, PublicValues), SizingError> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps)?;
+    Ok(prove_fibonacci_trace(&create_default_config(SecurityLevel::Test), trace, &mut ()))
+}
+
+// prove_fibonacci at `level`, padded as `options` asks, with the options
+// checked against the level's FRI parameters before anything is proved. The
+// padding is not part of the config: create_default_config(level) verifies.
+pub fn prove_fibonacci_with_options(
+    num_steps: usize,
+    level: SecurityLevel,
+    options: TraceOptions,
+) -> Result<(IdentifiedProof, PublicValues), ParamsError> {
+    let params = CostParams::at(level).with_trace_options(options)?;
+    let rows = num_steps.checked_add(1).ok_or(SizingError::HeightOverflow { rows: num_steps })?;
+    params.check(rows, FIBONACCI_LOG_QUOTIENT_DEGREE)?;
+    let trace = generate_fibonacci_trace_padded::<Val>(num_steps, params.min_height)?;
+    Ok(prove_fibonacci_trace(&create_default_config(level), trace, &mut ()))
+}
+
+// Verifies a prove_fibonacci proof under a freshly built default config. The
+// prover's provenance is not reported; verify_sequence returns it.
+pub fn verify_fibonacci(proof: &IdentifiedProof, public_values: &[Val]) -> Result<(), VerifyError> {
+    let claims = FibonacciClaims::from_public_values(public_values)?;
+    verify_sequence(&create_default_config(SecurityLevel::Test), proof, &claims).map_err(VerifyError::Proof)?;
+    Ok(())
+}
+
+// verify_fibonacci for untrusted bytes in proof_file's encoding, as received
+// from outside. Every way the bytes can be wrong is an Err: they are decoded
+// with a size limit, the proof's shape is checked before p3 sees it, and a
+// panic the shape check failed to rule out is caught as a last resort.
+pub fn verify_bytes(bytes: &[u8], public_values: &PublicValues) -> Result<(), VerifyError> {
+    let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+    verify_bytes_under(&descriptor, bytes, public_values)
+}
+
+// verify_bytes under the verifier's own `descriptor`. Bytes saved under any
+// other config are VerifyError::ConfigMismatch, whatever the proof inside.
+// The prover's provenance is not reported; verify_input returns it.
+pub fn verify_bytes_under(
+    descriptor: &ConfigDescriptor,
+    bytes: &[u8],
+    public_values: &PublicValues,
+) -> Result<(), VerifyError> {
+    let claims = FibonacciClaims::from_public_values(public_values)?;
+    let proof: IdentifiedProof = load_proof_from_reader(bytes, ConfigFingerprint::of(descriptor))?;
+    verify_untrusted(descriptor, &proof, &claims)?;
+    Ok(())
+}
+
+// verify_bytes under the config the bytes' own descriptor describes, for a
+// verifier that shares nothing with the prover but the bytes. Returns the
+// descriptor: a proof that verifies is only as strong as the parameters its
+// prover chose, and the caller decides whether those are enough.
+pub fn verify_described(bytes: &[u8], public_values: &PublicValues) -> Result<ConfigDescriptor, VerifyError> {
+    verify_described_with_provenance(bytes, public_values).map(|(descriptor, _)| descriptor)
+}
+
+// verify_described, also returning the provenance mismatch of a proof from a
+// differently built prover
+pub fn verify_described_with_provenance(
+    bytes: &[u8],
+    public_values: &PublicValues,
+) -> Result<(ConfigDescriptor, Option<ProvenanceMismatch>), VerifyError> {
+    let claims = FibonacciClaims::from_public_values(public_values)?;
+    let (descriptor, proof): (ConfigDescriptor, IdentifiedProof) = load_described_proof_from_reader(bytes)?;
+    descriptor.check(FIBONACCI_LOG_QUOTIENT_DEGREE)?;
+    let provenance = verify_untrusted(&descriptor, &proof, &claims)?;
+    Ok((descriptor, provenance))
+}
+
+// The shape check, then the verifier under `descriptor`'s config with a
+// panic caught as a last resort
+pub(crate) fn verify_untrusted(
+    descriptor: &ConfigDescriptor,
+    proof: &IdentifiedProof,
+    claims: &FibonacciClaims,
+) -> Result<Option<ProvenanceMismatch>, VerifyError> {
+    let params = descriptor.params();
+    check_proof_shape(&proof.proof, NUM_FIBONACCI_COLS, FIBONACCI_LOG_QUOTIENT_DEGREE, &params)?;
+    let verify = || verify_sequence(&create_config_from(descriptor), proof, claims);
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(verify))
+        .map_err(|payload| VerifyError::Panicked(panic_message(payload.as_ref())))?
+        .map_err(VerifyError::Proof)
+}
+
+// The config types are shared with the arithmetic example
+pub(crate) use stark_config::constants_rng;
+pub use stark_config::{
+    Challenge, ChallengeMmcs, Challenger, DEFAULT_CONFIG_SEED, Dft, MyCompress, MyConfig, MyHash, Pcs, Perm, Val,
+    ValMmcs, test_config,
+};
+
+// AIRs that can be proven and verified under MyConfig
+pub trait StarkAir:
+    Air<SymbolicAirBuilder<Val>>
+    + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+    + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+    + for<'a> Air<DebugConstraintBuilder<'a, Val>>
+{
+}
+
+impl<A> StarkAir for A where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val>>
+{
+}
+
+// The canonical config every example proves and verifies under, at the FRI
+// parameters of `level`
+pub fn create_default_config(level: SecurityLevel) -> MyConfig {
+    create_config_with_seed(DEFAULT_CONFIG_SEED, level)
+}
+
+// Explicit-seed constructor; the seed determines the Poseidon2 constants, so
+// configs built from different seeds cannot verify each other's proofs
+pub fn create_config_with_seed(seed: u64, level: SecurityLevel) -> MyConfig {
+    create_config(seed, &CostParams::at(level))
+}
+
+// create_config_with_seed with the blowup and query count of `params`; check
+// them with CostParams::check first, as FRI panics on ones it cannot use
+pub fn create_config(seed: u64, params: &CostParams) -> MyConfig {
+    create_config_from(&ConfigDescriptor::new(seed, params))
+}
+
+// The proof-of-work bits of SecurityLevel::Test
+pub const TEST_POW_BITS: usize = FriChoice::TEST.pow_bits;
+
+// Largest proof of work a descriptor may ask for. Grinding doubles per bit,
+// and the witness is checked against bits of a 31-bit field element.
+pub const MAX_POW_BITS: usize = 30;
+
+// Everything needed to rebuild a MyConfig: the seed of the Poseidon2
+// constants and the FRI parameters. Saved with every proof, so a verifier
+// can rebuild the prover's config from the file alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigDescriptor {
+    pub perm_seed: u64,
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub pow_bits: usize,
+    // log2 of the smallest trace height the prover pads to
+    pub min_log_height: usize,
+}
+
+impl ConfigDescriptor {
+    pub fn new(seed: u64, params: &CostParams) -> Self {
+        Self {
+            perm_seed: seed,
+            log_blowup: params.log_blowup,
+            num_queries: params.num_queries,
+            pow_bits: params.pow_bits,
+            min_log_height: params.min_height.trailing_zeros() as usize,
+        }
+    }
+
+    // A min_log_height too large for a usize gives min_height 0, which
+    // CostParams::check refuses
+    pub fn params(&self) -> CostParams {
+        let min_height = u32::try_from(self.min_log_height)
+            .ok()
+            .and_then(|bits| 1usize.checked_shl(bits))
+            .unwrap_or(0);
+        CostParams { log_blowup: self.log_blowup, num_queries: self.num_queries, pow_bits: self.pow_bits, min_height }
+    }
+
+    // A descriptor read from a file is untrusted; this rejects the ones
+    // create_config_from would build a config from that FRI then panics on,
+    // for an AIR of the given log quotient degree
+    pub fn check(&self, log_quotient_degree: usize) -> Result<(), ParamsError> {
+        let params = self.params();
+        params.check(params.min_height, log_quotient_degree)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "seed {}, blowup 2^{}, {} queries, {} proof-of-work bits, min height 2^{}",
+            self.perm_seed,
+            self.log_blowup,
+            self.num_queries,
+            self.pow_bits,
+            self.min_log_height
+        )
+    }
+}
+
+// FRI parameters for `params` over any MMCS, folding down to a final
+// polynomial of MIN_TRACE_HEIGHT coefficients
+pub(crate) fn fri_params<M>(params: &CostParams, mmcs: M) -> FriParameters<M> {
+    params.fri_choice().fri_params(mmcs)
+}
+
+// The FRI parameters of `level`, for configs built outside create_config
+pub fn fri_params_for<M>(level: SecurityLevel, mmcs: M) -> FriParameters<M> {
+    fri_params(&CostParams::at(level), mmcs)
+}
+
+// The config `descriptor` describes; check it first when it came from
+// outside, as FRI panics on parameters it cannot use
+pub fn create_config_from(descriptor: &ConfigDescriptor) -> MyConfig {
+    create_config_with_prefix(descriptor, &[])
+}
+
+// create_config_from with a challenger that has already observed `prefix`, so
+// it comes before everything prove and verify absorb; see the domain module
+pub(crate) fn create_config_with_prefix(descriptor: &ConfigDescriptor, prefix: &[Val]) -> MyConfig {
+    stark_config::create_config_with_prefix(descriptor.perm_seed, descriptor.params().fri_choice(), prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+    use test_support::checker::accepted_mutations;
+    use test_support::oracle::{fibonacci_mod, recurrence_mod};
+
+    use super::*;
+
+    #[test]
+    fn fast_doubling_matches_the_oracle() {
+        for k in [0, 1, 2, 46, 47, 1000, 1 << 20] {
+            let (f, g) = fibonacci_pair::<Val>(k);
+            assert_eq!((f, g), (fibonacci_mod(k as u64), fibonacci_mod(k as u64 + 1)), "k = {}", k);
+        }
+        let (a0, b0) = (Val::TWO, Val::ONE);
+        assert_eq!(recurrence_pair(a0, b0, 10).0, recurrence_mod(a0, b0, 10));
+    }
+
+    #[test]
+    fn trace_ends_on_the_oracle_value() {
+        for num_steps in [0, 1, 15, 100, 255, 256] {
+            let trace = generate_fibonacci_trace::<Val>(num_steps).unwrap();
+            let expected = fibonacci_mod(num_steps as u64);
+            assert_eq!(trace.meta.final_value, expected, "{} steps", num_steps);
+            assert_eq!(trace.public_values[2], expected);
+            assert_eq!(trace.public_values[3], Val::from_usize(num_steps));
+        }
+    }
+
+    #[test]
+    fn padding_keeps_at_least_one_row_past_the_last_step() {
+        assert_eq!(generate_fibonacci_trace::<Val>(255).unwrap().matrix.height(), 256);
+        assert_eq!(generate_fibonacci_trace::<Val>(256).unwrap().matrix.height(), 512);
+        assert_eq!(generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap().matrix.height(), 16);
+    }
+
+    #[test]
+    fn trace_options_pad_to_sixteen_rows_and_refuse_fewer() {
+        let options = TraceOptions { min_log_height: 4 };
+        let (proof, public_values) = prove_fibonacci_with_options(15, SecurityLevel::Test, options).unwrap();
+        assert_eq!(proof.proof.degree_bits, 4);
+        assert_eq!(verify_fibonacci(&proof, &public_values), Ok(()));
+
+        let too_small = TraceOptions { min_log_height: 3 };
+        assert_eq!(
+            prove_fibonacci_with_options(7, SecurityLevel::Test, too_small).err(),
+            Some(ParamsError::MinHeightTooSmall { min_height: 8, min: MIN_TRACE_HEIGHT })
+        );
+        let too_large = TraceOptions { min_log_height: usize::MAX };
+        assert!(matches!(
+            prove_fibonacci_with_options(7, SecurityLevel::Test, too_large),
+            Err(ParamsError::TooLarge { .. })
+        ));
+        assert_eq!(TraceOptions::default().min_height(), Some(DEFAULT_MIN_HEIGHT));
+    }
+
+    #[test]
+    fn step_counts_around_a_power_of_two_prove_and_verify() {
+        for num_steps in [100, 256, 257] {
+            let (proof, public_values) = prove_fibonacci(num_steps).unwrap();
+            assert_eq!(verify_fibonacci(&proof, &public_values), Ok(()), "{} steps", num_steps);
+            let trace = generate_fibonacci_trace::<Val>(num_steps).unwrap();
+            let selector = trace.matrix.values.chunks(NUM_FIBONACCI_COLS).map(|row| row[2]);
+            assert_eq!(selector.filter(|&is_real| is_real == Val::ONE).count(), num_steps);
+        }
+    }
+
+    #[test]
+    fn a_real_row_after_padding_is_refused() {
+        let trace = generate_fibonacci_trace::<Val>(100).unwrap();
+        let mut reopened = trace.matrix.clone();
+        reopened.values[101 * NUM_FIBONACCI_COLS + 2] = Val::ONE;
+        assert!(validate::validate_trace(&FibonacciAir, &reopened, &trace.public_values).is_err());
+        assert_eq!(validate::validate_trace(&FibonacciAir, &trace.matrix, &trace.public_values), Ok(()));
+    }
+
+    // [F(0), F(1), F(n), n]: a claim consistent in itself, whatever the proof covers
+    fn claim_of(num_steps: usize) -> Vec<Val> {
+        vec![Val::ZERO, Val::ONE, fibonacci_mod(num_steps as u64), Val::from_usize(num_steps)]
+    }
+
+    #[test]
+    fn only_the_proved_step_count_verifies() {
+        let (proof, public_values) = prove_fibonacci(100).unwrap();
+        assert_eq!(public_values, claim_of(100));
+        assert_eq!(verify_fibonacci(&proof, &claim_of(100)), Ok(()));
+        // One short, one over, and more steps than the 256-row trace has rows
+        for claimed in [99, 101, 256, 300] {
+            assert!(verify_fibonacci(&proof, &claim_of(claimed)).is_err(), "claimed {} steps", claimed);
+        }
+    }
+
+    #[test]
+    fn a_bits100_proof_verifies_and_opens_more_queries() {
+        let options = TraceOptions::default();
+        let (test, public_values) = prove_fibonacci_with_options(100, SecurityLevel::Test, options).unwrap();
+        let (strong, _) = prove_fibonacci_with_options(100, SecurityLevel::Bits100, options).unwrap();
+        assert_eq!(verify_fibonacci(&test, &public_values), Ok(()));
+        let claims = FibonacciClaims::from_public_values(&public_values).unwrap();
+        assert_eq!(verify_sequence(&create_default_config(SecurityLevel::Bits100), &strong, &claims), Ok(None));
+
+        let queries = |proof: &IdentifiedProof| proof.proof.opening_proof.query_proofs.len();
+        assert_eq!(queries(&test), SecurityLevel::Test.fri_choice().num_queries);
+        assert_eq!(queries(&strong), SecurityLevel::Bits100.fri_choice().num_queries);
+        assert!(queries(&strong) > queries(&test));
+    }
+
+    #[test]
+    fn a_proof_from_one_start_is_refused_under_another() {
+        let config = create_default_config(SecurityLevel::Test);
+        let lucas = generate_recurrence_trace::<Val>(Val::TWO, Val::ONE, 10).unwrap();
+        let (lucas_proof, lucas_values) = prove_fibonacci_trace(&config, lucas, &mut ());
+        // L(10) = 123
+        assert_eq!(lucas_values, vec![Val::TWO, Val::ONE, Val::from_u8(123), Val::from_u8(10)]);
+        assert_eq!(verify_fibonacci(&lucas_proof, &lucas_values), Ok(()));
+
+        let (fibonacci_proof, fibonacci_values) = prove_fibonacci(10).unwrap();
+        let claimed_as_lucas = vec![Val::TWO, Val::ONE, fibonacci_values[2], fibonacci_values[3]];
+        assert!(verify_fibonacci(&fibonacci_proof, &claimed_as_lucas).is_err());
+        let claimed_as_fibonacci = vec![Val::ZERO, Val::ONE, lucas_values[2], lucas_values[3]];
+        assert!(verify_fibonacci(&lucas_proof, &claimed_as_fibonacci).is_err());
+    }
+
+    #[test]
+    fn a_proof_from_another_seed_is_a_config_mismatch() {
+        let prover = ConfigDescriptor::new(42, &CostParams::test());
+        let verifier = ConfigDescriptor::new(1, &CostParams::test());
+        let trace = generate_fibonacci_trace::<Val>(15).unwrap();
+        let (proof, public_values) = prove_fibonacci_trace(&create_config_from(&prover), trace, &mut ());
+        let bytes = proof_file::encode_proof(&prover, &proof).unwrap();
+        assert_eq!(verify_bytes_under(&prover, &bytes, &public_values), Ok(()));
+        assert_eq!(
+            verify_bytes_under(&verifier, &bytes, &public_values),
+            Err(VerifyError::ConfigMismatch {
+                expected: ConfigFingerprint::of(&verifier),
+                found: ConfigFingerprint::of(&prover),
+            })
+        );
+    }
+
+    #[test]
+    fn a_proof_under_a_wider_blowup_is_a_config_mismatch() {
+        // log_blowup 2 (blowup 4) against the Test level's 1 (blowup 2)
+        let prover = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams { log_blowup: 2, ..CostParams::test() });
+        let verifier = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let trace = generate_fibonacci_trace::<Val>(15).unwrap();
+        let (proof, public_values) = prove_fibonacci_trace(&create_config_from(&prover), trace, &mut ());
+        let bytes = proof_file::encode_proof(&prover, &proof).unwrap();
+        let expected = ConfigFingerprint::of(&verifier);
+        let found = ConfigFingerprint::of(&prover);
+        assert_eq!(verify_bytes(&bytes, &public_values), Err(VerifyError::ConfigMismatch { expected, found }));
+        assert_eq!(verify_described(&bytes, &public_values), Ok(prover));
+    }
+
+    // The two halves of a hand-off that shares only the bytes: the prover picks
+    // its own config, and the verifier rebuilds it from the saved descriptor
+    fn prover_side(descriptor: &ConfigDescriptor, num_steps: usize) -> (Vec<u8>, PublicValues) {
+        let trace = generate_fibonacci_trace::<Val>(num_steps).unwrap();
+        let (proof, public_values) = prove_fibonacci_trace(&create_config_from(descriptor), trace, &mut ());
+        (proof_file::encode_proof(descriptor, &proof).unwrap(), public_values)
+    }
+
+    fn verifier_side(bytes: &[u8], public_values: &PublicValues) -> Result<ConfigDescriptor, VerifyError> {
+        verify_described(bytes, public_values)
+    }
+
+    #[test]
+    fn a_verifier_rebuilds_the_config_from_the_saved_descriptor() {
+        let descriptor = ConfigDescriptor::new(7, &CostParams { log_blowup: 2, num_queries: 3, ..CostParams::test() });
+        let (bytes, public_values) = prover_side(&descriptor, 30);
+        assert_eq!(verifier_side(&bytes, &public_values), Ok(descriptor));
+
+        // The same proof relabelled with another seed no longer verifies
+        let (_, proof): (ConfigDescriptor, IdentifiedProof) =
+            proof_file::load_described_proof_from_reader(bytes.as_slice()).unwrap();
+        let relabelled = proof_file::encode_proof(&ConfigDescriptor { perm_seed: 8, ..descriptor }, &proof).unwrap();
+        assert!(matches!(verifier_side(&relabelled, &public_values), Err(VerifyError::Proof(_))));
+    }
+
+    #[test]
+    fn every_corpus_mutation_is_refused() {
+        let trace = generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap();
+        assert_eq!(accepted_mutations(&FibonacciAir, &trace.matrix, &trace.public_values), vec![]);
+    }
+}
@@ -0,0 +1,208 @@
+// FibonacciAir generalized to order K: each term is the sum of the K before
+// it, so K = 2 is Fibonacci, K = 3 Tribonacci and K = 4 Tetranacci. A row
+// holds K consecutive terms and shifts them along by one per step, with the
+// same is_real and count columns, padding and public layout as FibonacciAir.
+use core::borrow::Borrow;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing};
+use p3_matrix::Matrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::last_row::{bind_physical_last_row, constrain_possibly_empty_prefix};
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, DEFAULT_MIN_HEIGHT, GeneratedTrace, SizingError, generate_rows, trace_height};
+
+// Every order shares one id, as LastRowDemoAir's bindings do: a proof of one
+// order has the wrong width and public values for any other
+pub const RECURRENCE_AIR_ID: AirId = AirId::new("recurrence", 1, 0, 0);
+
+// Order-K recurrence. Columns: is_real, count, then the K terms.
+// Public values: [G(0), ..., G(K - 1), G(num_steps), num_steps].
+#[derive(Debug, Clone)]
+pub struct RecurrenceAir<const K: usize>;
+
+impl<const K: usize> RecurrenceAir<K> {
+    // Order 0 has no terms and order 1 only repeats its one term. Evaluated
+    // by width and the generator, so using either fails to compile.
+    const ORDER_CHECK: () = assert!(K >= 2, "RecurrenceAir needs an order of at least 2");
+}
+
+// A RecurrenceAir row: whether it is a real step, how many real steps there
+// have been up to and including it, and G(i), ..., G(i + K - 1)
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct RecurrenceRow<F, const K: usize> {
+    pub is_real: F,
+    pub count: F,
+    pub terms: [F; K],
+}
+
+impl<F, const K: usize> Borrow<RecurrenceRow<F, K>> for [F] {
+    fn borrow(&self) -> &RecurrenceRow<F, K> {
+        debug_assert_eq!(self.len(), K + 2);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<RecurrenceRow<F, K>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F, const K: usize> BaseAir<F> for RecurrenceAir<K> {
+    fn width(&self) -> usize {
+        let () = Self::ORDER_CHECK;
+        K + 2
+    }
+}
+
+impl<const K: usize> AirLint for RecurrenceAir<K> {
+    fn num_public_values(&self) -> usize {
+        K + 2
+    }
+}
+
+impl<const K: usize> ProvableComputation for RecurrenceAir<K> {
+    fn air_id(&self) -> AirId {
+        RECURRENCE_AIR_ID
+    }
+}
+
+// The terms are left unnamed
+impl<const K: usize> ColumnNames for RecurrenceAir<K> {
+    fn column_names(&self) -> &[&'static str] {
+        &["is_real", "count"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues, const K: usize> Air<AB> for RecurrenceAir<K> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local_slice = main.row_slice(0).expect("Matrix is empty?");
+        let next_slice = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &RecurrenceRow<AB::Var, K> = (*local_slice).borrow();
+        let next: &RecurrenceRow<AB::Var, K> = (*next_slice).borrow();
+        let pis = builder.public_values();
+        let start: [AB::PublicVar; K] = core::array::from_fn(|i| pis[i]);
+        let (result, num_steps) = (pis[K], pis[K + 1]);
+
+        // Boundary: the first row holds the public start
+        let mut when_first_row = builder.when_first_row();
+        for (term, public) in local.terms.iter().zip(start) {
+            when_first_row.assert_eq(term.clone(), public);
+        }
+        when_first_row.assert_eq(local.count.clone(), local.is_real.clone());
+
+        // Output: padding carries G(num_steps) down to the last row
+        bind_physical_last_row(builder, local.terms[0].clone().into(), result.into());
+        bind_physical_last_row(builder, local.count.clone().into(), num_steps.into());
+
+        // Real rows form a prefix, possibly empty, ending before the last row,
+        // and count them
+        constrain_possibly_empty_prefix(builder, local.is_real.clone().into(), next.is_real.clone().into());
+        builder.when_last_row().assert_zero(local.is_real.clone());
+        builder
+            .when_transition()
+            .assert_eq(next.count.clone(), local.count.clone() + next.is_real.clone());
+
+        // Out of every real row the terms shift along by one, and the new last
+        // term is the sum of the K terms before it
+        let mut when_step = builder.when_transition().when(local.is_real.clone());
+        for (next_term, local_term) in next.terms.iter().zip(&local.terms[1..]) {
+            when_step.assert_eq(next_term.clone(), local_term.clone());
+        }
+        let sum = local.terms.iter().fold(AB::Expr::ZERO, |sum, term| sum + term.clone());
+        when_step.assert_eq(next.terms[K - 1].clone(), sum);
+
+        // Padding rows copy the row before them
+        let mut when_padding = builder.when_transition().when(AB::Expr::ONE - local.is_real.clone());
+        for (next_term, local_term) in next.terms.iter().zip(&local.terms) {
+            when_padding.assert_eq(next_term.clone(), local_term.clone());
+        }
+    }
+}
+
+// Order-K trace of `num_steps` steps from `start`, e.g. Tribonacci from
+// [0, 0, 1]. Row i holds G(i), ..., G(i + K - 1); rows from num_steps on are
+// padding holding G(num_steps), ... . The terms are computed once up front,
+// since unlike Fibonacci there is no fast doubling to start each chunk from.
+pub fn generate_linear_recurrence_trace<F: Field, const K: usize>(
+    start: [F; K],
+    num_steps: usize,
+) -> Result<GeneratedTrace<(), F>, SizingError> {
+    let () = RecurrenceAir::<K>::ORDER_CHECK;
+    let rows = num_steps.checked_add(1).ok_or(SizingError::HeightOverflow { rows: num_steps })?;
+    let height = trace_height(rows, DEFAULT_MIN_HEIGHT)?;
+
+    // G(0), ..., G(num_steps + K - 1)
+    let mut values = start.to_vec();
+    for i in K..num_steps + K {
+        let next = values[i - K..].iter().fold(F::ZERO, |sum, &term| sum + term);
+        values.push(next);
+    }
+
+    let count = F::from_usize(num_steps);
+    let values = &values;
+    let matrix = generate_rows(height, |range: Range<usize>| {
+        range.map(move |i| {
+            let terms = core::array::from_fn(|j| values[i.min(num_steps) + j]);
+            if i >= num_steps {
+                return RecurrenceRow { is_real: F::ZERO, count, terms };
+            }
+            RecurrenceRow { is_real: F::ONE, count: F::from_usize(i + 1), terms }
+        })
+    })?;
+
+    let mut public_values = start.to_vec();
+    public_values.extend([values[num_steps], count]);
+    Ok(GeneratedTrace { matrix, real_rows: num_steps, public_values, meta: () })
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeField64;
+
+    use super::*;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::cost::SecurityLevel;
+    use crate::validate::validate_trace;
+    use crate::{Val, create_default_config};
+
+    #[test]
+    fn tribonacci_satisfies_the_air() {
+        let trace = generate_linear_recurrence_trace::<Val, 3>([Val::ZERO, Val::ZERO, Val::ONE], 10).unwrap();
+        assert_eq!(trace.public_values[3].as_canonical_u64(), 81);
+        assert_eq!(validate_trace(&RecurrenceAir::<3>, &trace.matrix, &trace.public_values), Ok(()));
+    }
+
+    #[test]
+    fn an_edited_term_is_refused() {
+        let mut trace = generate_linear_recurrence_trace::<Val, 4>([Val::ONE; 4], 10).unwrap();
+        // The last term of row 5
+        trace.matrix.values[5 * 6 + 5] += Val::ONE;
+        assert!(validate_trace(&RecurrenceAir::<4>, &trace.matrix, &trace.public_values).is_err());
+    }
+
+    // Order K from [0, ..., 0, 1], proved and verified, returning G(num_steps)
+    fn prove_from_unit<const K: usize>(num_steps: usize) -> u64 {
+        let mut start = [Val::ZERO; K];
+        start[K - 1] = Val::ONE;
+        let trace = generate_linear_recurrence_trace(start, num_steps).unwrap();
+        let config = create_default_config(SecurityLevel::Test);
+        let proof = prove_identified(&config, &RecurrenceAir::<K>, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&config, &RecurrenceAir::<K>, &proof, &trace.public_values), Ok(None));
+
+        let mut wrong = trace.public_values.clone();
+        wrong[K] += Val::ONE;
+        assert!(verify_identified(&config, &RecurrenceAir::<K>, &proof, &wrong).is_err());
+        trace.public_values[K].as_canonical_u64()
+    }
+
+    #[test]
+    fn tribonacci_and_tetranacci_prove_their_hand_computed_terms() {
+        // 0, 0, 1, 1, 2, 4, 7, 13, 24, 44, 81, ..., T(20) = 35890
+        assert_eq!(prove_from_unit::<3>(20), 35890);
+        // 0, 0, 0, 1, 1, 2, 4, 8, 15, 29, 56, ..., Q(20) = 39648
+        assert_eq!(prove_from_unit::<4>(20), 39648);
+    }
+}
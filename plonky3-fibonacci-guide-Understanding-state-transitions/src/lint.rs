@@ -0,0 +1,156 @@
+use core::fmt;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_matrix::Matrix;
+use p3_uni_stark::{Entry, SymbolicAirBuilder, SymbolicExpression, get_symbolic_constraints};
+
+use crate::Val;
+
+// Per-AIR lint allowances. Advice columns that are deliberately left free
+// (e.g. hints only read by the generator) are listed here so the lint stays quiet.
+pub trait AirLint {
+    fn free_columns(&self) -> &'static [usize] {
+        &[]
+    }
+
+    fn num_public_values(&self) -> usize {
+        0
+    }
+}
+
+// Columns and public values that no constraint reads
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+    pub unconstrained_columns: Vec<usize>,
+    pub unused_public_values: Vec<usize>,
+}
+
+impl LintReport {
+    pub fn is_clean(&self) -> bool {
+        self.unconstrained_columns.is_empty() && self.unused_public_values.is_empty()
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "clean");
+        }
+        write!(
+            f,
+            "unconstrained columns {:?}, unused public values {:?}",
+            self.unconstrained_columns, self.unused_public_values
+        )
+    }
+}
+
+fn mark_used(expr: &SymbolicExpression<Val>, columns: &mut [bool], publics: &mut [bool]) {
+    match expr {
+        SymbolicExpression::Variable(var) => match var.entry {
+            Entry::Main { .. } => columns[var.index] = true,
+            Entry::Public => publics[var.index] = true,
+            _ => {}
+        },
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => {
+            mark_used(x, columns, publics);
+            mark_used(y, columns, publics);
+        }
+        SymbolicExpression::Neg { x, .. } => mark_used(x, columns, publics),
+        _ => {}
+    }
+}
+
+// Runs the symbolic constraint pass and reports every main column and public
+// value that no constraint references, minus the AIR's allowed free columns.
+// An unreferenced column can hold anything, which is almost always a soundness bug.
+pub fn lint_air<A>(air: &A) -> LintReport
+where
+    A: AirLint + BaseAir<Val> + Air<SymbolicAirBuilder<Val>>,
+{
+    let num_public_values = air.num_public_values();
+    let mut columns = vec![false; air.width()];
+    let mut publics = vec![false; num_public_values];
+    for constraint in get_symbolic_constraints(air, 0, num_public_values) {
+        mark_used(&constraint, &mut columns, &mut publics);
+    }
+    for &column in air.free_columns() {
+        columns[column] = true;
+    }
+
+    let unused = |used: Vec<bool>| {
+        used.iter().enumerate().filter(|(_, used)| !**used).map(|(i, _)| i).collect()
+    };
+    LintReport { unconstrained_columns: unused(columns), unused_public_values: unused(publics) }
+}
+
+// Deliberately broken AIR for the lint demo: column 2 is never constrained
+#[derive(Debug, Clone)]
+pub struct DanglingColumnAir;
+
+impl<F> BaseAir<F> for DanglingColumnAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl AirLint for DanglingColumnAir {}
+
+impl<AB: AirBuilder> Air<AB> for DanglingColumnAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        builder.assert_eq(local[0].clone(), local[1].clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FibonacciAir;
+
+    // DanglingColumnAir with column 2 allowed and one public value it ignores
+    struct AllowedDangling;
+
+    impl<F> BaseAir<F> for AllowedDangling {
+        fn width(&self) -> usize {
+            3
+        }
+    }
+
+    impl AirLint for AllowedDangling {
+        fn free_columns(&self) -> &'static [usize] {
+            &[2]
+        }
+
+        fn num_public_values(&self) -> usize {
+            1
+        }
+    }
+
+    impl<AB: AirBuilder> Air<AB> for AllowedDangling {
+        fn eval(&self, builder: &mut AB) {
+            DanglingColumnAir.eval(builder);
+        }
+    }
+
+    #[test]
+    fn fibonacci_reads_every_column_and_public_value() {
+        let report = lint_air(&FibonacciAir);
+        assert!(report.is_clean(), "{}", report);
+        assert_eq!(report.to_string(), "clean");
+    }
+
+    #[test]
+    fn a_column_no_constraint_reads_is_reported() {
+        let report = lint_air(&DanglingColumnAir);
+        assert_eq!(report, LintReport { unconstrained_columns: vec![2], unused_public_values: vec![] });
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn allowed_columns_are_quiet_but_unused_public_values_are_not() {
+        let report = lint_air(&AllowedDangling);
+        assert_eq!(report, LintReport { unconstrained_columns: vec![], unused_public_values: vec![0] });
+    }
+}
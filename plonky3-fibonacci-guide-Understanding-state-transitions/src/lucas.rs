@@ -0,0 +1,194 @@
+// The Lucas sequence U(n) = P * U(n-1) - Q * U(n-2), with P and Q public.
+// CoefficientAir keeps its coefficients private and range-checks them; here
+// they are public values multiplied straight into the transition constraint,
+// so one AIR proves Pell numbers (P = 2, Q = -1), 2^n - 1 (P = 3, Q = 2) or
+// Fibonacci (P = 1, Q = -1), and a proof says which. Padding and the output
+// binding are FibonacciAir's.
+use core::borrow::Borrow;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing};
+use p3_matrix::Matrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::last_row::{bind_physical_last_row, constrain_possibly_empty_prefix};
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, DEFAULT_MIN_HEIGHT, GeneratedTrace, SizingError, generate_rows, trace_height};
+
+const NUM_LUCAS_COLS: usize = 4;
+
+// Public values: [P, Q, U(0), U(1), U(num_steps), num_steps]
+pub const NUM_LUCAS_PUBLIC_VALUES: usize = 6;
+
+pub const LUCAS_AIR_ID: AirId = AirId::new("lucas", 1, 0, 0);
+
+#[derive(Debug, Clone)]
+pub struct LucasAir;
+
+// A LucasAir row: whether it is a real step, how many real steps there have
+// been up to and including it, and (U(i), U(i + 1))
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct LucasRow<F> {
+    pub is_real: F,
+    pub count: F,
+    pub a: F,
+    pub b: F,
+}
+
+impl<F> Borrow<LucasRow<F>> for [F] {
+    fn borrow(&self) -> &LucasRow<F> {
+        debug_assert_eq!(self.len(), NUM_LUCAS_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<LucasRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for LucasAir {
+    fn width(&self) -> usize {
+        NUM_LUCAS_COLS
+    }
+}
+
+impl AirLint for LucasAir {
+    fn num_public_values(&self) -> usize {
+        NUM_LUCAS_PUBLIC_VALUES
+    }
+}
+
+impl ProvableComputation for LucasAir {
+    fn air_id(&self) -> AirId {
+        LUCAS_AIR_ID
+    }
+}
+
+impl ColumnNames for LucasAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["is_real", "count", "a", "b"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for LucasAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &LucasRow<AB::Var> = (*local).borrow();
+        let next: &LucasRow<AB::Var> = (*next).borrow();
+        let pis = builder.public_values();
+        let (p, q, u0, u1, result, num_steps) = (pis[0], pis[1], pis[2], pis[3], pis[4], pis[5]);
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local.a.clone(), u0);
+        when_first_row.assert_eq(local.b.clone(), u1);
+        when_first_row.assert_eq(local.count.clone(), local.is_real.clone());
+
+        bind_physical_last_row(builder, local.a.clone().into(), result.into());
+        bind_physical_last_row(builder, local.count.clone().into(), num_steps.into());
+
+        constrain_possibly_empty_prefix(builder, local.is_real.clone().into(), next.is_real.clone().into());
+        builder.when_last_row().assert_zero(local.is_real.clone());
+        builder
+            .when_transition()
+            .assert_eq(next.count.clone(), local.count.clone() + next.is_real.clone());
+
+        // The coefficients are public, so P * b is still linear in the trace
+        let mut when_step = builder.when_transition().when(local.is_real.clone());
+        let (p, q): (AB::Expr, AB::Expr) = (p.into(), q.into());
+        when_step.assert_eq(next.b.clone(), p * local.b.clone() - q * local.a.clone());
+        when_step.assert_eq(next.a.clone(), local.b.clone());
+
+        let mut when_padding = builder.when_transition().when(AB::Expr::ONE - local.is_real.clone());
+        when_padding.assert_eq(next.a.clone(), local.a.clone());
+        when_padding.assert_eq(next.b.clone(), local.b.clone());
+    }
+}
+
+// Lucas trace of `num_steps` steps from (u0, u1). Row i holds
+// (U(i), U(i + 1)); rows from num_steps on are padding holding
+// (U(num_steps), U(num_steps + 1)).
+pub fn generate_lucas_trace<F: Field>(
+    p: F,
+    q: F,
+    u0: F,
+    u1: F,
+    num_steps: usize,
+) -> Result<GeneratedTrace<(), F>, SizingError> {
+    let rows = num_steps.checked_add(1).ok_or(SizingError::HeightOverflow { rows: num_steps })?;
+    let height = trace_height(rows, DEFAULT_MIN_HEIGHT)?;
+
+    // U(0), ..., U(num_steps + 1)
+    let mut values = vec![u0, u1];
+    for i in 2..num_steps + 2 {
+        values.push(p * values[i - 1] - q * values[i - 2]);
+    }
+
+    let count = F::from_usize(num_steps);
+    let values = &values;
+    let matrix = generate_rows(height, |range: Range<usize>| {
+        range.map(move |i| {
+            let first = i.min(num_steps);
+            let (a, b) = (values[first], values[first + 1]);
+            if i >= num_steps {
+                return LucasRow { is_real: F::ZERO, count, a, b };
+            }
+            LucasRow { is_real: F::ONE, count: F::from_usize(i + 1), a, b }
+        })
+    })?;
+
+    Ok(GeneratedTrace {
+        matrix,
+        real_rows: num_steps,
+        public_values: vec![p, q, u0, u1, values[num_steps], count],
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeField64;
+
+    use super::*;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::cost::SecurityLevel;
+    use crate::validate::validate_trace;
+    use crate::{Val, create_default_config};
+
+    #[test]
+    fn pell_numbers_satisfy_the_air() {
+        let trace = generate_lucas_trace::<Val>(Val::TWO, Val::NEG_ONE, Val::ZERO, Val::ONE, 10).unwrap();
+        assert_eq!(trace.public_values[4].as_canonical_u64(), 2378);
+        assert_eq!(validate_trace(&LucasAir, &trace.matrix, &trace.public_values), Ok(()));
+    }
+
+    #[test]
+    fn the_coefficients_are_bound_by_the_public_values() {
+        let trace = generate_lucas_trace::<Val>(Val::TWO, Val::NEG_ONE, Val::ZERO, Val::ONE, 10).unwrap();
+        // The same trace claimed as Fibonacci
+        let mut public_values = trace.public_values.clone();
+        public_values[0] = Val::ONE;
+        assert!(validate_trace(&LucasAir, &trace.matrix, &public_values).is_err());
+    }
+
+    #[test]
+    fn pell_and_p3_q2_prove_and_a_pell_proof_is_refused_under_p3() {
+        let config = create_default_config(SecurityLevel::Test);
+        // Pell: P(10) = 2378; P = 3, Q = 2: U(n) = 2^n - 1
+        let cases = [(Val::TWO, Val::NEG_ONE, 10, 2378), (Val::from_u8(3), Val::TWO, 20, (1 << 20) - 1)];
+        let mut proofs = Vec::new();
+        for (p, q, num_steps, expected) in cases {
+            let trace = generate_lucas_trace(p, q, Val::ZERO, Val::ONE, num_steps).unwrap();
+            assert_eq!(trace.public_values[4].as_canonical_u64(), expected);
+            let proof = prove_identified(&config, &LucasAir, trace.matrix, &trace.public_values);
+            assert_eq!(verify_identified(&config, &LucasAir, &proof, &trace.public_values), Ok(None));
+            proofs.push((proof, trace.public_values));
+        }
+
+        let (pell, mut public_values) = proofs.swap_remove(0);
+        public_values[0] = Val::from_u8(3);
+        assert!(verify_identified(&config, &LucasAir, &pell, &public_values).is_err());
+    }
+}
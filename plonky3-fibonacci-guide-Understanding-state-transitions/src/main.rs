@@ -1,247 +1,455 @@
-use core::borrow::Borrow;
-use p3_air::{Air, AirBuilder, BaseAir};
-use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::DuplexChallenger;
-use p3_commit::ExtensionMmcs;
-use p3_dft::Radix2DitParallel;
-use p3_field::extension::BinomialExtensionField;
-use p3_field::{Field, PrimeField64};
-use p3_fri::{TwoAdicFriPcs, create_test_fri_params};
-use p3_matrix::Matrix;
-use p3_matrix::dense::RowMajorMatrix;
-use p3_merkle_tree::MerkleTreeMmcs;
-use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{StarkConfig, prove, verify};
-
-// Fibonacci trace: 2 columns [a, b] representing consecutive Fibonacci numbers
-const NUM_FIBONACCI_COLS: usize = 2;
-
-#[derive(Debug, Clone)]
-pub struct FibonacciAir;
-
-impl<F> BaseAir<F> for FibonacciAir {
-    fn width(&self) -> usize {
-        NUM_FIBONACCI_COLS
-    }
+use std::panic;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use clap::{Args, Parser, Subcommand};
+use fibonacci_proof::DEFAULT_CONFIG_SEED;
+use fibonacci_proof::canonical::Reduction;
+use fibonacci_proof::capabilities::{format_capabilities, require};
+use fibonacci_proof::commands::{
+    run_calibrate, run_compare, run_conformance_check, run_constraint_snapshots, run_demo, run_inspect,
+    run_prove_dataset, run_self_test, run_soak, run_verify_dataset, run_verify_file,
+};
+use fibonacci_proof::cost::{CostParams, SecurityLevel};
+use fibonacci_proof::demos::{
+    run_big_fibonacci_demo, run_canonical_check, run_check_row, run_compare_challengers, run_compare_params,
+    run_dataset_demo, run_diff_traces, run_domain_check, run_dual_demo, run_index_check, run_lucas_demo,
+    run_multi_demo, run_panic_check, run_pow_check, run_provenance, run_reference_check, run_security_levels,
+    run_segments_demo, run_shared_config_check, run_trace_benchmark, run_tribonacci_demo, run_zk_demo,
+};
+use fibonacci_proof::exact::DEFAULT_EXACT_BOUND;
+use fibonacci_proof::stats::DEFAULT_THRESHOLD_PERCENT;
+use fibonacci_proof::walkthrough::{
+    CommitmentHash, ProverOptions, WalkthroughOptions, run_quiet, run_start_demo, run_walkthrough,
+};
+
+// Under mem-stats, soak measures the allocator's counters instead of RSS
+#[cfg(feature = "mem-stats")]
+#[global_allocator]
+static ALLOCATOR: fibonacci_proof::soak::CountingAllocator = fibonacci_proof::soak::CountingAllocator;
+
+// With no subcommand and no demo flag, the binary runs the walkthrough.
+// Unknown flags, and two demos at once, are usage errors. Flags and
+// subcommands of features this build lacks are still parsed, and fail naming
+// the feature.
+#[derive(Parser)]
+#[command(
+    about = "Proves F(n) with Plonky3, one stage at a time",
+    args_conflicts_with_subcommands = true,
+    disable_version_flag = true
+)]
+struct Cli {
+    #[arg(long, short = 'V', help = "Print the version; with --verbose, the compiled-in features too")]
+    version: bool,
+    #[arg(long, requires = "version")]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    demo: DemoArgs,
+    #[command(flatten)]
+    prover: ProverArgs,
+    #[command(flatten)]
+    walkthrough: WalkthroughArgs,
+    #[arg(long, global = true, help = "Take input values of p or more mod p instead of rejecting them")]
+    allow_reduction: bool,
 }
 
-impl<AB: AirBuilder> Air<AB> for FibonacciAir {
-    fn eval(&self, builder: &mut AB) {
-        let main = builder.main();
-
-        // Get current row and next row
-        let local_slice = main.row_slice(0).unwrap();
-        let next_slice = main.row_slice(1).unwrap();
-
-        let local: &FibonacciRow<AB::Var> = (*local_slice).borrow();
-        let next: &FibonacciRow<AB::Var> = (*next_slice).borrow();
-
-        // Constraint 1: Fibonacci recurrence relation
-        // next.b should equal local.a + local.b
-        let transition_constraint =
-            next.b.clone() - local.a.clone() - local.b.clone();
-        builder.assert_zero(transition_constraint);
-
-        // Constraint 2: State propagation
-        // next.a should equal local.b
-        let propagation_constraint =
-            next.a.clone() - local.b.clone();
-        builder.assert_zero(propagation_constraint);
-    }
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Prove and verify a tiny trace under every compiled-in config against its fingerprint")]
+    SelfTest,
+    #[command(about = "Verify a saved proof under the config described in its envelope")]
+    Verify {
+        proof: PathBuf,
+        #[arg(long, value_name = "F0,F1,RESULT,STEPS")]
+        public_values: String,
+        #[arg(long, help = "Also check a --emit-sequence file against the verified claim")]
+        sequence: Option<PathBuf>,
+    },
+    #[command(about = "Show a saved proof's config, AIR id and provenance digest without verifying it")]
+    Inspect { proof: PathBuf },
+    #[command(about = "Prove the sum and count of a CSV column")]
+    ProveDataset {
+        #[arg(long)]
+        csv: PathBuf,
+        #[arg(long)]
+        column: String,
+        #[arg(long, default_value = "dataset.proof")]
+        out: PathBuf,
+    },
+    #[command(about = "Verify a prove-dataset proof against its claim")]
+    VerifyDataset {
+        proof: PathBuf,
+        #[arg(long, value_name = "HEX")]
+        commitment: String,
+        #[arg(long)]
+        sum: u64,
+        #[arg(long)]
+        count: usize,
+    },
+    #[command(about = "Run every registered example, optionally writing an HTML report")]
+    Demo {
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+        #[arg(long, value_name = "PATH", help = "Also write each example's timings and throughput as JSON")]
+        json: Option<PathBuf>,
+    },
+    #[command(about = "Compare two demo --json files, failing if any metric regressed past the threshold")]
+    Compare {
+        old: PathBuf,
+        new: PathBuf,
+        #[arg(long, value_name = "PERCENT", default_value_t = DEFAULT_THRESHOLD_PERCENT)]
+        threshold: f64,
+    },
+    #[command(about = "Time this machine for the cost estimator")]
+    Calibrate {
+        #[arg(long, help = "Check the calibration without saving it")]
+        quick: bool,
+    },
+    #[command(about = "Prove, lint and attack every registered example")]
+    Conformance,
+    #[command(about = "Prove and verify in a loop, failing if memory keeps growing")]
+    Soak {
+        #[arg(long)]
+        minutes: u64,
+    },
+    #[command(about = "Compare every AIR's constraints with fixtures/constraints.txt")]
+    Constraints {
+        #[arg(long, help = "Print the current snapshots instead")]
+        print: bool,
+    },
+    #[command(about = "Write JSON test vectors for other verifiers, one file per security level")]
+    ExportVectors {
+        #[arg(long, default_value = "vectors")]
+        out: PathBuf,
+    },
+    #[command(about = "Rebuild every fixture and list what changed")]
+    RegenFixtures {
+        #[arg(long, help = "Write the changes instead of failing on them")]
+        apply: bool,
+    },
 }
 
-// Row structure: [a, b] where a = F(n-1), b = F(n)
-#[derive(Debug, Clone)]
-pub struct FibonacciRow<F> {
-    pub a: F,  // F(n-1)
-    pub b: F,  // F(n)
+// One stand-alone demo in place of the walkthrough
+#[derive(Args)]
+#[group(multiple = false)]
+struct DemoArgs {
+    #[arg(long, help = "The main proof as one line, for scripts")]
+    quiet: bool,
+    #[arg(long, num_args = 2, value_names = ["A", "B"], help = "The recurrence from (A, B) instead of (0, 1)")]
+    start: Option<Vec<String>>,
+    #[arg(
+        long,
+        num_args = 2..=4,
+        value_names = ["LOCAL", "NEXT", "KIND", "RESULT"],
+        help = "FibonacciAir's constraints on one window of two CSV rows"
+    )]
+    check_row: Option<Vec<String>>,
+    #[arg(long, num_args = 2, value_names = ["A", "B"], help = "The first rows where two trace files differ")]
+    diff_traces: Option<Vec<PathBuf>>,
+    #[arg(long, help = "Every library entry point down its error paths")]
+    panic_check: bool,
+    #[arg(long, help = "p - 1, p and 2^63 through every input parser")]
+    canonical_check: bool,
+    #[arg(long, help = "The Plonky3 revision and features of this build")]
+    provenance: bool,
+    #[arg(long, help = "One statement under the duplex and Keccak challenger configs")]
+    dual: bool,
+    #[arg(long, help = "The sample dataset proved, then edited")]
+    dataset_demo: bool,
+    #[arg(long, help = "1000 steps as a chain of segment proofs")]
+    segments: bool,
+    #[arg(long, help = "IndexedFibonacciAir and a repeated row index")]
+    index_check: bool,
+    #[arg(long, help = "A 1000-step trace against the BigUint reference")]
+    reference_check: bool,
+    #[arg(long, help = "F(100) over the integers in 16-bit limbs")]
+    big: bool,
+    #[arg(long, help = "8 sequences in one proof")]
+    multi: bool,
+    #[arg(long, help = "Lucas sequences for two (P, Q) pairs")]
+    lucas: bool,
+    #[arg(long, help = "Order-3 and order-4 recurrences")]
+    tribonacci: bool,
+    #[arg(long, help = "A proof verified under the shared stark_config constructor")]
+    shared_config_check: bool,
+    #[arg(long, help = "Proofs bound to a Fiat-Shamir domain string")]
+    domain_check: bool,
+    #[arg(long, help = "Hiding proofs under fresh blinding")]
+    zk: bool,
+    #[arg(long, help = "A ground proof under verifiers expecting other pow bits")]
+    pow_check: bool,
+    #[arg(long, help = "One trace under several FRI parameter sets")]
+    compare_params: bool,
+    #[arg(long, help = "The main statement at every security level")]
+    security_levels: bool,
+    #[arg(long, help = "Proving time under the duplex and Keccak challengers")]
+    compare_challengers: bool,
+    #[arg(long, help = "Serial against parallel trace generation")]
+    bench_trace: bool,
+    #[arg(long, help = "Every exercise slot against its cases")]
+    exercises: bool,
+    #[arg(long, value_name = "N", help = "Stage N of the tutorial")]
+    stage: Option<String>,
+    #[arg(long, value_name = "PATH", help = "Log the Fiat-Shamir transcripts of a small proof")]
+    transcript_log: Option<PathBuf>,
+    #[arg(long, help = "One trace proved over BabyBear and KoalaBear")]
+    compare_fields: bool,
 }
 
-impl<F> FibonacciRow<F> {
-    const fn new(a: F, b: F) -> Self {
-        Self { a, b }
-    }
+// What the main proof is built from; --log-blowup, --pow-bits and
+// --min-height override the security level's own values
+#[derive(Args)]
+struct ProverArgs {
+    #[arg(long, default_value_t = 100)]
+    steps: usize,
+    #[arg(long, default_value_t = DEFAULT_CONFIG_SEED)]
+    seed: u64,
+    #[arg(long, default_value_t = SecurityLevel::Test, help = "test, 80 or 100")]
+    security: SecurityLevel,
+    #[arg(long)]
+    log_blowup: Option<usize>,
+    #[arg(long)]
+    pow_bits: Option<usize>,
+    #[arg(long)]
+    min_height: Option<usize>,
+    #[arg(long, help = "Also prove under poseidon2, keccak or blake3 Merkle trees")]
+    hash: Option<CommitmentHash>,
+    #[arg(long, value_name = "DIR", help = "With --quiet, reuse a proof an earlier run stored in DIR")]
+    cache_dir: Option<PathBuf>,
 }
 
-// Memory layout conversion for efficient access
-impl<F> Borrow<FibonacciRow<F>> for [F] {
-    fn borrow(&self) -> &FibonacciRow<F> {
-        debug_assert_eq!(self.len(), NUM_FIBONACCI_COLS);
-        let (prefix, rows, suffix) = unsafe {
-            self.align_to::<FibonacciRow<F>>()
+impl ProverArgs {
+    fn options(&self) -> Result<ProverOptions, String> {
+        let defaults = CostParams::at(self.security);
+        let params = CostParams {
+            log_blowup: self.log_blowup.unwrap_or(defaults.log_blowup),
+            pow_bits: self.pow_bits.unwrap_or(defaults.pow_bits),
+            min_height: self.min_height.unwrap_or(defaults.min_height),
+            ..defaults
         };
-        debug_assert!(prefix.is_empty(), "Alignment should match");
-        debug_assert!(suffix.is_empty(), "Alignment should match");
-        debug_assert_eq!(rows.len(), 1);
-        &rows[0]
+        let options = ProverOptions::new(self.steps, self.seed, params, self.hash.unwrap_or_default())?;
+        Ok(ProverOptions { cache_dir: self.cache_dir.clone(), ..options })
     }
 }
 
-pub fn generate_fibonacci_trace<F: Field + PrimeField64>(num_steps: usize) -> RowMajorMatrix<F> {
-    // Ensure power of 2 for FFT operations
-    let n = num_steps.next_power_of_two().max(256);
-
-    let mut trace = RowMajorMatrix::new(
-        F::zero_vec(n * NUM_FIBONACCI_COLS),
-        NUM_FIBONACCI_COLS
-    );
-
-    let (prefix, rows, suffix) = unsafe {
-        trace.values.align_to_mut::<FibonacciRow<F>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), n);
-
-    // Initialize: F(0) = 0, F(1) = 1
-    rows[0] = FibonacciRow::new(F::ZERO, F::ONE);
-
-    // Generate Fibonacci sequence: F(n) = F(n-1) + F(n-2)
-    for i in 1..num_steps {
-        let prev_a = rows[i - 1].a;
-        let prev_b = rows[i - 1].b;
+#[derive(Args)]
+struct WalkthroughArgs {
+    #[arg(long, default_value_t = 0, help = "Prover threads, 0 for every core")]
+    threads: usize,
+    #[arg(long, default_value_t = DEFAULT_EXACT_BOUND, help = "Show sample values past this index mod p only")]
+    exact_bound: usize,
+    #[arg(long, help = "Report every constraint violation, not just the first")]
+    check_full: bool,
+    #[arg(long, help = "Shrink a failing trace to its smallest failing window")]
+    shrink: bool,
+    #[arg(long, value_name = "PATH")]
+    emit_trace: Option<PathBuf>,
+    #[arg(long, value_name = "PATH")]
+    emit_sequence: Option<PathBuf>,
+    #[arg(long, value_name = "PATH", help = "Check a sequence file against the proved result")]
+    sequence: Option<PathBuf>,
+    #[arg(long, help = "Refuse to prove an AIR with lint findings")]
+    strict: bool,
+    #[arg(long, help = "Report proving phases through the tracing observer")]
+    trace_phases: bool,
+    #[arg(long, value_name = "PATH", help = "Append the proof to a hash-chained journal")]
+    journal: Option<PathBuf>,
+}
 
-        rows[i] = FibonacciRow::new(
-            prev_b,           // a = previous b (shift forward)
-            prev_a + prev_b   // b = F(n) = F(n-1) + F(n-2)
-        );
+impl WalkthroughArgs {
+    fn options(self) -> WalkthroughOptions {
+        WalkthroughOptions {
+            threads: self.threads,
+            exact_bound: self.exact_bound,
+            check_full: self.check_full,
+            shrink: self.shrink,
+            emit_trace: self.emit_trace,
+            emit_sequence: self.emit_sequence,
+            sequence: self.sequence,
+            strict: self.strict,
+            trace_phases: self.trace_phases,
+            journal: self.journal,
+        }
     }
+}
 
-    // Pad remaining rows with final values to meet power-of-2 requirement
-    for i in num_steps..n {
-        rows[i] = rows[num_steps - 1].clone();
+fn run_command(command: Command, reduction: Reduction) -> Result<bool, String> {
+    match command {
+        Command::SelfTest => Ok(run_self_test()),
+        Command::Verify { proof, public_values, sequence } => {
+            run_verify_file(&proof, &public_values, sequence.as_deref(), reduction).map(|()| true)
+        }
+        Command::Inspect { proof } => run_inspect(&proof).map(|()| true),
+        Command::ProveDataset { csv, column, out } => run_prove_dataset(&csv, &column, &out).map(|()| true),
+        Command::VerifyDataset { proof, commitment, sum, count } => {
+            run_verify_dataset(&proof, &commitment, sum, count).map(|()| true)
+        }
+        Command::Demo { report, json } => Ok(run_demo(report.as_deref(), json.as_deref())),
+        Command::Compare { old, new, threshold } => run_compare(&old, &new, threshold),
+        Command::Calibrate { quick } => run_calibrate(quick).map(|()| true),
+        Command::Conformance => Ok(run_conformance_check()),
+        Command::Soak { minutes } => Ok(run_soak(minutes)),
+        Command::Constraints { print } => Ok(run_constraint_snapshots(print)),
+        Command::ExportVectors { out } => gated("transcript-log", "export-vectors", || {
+            #[cfg(feature = "transcript-log")]
+            fibonacci_proof::commands::run_export_vectors(&out)?;
+            #[cfg(not(feature = "transcript-log"))]
+            let _ = out;
+            Ok(())
+        })
+        .map(|()| true),
+        Command::RegenFixtures { apply } => gated("regen-fixtures", "regen-fixtures", || {
+            #[cfg(feature = "regen-fixtures")]
+            fibonacci_proof::commands::run_regen_fixtures(apply)?;
+            #[cfg(not(feature = "regen-fixtures"))]
+            let _ = apply;
+            Ok(())
+        })
+        .map(|()| true),
     }
-
-    trace
 }
 
-// Type definitions following Plonky3 patterns
-type Val = BabyBear;
-type Perm = Poseidon2BabyBear<16>;
-type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
-type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
-type ValMmcs = MerkleTreeMmcs<
-    <Val as Field>::Packing,
-    <Val as Field>::Packing,
-    MyHash,
-    MyCompress,
-    8
->;
-type Challenge = BinomialExtensionField<Val, 4>;
-type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
-type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
-type Dft = Radix2DitParallel<Val>;
-type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
-type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+// `run` when `feature` is compiled in, else the error naming it
+fn gated(
+    feature: &'static str,
+    flag: &'static str,
+    run: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    require(feature, flag).map_err(|e| e.to_string())?;
+    run()
+}
 
-// Simple RNG for deterministic setup
-struct SimpleRng {
-    state: u64,
+// `run` with panic messages silenced, for the exercise harness, which
+// catches an unfinished todo!() slot and reports it itself
+#[cfg(feature = "exercises")]
+fn quietly<T>(run: impl FnOnce() -> T) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = run();
+    panic::set_hook(hook);
+    result
 }
 
-impl SimpleRng {
-    fn new(seed: u64) -> Self {
-        Self { state: seed }
-    }
+// --panic-check under a hook that prints and counts every panic, including
+// those the library catches and turns into errors
+fn run_counted_panic_check() {
+    static PANICS: AtomicUsize = AtomicUsize::new(0);
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        PANICS.fetch_add(1, Ordering::SeqCst);
+        println!("   panic: {}", info);
+    }));
+    run_panic_check();
+    panic::set_hook(hook);
+    println!("   Panics: {}", PANICS.load(Ordering::SeqCst));
 }
 
-impl rand::RngCore for SimpleRng {
-    fn next_u32(&mut self) -> u32 {
-        self.state = self.state.wrapping_mul(1103515245).wrapping_add(12345);
-        (self.state >> 32) as u32
+// The demo named by a flag, if any flag named one
+fn run_demo_flag(demo: &DemoArgs, prover: &ProverArgs, reduction: Reduction) -> Option<Result<(), String>> {
+    if demo.exercises {
+        return Some(gated("exercises", "--exercises", || {
+            #[cfg(feature = "exercises")]
+            if !quietly(fibonacci_proof::demos::run_exercises) {
+                return Err("Not every exercise passes yet".to_string());
+            }
+            Ok(())
+        }));
     }
-
-    fn next_u64(&mut self) -> u64 {
-        let high = self.next_u32() as u64;
-        let low = self.next_u32() as u64;
-        (high << 32) | low
+    if let Some(stage) = &demo.stage {
+        return Some(gated("tutorial", "--stage", || {
+            #[cfg(feature = "tutorial")]
+            fibonacci_proof::demos::run_stage(stage);
+            #[cfg(not(feature = "tutorial"))]
+            let _ = stage;
+            Ok(())
+        }));
     }
-
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        for chunk in dest.chunks_mut(4) {
-            let val = self.next_u32().to_le_bytes();
-            for (i, &byte) in val.iter().enumerate() {
-                if i < chunk.len() {
-                    chunk[i] = byte;
-                }
-            }
-        }
+    if let Some(path) = &demo.transcript_log {
+        return Some(gated("transcript-log", "--transcript-log", || {
+            #[cfg(feature = "transcript-log")]
+            fibonacci_proof::demos::run_transcript_log(path);
+            #[cfg(not(feature = "transcript-log"))]
+            let _ = path;
+            Ok(())
+        }));
     }
+    if demo.compare_fields {
+        return Some(gated("koala-bear", "--compare-fields", || {
+            #[cfg(feature = "koala-bear")]
+            fibonacci_proof::demos::run_compare_fields();
+            Ok(())
+        }));
+    }
+    if demo.quiet {
+        return Some(prover.options().and_then(|options| run_quiet(&options).map_err(|e| format!("error: {}", e))));
+    }
+    if let Some(start) = &demo.start {
+        let start = [start[0].as_str(), start[1].as_str()];
+        return Some(prover.options().and_then(|options| run_start_demo(&options, start, reduction)));
+    }
+    if let Some(rows) = &demo.check_row {
+        return Some(run_check_row(rows, reduction));
+    }
+    if let Some(files) = &demo.diff_traces {
+        run_diff_traces(&files[0], &files[1]);
+        return Some(Ok(()));
+    }
+    let demos: [(bool, fn()); 20] = [
+        (demo.panic_check, run_counted_panic_check),
+        (demo.canonical_check, run_canonical_check),
+        (demo.provenance, run_provenance),
+        (demo.dual, run_dual_demo),
+        (demo.dataset_demo, run_dataset_demo),
+        (demo.segments, run_segments_demo),
+        (demo.index_check, run_index_check),
+        (demo.reference_check, run_reference_check),
+        (demo.big, run_big_fibonacci_demo),
+        (demo.multi, run_multi_demo),
+        (demo.lucas, run_lucas_demo),
+        (demo.tribonacci, run_tribonacci_demo),
+        (demo.shared_config_check, run_shared_config_check),
+        (demo.domain_check, run_domain_check),
+        (demo.zk, || run_zk_demo(100)),
+        (demo.pow_check, run_pow_check),
+        (demo.compare_params, run_compare_params),
+        (demo.security_levels, || run_security_levels(100)),
+        (demo.compare_challengers, run_compare_challengers),
+        (demo.bench_trace, run_trace_benchmark),
+    ];
+    let (_, run) = demos.into_iter().find(|(selected, _)| *selected)?;
+    run();
+    Some(Ok(()))
 }
 
-impl rand::CryptoRng for SimpleRng {}
-
-fn create_config() -> MyConfig {
-    let mut rng = SimpleRng::new(42);
-    let perm = Perm::new_from_rng_128(&mut rng);
-    let hash = MyHash::new(perm.clone());
-    let compress = MyCompress::new(perm.clone());
-    let val_mmcs = ValMmcs::new(hash, compress);
-    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-    let dft = Dft::default();
-    let fri_params = create_test_fri_params(challenge_mmcs, 4);
-    let pcs = Pcs::new(dft, val_mmcs, fri_params);
-    let challenger = Challenger::new(perm);
-    MyConfig::new(pcs, challenger)
-}
-
-fn main() {
-    println!(" Plonky3 Fibonacci Proof System");
-    println!("   Proving: F(n) = F(n-1) + F(n-2)");
-    println!("   Computing: F(0)=0, F(1)=1, F(2)=1, F(3)=2, F(4)=3, F(5)=5...");
-    println!();
-
-    // Generate Fibonacci sequence up to F(100)
-    let num_steps = 100;
-    let air = FibonacciAir;
-    let trace = generate_fibonacci_trace::<Val>(num_steps);
-    let config = create_config();
-
-    // Display some values from the trace
-    println!(" Generated execution trace:");
-    println!("   Computing {} Fibonacci numbers", num_steps);
-    println!("   Trace padded to {} rows (power of 2)", trace.height());
-
-    // Calculate and display some Fibonacci values
-    let trace_data = &trace.values;
-    println!("\n   Sample values:");
-    for i in [0, 1, 2, 3, 4, 5, 10, 20, 50, 99].iter() {
-        if *i < num_steps {
-            let idx = i * NUM_FIBONACCI_COLS;
-            let _a = trace_data[idx];
-            let b = trace_data[idx + 1];
-            println!("   F({}) = {}", i, b);
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.version {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        if cli.verbose {
+            print!("{}", format_capabilities());
         }
+        return ExitCode::SUCCESS;
     }
-    println!();
-
-    println!("   Constraints:");
-    println!("   1. Transition: next.b = local.a + local.b (Fibonacci rule)");
-    println!("   2. Propagation: next.a = local.b (state shift)");
-    println!();
-
-    println!(" Generating STARK proof...");
-    let proof = prove(&config, &air, trace, &vec![]);
-
-    println!(" Proof generated successfully!");
-    println!();
-
-    println!(" Verifying proof...");
-    let verify_result = verify(&config, &air, &proof, &vec![]);
-
-    match verify_result {
-        Ok(()) => {
-            println!(" Proof verified successfully!");
-            println!();
-            println!(" What was proven:");
-            println!("   - The prover knows a valid Fibonacci sequence");
-            println!("   - Every step satisfies F(n) = F(n-1) + F(n-2)");
-            println!("   - The sequence starts with F(0)=0, F(1)=1");
-            println!("   - All {} steps are correctly computed", num_steps);
+    let reduction = if cli.allow_reduction { Reduction::Allow } else { Reduction::Reject };
+    let outcome = match cli.command {
+        Some(command) => run_command(command, reduction),
+        None => match run_demo_flag(&cli.demo, &cli.prover, reduction) {
+            Some(result) => result.map(|()| true),
+            None => cli.prover.options().map(|options| {
+                run_walkthrough(&options, &cli.walkthrough.options());
+                true
+            }),
         },
+    };
+    match outcome {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
         Err(e) => {
-            println!(" Verification failed: {:?}", e);
-            return;
+            println!(" {}", e);
+            ExitCode::FAILURE
         }
     }
 }
@@ -0,0 +1,73 @@
+// FibonacciAir over Mersenne31 (p = 2^31 - 1) with a circle PCS. p - 1 has
+// only one factor of two, so Mersenne31 has no two-adic subgroups for
+// TwoAdicFriPcs; CirclePcs evaluates the trace over the circle group
+// x^2 + y^2 = 1 instead, whose order p + 1 = 2^31 is a power of two. The AIR
+// is unchanged: the PCS is the only part of the config that knows about the
+// domain. Poseidon2 over 16 elements and the FRI parameters match the
+// BabyBear config, so the two proofs differ in the PCS and the field alone.
+//
+// As with the goldilocks module, proof files, AIR ids and the journal stay on
+// BabyBear; this module proves and verifies plain p3 proofs.
+use p3_challenger::DuplexChallenger;
+use p3_circle::CirclePcs;
+use p3_commit::ExtensionMmcs;
+use p3_field::Field;
+use p3_field::extension::BinomialExtensionField;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_mersenne_31::{Mersenne31, Poseidon2Mersenne31};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::cost::SecurityLevel;
+use crate::trace::SizingError;
+use crate::{DEFAULT_CONFIG_SEED, FibonacciAir, constants_rng, fri_params_for, generate_fibonacci_trace};
+
+pub type Mersenne31Perm = Poseidon2Mersenne31<16>;
+pub type Mersenne31Hash = PaddingFreeSponge<Mersenne31Perm, 16, 8, 8>;
+pub type Mersenne31Compress = TruncatedPermutation<Mersenne31Perm, 2, 8, 16>;
+pub type Mersenne31ValMmcs = MerkleTreeMmcs<
+    <Mersenne31 as Field>::Packing,
+    <Mersenne31 as Field>::Packing,
+    Mersenne31Hash,
+    Mersenne31Compress,
+    8,
+>;
+// Mersenne31 has no binomial extension of degree 4; degree 3 gives 93 bits
+pub type Mersenne31Challenge = BinomialExtensionField<Mersenne31, 3>;
+pub type Mersenne31ChallengeMmcs = ExtensionMmcs<Mersenne31, Mersenne31Challenge, Mersenne31ValMmcs>;
+pub type Mersenne31Challenger = DuplexChallenger<Mersenne31, Mersenne31Perm, 16, 8>;
+pub type Mersenne31Pcs = CirclePcs<Mersenne31, Mersenne31ValMmcs, Mersenne31ChallengeMmcs>;
+pub type Mersenne31Config = StarkConfig<Mersenne31Pcs, Mersenne31Challenge, Mersenne31Challenger>;
+
+// create_config_with_seed for Mersenne31: same seed handling and FRI
+// parameters, with no DFT since CirclePcs does its own extrapolation
+pub fn create_mersenne_31_config(seed: u64) -> Mersenne31Config {
+    let mut rng = constants_rng(seed);
+    let perm = Mersenne31Perm::new_from_rng_128(&mut rng);
+    let hash = Mersenne31Hash::new(perm.clone());
+    let compress = Mersenne31Compress::new(perm.clone());
+    let val_mmcs = Mersenne31ValMmcs::new(hash, compress);
+    let challenge_mmcs = Mersenne31ChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = Mersenne31Pcs::new(val_mmcs, fri_params);
+    Mersenne31Config::new(pcs, Mersenne31Challenger::new(perm))
+}
+
+// prove_fibonacci over Mersenne31, returning the public values
+// [F(0), F(1), F(num_steps), num_steps]
+pub fn prove_fibonacci_mersenne_31(
+    num_steps: usize,
+) -> Result<(Proof<Mersenne31Config>, Vec<Mersenne31>), SizingError> {
+    let trace = generate_fibonacci_trace::<Mersenne31>(num_steps)?;
+    let config = create_mersenne_31_config(DEFAULT_CONFIG_SEED);
+    let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_fibonacci_mersenne_31(
+    proof: &Proof<Mersenne31Config>,
+    public_values: &Vec<Mersenne31>,
+) -> Result<(), String> {
+    let config = create_mersenne_31_config(DEFAULT_CONFIG_SEED);
+    verify(&config, &FibonacciAir, proof, public_values).map_err(|e| format!("{:?}", e))
+}
@@ -0,0 +1,143 @@
+// Many independent Fibonacci sequences side by side in one trace, for
+// throughput experiments: one proof, one commitment and one set of FRI
+// queries cover them all. The width depends on the instance, so there is no
+// fixed-size row struct to borrow; eval indexes the row slice by column pair.
+//
+// Every row is a step, as in CounterAir: sequence s starts from its public
+// pair on the first row and its public final value is the b column of the
+// last row, G(height) for a trace of `height` rows.
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::claims::{NUM_FIBONACCI_CLAIM_VALUES, claim_values};
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, DEFAULT_MIN_HEIGHT, GeneratedTrace, SizingError, trace_height, trace_len};
+
+pub const MULTI_FIBONACCI_AIR_ID: AirId = AirId::new("multi_fibonacci", 1, 0, 0);
+
+// Columns: a and b of each sequence in turn. Public values: the FibonacciClaims
+// layout [a0, b0, final] of each sequence in turn.
+#[derive(Debug, Clone)]
+pub struct MultiFibonacciAir {
+    pub num_sequences: usize,
+}
+
+impl<F> BaseAir<F> for MultiFibonacciAir {
+    fn width(&self) -> usize {
+        2 * self.num_sequences
+    }
+}
+
+impl AirLint for MultiFibonacciAir {
+    fn num_public_values(&self) -> usize {
+        NUM_FIBONACCI_CLAIM_VALUES * self.num_sequences
+    }
+}
+
+impl ProvableComputation for MultiFibonacciAir {
+    fn air_id(&self) -> AirId {
+        MULTI_FIBONACCI_AIR_ID
+    }
+}
+
+// The columns are left unnamed
+impl ColumnNames for MultiFibonacciAir {
+    fn column_names(&self) -> &[&'static str] {
+        &[]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for MultiFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let pis = builder.public_values().to_vec();
+
+        // FibonacciAir's two constraints and boundaries, once per column pair
+        for (s, claim) in pis.chunks_exact(NUM_FIBONACCI_CLAIM_VALUES).enumerate() {
+            let (a, b) = (local[2 * s].clone(), local[2 * s + 1].clone());
+            let (next_a, next_b) = (next[2 * s].clone(), next[2 * s + 1].clone());
+
+            let mut when_first_row = builder.when_first_row();
+            when_first_row.assert_eq(a.clone(), claim[0]);
+            when_first_row.assert_eq(b.clone(), claim[1]);
+
+            let mut when_transition = builder.when_transition();
+            when_transition.assert_eq(next_b, a + b.clone());
+            when_transition.assert_eq(next_a, b.clone());
+
+            builder.when_last_row().assert_eq(b, claim[2]);
+        }
+    }
+}
+
+// Starting pairs (s, 1) for s = 0, 1, ...: Fibonacci first and the Lucas
+// numbers third
+pub fn sample_starts<F: Field>(num_sequences: usize) -> Vec<(F, F)> {
+    (0..num_sequences).map(|s| (F::from_usize(s), F::ONE)).collect()
+}
+
+// One sequence per pair in `starts`, all over the same rows: `rows` rounded
+// up to a power of two of at least DEFAULT_MIN_HEIGHT, every one a step
+pub fn generate_multi_fibonacci_trace<F: Field>(
+    starts: &[(F, F)],
+    rows: usize,
+) -> Result<GeneratedTrace<(), F>, SizingError> {
+    if starts.is_empty() {
+        return Err(SizingError::NoColumns);
+    }
+    let height = trace_height(rows, DEFAULT_MIN_HEIGHT)?;
+    let width = 2 * starts.len();
+    let mut matrix = RowMajorMatrix::new(F::zero_vec(trace_len(height, width)?), width);
+
+    let mut pairs = starts.to_vec();
+    for row in matrix.values.chunks_exact_mut(width) {
+        for (cells, (a, b)) in row.chunks_exact_mut(2).zip(pairs.iter_mut()) {
+            (cells[0], cells[1]) = (*a, *b);
+            // F(n) = F(n-1) + F(n-2)
+            (*a, *b) = (*b, *a + *b);
+        }
+    }
+
+    // After the last row `pairs` has moved one step on, so each a is the
+    // last row's b
+    let public_values = starts.iter().zip(&pairs).flat_map(|(&(a0, b0), &(a, _))| claim_values(a0, b0, a)).collect();
+    Ok(GeneratedTrace { matrix, real_rows: height, public_values, meta: () })
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::cost::SecurityLevel;
+    use crate::{Val, create_default_config, generate_recurrence_trace};
+
+    #[test]
+    fn eight_sequences_of_1024_steps_prove_each_final_value() {
+        let (num_sequences, num_steps) = (8, 1024);
+        let air = MultiFibonacciAir { num_sequences };
+        let starts = sample_starts::<Val>(num_sequences);
+        let trace = generate_multi_fibonacci_trace(&starts, num_steps).unwrap();
+        assert_eq!(trace.matrix.width(), 2 * num_sequences);
+
+        let config = create_default_config(SecurityLevel::Test);
+        let proof = prove_identified(&config, &air, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&config, &air, &proof, &trace.public_values), Ok(None));
+        let claims: Vec<&[Val]> = trace.public_values.chunks_exact(3).collect();
+        assert_eq!(claims.len(), num_sequences);
+        for (claim, &(a0, b0)) in claims.iter().zip(&starts) {
+            let single = generate_recurrence_trace(a0, b0, num_steps).unwrap();
+            assert_eq!(*claim, [a0, b0, single.meta.final_value], "from ({}, {})", a0, b0);
+        }
+
+        let mut wrong = trace.public_values.clone();
+        wrong[5 * 3 + 2] += Val::ONE;
+        assert!(verify_identified(&config, &air, &proof, &wrong).is_err());
+    }
+}
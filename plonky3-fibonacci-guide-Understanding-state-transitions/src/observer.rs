@@ -0,0 +1,143 @@
+use core::fmt;
+use std::time::{Duration, Instant};
+
+// Stages of prove_identified that are visible from outside p3_uni_stark.
+// Trace commitment, quotient and openings all happen inside one prove call,
+// so Stark is the finest split available without forking the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhaseId {
+    // The whole wrapper; every other phase is nested inside it
+    Total,
+    // Hashing the AirId into its public value
+    AirId,
+    // The inner p3_uni_stark::prove call
+    Stark,
+}
+
+impl fmt::Display for PhaseId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Total => "total",
+            Self::AirId => "air id",
+            Self::Stark => "stark prove",
+        };
+        f.write_str(name)
+    }
+}
+
+// Callbacks around each phase. Calls are strictly nested: every start is
+// matched by an end before the enclosing phase ends.
+pub trait ProofObserver {
+    fn on_phase_start(&mut self, phase: PhaseId);
+    fn on_phase_end(&mut self, phase: PhaseId);
+}
+
+// Observes nothing; used by the plain prove wrappers
+impl ProofObserver for () {
+    fn on_phase_start(&mut self, _phase: PhaseId) {}
+    fn on_phase_end(&mut self, _phase: PhaseId) {}
+}
+
+// Runs `f` as `phase`, reporting its start and end to `observer`. `f` gets the
+// observer back so it can report nested phases.
+pub fn observe<R>(
+    observer: &mut dyn ProofObserver,
+    phase: PhaseId,
+    f: impl FnOnce(&mut dyn ProofObserver) -> R,
+) -> R {
+    observer.on_phase_start(phase);
+    let result = f(&mut *observer);
+    observer.on_phase_end(phase);
+    result
+}
+
+// Prints each phase to stderr as it starts and ends, indented by nesting
+// depth, so it never mixes with a caller's stdout
+#[derive(Debug, Default)]
+pub struct TracingObserver {
+    open: Vec<Instant>,
+}
+
+impl ProofObserver for TracingObserver {
+    fn on_phase_start(&mut self, phase: PhaseId) {
+        eprintln!("   {}> {}", "  ".repeat(self.open.len()), phase);
+        self.open.push(Instant::now());
+    }
+
+    fn on_phase_end(&mut self, phase: PhaseId) {
+        // observe() always pairs start and end; an unmatched end is a caller
+        // bug, so flag it in debug builds and otherwise drop it
+        let Some(start) = self.open.pop() else {
+            debug_assert!(false, "phase ended without starting");
+            return;
+        };
+        eprintln!("   {}< {} ({:?})", "  ".repeat(self.open.len()), phase, start.elapsed());
+    }
+}
+
+// A finished phase, with its nesting depth (0 for Total)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub phase: PhaseId,
+    pub depth: usize,
+    pub duration: Duration,
+}
+
+// Records every phase in the order it ended
+#[derive(Debug, Default)]
+pub struct CollectingObserver {
+    open: Vec<(PhaseId, Instant)>,
+    pub phases: Vec<PhaseTiming>,
+}
+
+impl CollectingObserver {
+    pub fn duration(&self, phase: PhaseId) -> Duration {
+        self.phases.iter().filter(|t| t.phase == phase).map(|t| t.duration).sum()
+    }
+}
+
+impl ProofObserver for CollectingObserver {
+    fn on_phase_start(&mut self, phase: PhaseId) {
+        self.open.push((phase, Instant::now()));
+    }
+
+    fn on_phase_end(&mut self, phase: PhaseId) {
+        let Some((started, start)) = self.open.pop() else {
+            debug_assert!(false, "phase ended without starting");
+            return;
+        };
+        debug_assert_eq!(started, phase, "phases must end in the reverse order they started");
+        let depth = self.open.len();
+        self.phases.push(PhaseTiming { phase, depth, duration: start.elapsed() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air_id::prove_identified_observed;
+    use crate::cost::MIN_TRACE_HEIGHT;
+    use crate::{FibonacciAir, Val, generate_fibonacci_trace_padded, test_config};
+
+    #[test]
+    fn phases_end_innermost_first_with_their_depth() {
+        let trace = generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap();
+        let mut observer = CollectingObserver::default();
+        prove_identified_observed(&test_config(), &FibonacciAir, trace.matrix, &trace.public_values, &mut observer);
+        let ended: Vec<_> = observer.phases.iter().map(|t| (t.phase, t.depth)).collect();
+        assert_eq!(ended, [(PhaseId::AirId, 1), (PhaseId::Stark, 1), (PhaseId::Total, 0)]);
+        assert!(observer.duration(PhaseId::Total) >= observer.duration(PhaseId::Stark));
+    }
+
+    #[test]
+    fn observe_returns_the_result_and_counts_repeats() {
+        let mut observer = CollectingObserver::default();
+        let sum = observe(&mut observer, PhaseId::Total, |observer| {
+            observe(observer, PhaseId::Stark, |_| 1) + observe(observer, PhaseId::Stark, |_| 2)
+        });
+        assert_eq!(sum, 3);
+        assert_eq!(observer.phases.len(), 3);
+        assert_eq!(observer.phases.iter().filter(|t| t.phase == PhaseId::Stark).count(), 2);
+        assert_eq!(observer.duration(PhaseId::AirId), Duration::ZERO);
+    }
+}
@@ -0,0 +1,229 @@
+use core::borrow::Borrow;
+use core::fmt;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::air_id::{AirId, ProvableComputation};
+use crate::last_row::bind_physical_last_row;
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, GeneratedTrace, trace_len};
+use crate::{FibonacciRow, Val};
+
+// half is range-checked to [0, HALF_BOUND] with HALF_BITS-bit limbs
+const HALF_BITS: usize = 30;
+// Any field element b = 2 * half + parity has two splittings, one with half
+// below (p - 1) / 2 and one at or above it. Capping half just under (p - 1) / 2
+// keeps only the first, i.e. parity is the low bit of b's canonical value.
+// The one element this excludes is p - 1, whose splitting needs half = (p - 1) / 2.
+const HALF_BOUND: u64 = (Val::ORDER_U64 - 1) / 2 - 1;
+
+const NUM_PARITY_COLS: usize = 4 + 2 * HALF_BITS;
+
+pub const PARITY_AIR_ID: AirId = AirId::new("parity", 1, 0, 0);
+
+// Fibonacci with the parity of each b split off: b = 2 * half + parity, with
+// half range-checked so the split is unique. The last row's parity is public.
+//
+// This is the parity of F(n) mod p. It agrees with the integer parity (the
+// 0, 1, 1 period-3 pattern) only while F(n) < p, i.e. up to n = 46.
+#[derive(Debug, Clone)]
+pub struct ParityAir;
+
+#[repr(C)]
+pub struct ParityRow<F> {
+    pub fib: FibonacciRow<F>,
+    pub half: F,
+    pub parity: F,
+    pub half_bits: [F; HALF_BITS],
+    // HALF_BOUND - half, proving half <= HALF_BOUND
+    pub slack_bits: [F; HALF_BITS],
+}
+
+impl<F> Borrow<ParityRow<F>> for [F] {
+    fn borrow(&self) -> &ParityRow<F> {
+        debug_assert_eq!(self.len(), NUM_PARITY_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<ParityRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+impl<F> BaseAir<F> for ParityAir {
+    fn width(&self) -> usize {
+        NUM_PARITY_COLS
+    }
+}
+
+impl AirLint for ParityAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl ProvableComputation for ParityAir {
+    fn air_id(&self) -> AirId {
+        PARITY_AIR_ID
+    }
+}
+
+// The bit limbs are left unnamed
+impl ColumnNames for ParityAir {
+    fn column_names(&self) -> &[&'static str] {
+        &["a", "b", "half", "parity"]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for ParityAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &ParityRow<AB::Var> = (*local).borrow();
+        let next: &ParityRow<AB::Var> = (*next).borrow();
+        let final_parity: AB::Expr = builder.public_values()[0].into();
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_zero(local.fib.a.clone());
+        when_first_row.assert_one(local.fib.b.clone());
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(next.fib.a.clone(), local.fib.b.clone());
+        when_transition.assert_eq(next.fib.b.clone(), local.fib.a.clone() + local.fib.b.clone());
+
+        // b = 2 * half + parity with half in [0, HALF_BOUND]
+        builder.assert_bool(local.parity.clone());
+        let mut half = AB::Expr::ZERO;
+        let mut slack = AB::Expr::ZERO;
+        for i in 0..HALF_BITS {
+            builder.assert_bool(local.half_bits[i].clone());
+            builder.assert_bool(local.slack_bits[i].clone());
+            half += AB::Expr::from_u64(1 << i) * local.half_bits[i].clone();
+            slack += AB::Expr::from_u64(1 << i) * local.slack_bits[i].clone();
+        }
+        builder.assert_eq(local.half.clone(), half);
+        builder.assert_eq(local.half.clone() + slack, AB::Expr::from_u64(HALF_BOUND));
+        builder.assert_eq(
+            local.fib.b.clone(),
+            local.half.clone() * AB::Expr::TWO + local.parity.clone(),
+        );
+
+        // The trace is generated exactly full, so the physical last row is the last step
+        bind_physical_last_row(builder, local.parity.clone().into(), final_parity);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParityError {
+    // Parity traces are exactly full: a power of two of at least 256 rows
+    InvalidRowCount(usize),
+    // F(row + 1) is p - 1, which has no split with half in range
+    NoBoundedSplit { row: usize },
+}
+
+impl fmt::Display for ParityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRowCount(rows) => {
+                write!(f, "{} rows is not a power of two of at least 256", rows)
+            }
+            Self::NoBoundedSplit { row } => write!(f, "row {} holds p - 1, which has no bounded split", row),
+        }
+    }
+}
+
+impl std::error::Error for ParityError {}
+
+// F(1)..F(num_rows) with their parities, filling exactly `num_rows` rows
+// (a power of two, at least 256) so there is no padding to account for
+pub fn generate_parity_trace<F: PrimeField64>(
+    num_rows: usize,
+) -> Result<GeneratedTrace<(), F>, ParityError> {
+    if !num_rows.is_power_of_two() || num_rows < 256 {
+        return Err(ParityError::InvalidRowCount(num_rows));
+    }
+    let len = trace_len(num_rows, NUM_PARITY_COLS).map_err(|_| ParityError::InvalidRowCount(num_rows))?;
+    let mut trace = RowMajorMatrix::new(F::zero_vec(len), NUM_PARITY_COLS);
+
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<ParityRow<F>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), num_rows);
+
+    let (mut a, mut b) = (F::ZERO, F::ONE);
+    for (i, row) in rows.iter_mut().enumerate() {
+        let value = b.as_canonical_u64();
+        let half = value / 2;
+        if half > HALF_BOUND {
+            return Err(ParityError::NoBoundedSplit { row: i });
+        }
+        row.fib = FibonacciRow::new(a, b);
+        row.half = F::from_u64(half);
+        row.parity = F::from_u64(value & 1);
+        for i in 0..HALF_BITS {
+            row.half_bits[i] = F::from_u64((half >> i) & 1);
+            row.slack_bits[i] = F::from_u64(((HALF_BOUND - half) >> i) & 1);
+        }
+        (a, b) = (b, a + b);
+    }
+    let final_parity = rows[num_rows - 1].parity;
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: num_rows,
+        public_values: vec![final_parity],
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::oracle::{fibonacci_big, fibonacci_mod};
+
+    use super::*;
+    use crate::air_id::{prove_identified, verify_identified};
+    use crate::test_config;
+    use crate::validate::validate_trace;
+
+    #[test]
+    fn parity_is_the_low_bit_of_each_canonical_value() {
+        let trace = generate_parity_trace::<Val>(256).unwrap();
+        let parity_column = |row: usize| trace.matrix.values[row * NUM_PARITY_COLS + 3];
+        // Exact while F(n) < p: the integer pattern 1, 1, 0, ...
+        for row in 0..46 {
+            let integer_parity = fibonacci_big(row + 1).bit(0);
+            assert_eq!(parity_column(row), Val::from_bool(integer_parity), "row {}", row);
+        }
+        let last = fibonacci_mod::<Val>(256).as_canonical_u64() & 1;
+        assert_eq!(trace.public_values, [Val::from_u64(last)]);
+    }
+
+    #[test]
+    fn a_parity_trace_proves_and_a_flipped_claim_does_not() {
+        let trace = generate_parity_trace::<Val>(256).unwrap();
+        let flipped = [Val::ONE - trace.public_values[0]];
+        assert!(validate_trace(&ParityAir, &trace.matrix, &flipped).is_err());
+        let proof = prove_identified(&test_config(), &ParityAir, trace.matrix, &trace.public_values);
+        assert_eq!(verify_identified(&test_config(), &ParityAir, &proof, &trace.public_values), Ok(None));
+    }
+
+    #[test]
+    fn a_parity_that_does_not_split_b_is_refused() {
+        let mut trace = generate_parity_trace::<Val>(256).unwrap();
+        // Row 2 holds F(3) = 2 = 2 * 1 + 0; claim it is 2 * 1 + 1 instead
+        let parity = 2 * NUM_PARITY_COLS + 3;
+        assert_eq!(trace.matrix.values[parity], Val::ZERO);
+        trace.matrix.values[parity] = Val::ONE;
+        assert!(validate_trace(&ParityAir, &trace.matrix, &trace.public_values).is_err());
+    }
+
+    #[test]
+    fn only_full_power_of_two_traces_are_generated() {
+        for rows in [0, 100, 128] {
+            assert_eq!(generate_parity_trace::<Val>(rows).err(), Some(ParityError::InvalidRowCount(rows)));
+        }
+    }
+}
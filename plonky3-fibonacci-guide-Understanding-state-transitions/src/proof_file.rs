@@ -0,0 +1,473 @@
+// Proofs on disk, as bincode, or as armored text for pasting into issues and
+// chat. Works for a bare Proof<SC> under any config whose components
+// serialize, and for an IdentifiedProof, which keeps its AIR id with it. The
+// public values are not stored: the verifier brings the claim it wants checked.
+//
+// Every encoding starts with an EnvelopeHeader naming the format version and
+// the ConfigDescriptor the proof was made under. A verifier either checks the
+// descriptor's fingerprint against its own config before the proof is
+// decoded, so a proof from another seed or FRI setup is refused by name
+// instead of failing somewhere inside FRI, or takes the descriptor and
+// rebuilds the prover's config from it.
+use core::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use bincode::Options;
+use p3_field::{BasedVectorSpace, PrimeCharacteristicRing, PrimeField64};
+use p3_symmetric::CryptographicHasher;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::air_id::AirId;
+use crate::{Challenge, ConfigDescriptor, DEFAULT_CONFIG_SEED, MyHash, Perm, Val, constants_rng};
+
+// Written by the binary after proving
+pub const DEFAULT_PROOF_PATH: &str = "fibonacci.proof";
+
+// Largest encoding load_proof_from_reader decodes. A test-parameter proof is
+// well under a megabyte; the cap stops a corrupt length prefix from making
+// the decoder allocate without bound.
+pub const MAX_PROOF_BYTES: u64 = 64 << 20;
+
+// Bump whenever the envelope or the proof encoding changes. 2: the header
+// carries the whole ConfigDescriptor rather than its fingerprint.
+pub const PROOF_FORMAT_VERSION: u32 = 2;
+
+const FIELD_NAME: &str = "BabyBear";
+
+// Everything a verifier has to rebuild to check a proof: the field, the
+// Poseidon2 seed and the FRI parameters. min_log_height is not included; it
+// only shapes the trace, which the proof's degree already records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigFingerprint(pub u64);
+
+impl ConfigFingerprint {
+    // Poseidon2 sponge over the field name bytes, the seed as four 16-bit
+    // limbs, log_blowup, num_queries, pow_bits and the extension degree
+    pub fn of(descriptor: &ConfigDescriptor) -> Self {
+        Self::of_variant(descriptor, "")
+    }
+
+    // `of` for a config that differs from MyConfig in a part the descriptor
+    // does not record, such as its challenger. The variant's bytes follow the
+    // field name's, so the empty variant is MyConfig's own fingerprint.
+    pub fn of_variant(descriptor: &ConfigDescriptor, variant: &str) -> Self {
+        let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+        let hasher = MyHash::new(Perm::new_from_rng_128(&mut rng));
+        let seed = descriptor.perm_seed;
+        let seed_limbs = (0..4).map(|i| Val::from_u64((seed >> (16 * i)) & 0xffff));
+        let params = [
+            descriptor.log_blowup,
+            descriptor.num_queries,
+            descriptor.pow_bits,
+            <Challenge as BasedVectorSpace<Val>>::DIMENSION,
+        ];
+        let input = FIELD_NAME
+            .bytes()
+            .chain(variant.bytes())
+            .map(Val::from_u8)
+            .chain(seed_limbs)
+            .chain(params.map(Val::from_usize));
+        let digest = hasher.hash_iter(input);
+        Self((digest[0].as_canonical_u64() << 32) | digest[1].as_canonical_u64())
+    }
+}
+
+impl fmt::Display for ConfigFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+// Encoded ahead of the proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvelopeHeader {
+    pub version: u32,
+    pub config: ConfigDescriptor,
+}
+
+#[derive(Debug)]
+pub enum ProofFileError {
+    Io(io::Error),
+    // bincode refused the value; on load, a file that is not a proof of the
+    // expected type, e.g. a truncated one
+    Encoding(bincode::Error),
+    // The reader still had data after a complete proof
+    TrailingBytes,
+    UnsupportedVersion { found: u32 },
+    // The proof was made under a different seed, field or FRI setup than the
+    // verifier's; checked before the proof is decoded
+    ConfigMismatch { expected: ConfigFingerprint, found: ConfigFingerprint },
+    // Armored text without the expected header, checksum or trailer lines, or
+    // with a body that is not hex
+    MalformedArmor(&'static str),
+    // The body does not hash to the armor's CRC-32, so it was not decoded
+    ChecksumMismatch { expected: u32, found: u32 },
+}
+
+impl fmt::Display for ProofFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Encoding(e) => write!(f, "not a valid proof encoding: {}", e),
+            Self::TrailingBytes => write!(f, "data after the end of the proof"),
+            Self::UnsupportedVersion { found } => {
+                write!(f, "proof format version {} is not {}", found, PROOF_FORMAT_VERSION)
+            }
+            Self::ConfigMismatch { expected, found } => {
+                write!(f, "proof was made under config {} but the verifier's is {}", found, expected)
+            }
+            Self::MalformedArmor(what) => write!(f, "malformed armored proof: {}", what),
+            Self::ChecksumMismatch { expected, found } => {
+                write!(f, "armored proof checksum is {:08x} but its body hashes to {:08x}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofFileError {}
+
+impl From<io::Error> for ProofFileError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+// Both directions must agree on these. Readers are decoded item by item, so
+// trailing data is checked by hand in load_proof_from_reader.
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+// save_proof's bytes, for sending a proof somewhere other than a file
+pub fn encode_proof<P: Serialize>(config: &ConfigDescriptor, proof: &P) -> Result<Vec<u8>, ProofFileError> {
+    let header = EnvelopeHeader { version: PROOF_FORMAT_VERSION, config: *config };
+    options().serialize(&(header, proof)).map_err(ProofFileError::Encoding)
+}
+
+// `config` describes the config `proof` was made under
+pub fn save_proof<P: Serialize>(path: &Path, config: &ConfigDescriptor, proof: &P) -> Result<(), ProofFileError> {
+    Ok(fs::write(path, encode_proof(config, proof)?)?)
+}
+
+// Bytes of the encoded proof, without its envelope header
+pub fn encoded_size<P: Serialize>(proof: &P) -> Result<usize, ProofFileError> {
+    let size = options().serialized_size(proof).map_err(ProofFileError::Encoding)?;
+    Ok(size as usize)
+}
+
+// `expected` is the fingerprint of the verifier's config. A short file is
+// an Encoding error from bincode, never a partial proof.
+pub fn load_proof<P: DeserializeOwned>(path: &Path, expected: ConfigFingerprint) -> Result<P, ProofFileError> {
+    load_proof_from_reader(File::open(path)?, expected)
+}
+
+// Decodes while reading, through one BufReader, so peak memory is the proof
+// itself rather than the proof plus a copy of its bytes. The header is read
+// and checked first; at most MAX_PROOF_BYTES of proof are decoded, and the
+// reader must end with the proof.
+pub fn load_proof_from_reader<P: DeserializeOwned>(
+    reader: impl Read,
+    expected: ConfigFingerprint,
+) -> Result<P, ProofFileError> {
+    let (_, proof) = read_envelope(reader, |config| {
+        let found = ConfigFingerprint::of(config);
+        if found != expected {
+            return Err(ProofFileError::ConfigMismatch { expected, found });
+        }
+        Ok(())
+    })?;
+    Ok(proof)
+}
+
+// The proof with the descriptor it was saved under, for a verifier that
+// rebuilds its config from the file. The descriptor is untrusted: check it
+// before building a config from it.
+pub fn load_described_proof<P: DeserializeOwned>(path: &Path) -> Result<(ConfigDescriptor, P), ProofFileError> {
+    load_described_proof_from_reader(File::open(path)?)
+}
+
+pub fn load_described_proof_from_reader<P: DeserializeOwned>(
+    reader: impl Read,
+) -> Result<(ConfigDescriptor, P), ProofFileError> {
+    read_envelope(reader, |_| Ok(()))
+}
+
+// Reads the header, lets `check` refuse its descriptor before the proof is
+// decoded, then reads the proof
+fn read_envelope<P: DeserializeOwned>(
+    reader: impl Read,
+    check: impl FnOnce(&ConfigDescriptor) -> Result<(), ProofFileError>,
+) -> Result<(ConfigDescriptor, P), ProofFileError> {
+    let mut reader = BufReader::new(reader);
+    let header: EnvelopeHeader = options()
+        .with_limit(MAX_PROOF_BYTES)
+        .deserialize_from(&mut reader)
+        .map_err(ProofFileError::Encoding)?;
+    if header.version != PROOF_FORMAT_VERSION {
+        return Err(ProofFileError::UnsupportedVersion { found: header.version });
+    }
+    check(&header.config)?;
+    let proof = options()
+        .with_limit(MAX_PROOF_BYTES)
+        .deserialize_from(&mut reader)
+        .map_err(ProofFileError::Encoding)?;
+    if reader.read(&mut [0u8])? != 0 {
+        return Err(ProofFileError::TrailingBytes);
+    }
+    Ok((header.config, proof))
+}
+
+pub const ARMOR_BEGIN: &str = "-----BEGIN PROOF ";
+const ARMOR_END: &str = "-----END PROOF-----";
+const ARMOR_DASHES: &str = "-----";
+const ARMOR_LINE_WIDTH: usize = 76;
+
+// CRC-32 (IEEE, as in zip and PNG); catches a mangled paste, not tampering
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// The proof as text:
+//
+//     -----BEGIN PROOF fibonacci@5.0.0-----
+//     <save_proof's bytes as lowercase hex, 76 columns per line>
+//     =<CRC-32 of the bytes, 8 hex digits>
+//     -----END PROOF-----
+//
+// The header names the AIR for readers; the verifier still checks the AIR
+// id carried inside an IdentifiedProof.
+pub fn proof_to_string<P: Serialize>(
+    air: &AirId,
+    config: &ConfigDescriptor,
+    proof: &P,
+) -> Result<String, ProofFileError> {
+    let bytes = encode_proof(config, proof)?;
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let mut text = format!("{}{}{}\n", ARMOR_BEGIN, air, ARMOR_DASHES);
+    for line in hex.as_bytes().chunks(ARMOR_LINE_WIDTH) {
+        text.push_str(core::str::from_utf8(line).expect("hex is ASCII"));
+        text.push('\n');
+    }
+    text.push_str(&format!("={:08x}\n{}\n", crc32(&bytes), ARMOR_END));
+    Ok(text)
+}
+
+// Reads proof_to_string's output back, returning the header's AIR name with
+// the proof. Any whitespace and line wrapping in the body is accepted, and
+// the checksum is compared before bincode sees a byte.
+pub fn proof_from_string<P: DeserializeOwned>(
+    text: &str,
+    expected: ConfigFingerprint,
+) -> Result<(String, P), ProofFileError> {
+    let (air, bytes) = unarmor(text)?;
+    let proof = load_proof_from_reader(bytes.as_slice(), expected)?;
+    Ok((air, proof))
+}
+
+// The header's AIR name and the checksummed bytes of an armored proof,
+// before any of them is decoded
+pub fn unarmor(text: &str) -> Result<(String, Vec<u8>), ProofFileError> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let air = lines
+        .next()
+        .and_then(|line| line.strip_prefix(ARMOR_BEGIN))
+        .and_then(|line| line.strip_suffix(ARMOR_DASHES))
+        .ok_or(ProofFileError::MalformedArmor("missing BEGIN PROOF header"))?;
+    let rest: Vec<&str> = lines.collect();
+    let Some((&end, rest)) = rest.split_last() else {
+        return Err(ProofFileError::MalformedArmor("missing END PROOF trailer"));
+    };
+    if end != ARMOR_END {
+        return Err(ProofFileError::MalformedArmor("missing END PROOF trailer"));
+    }
+    let Some((checksum, body)) = rest.split_last() else {
+        return Err(ProofFileError::MalformedArmor("missing checksum line"));
+    };
+    let recorded = checksum
+        .strip_prefix('=')
+        .filter(|hex| hex.len() == 8)
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or(ProofFileError::MalformedArmor("missing checksum line"))?;
+    let hex: String = body.concat().split_whitespace().collect();
+    let bytes = decode_hex(&hex).ok_or(ProofFileError::MalformedArmor("body is not hex"))?;
+    let found = crc32(&bytes);
+    if found != recorded {
+        return Err(ProofFileError::ChecksumMismatch { expected: recorded, found });
+    }
+    Ok((air.to_string(), bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::air_id::IdentifiedProof;
+    use crate::cost::CostParams;
+    use crate::{FIBONACCI_AIR_ID, prove_fibonacci, verify_fibonacci};
+
+    fn test_descriptor() -> ConfigDescriptor {
+        ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test())
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fibonacci-{}-{}.proof", name, std::process::id()))
+    }
+
+    #[test]
+    fn a_saved_proof_loads_and_verifies() {
+        let path = temp_path("round-trip");
+        let (proof, public_values) = prove_fibonacci(20).unwrap();
+        save_proof(&path, &test_descriptor(), &proof).unwrap();
+        let loaded = load_proof::<IdentifiedProof>(&path, ConfigFingerprint::of(&test_descriptor()));
+        let described = load_described_proof::<IdentifiedProof>(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(verify_fibonacci(&loaded.unwrap(), &public_values), Ok(()));
+        let (descriptor, proof) = described.unwrap();
+        assert_eq!(descriptor, test_descriptor());
+        assert_eq!(verify_fibonacci(&proof, &public_values), Ok(()));
+    }
+
+    #[test]
+    fn a_truncated_file_is_an_error() {
+        let path = temp_path("truncated");
+        let (proof, _) = prove_fibonacci(20).unwrap();
+        let bytes = encode_proof(&test_descriptor(), &proof).unwrap();
+        let expected = ConfigFingerprint::of(&test_descriptor());
+        for len in [0, 1, bytes.len() / 2, bytes.len() - 1] {
+            fs::write(&path, &bytes[..len]).unwrap();
+            let loaded = load_proof::<IdentifiedProof>(&path, expected);
+            assert!(matches!(loaded, Err(ProofFileError::Encoding(_))), "{} of {} bytes", len, bytes.len());
+        }
+        let _ = fs::remove_file(&path);
+        assert!(matches!(load_proof::<IdentifiedProof>(&path, expected), Err(ProofFileError::Io(_))));
+    }
+
+    #[test]
+    fn armored_text_round_trips_however_it_is_wrapped() {
+        let (proof, public_values) = prove_fibonacci(20).unwrap();
+        let text = proof_to_string(&FIBONACCI_AIR_ID, &test_descriptor(), &proof).unwrap();
+        assert!(text.starts_with("-----BEGIN PROOF fibonacci@5.0.0-----\n"));
+        assert!(text.lines().all(|line| line.len() <= ARMOR_LINE_WIDTH));
+        let expected = ConfigFingerprint::of(&test_descriptor());
+        let (air, decoded) = proof_from_string::<IdentifiedProof>(&text, expected).unwrap();
+        assert_eq!(air, FIBONACCI_AIR_ID.to_string());
+        assert_eq!(verify_fibonacci(&decoded, &public_values), Ok(()));
+
+        // As pasted into a chat that indents, splits and rewraps the body
+        let lines: Vec<&str> = text.lines().collect();
+        let last_body_line = lines.len() - 3;
+        let rewrapped: String = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| match i {
+                i if (1..=last_body_line).contains(&i) => format!("  {}\n\n", line.replace('a', "a ")),
+                _ => format!("  {}\n", line),
+            })
+            .collect();
+        assert_eq!(unarmor(&rewrapped).unwrap(), unarmor(&text).unwrap());
+    }
+
+    #[test]
+    fn an_edited_body_fails_its_checksum() {
+        let (proof, _) = prove_fibonacci(20).unwrap();
+        let text = proof_to_string(&FIBONACCI_AIR_ID, &test_descriptor(), &proof).unwrap();
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        let digit = if lines[1].starts_with('0') { "1" } else { "0" };
+        lines[1].replace_range(0..1, digit);
+        let edited = lines.join("\n");
+        let decoded = proof_from_string::<IdentifiedProof>(&edited, ConfigFingerprint::of(&test_descriptor()));
+        assert!(matches!(decoded, Err(ProofFileError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn garbage_is_malformed_armor() {
+        let (proof, _) = prove_fibonacci(20).unwrap();
+        let text = proof_to_string(&FIBONACCI_AIR_ID, &test_descriptor(), &proof).unwrap();
+        let without_trailer = text.replace(ARMOR_END, "");
+        let mut not_hex: Vec<String> = text.lines().map(str::to_string).collect();
+        not_hex[1].replace_range(0..1, "g");
+        let not_hex = not_hex.join("\n");
+        let cases = [
+            ("", "missing BEGIN PROOF header"),
+            ("not a proof", "missing BEGIN PROOF header"),
+            ("-----BEGIN PROOF fibonacci@5.0.0-----\n", "missing END PROOF trailer"),
+            (&without_trailer, "missing END PROOF trailer"),
+            ("-----BEGIN PROOF x-----\n=zz\n-----END PROOF-----\n", "missing checksum line"),
+            (&not_hex, "body is not hex"),
+        ];
+        for (text, reason) in cases {
+            let found = unarmor(text);
+            assert!(matches!(found, Err(ProofFileError::MalformedArmor(what)) if what == reason), "{:?}", text);
+        }
+    }
+
+    // Hands out at most `chunk` bytes per read, as a slow socket would
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.bytes.len());
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Ok(n)
+        }
+    }
+
+    // A connection that drops
+    struct Reset;
+
+    impl Read for Reset {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"))
+        }
+    }
+
+    #[test]
+    fn a_proof_arriving_a_few_bytes_at_a_time_decodes() {
+        let (proof, public_values) = prove_fibonacci(20).unwrap();
+        let bytes = encode_proof(&test_descriptor(), &proof).unwrap();
+        let expected = ConfigFingerprint::of(&test_descriptor());
+        for chunk in [1, 7, 4096] {
+            let loaded = load_proof_from_reader::<IdentifiedProof>(Trickle { bytes: &bytes, chunk }, expected);
+            assert_eq!(verify_fibonacci(&loaded.unwrap(), &public_values), Ok(()), "{}-byte reads", chunk);
+        }
+    }
+
+    #[test]
+    fn a_reader_failing_midway_or_running_on_is_an_error() {
+        let (proof, _) = prove_fibonacci(20).unwrap();
+        let bytes = encode_proof(&test_descriptor(), &proof).unwrap();
+        let expected = ConfigFingerprint::of(&test_descriptor());
+        let dropped = Trickle { bytes: &bytes[..bytes.len() / 2], chunk: 64 }.chain(Reset);
+        let loaded = load_proof_from_reader::<IdentifiedProof>(dropped, expected);
+        assert!(matches!(loaded, Err(ProofFileError::Encoding(_))));
+
+        let trailing = bytes.as_slice().chain(&[0u8][..]);
+        let loaded = load_proof_from_reader::<IdentifiedProof>(trailing, expected);
+        assert!(matches!(loaded, Err(ProofFileError::TrailingBytes)));
+    }
+}
@@ -0,0 +1,73 @@
+// Where this binary's prover came from: the resolved Plonky3 git revision and
+// the enabled cargo features, both captured by build.rs. Proofs carry a digest
+// of it outside the transcript, so a verification failure months later can be
+// traced to a dependency bump.
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Provenance {
+    // Comma-separated if the lock file resolved several revisions
+    pub plonky3_revision: &'static str,
+    pub features: &'static str,
+}
+
+pub const PROVENANCE: Provenance = Provenance {
+    plonky3_revision: env!("P3_REVISION"),
+    features: env!("P3_FEATURES"),
+};
+
+impl Provenance {
+    // FNV-1a over both fields; stable across Rust versions, unlike DefaultHasher
+    pub fn digest(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in self.plonky3_revision.bytes().chain([0]).chain(self.features.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let features = if self.features.is_empty() { "none" } else { self.features };
+        write!(f, "Plonky3 {} (features: {})", self.plonky3_revision, features)
+    }
+}
+
+// A proof made by a differently built prover. Transcript-compatible
+// revisions still verify, so this is only a hint for when verification does
+// fail, never a reason to reject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProvenanceMismatch {
+    pub found: u64,
+    pub expected: u64,
+}
+
+impl fmt::Display for ProvenanceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "proof provenance {:016x} differs from this build's {:016x} ({})",
+            self.found, self.expected, PROVENANCE
+        )
+    }
+}
+
+// Ok when `found` is this build's digest
+pub fn check_provenance(found: u64) -> Result<(), ProvenanceMismatch> {
+    let expected = PROVENANCE.digest();
+    if found == expected { Ok(()) } else { Err(ProvenanceMismatch { found, expected }) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_another_build_is_a_mismatch() {
+        let expected = PROVENANCE.digest();
+        assert_eq!(check_provenance(expected), Ok(()));
+        assert_eq!(check_provenance(expected ^ 1), Err(ProvenanceMismatch { found: expected ^ 1, expected }));
+    }
+}
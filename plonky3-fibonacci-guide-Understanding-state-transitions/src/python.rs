@@ -0,0 +1,150 @@
+// Python bindings, compiled with the `python` feature; `maturin build` turns
+// them into a wheel (see pyproject.toml). Proofs cross the boundary as
+// proof_file's bytes, and the GIL is released while proving and verifying.
+// A proof the verifier rejects is False; bytes that cannot be checked at all
+// raise ProofError with verify_input's message, which names the stage
+// that failed.
+use std::time::Instant;
+
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::claims::sequence_claim_values;
+use crate::cost::CostParams;
+use crate::input::{ProofInputError, verify_input};
+use crate::proof_file::encode_proof;
+use crate::{ConfigDescriptor, Val, VerifyError, create_config_from, generate_fibonacci_trace, prove_fibonacci_trace};
+
+create_exception!(fibonacci_proof, ProofError, PyException);
+
+struct Proved {
+    bytes: Vec<u8>,
+    rows: usize,
+    prove_seconds: f64,
+}
+
+// F(steps) from (0, 1) under `seed` at the Test level, in proof_file's
+// encoding with its descriptor
+fn prove_bytes(steps: usize, seed: u64) -> Result<Proved, String> {
+    let descriptor = ConfigDescriptor::new(seed, &CostParams::test());
+    let trace = generate_fibonacci_trace::<Val>(steps).map_err(|e| e.to_string())?;
+    let rows = trace.matrix.height();
+    let start = Instant::now();
+    let (proof, _) = prove_fibonacci_trace(&create_config_from(&descriptor), trace, &mut ());
+    let prove_seconds = start.elapsed().as_secs_f64();
+    let bytes = encode_proof(&descriptor, &proof).map_err(|e| e.to_string())?;
+    Ok(Proved { bytes, rows, prove_seconds })
+}
+
+// Ok(false) when the verifier rejected the proof, Err when the bytes or the
+// claim could not be checked
+fn verify_claim(py: Python<'_>, proof: &[u8], steps: usize, claimed: u64) -> PyResult<(bool, String)> {
+    if claimed >= Val::ORDER_U64 {
+        return Err(PyValueError::new_err(format!("claimed value {} is not below {}", claimed, Val::ORDER_U64)));
+    }
+    let public_values = sequence_claim_values(Val::ZERO, Val::ONE, Val::from_u64(claimed), Val::from_usize(steps));
+    match py.allow_threads(|| verify_input(proof, &public_values)) {
+        Ok(verified) => Ok((true, verified.descriptor.to_string())),
+        Err(ProofInputError::Verify(VerifyError::Proof(_))) => Ok((false, String::new())),
+        Err(e) => Err(ProofError::new_err(e.to_string())),
+    }
+}
+
+#[pyfunction]
+fn prove_fibonacci(py: Python<'_>, steps: usize, seed: u64) -> PyResult<Bound<'_, PyBytes>> {
+    let proved = py.allow_threads(|| prove_bytes(steps, seed)).map_err(PyValueError::new_err)?;
+    Ok(PyBytes::new_bound(py, &proved.bytes))
+}
+
+// prove_fibonacci, with {"rows", "prove_seconds", "proof_bytes"}
+#[pyfunction]
+fn prove_fibonacci_with_stats(
+    py: Python<'_>,
+    steps: usize,
+    seed: u64,
+) -> PyResult<(Bound<'_, PyBytes>, Bound<'_, PyDict>)> {
+    let proved = py.allow_threads(|| prove_bytes(steps, seed)).map_err(PyValueError::new_err)?;
+    let stats = PyDict::new_bound(py);
+    stats.set_item("rows", proved.rows)?;
+    stats.set_item("prove_seconds", proved.prove_seconds)?;
+    stats.set_item("proof_bytes", proved.bytes.len())?;
+    Ok((PyBytes::new_bound(py, &proved.bytes), stats))
+}
+
+// Whether `proof` shows F(steps) = claimed, under the config saved with it
+#[pyfunction]
+fn verify_fibonacci(py: Python<'_>, proof: &[u8], steps: usize, claimed: u64) -> PyResult<bool> {
+    Ok(verify_claim(py, proof, steps, claimed)?.0)
+}
+
+// verify_fibonacci as {"valid", "verify_seconds", "config"}; config is the
+// saved descriptor, empty when the proof was rejected
+#[pyfunction]
+fn verify_fibonacci_with_stats<'py>(
+    py: Python<'py>,
+    proof: &[u8],
+    steps: usize,
+    claimed: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let start = Instant::now();
+    let (valid, config) = verify_claim(py, proof, steps, claimed)?;
+    let stats = PyDict::new_bound(py);
+    stats.set_item("valid", valid)?;
+    stats.set_item("verify_seconds", start.elapsed().as_secs_f64())?;
+    stats.set_item("config", config)?;
+    Ok(stats)
+}
+
+#[pymodule]
+fn fibonacci_proof(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(prove_fibonacci, m)?)?;
+    m.add_function(wrap_pyfunction!(prove_fibonacci_with_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_fibonacci, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_fibonacci_with_stats, m)?)?;
+    m.add("ProofError", m.py().get_type_bound::<ProofError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_CONFIG_SEED;
+
+    // F(15)
+    const CLAIMED: u64 = 610;
+
+    #[test]
+    fn wrappers_prove_and_verify() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let proof = prove_fibonacci(py, 15, DEFAULT_CONFIG_SEED).unwrap();
+            let bytes = proof.as_bytes();
+            assert!(verify_fibonacci(py, bytes, 15, CLAIMED).unwrap());
+            assert!(!verify_fibonacci(py, bytes, 15, CLAIMED + 1).unwrap());
+
+            let (proof, stats) = prove_fibonacci_with_stats(py, 15, 1).unwrap();
+            let proof_bytes = stats.get_item("proof_bytes").unwrap().unwrap();
+            assert_eq!(proof_bytes.extract::<usize>().unwrap(), proof.as_bytes().len());
+            let verified = verify_fibonacci_with_stats(py, proof.as_bytes(), 15, CLAIMED).unwrap();
+            assert!(verified.get_item("valid").unwrap().unwrap().extract::<bool>().unwrap());
+        })
+    }
+
+    #[test]
+    fn unusable_input_raises_classified_errors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let proof = prove_fibonacci(py, 15, DEFAULT_CONFIG_SEED).unwrap();
+            let bytes = proof.as_bytes();
+            let truncated = verify_fibonacci(py, &bytes[..bytes.len() - 1], 15, CLAIMED).unwrap_err();
+            assert!(truncated.is_instance_of::<ProofError>(py));
+            assert!(truncated.to_string().contains("decoding the proof"), "{}", truncated);
+            let too_large = verify_fibonacci(py, bytes, 15, Val::ORDER_U64).unwrap_err();
+            assert!(too_large.is_instance_of::<PyValueError>(py));
+        })
+    }
+}
@@ -0,0 +1,424 @@
+// The `demo` command: proves every registered example at a modest size and
+// renders the results as one self-contained HTML page. An example that fails,
+// or panics, becomes a red row; the others still run.
+use core::any::Any;
+use core::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use p3_air::BaseAir;
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{get_symbolic_constraints, prove, verify};
+
+use crate::big_fibonacci::{BigFibonacciAir, generate_big_fibonacci_trace};
+use crate::coefficients::{CoefficientAir, generate_coefficient_trace};
+use crate::conformance::{
+    ConformanceReport, check_big_fibonacci, check_coefficients, check_counter, check_dataset, check_fibonacci,
+    check_indexed_fibonacci, check_last_row, check_lucas, check_multi_fibonacci, check_parity, check_tribonacci,
+};
+use crate::cost::SecurityLevel;
+use crate::counter::{CounterAir, generate_counter_trace};
+use crate::dataset::{DatasetAir, SAMPLE_DATASET, generate_dataset_trace, parse_dataset};
+use crate::indexed::{IndexedFibonacciAir, generate_indexed_fibonacci_trace};
+use crate::last_row::{LastRowBinding, LastRowDemoAir, PaddingStrategy, generate_last_row_trace};
+use crate::linear_recurrence::{RecurrenceAir, generate_linear_recurrence_trace};
+use crate::lint::AirLint;
+use crate::lucas::{LucasAir, generate_lucas_trace};
+use crate::multi::{MultiFibonacciAir, generate_multi_fibonacci_trace, sample_starts};
+use crate::parity::{ParityAir, generate_parity_trace};
+use crate::proof_file::encoded_size;
+use crate::snapshot::{
+    ConstraintSnapshot, snapshot_big_fibonacci, snapshot_coefficients, snapshot_counter, snapshot_dataset,
+    snapshot_fibonacci, snapshot_indexed_fibonacci, snapshot_last_row, snapshot_lucas, snapshot_multi_fibonacci,
+    snapshot_parity, snapshot_tribonacci,
+};
+use crate::{FibonacciAir, StarkAir, Val, create_default_config, generate_fibonacci_trace};
+
+// What the symbolic constraint pass says about an AIR, before any proving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintSummary {
+    pub width: usize,
+    pub constraints: usize,
+    // Largest degree multiple among the constraints
+    pub max_degree: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofStats {
+    pub rows: usize,
+    pub prove_time: Duration,
+    pub verify_time: Duration,
+    // bincode length of the proof, without proof_file's envelope header
+    pub proof_bytes: usize,
+    // Workers in the rayon pool the proof was made in
+    pub threads: usize,
+}
+
+// One row of the report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExampleReport {
+    pub name: &'static str,
+    pub summary: Option<ConstraintSummary>,
+    pub stats: Option<ProofStats>,
+    // None when the example proved and verified
+    pub failure: Option<String>,
+}
+
+impl ExampleReport {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl fmt::Display for ExampleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed() { "PASS" } else { "FAIL" };
+        write!(f, "{} {}", verdict, self.name)?;
+        if let Some(stats) = &self.stats {
+            write!(
+                f,
+                " ({} rows, prove {:?} on {} threads, verify {:?}, {} bytes)",
+                stats.rows, stats.prove_time, stats.threads, stats.verify_time, stats.proof_bytes
+            )?;
+        }
+        if let Some(failure) = &self.failure {
+            write!(f, ": {}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Example {
+    pub name: &'static str,
+    // Both are called with `name`, which they report under
+    pub run: fn(&'static str) -> ExampleReport,
+    pub conformance: fn(&'static str) -> ConformanceReport,
+    // The AIR's canonical constraints, compared against fixtures/constraints.txt
+    pub snapshot: fn() -> ConstraintSnapshot,
+}
+
+// Every example the demo, the conformance suite and the constraint snapshots
+// run, in report order
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "FibonacciAir, 100 steps",
+        run: run_fibonacci,
+        conformance: check_fibonacci,
+        snapshot: snapshot_fibonacci,
+    },
+    Example {
+        name: "CoefficientAir, p = q = 1, 256 rows",
+        run: run_coefficients,
+        conformance: check_coefficients,
+        snapshot: snapshot_coefficients,
+    },
+    Example { name: "CounterAir, 256 rows", run: run_counter, conformance: check_counter, snapshot: snapshot_counter },
+    Example { name: "ParityAir, 256 rows", run: run_parity, conformance: check_parity, snapshot: snapshot_parity },
+    Example {
+        name: "LastRowDemoAir, last real row, zero padding",
+        run: run_last_row,
+        conformance: check_last_row,
+        snapshot: snapshot_last_row,
+    },
+    Example {
+        name: "DatasetAir, sample transactions",
+        run: run_dataset,
+        conformance: check_dataset,
+        snapshot: snapshot_dataset,
+    },
+    Example {
+        name: "RecurrenceAir<3> (Tribonacci), 20 steps",
+        run: run_tribonacci,
+        conformance: check_tribonacci,
+        snapshot: snapshot_tribonacci,
+    },
+    Example {
+        name: "LucasAir, Pell numbers, 20 steps",
+        run: run_lucas,
+        conformance: check_lucas,
+        snapshot: snapshot_lucas,
+    },
+    Example {
+        name: "MultiFibonacciAir, 8 sequences, 256 rows",
+        run: run_multi_fibonacci,
+        conformance: check_multi_fibonacci,
+        snapshot: snapshot_multi_fibonacci,
+    },
+    Example {
+        name: "BigFibonacciAir, 100 steps",
+        run: run_big_fibonacci,
+        conformance: check_big_fibonacci,
+        snapshot: snapshot_big_fibonacci,
+    },
+    Example {
+        name: "IndexedFibonacciAir, 100 steps",
+        run: run_indexed_fibonacci,
+        conformance: check_indexed_fibonacci,
+        snapshot: snapshot_indexed_fibonacci,
+    },
+];
+
+pub fn constraint_summary<A>(air: &A) -> ConstraintSummary
+where
+    A: StarkAir + BaseAir<Val> + AirLint,
+{
+    let constraints = get_symbolic_constraints(air, 0, air.num_public_values());
+    ConstraintSummary {
+        width: air.width(),
+        constraints: constraints.len(),
+        max_degree: constraints.iter().map(|c| c.degree_multiple()).max().unwrap_or(0),
+    }
+}
+
+// Proves and verifies under the default config, in whichever rayon pool it
+// is called from. The summary is taken first, so an example whose proof
+// fails still shows its constraints.
+pub(crate) fn measure<A>(
+    name: &'static str,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    public_values: &Vec<Val>,
+) -> ExampleReport
+where
+    A: StarkAir + BaseAir<Val> + AirLint,
+{
+    let summary = constraint_summary(air);
+    let rows = trace.height();
+    let config = create_default_config(SecurityLevel::Test);
+    let threads = rayon::current_num_threads();
+    let start = Instant::now();
+    let proof = prove(&config, air, trace, public_values);
+    let prove_time = start.elapsed();
+    let start = Instant::now();
+    let verified = verify(&config, air, &proof, public_values);
+    let verify_time = start.elapsed();
+    let mut failure = verified.err().map(|e| format!("verification failed: {:?}", e));
+    let proof_bytes = encoded_size(&proof).unwrap_or_else(|e| {
+        failure.get_or_insert(e.to_string());
+        0
+    });
+    ExampleReport {
+        name,
+        summary: Some(summary),
+        stats: Some(ProofStats { rows, prove_time, verify_time, proof_bytes, threads }),
+        failure,
+    }
+}
+
+fn not_generated(name: &'static str, e: impl fmt::Display) -> ExampleReport {
+    ExampleReport { name, summary: None, stats: None, failure: Some(format!("trace: {}", e)) }
+}
+
+fn run_fibonacci(name: &'static str) -> ExampleReport {
+    match generate_fibonacci_trace::<Val>(100) {
+        Ok(trace) => measure(name, &FibonacciAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_coefficients(name: &'static str) -> ExampleReport {
+    match generate_coefficient_trace::<Val>(Val::ZERO, Val::ONE, 1, 1, 256) {
+        Ok(trace) => measure(name, &CoefficientAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_counter(name: &'static str) -> ExampleReport {
+    match generate_counter_trace::<Val>(256) {
+        Ok(trace) => measure(name, &CounterAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_parity(name: &'static str) -> ExampleReport {
+    match generate_parity_trace::<Val>(256) {
+        Ok(trace) => measure(name, &ParityAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_last_row(name: &'static str) -> ExampleReport {
+    let air = LastRowDemoAir { binding: LastRowBinding::LastReal };
+    match generate_last_row_trace::<Val>(100, PaddingStrategy::Zero) {
+        Ok(trace) => measure(name, &air, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_dataset(name: &'static str) -> ExampleReport {
+    match parse_dataset(SAMPLE_DATASET, "amount").and_then(|amounts| generate_dataset_trace(&amounts)) {
+        Ok(trace) => measure(name, &DatasetAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_tribonacci(name: &'static str) -> ExampleReport {
+    match generate_linear_recurrence_trace::<Val, 3>([Val::ZERO, Val::ZERO, Val::ONE], 20) {
+        Ok(trace) => measure(name, &RecurrenceAir::<3>, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_lucas(name: &'static str) -> ExampleReport {
+    match generate_lucas_trace::<Val>(Val::TWO, Val::NEG_ONE, Val::ZERO, Val::ONE, 20) {
+        Ok(trace) => measure(name, &LucasAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_multi_fibonacci(name: &'static str) -> ExampleReport {
+    let air = MultiFibonacciAir { num_sequences: 8 };
+    match generate_multi_fibonacci_trace(&sample_starts(air.num_sequences), 256) {
+        Ok(trace) => measure(name, &air, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_big_fibonacci(name: &'static str) -> ExampleReport {
+    match generate_big_fibonacci_trace::<Val>(100) {
+        Ok(trace) => measure(name, &BigFibonacciAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+fn run_indexed_fibonacci(name: &'static str) -> ExampleReport {
+    match generate_indexed_fibonacci_trace::<Val>(100) {
+        Ok(trace) => measure(name, &IndexedFibonacciAir, trace.matrix, &trace.public_values),
+        Err(e) => not_generated(name, e),
+    }
+}
+
+// The message a caught panic was raised with
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+// Runs every registered example; a panic (e.g. the prover's debug constraint
+// check) is reported as that example's failure
+pub fn run_examples() -> Vec<ExampleReport> {
+    EXAMPLES
+        .iter()
+        .map(|example| {
+            panic::catch_unwind(AssertUnwindSafe(|| (example.run)(example.name))).unwrap_or_else(|payload| {
+                ExampleReport {
+                    name: example.name,
+                    summary: None,
+                    stats: None,
+                    failure: Some(format!("panicked: {}", panic_message(payload.as_ref()))),
+                }
+            })
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// A bar scaled against the largest value in its column, with the value after it
+fn bar(value: f64, max: f64, label: String) -> String {
+    let percent = if max > 0.0 { 100.0 * value / max } else { 0.0 };
+    format!("<div class=\"bar\"><span style=\"width:{:.1}%\"></span></div> {}", percent, label)
+}
+
+const REPORT_STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }
+tr.fail td { background: #f8d0d0; }
+.bar { display: inline-block; width: 120px; height: 10px; background: #eee; }
+.bar span { display: block; height: 100%; background: #4a7; }
+";
+
+// The report page for `reports`: one table row per example, with inline bars
+// for prove time and proof size
+pub fn render_report(reports: &[ExampleReport]) -> String {
+    let stats: Vec<&ProofStats> = reports.iter().filter_map(|report| report.stats.as_ref()).collect();
+    let max_prove = stats.iter().map(|s| s.prove_time.as_secs_f64()).fold(0.0, f64::max);
+    let max_bytes = stats.iter().map(|s| s.proof_bytes).max().unwrap_or(0) as f64;
+    let passed = reports.iter().filter(|report| report.passed()).count();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Plonky3 Fibonacci examples</title>\n");
+    html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", REPORT_STYLE));
+    html.push_str("<h1>Plonky3 Fibonacci examples</h1>\n");
+    html.push_str(&format!("<p>{} of {} examples passed.</p>\n", passed, reports.len()));
+    html.push_str("<table>\n<tr><th>Example</th><th>Status</th><th>Rows</th><th>Width</th>");
+    html.push_str("<th>Constraints</th><th>Max degree</th><th>Prove time</th><th>Verify time</th>");
+    html.push_str("<th>Proof size</th></tr>\n");
+    for report in reports {
+        let class = if report.passed() { "pass" } else { "fail" };
+        let status = match &report.failure {
+            None => "PASS".to_string(),
+            Some(failure) => format!("FAIL: {}", escape_html(failure)),
+        };
+        let summary = match &report.summary {
+            Some(s) => format!("<td>{}</td><td>{}</td><td>{}</td>", s.width, s.constraints, s.max_degree),
+            None => "<td></td><td></td><td></td>".to_string(),
+        };
+        let (rows, prove_cell, verify_cell, size_cell) = match &report.stats {
+            Some(s) => (
+                s.rows.to_string(),
+                bar(s.prove_time.as_secs_f64(), max_prove, format!("{:.2} ms", s.prove_time.as_secs_f64() * 1e3)),
+                format!("{:.2} ms", s.verify_time.as_secs_f64() * 1e3),
+                bar(s.proof_bytes as f64, max_bytes, format!("{} bytes", s.proof_bytes)),
+            ),
+            None => Default::default(),
+        };
+        html.push_str(&format!(
+            "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td>{}<td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            class,
+            escape_html(report.name),
+            status,
+            rows,
+            summary,
+            prove_cell,
+            verify_cell,
+            size_cell
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_example_passes_and_gets_a_row() {
+        let reports = run_examples();
+        assert_eq!(reports.len(), EXAMPLES.len());
+        for report in &reports {
+            assert!(report.passed(), "{}", report);
+        }
+        let html = render_report(&reports);
+        assert_eq!(html.matches("<tr class=\"pass\">").count(), EXAMPLES.len());
+        assert!(!html.contains("<tr class=\"fail\">"));
+        assert!(html.contains(&format!("{} of {} examples passed", EXAMPLES.len(), EXAMPLES.len())));
+    }
+
+    #[test]
+    fn a_failure_is_a_red_row_with_its_reason_escaped() {
+        let passing = run_fibonacci("FibonacciAir, 100 steps");
+        let failing = ExampleReport {
+            name: "Broken <example>",
+            summary: None,
+            stats: None,
+            failure: Some("panicked: a < b".to_string()),
+        };
+        let html = render_report(&[passing, failing]);
+        assert_eq!(html.matches("<tr class=\"pass\">").count(), 1);
+        assert_eq!(html.matches("<tr class=\"fail\">").count(), 1);
+        assert!(html.contains("<td>Broken &lt;example&gt;</td><td>FAIL: panicked: a &lt; b</td>"));
+        assert!(html.contains("1 of 2 examples passed"));
+        assert!(REPORT_STYLE.contains("tr.fail td { background: #f8d0d0; }"));
+    }
+}
@@ -0,0 +1,374 @@
+// A long Fibonacci run proved as a chain of segments, one proof each, without
+// recursion. Segment k starts from the pair segment k - 1 ended on, and
+// verify_chain checks every proof and that each one starts where the one
+// before it ended.
+//
+// FibonacciAir makes only the end value a public, and a chain linked on a
+// alone would leave the next segment free to pick its b. SegmentAir is
+// FibonacciAir with the last row's b bound to one more public value, so a
+// segment publishes the whole pair it ends on.
+use core::borrow::Borrow;
+use core::fmt;
+use core::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+
+use crate::air_id::{AirId, AirIdError, IdentifiedProof, ProvableComputation, prove_identified, verify_identified};
+use crate::cost::{CostParams, ERROR_FACTOR, SecurityLevel, estimate_proving_cost};
+use crate::last_row::bind_physical_last_row;
+use crate::lint::AirLint;
+use crate::trace::{ColumnNames, SizingError};
+use crate::{
+    FibonacciAir, FibonacciTraceRow, NUM_FIBONACCI_COLS, NUM_FIBONACCI_PUBLIC_VALUES, Val, create_default_config,
+    eval_fibonacci, generate_recurrence_trace, recurrence_pair,
+};
+
+// Public values: FibonacciAir's [a0, b0, end a, num_steps], then end b
+pub const NUM_SEGMENT_PUBLIC_VALUES: usize = NUM_FIBONACCI_PUBLIC_VALUES + 1;
+
+pub const SEGMENT_AIR_ID: AirId = AirId::new("fibonacci_segment", 1, 0, 0);
+
+#[derive(Debug, Clone)]
+pub struct SegmentAir;
+
+impl<F> BaseAir<F> for SegmentAir {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl AirLint for SegmentAir {
+    fn num_public_values(&self) -> usize {
+        NUM_SEGMENT_PUBLIC_VALUES
+    }
+}
+
+impl ProvableComputation for SegmentAir {
+    fn air_id(&self) -> AirId {
+        SEGMENT_AIR_ID
+    }
+}
+
+impl ColumnNames for SegmentAir {
+    fn column_names(&self) -> &[&'static str] {
+        FibonacciAir.column_names()
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SegmentAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &FibonacciTraceRow<AB::Var> = (*local).borrow();
+        let next: &FibonacciTraceRow<AB::Var> = (*next).borrow();
+        let end_b = builder.public_values()[NUM_FIBONACCI_PUBLIC_VALUES];
+
+        eval_fibonacci(builder, local, next);
+        // Padding carries the end pair down, so the last row's b is the pair's b
+        bind_physical_last_row(builder, local.fib.b.clone().into(), end_b.into());
+    }
+}
+
+// One segment's proof and the public values it was proved against. Both
+// fields are public, so a segment handed to verify_chain may be any length;
+// the accessors are None on one that is not NUM_SEGMENT_PUBLIC_VALUES long.
+pub struct SegmentProof {
+    pub proof: IdentifiedProof,
+    pub public_values: Vec<Val>,
+}
+
+impl SegmentProof {
+    fn public_values(&self) -> Option<&[Val; NUM_SEGMENT_PUBLIC_VALUES]> {
+        self.public_values.as_slice().try_into().ok()
+    }
+
+    pub fn start(&self) -> Option<(Val, Val)> {
+        self.public_values().map(|pvs| (pvs[0], pvs[1]))
+    }
+
+    pub fn end(&self) -> Option<(Val, Val)> {
+        self.public_values().map(|pvs| (pvs[2], pvs[NUM_FIBONACCI_PUBLIC_VALUES]))
+    }
+
+    // None also when the count does not fit a usize on this target
+    pub fn num_steps(&self) -> Option<usize> {
+        self.public_values().and_then(|pvs| usize::try_from(pvs[3].as_canonical_u64()).ok())
+    }
+}
+
+// `num_steps` steps of the recurrence from `start` as one segment
+pub fn prove_segment(start: (Val, Val), num_steps: usize) -> Result<SegmentProof, SizingError> {
+    let trace = generate_recurrence_trace(start.0, start.1, num_steps)?;
+    let (_, end_b) = recurrence_pair(start.0, start.1, num_steps);
+    let mut public_values = trace.public_values;
+    public_values.push(end_b);
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove_identified(&config, &SegmentAir, trace.matrix, &public_values);
+    Ok(SegmentProof { proof, public_values })
+}
+
+// F(0), ..., F(num_steps) from (0, 1) in segments of `segment_len` steps, the
+// last one shorter if segment_len does not divide num_steps. No steps is one
+// empty segment.
+pub fn prove_segments(num_steps: usize, segment_len: NonZeroUsize) -> Result<Vec<SegmentProof>, SizingError> {
+    prove_segments_timed(num_steps, segment_len, |_, _| ())
+}
+
+// prove_segments, passing each segment's step count and proving time to
+// `on_segment`
+fn prove_segments_timed(
+    num_steps: usize,
+    segment_len: NonZeroUsize,
+    mut on_segment: impl FnMut(usize, Duration),
+) -> Result<Vec<SegmentProof>, SizingError> {
+    let mut segments = Vec::new();
+    let (mut start, mut remaining) = ((Val::ZERO, Val::ONE), num_steps);
+    loop {
+        let steps = remaining.min(segment_len.get());
+        let started = Instant::now();
+        let segment = prove_segment(start, steps)?;
+        on_segment(steps, started.elapsed());
+        start = recurrence_pair(start.0, start.1, steps);
+        remaining -= steps;
+        segments.push(segment);
+        if remaining == 0 {
+            return Ok(segments);
+        }
+    }
+}
+
+// What auto_chunk has seen: for every segment it proved, the cost model's
+// estimate and the time proving really took. Keep one across batches and
+// each batch sizes its segments from all the earlier ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkTimings {
+    pub estimated: Vec<Duration>,
+    pub realized: Vec<Duration>,
+}
+
+impl ChunkTimings {
+    pub fn record(&mut self, estimated: Duration, realized: Duration) {
+        self.estimated.push(estimated);
+        self.realized.push(realized);
+    }
+
+    // Realized over estimated time, summed over every record; None before
+    // the first
+    pub fn correction(&self) -> Option<f64> {
+        let estimated: Duration = self.estimated.iter().sum();
+        let realized: Duration = self.realized.iter().sum();
+        (!estimated.is_zero()).then(|| realized.as_secs_f64() / estimated.as_secs_f64())
+    }
+}
+
+// The cost model's central estimate for one segment of `steps` steps, which
+// fills steps + 1 rows
+fn estimated_segment_time(steps: usize) -> Result<Duration, SizingError> {
+    let rows = steps.checked_add(1).ok_or(SizingError::HeightOverflow { rows: steps })?;
+    let estimate = estimate_proving_cost(rows, NUM_FIBONACCI_COLS, &CostParams::test())?;
+    Ok(estimate.time_range.1.div_f64(ERROR_FACTOR))
+}
+
+// The most steps whose padded height is a power of two and whose corrected
+// estimate fits `target`, never below one minimum-height segment and never
+// past `total_steps`
+fn chunk_len_for(total_steps: usize, target: Duration, correction: f64) -> Result<NonZeroUsize, SizingError> {
+    let mut height = CostParams::test().min_height;
+    while height - 1 < total_steps {
+        let Some(next) = height.checked_mul(2) else { break };
+        if estimated_segment_time(next - 1)?.mul_f64(correction) > target {
+            break;
+        }
+        height = next;
+    }
+    Ok(NonZeroUsize::new((height - 1).min(total_steps)).unwrap_or(NonZeroUsize::MIN))
+}
+
+// A chain from auto_chunk and the segment length it picked; the last segment
+// holds the remainder
+pub struct AutoChunked {
+    pub segments: Vec<SegmentProof>,
+    pub chunk_len: NonZeroUsize,
+}
+
+// prove_segments with the segment length picked so each segment should prove
+// within `target`. The estimate comes from the cost model, scaled by what
+// `timings` has recorded; with no records yet, a minimum-height segment is
+// proved first as the calibration proof. Every segment's time is added to
+// `timings`.
+pub fn auto_chunk(
+    total_steps: usize,
+    target: Duration,
+    timings: &mut ChunkTimings,
+) -> Result<AutoChunked, SizingError> {
+    let correction = match timings.correction() {
+        Some(correction) => correction,
+        None => {
+            let steps = CostParams::test().min_height - 1;
+            let estimated = estimated_segment_time(steps)?;
+            let started = Instant::now();
+            prove_segment((Val::ZERO, Val::ONE), steps)?;
+            timings.record(estimated, started.elapsed());
+            timings.correction().unwrap_or(1.0)
+        }
+    };
+    let chunk_len = chunk_len_for(total_steps, target, correction)?;
+
+    let mut estimates = Vec::new();
+    let mut realized = Vec::new();
+    let segments = prove_segments_timed(total_steps, chunk_len, |steps, elapsed| {
+        estimates.push(estimated_segment_time(steps));
+        realized.push(elapsed);
+    })?;
+    for (estimated, realized) in estimates.into_iter().zip(realized) {
+        timings.record(estimated?, realized);
+    }
+    Ok(AutoChunked { segments, chunk_len })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    // A chain needs at least one segment
+    Empty,
+    // Segment `index` has `len` public values, not NUM_SEGMENT_PUBLIC_VALUES
+    Malformed { index: usize, len: usize },
+    // Segment `index` does not start where the chain so far ends; for index 0,
+    // where the chain was claimed to start
+    Disconnected { index: usize },
+    // Segment `index`'s own proof was rejected
+    Segment { index: usize, error: AirIdError },
+    // The chain ends somewhere other than the claimed pair
+    WrongEnd { claimed: (Val, Val), found: (Val, Val) },
+    // The segments' step counts add up past usize
+    TooManySteps,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "a chain needs at least one segment"),
+            Self::Malformed { index, len } => write!(
+                f,
+                "segment {} has {} public values, not {}",
+                index, len, NUM_SEGMENT_PUBLIC_VALUES
+            ),
+            Self::Disconnected { index: 0 } => write!(f, "segment 0 does not start from the claimed pair"),
+            Self::Disconnected { index } => {
+                write!(f, "segment {} does not start where segment {} ends", index, index - 1)
+            }
+            Self::Segment { index, error } => write!(f, "segment {}: {}", index, error),
+            Self::WrongEnd { claimed, found } => {
+                write!(f, "the chain ends on {:?}, not the claimed {:?}", found, claimed)
+            }
+            Self::TooManySteps => write!(f, "the chain covers more steps than a usize holds"),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+// Checks every segment's proof and the links between them. Returns the number
+// of steps the chain covers. A segment's public values are read only once its
+// length is checked and its proof verified against them.
+pub fn verify_chain(
+    segments: &[SegmentProof],
+    claimed_start: (Val, Val),
+    claimed_end: (Val, Val),
+) -> Result<usize, ChainError> {
+    if segments.is_empty() {
+        return Err(ChainError::Empty);
+    }
+    let config = create_default_config(SecurityLevel::Test);
+    let (mut end, mut steps) = (claimed_start, 0usize);
+    for (index, segment) in segments.iter().enumerate() {
+        let malformed = ChainError::Malformed { index, len: segment.public_values.len() };
+        let (Some(start), Some(next_end)) = (segment.start(), segment.end()) else {
+            return Err(malformed);
+        };
+        verify_identified(&config, &SegmentAir, &segment.proof, &segment.public_values)
+            .map_err(|error| ChainError::Segment { index, error })?;
+        if start != end {
+            return Err(ChainError::Disconnected { index });
+        }
+        end = next_end;
+        steps = segment.num_steps().and_then(|n| steps.checked_add(n)).ok_or(ChainError::TooManySteps)?;
+    }
+    if end != claimed_end {
+        return Err(ChainError::WrongEnd { claimed: claimed_end, found: end });
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOTAL_STEPS: usize = 1000;
+
+    fn claimed_end() -> (Val, Val) {
+        recurrence_pair(Val::ZERO, Val::ONE, TOTAL_STEPS)
+    }
+
+    #[test]
+    fn a_tiny_target_gives_many_minimum_height_segments() {
+        let mut timings = ChunkTimings::default();
+        let chained = auto_chunk(TOTAL_STEPS, Duration::from_nanos(1), &mut timings).unwrap();
+        let min_len = CostParams::test().min_height - 1;
+        assert_eq!(chained.chunk_len.get(), min_len);
+        assert_eq!(chained.segments.len(), TOTAL_STEPS.div_ceil(min_len));
+        assert_eq!(chained.segments.last().unwrap().num_steps(), Some(TOTAL_STEPS % min_len));
+        assert_eq!(verify_chain(&chained.segments, (Val::ZERO, Val::ONE), claimed_end()), Ok(TOTAL_STEPS));
+        // The calibration proof, then one record per segment
+        assert_eq!(timings.realized.len(), chained.segments.len() + 1);
+    }
+
+    #[test]
+    fn a_huge_target_gives_one_segment() {
+        let mut timings = ChunkTimings::default();
+        let chained = auto_chunk(TOTAL_STEPS, Duration::from_secs(3600), &mut timings).unwrap();
+        assert_eq!(chained.chunk_len.get(), TOTAL_STEPS);
+        assert_eq!(chained.segments.len(), 1);
+        assert_eq!(verify_chain(&chained.segments, (Val::ZERO, Val::ONE), claimed_end()), Ok(TOTAL_STEPS));
+
+        // A second batch sizes from the first's records without another calibration proof
+        let recorded = timings.realized.len();
+        auto_chunk(TOTAL_STEPS, Duration::from_secs(3600), &mut timings).unwrap();
+        assert_eq!(timings.realized.len(), recorded + 1);
+    }
+
+    #[test]
+    fn a_segment_with_short_public_values_is_refused_not_indexed() {
+        let mut segments = prove_segments(20, NonZeroUsize::new(10).unwrap()).unwrap();
+        let end = recurrence_pair(Val::ZERO, Val::ONE, 20);
+        assert_eq!(verify_chain(&segments, (Val::ZERO, Val::ONE), end), Ok(20));
+
+        segments[1].public_values.truncate(2);
+        assert_eq!((segments[1].start(), segments[1].num_steps()), (None, None));
+        assert_eq!(
+            verify_chain(&segments, (Val::ZERO, Val::ONE), end),
+            Err(ChainError::Malformed { index: 1, len: 2 })
+        );
+        segments[1].public_values.clear();
+        assert_eq!(
+            verify_chain(&segments, (Val::ZERO, Val::ONE), end),
+            Err(ChainError::Malformed { index: 1, len: 0 })
+        );
+    }
+
+    #[test]
+    fn a_segment_whose_proof_fails_is_refused_before_its_links_are_read() {
+        let mut segments = prove_segments(20, NonZeroUsize::new(10).unwrap()).unwrap();
+        let end = recurrence_pair(Val::ZERO, Val::ONE, 20);
+        // Segment 1 now claims another start: its proof fails, and is checked
+        // before the start is compared with segment 0's end
+        segments[1].public_values[0] += Val::ONE;
+        assert!(matches!(
+            verify_chain(&segments, (Val::ZERO, Val::ONE), end),
+            Err(ChainError::Segment { index: 1, .. })
+        ));
+    }
+}
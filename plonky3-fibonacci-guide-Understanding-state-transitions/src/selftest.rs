@@ -0,0 +1,262 @@
+// Deploy-time health check: proves and verifies a tiny fixed trace under every
+// compiled-in config. Proving is deterministic, so the quotient commitment of
+// that trace fingerprints the whole transcript; a miscompiled hash or field
+// path changes it even when the proof still verifies. The zk config draws its
+// blinding from a fixed seed here so that it fingerprints too.
+use core::fmt;
+use std::time::{Duration, Instant};
+
+use p3_field::PrimeField64;
+use p3_uni_stark::{prove, verify};
+
+use crate::blake3::{prove_fibonacci_blake3, verify_fibonacci_blake3};
+use crate::challenger::create_keccak_challenger_config;
+use crate::cost::SecurityLevel;
+use crate::dual::{DUPLEX_NAME, KECCAK_NAME};
+use crate::keccak::{prove_fibonacci_keccak, verify_fibonacci_keccak};
+use crate::trace::SizingError;
+use crate::zk::{BlindingSource, prove_fibonacci_zk, verify_fibonacci_zk};
+use crate::{FibonacciAir, Val, create_default_config, generate_fibonacci_trace};
+
+const SELF_TEST_STEPS: usize = 8;
+
+// The zk config's self-test proof is blinded from a fixed seed, so its
+// fingerprint repeats; real blinded proofs draw from the OS
+const SELF_TEST_BLINDING: BlindingSource = BlindingSource::Seeded(0);
+
+pub const BLAKE3_NAME: &str = "Blake3";
+pub const KECCAK_HASH_NAME: &str = "Keccak-256 hash";
+pub const ZK_NAME: &str = "Poseidon2 zk";
+#[cfg(feature = "goldilocks")]
+pub const GOLDILOCKS_NAME: &str = "Goldilocks";
+#[cfg(feature = "koala-bear")]
+pub const KOALA_BEAR_NAME: &str = "KoalaBear";
+#[cfg(feature = "mersenne-31")]
+pub const MERSENNE_31_NAME: &str = "Mersenne31";
+
+// Fingerprints printed by `self-test` on a reference build, kept in fixtures/
+// so regen-fixtures can rewrite them. regen-fixtures compiles in every field,
+// so the file covers every config the crate ships.
+pub const EXPECTED_FINGERPRINTS: &str = include_str!("../fixtures/fingerprints.txt");
+
+const FINGERPRINTS_HEADER: &str = "\
+# Quotient commitment of the self-test proof under each config, one
+# \"fingerprint config\" line each. Rewritten by:
+#   cargo run --features regen-fixtures -- regen-fixtures --apply
+";
+
+// (config, fingerprint) pairs from a fingerprints file, skipping # comments
+pub fn parse_fingerprints(text: &str) -> Vec<(&str, &str)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(' '))
+        .map(|(fingerprint, config)| (config, fingerprint))
+        .collect()
+}
+
+// The fingerprints file for `reports`, in the form parse_fingerprints reads
+pub fn format_fingerprints(reports: &[ConfigReport]) -> String {
+    let mut text = FINGERPRINTS_HEADER.to_string();
+    for report in reports {
+        text.push_str(&format!("{} {}\n", report.fingerprint, report.config));
+    }
+    text
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigReport {
+    pub config: &'static str,
+    pub elapsed: Duration,
+    pub fingerprint: String,
+    // Whether EXPECTED_FINGERPRINTS had an entry to compare against
+    pub fingerprint_checked: bool,
+    // None when the config passed
+    pub failure: Option<String>,
+}
+
+impl ConfigReport {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    // Passed, and against a recorded fingerprint rather than none
+    pub fn passed_fingerprint(&self) -> bool {
+        self.passed() && self.fingerprint_checked
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed() { "PASS" } else { "FAIL" };
+        write!(f, "{} {} ({:?}, fingerprint {}", verdict, self.config, self.elapsed, self.fingerprint)?;
+        if !self.fingerprint_checked {
+            write!(f, ", none recorded")?;
+        }
+        write!(f, ")")?;
+        if let Some(failure) = &self.failure {
+            write!(f, ": {}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+// Fixed-width hex of a field-element digest: 8 digits per element for 32-bit
+// fields, 16 for Goldilocks
+fn field_hex<F: PrimeField64>(digest: &[F]) -> String {
+    let width = if F::ORDER_U64 > u32::MAX as u64 { 16 } else { 8 };
+    digest.iter().map(|v| format!("{:0width$x}", v.as_canonical_u64(), width = width)).collect()
+}
+
+fn bytes_hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// One config's fingerprint with its verification verdict, or why there is no
+// proof to fingerprint
+type Run = Result<(String, Result<(), String>), String>;
+
+fn trace_failure(e: SizingError) -> String {
+    format!("self-test trace: {}", e)
+}
+
+// Times `run` and checks its fingerprint against the one `expected` records
+fn report(config: &'static str, expected: &[(&str, &str)], run: impl FnOnce() -> Run) -> ConfigReport {
+    let start = Instant::now();
+    let (fingerprint, failure) = match run() {
+        Ok((fingerprint, verified)) => (fingerprint, verified.err().map(|e| format!("verification failed: {}", e))),
+        Err(e) => (String::new(), Some(e)),
+    };
+    let recorded = expected.iter().find(|(name, _)| *name == config).map(|(_, value)| *value);
+    let failure = match recorded {
+        Some(value) if failure.is_none() && value != fingerprint => {
+            Some(format!("fingerprint differs from {}", value))
+        }
+        _ => failure,
+    };
+    ConfigReport {
+        config,
+        elapsed: start.elapsed(),
+        fingerprint,
+        fingerprint_checked: recorded.is_some(),
+        failure,
+    }
+}
+
+// commands::run_self_test passes the parsed EXPECTED_FINGERPRINTS; any other
+// table, e.g. one with a value changed, exercises the failure path
+pub fn self_test(expected: &[(&str, &str)]) -> Vec<ConfigReport> {
+    let reports = vec![
+        report(DUPLEX_NAME, expected, || {
+            let trace = generate_fibonacci_trace::<Val>(SELF_TEST_STEPS).map_err(trace_failure)?;
+            let config = create_default_config(SecurityLevel::Test);
+            let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+            let verified = verify(&config, &FibonacciAir, &proof, &trace.public_values).map_err(|e| format!("{:?}", e));
+            Ok((field_hex(&<[Val; 8]>::from(proof.commitments.quotient_chunks)), verified))
+        }),
+        report(KECCAK_NAME, expected, || {
+            let trace = generate_fibonacci_trace::<Val>(SELF_TEST_STEPS).map_err(trace_failure)?;
+            let config = create_keccak_challenger_config();
+            let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+            let verified = verify(&config, &FibonacciAir, &proof, &trace.public_values).map_err(|e| format!("{:?}", e));
+            Ok((field_hex(&<[Val; 8]>::from(proof.commitments.quotient_chunks)), verified))
+        }),
+        report(BLAKE3_NAME, expected, || {
+            let (proof, public_values) = prove_fibonacci_blake3(SELF_TEST_STEPS).map_err(trace_failure)?;
+            let verified = verify_fibonacci_blake3(&proof, &public_values);
+            Ok((bytes_hex(&<[u8; 32]>::from(proof.commitments.quotient_chunks)), verified))
+        }),
+        report(KECCAK_HASH_NAME, expected, || {
+            let (proof, public_values) = prove_fibonacci_keccak(SELF_TEST_STEPS).map_err(trace_failure)?;
+            let verified = verify_fibonacci_keccak(&proof, &public_values);
+            Ok((bytes_hex(&<[u8; 32]>::from(proof.commitments.quotient_chunks)), verified))
+        }),
+        report(ZK_NAME, expected, || {
+            let (proof, public_values) =
+                prove_fibonacci_zk(SELF_TEST_STEPS, SELF_TEST_BLINDING).map_err(trace_failure)?;
+            let verified = verify_fibonacci_zk(&proof, &public_values);
+            Ok((field_hex(&<[Val; 8]>::from(proof.commitments.quotient_chunks)), verified))
+        }),
+    ];
+    #[cfg(feature = "goldilocks")]
+    let reports = [reports, vec![report(GOLDILOCKS_NAME, expected, || {
+        use p3_goldilocks::Goldilocks;
+
+        use crate::goldilocks::{prove_fibonacci_goldilocks, verify_fibonacci_goldilocks};
+
+        let (proof, public_values) = prove_fibonacci_goldilocks(SELF_TEST_STEPS).map_err(trace_failure)?;
+        let verified = verify_fibonacci_goldilocks(&proof, &public_values);
+        Ok((field_hex(&<[Goldilocks; 4]>::from(proof.commitments.quotient_chunks)), verified))
+    })]]
+    .concat();
+    #[cfg(feature = "koala-bear")]
+    let reports = [reports, vec![report(KOALA_BEAR_NAME, expected, || {
+        use p3_koala_bear::KoalaBear;
+
+        use crate::koala_bear::{prove_fibonacci_koala_bear, verify_fibonacci_koala_bear};
+
+        let (proof, public_values) = prove_fibonacci_koala_bear(SELF_TEST_STEPS).map_err(trace_failure)?;
+        let verified = verify_fibonacci_koala_bear(&proof, &public_values);
+        Ok((field_hex(&<[KoalaBear; 8]>::from(proof.commitments.quotient_chunks)), verified))
+    })]]
+    .concat();
+    #[cfg(feature = "mersenne-31")]
+    let reports = [reports, vec![report(MERSENNE_31_NAME, expected, || {
+        use p3_mersenne_31::Mersenne31;
+
+        use crate::mersenne_31::{prove_fibonacci_mersenne_31, verify_fibonacci_mersenne_31};
+
+        let (proof, public_values) = prove_fibonacci_mersenne_31(SELF_TEST_STEPS).map_err(trace_failure)?;
+        let verified = verify_fibonacci_mersenne_31(&proof, &public_values);
+        Ok((field_hex(&<[Mersenne31; 8]>::from(proof.commitments.quotient_chunks)), verified))
+    })]]
+    .concat();
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The fingerprints a run produced, as the table self_test compares against
+    fn recorded(reports: &[ConfigReport]) -> Vec<(&'static str, String)> {
+        reports.iter().map(|report| (report.config, report.fingerprint.clone())).collect()
+    }
+
+    fn table(recorded: &[(&'static str, String)]) -> Vec<(&'static str, &str)> {
+        recorded.iter().map(|(config, fingerprint)| (*config, fingerprint.as_str())).collect()
+    }
+
+    #[test]
+    fn the_shipped_fingerprints_pass() {
+        let reports = self_test(&parse_fingerprints(EXPECTED_FINGERPRINTS));
+        assert!(reports.iter().all(ConfigReport::passed_fingerprint), "{:?}", reports);
+    }
+
+    #[test]
+    fn a_rerun_matches_its_own_fingerprints() {
+        let first = recorded(&self_test(&[]));
+        let reports = self_test(&table(&first));
+        assert!(reports.iter().all(ConfigReport::passed_fingerprint), "{:?}", reports);
+    }
+
+    #[test]
+    fn a_perturbed_fingerprint_fails_only_its_config() {
+        let mut fingerprints = recorded(&self_test(&[]));
+        let perturbed = &mut fingerprints[0].1;
+        let first = if perturbed.starts_with('0') { "1" } else { "0" };
+        perturbed.replace_range(..1, first);
+        let reports = self_test(&table(&fingerprints));
+        assert!(!reports[0].passed(), "{}", reports[0]);
+        assert!(reports[0].to_string().contains(&format!("fingerprint differs from {}", fingerprints[0].1)));
+        assert!(reports[1..].iter().all(ConfigReport::passed), "{:?}", reports);
+    }
+
+    #[test]
+    fn fingerprints_round_trip_through_the_fixture_format() {
+        let reports = self_test(&[]);
+        let text = format_fingerprints(&reports);
+        let parsed = parse_fingerprints(&text);
+        assert_eq!(parsed, table(&recorded(&reports)));
+    }
+}
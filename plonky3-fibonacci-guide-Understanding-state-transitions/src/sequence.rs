@@ -0,0 +1,170 @@
+use core::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::Val;
+use crate::canonical::{NonCanonicalValue, parse_canonical};
+use crate::claims::FibonacciClaims;
+
+// The real sequence F(0), F(1), ..., F(num_steps) read off a Fibonacci trace:
+// F(0) from the first row's a column, then each real row's b column
+pub fn sequence_values(
+    trace: &RowMajorMatrix<Val>,
+    real_rows: usize,
+) -> impl Iterator<Item = u64> + '_ {
+    let first = trace.values[0].as_canonical_u64();
+    let rest = trace
+        .values
+        .chunks(trace.width())
+        .take(real_rows)
+        .map(|row| row[1].as_canonical_u64());
+    core::iter::once(first).chain(rest)
+}
+
+// Streams "index,value" CSV lines so large sequences never sit in memory as text
+pub fn write_sequence(path: &Path, values: impl Iterator<Item = u64>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "index,value")?;
+    for (index, value) in values.enumerate() {
+        writeln!(out, "{},{}", index, value)?;
+    }
+    out.flush()
+}
+
+#[derive(Debug)]
+pub enum SequenceError {
+    Io(io::Error),
+    // A line that is not "index,value" with the expected index
+    Malformed { line: usize },
+    // A value on this line of p or more
+    NonCanonical { line: usize, error: NonCanonicalValue },
+    // The entry at `index` does not follow from the two before it (or from the claimed F(0), F(1))
+    Inconsistent { index: usize },
+    // The recurrence holds but ends on a different value than the claim
+    FinalMismatch { expected: Val, found: Val },
+    // The file holds a different number of entries than the claim's num_steps + 1
+    LengthMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not read sequence: {}", e),
+            Self::Malformed { line } => write!(f, "malformed sequence entry on line {}", line),
+            Self::NonCanonical { line, error } => write!(f, "line {}: {}", line, error),
+            Self::Inconsistent { index } => write!(f, "entry {} breaks F(n) = F(n-1) + F(n-2)", index),
+            Self::FinalMismatch { expected, found } => {
+                write!(f, "sequence ends at {} but the claimed final value is {}", found, expected)
+            }
+            Self::LengthMismatch { expected, found } => {
+                write!(f, "sequence has {} entries but the claim covers {}", found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+impl From<io::Error> for SequenceError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+// Re-checks a sequence file line by line against the recurrence from the
+// claim's F(0), F(1), then its last entry and length against the claimed
+// result and num_steps, returning the number of entries. Pass the claims of a
+// proof that verified: the file is then checked against what was proved.
+pub fn check_sequence(path: &Path, claims: &FibonacciClaims) -> Result<usize, SequenceError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut expected = (claims.f0, claims.f1);
+    let mut last = None;
+    let mut count = 0;
+
+    // Line 1 is the header
+    for (line_index, line) in reader.lines().enumerate().skip(1) {
+        let line = line?;
+        let malformed = || SequenceError::Malformed { line: line_index + 1 };
+        let (index, value) = line.split_once(',').ok_or_else(malformed)?;
+        let index: usize = index.parse().map_err(|_| malformed())?;
+        let value: u64 = value.parse().map_err(|_| malformed())?;
+        if index != count {
+            return Err(malformed());
+        }
+
+        let value =
+            parse_canonical(value).map_err(|error| SequenceError::NonCanonical { line: line_index + 1, error })?;
+        if value != expected.0 {
+            return Err(SequenceError::Inconsistent { index });
+        }
+        expected = (expected.1, expected.0 + expected.1);
+        last = Some(value);
+        count += 1;
+    }
+
+    match last {
+        Some(found) if found != claims.result => Err(SequenceError::FinalMismatch { expected: claims.result, found }),
+        Some(_) if count != claims.num_steps + 1 => {
+            Err(SequenceError::LengthMismatch { expected: claims.num_steps + 1, found: count })
+        }
+        Some(_) => Ok(count),
+        None => Err(SequenceError::Malformed { line: 2 }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_fibonacci_trace, prove_fibonacci, verify_fibonacci};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("fibonacci-sequence-{}-{}.csv", name, std::process::id()))
+    }
+
+    #[test]
+    fn a_long_sequence_round_trips() {
+        let trace = generate_fibonacci_trace::<Val>(5000).unwrap();
+        let claims = FibonacciClaims::from_public_values(&trace.public_values).unwrap();
+        let path = temp_path("round-trip");
+        write_sequence(&path, sequence_values(&trace.matrix, trace.real_rows)).unwrap();
+
+        let checked = check_sequence(&path, &claims);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(checked.unwrap(), 5001);
+    }
+
+    #[test]
+    fn an_edited_entry_is_flagged_while_the_proof_verifies() {
+        let trace = generate_fibonacci_trace::<Val>(15).unwrap();
+        let path = temp_path("edited");
+        write_sequence(&path, sequence_values(&trace.matrix, trace.real_rows)).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        // F(7) = 13
+        std::fs::write(&path, text.replace("\n7,13\n", "\n7,14\n")).unwrap();
+
+        let (proof, public_values) = prove_fibonacci(15).unwrap();
+        assert!(verify_fibonacci(&proof, &public_values).is_ok());
+        let claims = FibonacciClaims::from_public_values(&public_values).unwrap();
+        let checked = check_sequence(&path, &claims);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(checked, Err(SequenceError::Inconsistent { index: 7 })), "{:?}", checked);
+    }
+
+    #[test]
+    fn a_sequence_for_another_claim_is_flagged() {
+        let trace = generate_fibonacci_trace::<Val>(15).unwrap();
+        let path = temp_path("other-claim");
+        write_sequence(&path, sequence_values(&trace.matrix, trace.real_rows)).unwrap();
+
+        let (_, public_values) = prove_fibonacci(16).unwrap();
+        let claims = FibonacciClaims::from_public_values(&public_values).unwrap();
+        let checked = check_sequence(&path, &claims);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(checked, Err(SequenceError::FinalMismatch { .. })), "{:?}", checked);
+    }
+}
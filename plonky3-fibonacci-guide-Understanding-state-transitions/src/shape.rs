@@ -0,0 +1,143 @@
+// Structural checks on a decoded proof, run before it reaches p3's verifier.
+// A proof from untrusted bytes decodes into well-typed vectors of any length,
+// and the verifier indexes into them assuming the lengths its own prover
+// produces; a short opening or a degree past the field's two-adic subgroup
+// panics there instead of returning an error. Every length the verifier relies
+// on is recomputed here from the AIR and the FRI parameters.
+use core::fmt;
+
+use p3_field::{BasedVectorSpace, TwoAdicField};
+use p3_uni_stark::Proof;
+
+use crate::cost::{CostParams, MIN_TRACE_HEIGHT};
+use crate::{Challenge, MyConfig, Val};
+
+// FRI stops folding at MIN_TRACE_HEIGHT coefficients
+const LOG_FINAL_POLY_LEN: usize = MIN_TRACE_HEIGHT.trailing_zeros() as usize;
+
+// Committed batches opened by every query: the trace, then the quotient chunks
+const NUM_INPUT_BATCHES: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeError {
+    // A trace this tall could not have been proven under the parameters
+    DegreeOutOfRange { degree_bits: usize, min: usize, max: usize },
+    // Some part of the proof has the wrong number of entries
+    Mismatch { what: &'static str, expected: usize, found: usize },
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DegreeOutOfRange { degree_bits, min, max } => {
+                write!(f, "trace of 2^{} rows is outside 2^{}..=2^{}", degree_bits, min, max)
+            }
+            Self::Mismatch { what, expected, found } => {
+                write!(f, "proof has {} {}, expected {}", found, what, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+fn expect_len(what: &'static str, expected: usize, found: usize) -> Result<(), ShapeError> {
+    if expected != found {
+        return Err(ShapeError::Mismatch { what, expected, found });
+    }
+    Ok(())
+}
+
+// Checks `proof` against an AIR of `width` columns and the given log quotient
+// degree, proven with `params`. Only lengths are checked: a proof that passes
+// can still be rejected by the verifier, but no longer panics it.
+pub fn check_proof_shape(
+    proof: &Proof<MyConfig>,
+    width: usize,
+    log_quotient_degree: usize,
+    params: &CostParams,
+) -> Result<(), ShapeError> {
+    let degree_bits = proof.degree_bits;
+    let max = Val::TWO_ADICITY.saturating_sub(params.log_blowup);
+    if degree_bits < LOG_FINAL_POLY_LEN || degree_bits > max {
+        return Err(ShapeError::DegreeOutOfRange { degree_bits, min: LOG_FINAL_POLY_LEN, max });
+    }
+    let log_lde_height = degree_bits + params.log_blowup;
+    let num_chunks = 1 << log_quotient_degree;
+    let dimension = <Challenge as BasedVectorSpace<Val>>::DIMENSION;
+
+    let opened = &proof.opened_values;
+    expect_len("trace columns opened at zeta", width, opened.trace_local.len())?;
+    expect_len("trace columns opened at the next point", width, opened.trace_next.len())?;
+    expect_len("quotient chunks opened", num_chunks, opened.quotient_chunks.len())?;
+    for chunk in &opened.quotient_chunks {
+        expect_len("limbs in an opened quotient chunk", dimension, chunk.len())?;
+    }
+
+    let fri = &proof.opening_proof;
+    let rounds = degree_bits - LOG_FINAL_POLY_LEN;
+    expect_len("FRI commit-phase commitments", rounds, fri.commit_phase_commits.len())?;
+    expect_len("final polynomial coefficients", 1 << LOG_FINAL_POLY_LEN, fri.final_poly.len())?;
+    expect_len("FRI queries", params.num_queries, fri.query_proofs.len())?;
+
+    for query in &fri.query_proofs {
+        expect_len("batches opened by a query", NUM_INPUT_BATCHES, query.input_proof.len())?;
+        let (trace, quotient) = (&query.input_proof[0], &query.input_proof[1]);
+        expect_len("trace matrices opened by a query", 1, trace.opened_values.len())?;
+        expect_len("trace values opened by a query", width, trace.opened_values[0].len())?;
+        expect_len("quotient matrices opened by a query", num_chunks, quotient.opened_values.len())?;
+        for chunk in &quotient.opened_values {
+            expect_len("quotient values opened by a query", dimension, chunk.len())?;
+        }
+        for batch in &query.input_proof {
+            expect_len("Merkle path nodes for an input batch", log_lde_height, batch.opening_proof.len())?;
+        }
+
+        expect_len("FRI rounds opened by a query", rounds, query.commit_phase_openings.len())?;
+        // Each fold halves the committed height
+        for (round, step) in query.commit_phase_openings.iter().enumerate() {
+            let path_len = log_lde_height - round - 1;
+            expect_len("Merkle path nodes for a FRI round", path_len, step.opening_proof.len())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FIBONACCI_LOG_QUOTIENT_DEGREE, NUM_FIBONACCI_COLS, generate_fibonacci_trace_padded, prove_fibonacci_trace,
+        test_config,
+    };
+
+    fn honest_proof() -> Proof<MyConfig> {
+        let trace = generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap();
+        prove_fibonacci_trace(&test_config(), trace, &mut ()).0.proof
+    }
+
+    fn check(proof: &Proof<MyConfig>) -> Result<(), ShapeError> {
+        check_proof_shape(proof, NUM_FIBONACCI_COLS, FIBONACCI_LOG_QUOTIENT_DEGREE, &CostParams::minimal())
+    }
+
+    #[test]
+    fn an_honest_proof_has_the_expected_shape() {
+        assert_eq!(check(&honest_proof()), Ok(()));
+    }
+
+    #[test]
+    fn a_short_opening_or_an_impossible_degree_is_refused() {
+        let mut short = honest_proof();
+        short.opened_values.trace_local.pop();
+        let expected = ShapeError::Mismatch {
+            what: "trace columns opened at zeta",
+            expected: NUM_FIBONACCI_COLS,
+            found: NUM_FIBONACCI_COLS - 1,
+        };
+        assert_eq!(check(&short), Err(expected));
+
+        let mut tall = honest_proof();
+        tall.degree_bits = 64;
+        assert!(matches!(check(&tall), Err(ShapeError::DegreeOutOfRange { degree_bits: 64, .. })));
+    }
+}
@@ -0,0 +1,479 @@
+// Snapshots of every registered AIR's constraint system, checked against
+// fixtures/constraints.txt by the `constraints` command. A refactor of eval
+// (dropping clones, extracting a gadget, reordering asserts) must leave the
+// snapshot alone; a change to what the constraints mean must not.
+//
+// Each constraint is expanded into a sum of monomials over named variables
+// (local[i], next[i], public[i] and the row selectors), terms are sorted, and
+// the whole polynomial is scaled so its leading coefficient is 1, which makes
+// assert_eq(a, b) and assert_eq(b, a) the same constraint. The constraints of
+// an AIR are then sorted, so the order eval asserts them in does not matter.
+//
+// When a snapshot fails on purpose, bump the AIR's id and rewrite the file:
+//   cargo run --features regen-fixtures -- regen-fixtures --apply
+use core::fmt;
+use std::collections::BTreeMap;
+
+use p3_air::BaseAir;
+use p3_field::{Field, PrimeCharacteristicRing, PrimeField64};
+use p3_uni_stark::{Entry, SymbolicExpression, get_symbolic_constraints};
+
+use crate::air_id::ProvableComputation;
+use crate::big_fibonacci::BigFibonacciAir;
+use crate::coefficients::CoefficientAir;
+use crate::counter::CounterAir;
+use crate::dataset::DatasetAir;
+use crate::indexed::IndexedFibonacciAir;
+use crate::last_row::{LastRowBinding, LastRowDemoAir};
+use crate::linear_recurrence::RecurrenceAir;
+use crate::lint::AirLint;
+use crate::lucas::LucasAir;
+use crate::multi::MultiFibonacciAir;
+use crate::parity::ParityAir;
+use crate::report::EXAMPLES;
+use crate::{FibonacciAir, StarkAir, Val};
+
+// Snapshots recorded on a reference build, rewritten by regen-fixtures. An
+// AIR missing there fails, as an AIR with no snapshot is not checked at all.
+pub const EXPECTED_SNAPSHOTS: &str = include_str!("../fixtures/constraints.txt");
+
+const SNAPSHOTS_HEADER: &str = "\
+# Canonical constraints of every registered AIR, one [air@version] section
+# each, one \"deg N: polynomial = 0\" line per constraint. Rewritten by:
+#   cargo run --features regen-fixtures -- regen-fixtures --apply
+";
+
+// Ordered so that selectors come first in a monomial, then columns, then
+// public values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Variable {
+    FirstRow,
+    LastRow,
+    Transition,
+    Local(usize),
+    Next(usize),
+    Public(usize),
+    // Preprocessed, permutation and challenge entries, which no AIR here uses
+    Other(usize),
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FirstRow => write!(f, "first_row"),
+            Self::LastRow => write!(f, "last_row"),
+            Self::Transition => write!(f, "transition"),
+            Self::Local(i) => write!(f, "local[{}]", i),
+            Self::Next(i) => write!(f, "next[{}]", i),
+            Self::Public(i) => write!(f, "public[{}]", i),
+            Self::Other(i) => write!(f, "other[{}]", i),
+        }
+    }
+}
+
+// Sorted variables, repeated for powers, mapped to their coefficient
+type Polynomial = BTreeMap<Vec<Variable>, Val>;
+
+fn monomial(variable: Variable) -> Polynomial {
+    BTreeMap::from([(vec![variable], Val::ONE)])
+}
+
+fn add_scaled(mut a: Polynomial, b: Polynomial, scale: Val) -> Polynomial {
+    for (term, coefficient) in b {
+        *a.entry(term).or_insert(Val::ZERO) += coefficient * scale;
+    }
+    a
+}
+
+fn multiply(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    let mut product = Polynomial::new();
+    for (x, cx) in a {
+        for (y, cy) in b {
+            let mut term = [x.as_slice(), y.as_slice()].concat();
+            term.sort();
+            *product.entry(term).or_insert(Val::ZERO) += *cx * *cy;
+        }
+    }
+    product
+}
+
+fn expand(expr: &SymbolicExpression<Val>) -> Polynomial {
+    let mut polynomial = match expr {
+        SymbolicExpression::Variable(var) => monomial(match var.entry {
+            Entry::Main { offset: 0 } => Variable::Local(var.index),
+            Entry::Main { .. } => Variable::Next(var.index),
+            Entry::Public => Variable::Public(var.index),
+            _ => Variable::Other(var.index),
+        }),
+        SymbolicExpression::IsFirstRow => monomial(Variable::FirstRow),
+        SymbolicExpression::IsLastRow => monomial(Variable::LastRow),
+        SymbolicExpression::IsTransition => monomial(Variable::Transition),
+        SymbolicExpression::Constant(c) => BTreeMap::from([(vec![], *c)]),
+        SymbolicExpression::Add { x, y, .. } => add_scaled(expand(x), expand(y), Val::ONE),
+        SymbolicExpression::Sub { x, y, .. } => add_scaled(expand(x), expand(y), Val::NEG_ONE),
+        SymbolicExpression::Neg { x, .. } => add_scaled(Polynomial::new(), expand(x), Val::NEG_ONE),
+        SymbolicExpression::Mul { x, y, .. } => multiply(&expand(x), &expand(y)),
+    };
+    polynomial.retain(|_, coefficient| *coefficient != Val::ZERO);
+    polynomial
+}
+
+// A coefficient as a small signed number where one exists, e.g. p - 1 as -1
+fn signed(coefficient: Val) -> (bool, u64) {
+    let value = coefficient.as_canonical_u64();
+    if value > Val::ORDER_U64 / 2 {
+        (true, Val::ORDER_U64 - value)
+    } else {
+        (false, value)
+    }
+}
+
+// The canonical line for one constraint
+fn canonical(expr: &SymbolicExpression<Val>) -> String {
+    let polynomial = expand(expr);
+    let Some(&leading) = polynomial.values().next_back() else {
+        return format!("deg {}: 0 = 0", expr.degree_multiple());
+    };
+    let scale = leading.inverse();
+    let mut text = String::new();
+    for (i, (term, coefficient)) in polynomial.iter().rev().enumerate() {
+        let (negative, magnitude) = signed(*coefficient * scale);
+        text.push_str(match (i, negative) {
+            (0, _) => "",
+            (_, false) => " + ",
+            (_, true) => " - ",
+        });
+        let variables: Vec<String> = term.iter().map(Variable::to_string).collect();
+        match (magnitude, variables.is_empty()) {
+            (_, true) => text.push_str(&magnitude.to_string()),
+            (1, false) => text.push_str(&variables.join("*")),
+            (_, false) => text.push_str(&format!("{}*{}", magnitude, variables.join("*"))),
+        }
+    }
+    format!("deg {}: {} = 0", expr.degree_multiple(), text)
+}
+
+// The sorted canonical lines for a list of constraints
+pub fn canonical_constraints(constraints: &[SymbolicExpression<Val>]) -> Vec<String> {
+    let mut lines: Vec<String> = constraints.iter().map(canonical).collect();
+    lines.sort();
+    lines
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintSnapshot {
+    // The AIR's id, e.g. fibonacci@5.0.0
+    pub air: String,
+    pub constraints: Vec<String>,
+}
+
+pub fn snapshot_air<A>(air: &A) -> ConstraintSnapshot
+where
+    A: ProvableComputation + StarkAir + BaseAir<Val> + AirLint,
+{
+    let constraints = get_symbolic_constraints(air, 0, air.num_public_values());
+    ConstraintSnapshot { air: air.air_id().to_string(), constraints: canonical_constraints(&constraints) }
+}
+
+pub fn snapshot_fibonacci() -> ConstraintSnapshot {
+    snapshot_air(&FibonacciAir)
+}
+
+pub fn snapshot_coefficients() -> ConstraintSnapshot {
+    snapshot_air(&CoefficientAir)
+}
+
+pub fn snapshot_counter() -> ConstraintSnapshot {
+    snapshot_air(&CounterAir)
+}
+
+pub fn snapshot_parity() -> ConstraintSnapshot {
+    snapshot_air(&ParityAir)
+}
+
+pub fn snapshot_tribonacci() -> ConstraintSnapshot {
+    snapshot_air(&RecurrenceAir::<3>)
+}
+
+pub fn snapshot_lucas() -> ConstraintSnapshot {
+    snapshot_air(&LucasAir)
+}
+
+pub fn snapshot_multi_fibonacci() -> ConstraintSnapshot {
+    snapshot_air(&MultiFibonacciAir { num_sequences: 8 })
+}
+
+pub fn snapshot_big_fibonacci() -> ConstraintSnapshot {
+    snapshot_air(&BigFibonacciAir)
+}
+
+pub fn snapshot_indexed_fibonacci() -> ConstraintSnapshot {
+    snapshot_air(&IndexedFibonacciAir)
+}
+
+// Both bindings share an id; the registered example binds the last real row
+pub fn snapshot_last_row() -> ConstraintSnapshot {
+    snapshot_air(&LastRowDemoAir { binding: LastRowBinding::LastReal })
+}
+
+pub fn snapshot_dataset() -> ConstraintSnapshot {
+    snapshot_air(&DatasetAir)
+}
+
+// Every registered example's snapshot, once per AIR id, in report order
+pub fn current_snapshots() -> Vec<ConstraintSnapshot> {
+    let mut snapshots: Vec<ConstraintSnapshot> = Vec::new();
+    for example in EXAMPLES {
+        let snapshot = (example.snapshot)();
+        if !snapshots.iter().any(|s| s.air == snapshot.air) {
+            snapshots.push(snapshot);
+        }
+    }
+    snapshots
+}
+
+// The constraints file for `snapshots`, in the form parse_snapshots reads
+pub fn format_snapshots(snapshots: &[ConstraintSnapshot]) -> String {
+    let mut text = SNAPSHOTS_HEADER.to_string();
+    for snapshot in snapshots {
+        text.push_str(&format!("\n[{}]\n", snapshot.air));
+        for line in &snapshot.constraints {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+// (air, constraint lines) for each section of a constraints file, skipping
+// # comments and blank lines
+pub fn parse_snapshots(text: &str) -> Vec<(&str, Vec<&str>)> {
+    let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        match line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            Some(air) => sections.push((air, Vec::new())),
+            None => {
+                if let Some((_, lines)) = sections.last_mut() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    sections
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotReport {
+    pub air: String,
+    pub constraints: usize,
+    // Whether the expected snapshots had a section for this AIR
+    pub recorded: bool,
+    // Canonical lines in the current build but not the snapshot, and the reverse
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SnapshotReport {
+    pub fn passed(&self) -> bool {
+        self.recorded && self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed() { "PASS" } else { "FAIL" };
+        write!(f, "{} {} ({} constraints", verdict, self.air, self.constraints)?;
+        if !self.recorded {
+            write!(f, ", none recorded")?;
+        }
+        write!(f, ")")?;
+        for line in &self.added {
+            write!(f, "\n     + {}", line)?;
+        }
+        for line in &self.removed {
+            write!(f, "\n     - {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// Lines of `a` left over after removing one match from `b` for each, so a
+// duplicated constraint counts twice
+fn difference(a: &[String], b: &[&str]) -> Vec<String> {
+    let mut remaining: BTreeMap<&str, usize> = BTreeMap::new();
+    for &line in b {
+        *remaining.entry(line).or_insert(0) += 1;
+    }
+    a.iter()
+        .filter(|line| match remaining.get_mut(line.as_str()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+// Compares `snapshots` with the sections of `expected`, a constraints file;
+// commands::run_constraint_snapshots passes EXPECTED_SNAPSHOTS
+pub fn check_snapshots(snapshots: &[ConstraintSnapshot], expected: &str) -> Vec<SnapshotReport> {
+    let sections = parse_snapshots(expected);
+    snapshots
+        .iter()
+        .map(|snapshot| {
+            let recorded = sections.iter().find(|(air, _)| *air == snapshot.air).map(|(_, lines)| lines);
+            let (added, removed) = match recorded {
+                Some(lines) => {
+                    let owned: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+                    let current: Vec<&str> = snapshot.constraints.iter().map(String::as_str).collect();
+                    (difference(&snapshot.constraints, lines), difference(&owned, &current))
+                }
+                None => (Vec::new(), Vec::new()),
+            };
+            SnapshotReport {
+                air: snapshot.air.clone(),
+                constraints: snapshot.constraints.len(),
+                recorded: recorded.is_some(),
+                added,
+                removed,
+            }
+        })
+        .collect()
+}
+
+// Swaps the operands of every +, * and -, the last as x - y = -y + x
+fn commuted(expr: &SymbolicExpression<Val>) -> SymbolicExpression<Val> {
+    match expr {
+        SymbolicExpression::Add { x, y, .. } => commuted(y) + commuted(x),
+        SymbolicExpression::Mul { x, y, .. } => commuted(y) * commuted(x),
+        SymbolicExpression::Sub { x, y, .. } => -commuted(y) + commuted(x),
+        SymbolicExpression::Neg { x, .. } => -commuted(x),
+        other => other.clone(),
+    }
+}
+
+fn first_variable(expr: &SymbolicExpression<Val>) -> Option<SymbolicExpression<Val>> {
+    match expr {
+        SymbolicExpression::Variable(_) => Some(expr.clone()),
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => first_variable(x).or_else(|| first_variable(y)),
+        SymbolicExpression::Neg { x, .. } => first_variable(x),
+        _ => None,
+    }
+}
+
+// One rewrite of FibonacciAir's constraints and whether its snapshot should
+// still match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalCase {
+    pub name: &'static str,
+    pub should_match: bool,
+    pub matched: bool,
+}
+
+impl CanonicalCase {
+    pub fn passed(&self) -> bool {
+        self.should_match == self.matched
+    }
+}
+
+impl fmt::Display for CanonicalCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed() { "PASS" } else { "FAIL" };
+        let outcome = if self.matched { "matches" } else { "differs" };
+        write!(f, "{} {}: snapshot {}", verdict, self.name, outcome)
+    }
+}
+
+// Meaning-preserving rewrites of FibonacciAir's constraints must keep its
+// snapshot; a changed coefficient must not
+pub fn canonical_cases() -> Vec<CanonicalCase> {
+    let constraints = get_symbolic_constraints(&FibonacciAir, 0, FibonacciAir.num_public_values());
+    let snapshot = canonical_constraints(&constraints);
+
+    let mut reordered = constraints.clone();
+    reordered.reverse();
+    let swapped: Vec<_> = constraints.iter().map(|c| -c.clone()).collect();
+    let reversed_operands: Vec<_> = constraints.iter().map(commuted).collect();
+    let scaled: Vec<_> = constraints.iter().map(|c| c.clone() * Val::TWO).collect();
+    // One more of the first variable in the last constraint
+    let mut changed = constraints.clone();
+    if let Some(last) = changed.last_mut() {
+        if let Some(variable) = first_variable(last) {
+            *last = last.clone() + variable;
+        }
+    }
+
+    [
+        ("constraints asserted in reverse order", true, reordered),
+        ("both sides of every assert_eq swapped", true, swapped),
+        ("operands of every +, - and * swapped", true, reversed_operands),
+        ("every constraint doubled", true, scaled),
+        ("a coefficient changed", false, changed),
+    ]
+    .into_iter()
+    .map(|(name, should_match, rewritten)| CanonicalCase {
+        name,
+        should_match,
+        matched: canonical_constraints(&rewritten) == snapshot,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_recorded_snapshots_match_the_build() {
+        let reports = check_snapshots(&current_snapshots(), EXPECTED_SNAPSHOTS);
+        let failed: Vec<String> = reports.iter().filter(|r| !r.passed()).map(|r| r.to_string()).collect();
+        assert!(failed.is_empty(), "{}", failed.join("\n"));
+    }
+
+    #[test]
+    fn rewrites_that_keep_the_meaning_keep_the_snapshot() {
+        for case in canonical_cases() {
+            assert!(case.passed(), "{}", case);
+        }
+    }
+
+    #[test]
+    fn a_changed_constraint_fails_only_its_air() {
+        let snapshots = current_snapshots();
+        let file = format_snapshots(&snapshots);
+        assert!(check_snapshots(&snapshots, &file).iter().all(SnapshotReport::passed));
+
+        let mut changed = snapshots.clone();
+        let line = changed[0].constraints[0].clone();
+        changed[0].constraints[0] = line.replacen(" = 0", " + 1 = 0", 1);
+        let reports = check_snapshots(&changed, &file);
+        assert_eq!(reports[0].added, [changed[0].constraints[0].clone()]);
+        assert_eq!(reports[0].removed, [line]);
+        assert!(reports[1..].iter().all(SnapshotReport::passed));
+    }
+
+    #[test]
+    fn an_air_with_no_snapshot_fails() {
+        let snapshots = current_snapshots();
+        let without_first = format_snapshots(&snapshots[1..]);
+        let reports = check_snapshots(&snapshots, &without_first);
+        assert!(!reports[0].recorded && !reports[0].passed(), "{}", reports[0]);
+        assert!(reports[1..].iter().all(SnapshotReport::passed));
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_the_fixture_format() {
+        let snapshots = current_snapshots();
+        let file = format_snapshots(&snapshots);
+        let parsed = parse_snapshots(&file);
+        assert_eq!(parsed.len(), snapshots.len());
+        for ((air, lines), snapshot) in parsed.iter().zip(&snapshots) {
+            assert_eq!(*air, snapshot.air);
+            assert_eq!(*lines, snapshot.constraints);
+        }
+    }
+}
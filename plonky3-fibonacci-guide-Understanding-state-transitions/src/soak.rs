@@ -0,0 +1,280 @@
+use core::fmt;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::air_id::{prove_identified, verify_identified};
+use crate::cost::SecurityLevel;
+use crate::trace::SizingError;
+use crate::{FibonacciAir, MyConfig, create_default_config, generate_fibonacci_trace};
+
+// Rows per soak iteration: big enough to exercise the DFT and Merkle buffers
+const SOAK_STEPS: usize = 1 << 14;
+
+// The kernel's page size, which /proc/self/statm counts in
+#[cfg(target_os = "linux")]
+fn page_bytes() -> Option<u64> {
+    // SAFETY: sysconf only reads a configuration value
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    u64::try_from(size).ok().filter(|&size| size > 0)
+}
+
+// Resident set size of this process, read from /proc/self/statm
+#[cfg(target_os = "linux")]
+pub fn resident_bytes() -> Result<u64, SoakError> {
+    let statm = std::fs::read_to_string("/proc/self/statm").map_err(|_| SoakError::Unsupported)?;
+    let resident_pages: u64 =
+        statm.split_whitespace().nth(1).and_then(|pages| pages.parse().ok()).ok_or(SoakError::Unsupported)?;
+    Ok(resident_pages * page_bytes().ok_or(SoakError::Unsupported)?)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn resident_bytes() -> Result<u64, SoakError> {
+    Err(SoakError::Unsupported)
+}
+
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+// The system allocator, counting the bytes live through it. Installed as the
+// #[global_allocator], as the binary and tests/soak.rs do under mem-stats, it
+// is what the soak measures, on any platform.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            COUNTING.store(true, Ordering::Relaxed);
+            LIVE_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            COUNTING.store(true, Ordering::Relaxed);
+            LIVE_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            // Added before subtracting, so the count never dips below zero
+            LIVE_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+            LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+// Bytes live through CountingAllocator, if it is the global allocator
+pub fn allocated_bytes() -> Result<u64, SoakError> {
+    if COUNTING.load(Ordering::Relaxed) {
+        Ok(LIVE_BYTES.load(Ordering::Relaxed))
+    } else {
+        Err(SoakError::Unsupported)
+    }
+}
+
+// Where the soak reads memory from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemorySource {
+    // CountingAllocator's live bytes
+    Allocator,
+    // The resident set size, Linux only
+    Resident,
+}
+
+impl MemorySource {
+    // The allocator counters when CountingAllocator is installed, since they
+    // count exactly what the prover holds; otherwise RSS where the platform
+    // has it
+    pub fn detect() -> Result<Self, SoakError> {
+        if allocated_bytes().is_ok() {
+            Ok(Self::Allocator)
+        } else {
+            resident_bytes().map(|_| Self::Resident)
+        }
+    }
+
+    pub fn read(self) -> Result<u64, SoakError> {
+        match self {
+            Self::Allocator => allocated_bytes(),
+            Self::Resident => resident_bytes(),
+        }
+    }
+}
+
+impl fmt::Display for MemorySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allocator => write!(f, "allocated"),
+            Self::Resident => write!(f, "rss"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoakConfig {
+    pub duration: Duration,
+    // Samples taken before the baseline, while caches and pools fill up
+    pub warmup_samples: usize,
+    // Largest tolerated RSS growth after warmup, in bytes per minute
+    pub max_slope: f64,
+}
+
+// Memory in use after each sampling interval, as `source` counts it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoakSample {
+    pub minute: usize,
+    pub iterations: usize,
+    pub source: MemorySource,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SoakError {
+    // No RSS on this platform and no CountingAllocator installed
+    Unsupported,
+    Trace(SizingError),
+    Verification(String),
+    // RSS grew faster than max_slope after warmup
+    Leak { slope: f64, max_slope: f64 },
+}
+
+impl fmt::Display for SoakError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => write!(
+                f,
+                "no memory source: resident memory is Linux only, and CountingAllocator is not installed \
+                 (build with --features mem-stats)"
+            ),
+            Self::Trace(e) => write!(f, "soak trace: {}", e),
+            Self::Verification(msg) => write!(f, "soak proof failed to verify: {}", msg),
+            Self::Leak { slope, max_slope } => write!(
+                f,
+                "memory grows {:.0} bytes/minute after warmup (limit {:.0})",
+                slope, max_slope
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SoakError {}
+
+// Least-squares slope of y over x
+fn slope(points: &[(f64, f64)]) -> f64 {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (x, y) in points {
+        let dx = x - mean_x;
+        num += dx * (y - mean_y);
+        den += dx * dx;
+    }
+    if den == 0.0 { 0.0 } else { num / den }
+}
+
+// One prove/verify round over `steps` rows
+fn soak_round(stark_config: &MyConfig, steps: usize) -> Result<(), SoakError> {
+    let trace = generate_fibonacci_trace(steps).map_err(SoakError::Trace)?;
+    let proof = prove_identified(stark_config, &FibonacciAir, trace.matrix, &trace.public_values);
+    verify_identified(stark_config, &FibonacciAir, &proof, &trace.public_values)
+        .map_err(|e| SoakError::Verification(e.to_string()))?;
+    Ok(())
+}
+
+// Loops prove/verify on a mid-sized Fibonacci trace for `config.duration`,
+// sampling memory once a minute and handing each sample to `on_sample` as it
+// is taken. Fails if memory keeps growing faster than max_slope once warmup is
+// over.
+pub fn soak(config: &SoakConfig, mut on_sample: impl FnMut(&SoakSample)) -> Result<Vec<SoakSample>, SoakError> {
+    let source = MemorySource::detect()?;
+    let stark_config = create_default_config(SecurityLevel::Test);
+    let start = Instant::now();
+    let mut samples = Vec::new();
+    let mut iterations = 0;
+    let mut next_sample = Duration::from_secs(60);
+
+    while start.elapsed() < config.duration {
+        soak_round(&stark_config, SOAK_STEPS)?;
+        iterations += 1;
+
+        if start.elapsed() >= next_sample {
+            let sample = SoakSample { minute: samples.len() + 1, iterations, source, bytes: source.read()? };
+            on_sample(&sample);
+            samples.push(sample);
+            next_sample += Duration::from_secs(60);
+        }
+    }
+
+    let measured = samples.get(config.warmup_samples..).unwrap_or(&[]);
+    if measured.len() >= 2 {
+        let points: Vec<_> = measured.iter().map(|s| (s.minute as f64, s.bytes as f64)).collect();
+        let slope = slope(&points);
+        if slope > config.max_slope {
+            return Err(SoakError::Leak { slope, max_slope: config.max_slope });
+        }
+    }
+    Ok(samples)
+}
+
+// The short variant: `iterations` rounds over `steps` rows, memory read after
+// each. Fails if memory grows faster than `max_slope` bytes per iteration over
+// the rounds after `warmup`. Returns the memory in use after every round.
+pub fn soak_iterations(steps: usize, iterations: usize, warmup: usize, max_slope: f64) -> Result<Vec<u64>, SoakError> {
+    let source = MemorySource::detect()?;
+    let stark_config = create_default_config(SecurityLevel::Test);
+    let mut in_use = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        soak_round(&stark_config, steps)?;
+        in_use.push(source.read()?);
+    }
+
+    let points: Vec<_> = in_use.iter().enumerate().skip(warmup).map(|(i, &r)| (i as f64, r as f64)).collect();
+    if points.len() >= 2 {
+        let slope = slope(&points);
+        if slope > max_slope {
+            return Err(SoakError::Leak { slope, max_slope });
+        }
+    }
+    Ok(in_use)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slope_fits_a_line() {
+        assert_eq!(slope(&[(1.0, 10.0), (2.0, 12.0), (3.0, 14.0)]), 2.0);
+        assert_eq!(slope(&[(1.0, 5.0), (2.0, 5.0)]), 0.0);
+        // One x value has no slope to fit
+        assert_eq!(slope(&[(1.0, 5.0), (1.0, 9.0)]), 0.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rss_is_counted_in_the_kernels_pages() {
+        let page = page_bytes().unwrap();
+        assert!(page.is_power_of_two() && page >= 4096, "{}", page);
+        assert_eq!(resident_bytes().unwrap() % page, 0);
+    }
+
+    #[test]
+    fn a_failed_round_is_reported_not_counted() {
+        let config = create_default_config(SecurityLevel::Test);
+        assert!(matches!(soak_round(&config, usize::MAX), Err(SoakError::Trace(_))));
+    }
+}
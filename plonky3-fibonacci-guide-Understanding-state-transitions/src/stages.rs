@@ -0,0 +1,232 @@
+// FibonacciAir built up in four stages, each adding the constraints that stop
+// a trace the stage before it wrongly accepts. Every stage reads FibonacciAir's
+// columns [a, b, is_real, count] and public values [F(0), F(1), F(n), n], so
+// generate_fibonacci_trace serves them all; the earlier stages ignore what
+// they do not constrain yet. `--stage N` (built with --features tutorial)
+// checks stage N against every case below and proves it.
+//
+// 1. The recurrence on every row. The last row's window wraps around to the
+//    first, so not even an honest trace satisfies it.
+// 2. The recurrence on transitions only. An honest trace that fills the whole
+//    height passes, but so does any other sequence and any claim at all.
+// 3. + the first row bound to (F(0), F(1)) and the last row to F(n). The
+//    trace still has to run the full height: padding rows that copy the
+//    result break the recurrence, and nothing checks the claimed n.
+// 4. + is_real and count: FibonacciAir itself.
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{prove, verify};
+
+use crate::cost::SecurityLevel;
+use crate::trace::SizingError;
+use crate::validate::{Attack, AttackReport, ConstraintChecker, run_attacks};
+use crate::{
+    FibonacciAir, FibonacciRow, FibonacciTraceRow, NUM_FIBONACCI_COLS, StarkAir, Val, create_default_config,
+    generate_fibonacci_trace,
+};
+
+pub const NUM_STAGES: usize = 4;
+
+pub const STAGE_NAMES: [&str; NUM_STAGES] = [
+    "stage 1: recurrence on every row",
+    "stage 2: recurrence on transitions",
+    "stage 3: public boundary and final value",
+    "stage 4: is_real selector and length claim",
+];
+
+// Steps that fill a 256-row trace: the one padding row holds
+// (F(255), F(256)), which follows the recurrence from the row before it
+const FULL_STEPS: usize = 255;
+
+// Steps in a trace padded the stage-4 way, with rows that copy the result
+const SHORT_STEPS: usize = 100;
+
+// next = (b, a + b)
+fn recurrence<AB: AirBuilder>(builder: &mut AB, local: &FibonacciRow<AB::Var>, next: &FibonacciRow<AB::Var>) {
+    builder.assert_eq(next.a.clone(), local.b.clone());
+    builder.assert_eq(next.b.clone(), local.a.clone() + local.b.clone());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage1Air;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage2Air;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage3Air;
+
+impl<F> BaseAir<F> for Stage1Air {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl<F> BaseAir<F> for Stage2Air {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl<F> BaseAir<F> for Stage3Air {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for Stage1Air {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &FibonacciTraceRow<AB::Var> = (*local).borrow();
+        let next: &FibonacciTraceRow<AB::Var> = (*next).borrow();
+
+        recurrence(builder, &local.fib, &next.fib);
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for Stage2Air {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &FibonacciTraceRow<AB::Var> = (*local).borrow();
+        let next: &FibonacciTraceRow<AB::Var> = (*next).borrow();
+
+        // The last row has no successor, so it is exempt
+        recurrence(&mut builder.when_transition(), &local.fib, &next.fib);
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for Stage3Air {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let next = main.row_slice(1).expect("Matrix only has 1 row?");
+        let local: &FibonacciTraceRow<AB::Var> = (*local).borrow();
+        let next: &FibonacciTraceRow<AB::Var> = (*next).borrow();
+        let pis = builder.public_values();
+        let (f0, f1, result) = (pis[0], pis[1], pis[2]);
+
+        recurrence(&mut builder.when_transition(), &local.fib, &next.fib);
+
+        // The sequence starts from the public pair and ends on the claim
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local.fib.a.clone(), f0);
+        when_first_row.assert_eq(local.fib.b.clone(), f1);
+        builder.when_last_row().assert_eq(local.fib.a.clone(), result);
+    }
+}
+
+// A trace and claim, and which stages accept it
+pub struct StageCase {
+    pub name: &'static str,
+    pub trace: RowMajorMatrix<Val>,
+    pub public_values: Vec<Val>,
+    pub accepted_by: [bool; NUM_STAGES],
+}
+
+pub fn stage_cases() -> Result<Vec<StageCase>, SizingError> {
+    let full = generate_fibonacci_trace::<Val>(FULL_STEPS)?;
+    let short = generate_fibonacci_trace::<Val>(SHORT_STEPS)?;
+
+    // The recurrence from (2, 3), claimed as the sequence from (0, 1)
+    let mut wrong_seed = full.matrix.clone();
+    let (mut a, mut b) = (Val::TWO, Val::from_u64(3));
+    let mut last = a;
+    for row in wrong_seed.values.chunks_mut(NUM_FIBONACCI_COLS) {
+        (row[0], row[1], last) = (a, b, a);
+        (a, b) = (b, a + b);
+    }
+    let mut wrong_seed_claim = full.public_values.clone();
+    wrong_seed_claim[2] = last;
+
+    let mut wrong_result = full.public_values.clone();
+    wrong_result[2] += Val::ONE;
+    let mut wrong_length = full.public_values.clone();
+    wrong_length[3] -= Val::ONE;
+
+    Ok(vec![
+        StageCase {
+            name: "honest, filling the trace",
+            trace: full.matrix.clone(),
+            public_values: full.public_values.clone(),
+            accepted_by: [false, true, true, true],
+        },
+        StageCase {
+            name: "sequence from the wrong seed",
+            trace: wrong_seed,
+            public_values: wrong_seed_claim,
+            accepted_by: [false, true, false, false],
+        },
+        StageCase {
+            name: "wrong final value",
+            trace: full.matrix.clone(),
+            public_values: wrong_result,
+            accepted_by: [false, true, false, false],
+        },
+        StageCase {
+            name: "wrong length claim",
+            trace: full.matrix,
+            public_values: wrong_length,
+            accepted_by: [false, true, true, false],
+        },
+        StageCase {
+            name: "honest, padded by copying the result",
+            trace: short.matrix,
+            public_values: short.public_values,
+            accepted_by: [false, false, false, true],
+        },
+    ])
+}
+
+fn check<A>(stage: usize, air: &A, cases: &[StageCase]) -> AttackReport
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let attacks = cases
+        .iter()
+        .map(|case| Attack {
+            name: case.name,
+            trace: case.trace.clone(),
+            public_values: case.public_values.clone(),
+            valid: case.accepted_by[stage - 1],
+        })
+        .collect();
+    run_attacks(STAGE_NAMES[stage - 1], air, attacks)
+}
+
+// Stage `stage` (1-based) against every case, passing if each is accepted
+// or rejected as its accepted_by says; None for a stage that does not exist
+pub fn check_stage(stage: usize, cases: &[StageCase]) -> Option<AttackReport> {
+    match stage {
+        1 => Some(check(stage, &Stage1Air, cases)),
+        2 => Some(check(stage, &Stage2Air, cases)),
+        3 => Some(check(stage, &Stage3Air, cases)),
+        4 => Some(check(stage, &FibonacciAir, cases)),
+        _ => None,
+    }
+}
+
+fn prove_full_trace<A: StarkAir>(air: &A) -> Result<(), String> {
+    let trace = generate_fibonacci_trace::<Val>(FULL_STEPS).map_err(|e| e.to_string())?;
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, air, trace.matrix, &trace.public_values);
+    verify(&config, air, &proof, &trace.public_values).map_err(|e| format!("{:?}", e))
+}
+
+// Proves and verifies the honest full-height trace under stage `stage`.
+// None for stage 1, which no honest trace satisfies, and for unknown stages.
+pub fn prove_stage(stage: usize) -> Option<Result<(), String>> {
+    match stage {
+        2 => Some(prove_full_trace(&Stage2Air)),
+        3 => Some(prove_full_trace(&Stage3Air)),
+        4 => Some(prove_full_trace(&FibonacciAir)),
+        _ => None,
+    }
+}
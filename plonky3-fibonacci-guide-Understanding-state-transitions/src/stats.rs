@@ -0,0 +1,321 @@
+// The `demo --json` stats file and the `compare` command over two of them, so
+// comparing the examples' performance across branches is one command. Both
+// files must come from the same config: a different blowup or query count
+// changes the work, and the deltas would say nothing about the code.
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cost::{CostParams, SecurityLevel};
+use crate::proof_file::ConfigFingerprint;
+use crate::report::ExampleReport;
+use crate::{ConfigDescriptor, DEFAULT_CONFIG_SEED};
+
+// One example's ProofStats, with times in seconds and the throughput they give
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExampleStats {
+    pub name: String,
+    pub rows: usize,
+    pub threads: usize,
+    pub proof_bytes: usize,
+    pub prove_seconds: f64,
+    pub verify_seconds: f64,
+    pub prove_rows_per_second: f64,
+    pub verify_rows_per_second: f64,
+}
+
+// Rows over seconds, 0 for a time too short to measure, as JSON has no infinity
+fn rows_per_second(rows: usize, seconds: f64) -> f64 {
+    if seconds > 0.0 { rows as f64 / seconds } else { 0.0 }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsFile {
+    // The config every example was proved under, and its fingerprint, which
+    // also covers the field
+    pub config: ConfigDescriptor,
+    pub fingerprint: String,
+    pub examples: Vec<ExampleStats>,
+}
+
+impl StatsFile {
+    // The examples that proved, under the default config `demo` uses
+    pub fn from_reports(reports: &[ExampleReport]) -> Self {
+        let config = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::at(SecurityLevel::Test));
+        let examples = reports
+            .iter()
+            .filter_map(|report| {
+                let stats = report.stats.as_ref()?;
+                let (prove_seconds, verify_seconds) = (stats.prove_time.as_secs_f64(), stats.verify_time.as_secs_f64());
+                Some(ExampleStats {
+                    name: report.name.to_string(),
+                    rows: stats.rows,
+                    threads: stats.threads,
+                    proof_bytes: stats.proof_bytes,
+                    prove_seconds,
+                    verify_seconds,
+                    prove_rows_per_second: rows_per_second(stats.rows, prove_seconds),
+                    verify_rows_per_second: rows_per_second(stats.rows, verify_seconds),
+                })
+            })
+            .collect();
+        Self { config, fingerprint: ConfigFingerprint::of(&config).to_string(), examples }
+    }
+}
+
+#[derive(Debug)]
+pub enum StatsError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    // The two files were measured under different configs
+    ConfigMismatch { old: String, new: String },
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Json(e) => write!(f, "not a stats file: {}", e),
+            Self::ConfigMismatch { old, new } => {
+                write!(f, "the stats were measured under different configs ({} and {})", old, new)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+impl From<io::Error> for StatsError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for StatsError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+pub fn save_stats(path: &Path, stats: &StatsFile) -> Result<(), StatsError> {
+    let mut json = serde_json::to_string_pretty(stats)?;
+    json.push('\n');
+    Ok(fs::write(path, json)?)
+}
+
+pub fn load_stats(path: &Path) -> Result<StatsFile, StatsError> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+// Percentage change past which a delta is flagged
+pub const DEFAULT_THRESHOLD_PERCENT: f64 = 10.0;
+
+// The compared metrics, and whether a larger value is better
+const METRICS: [(&str, bool); 5] = [
+    ("prove time", false),
+    ("verify time", false),
+    ("prove rows/s", true),
+    ("verify rows/s", true),
+    ("proof bytes", false),
+];
+
+fn metric_values(stats: &ExampleStats) -> [f64; 5] {
+    [
+        stats.prove_seconds,
+        stats.verify_seconds,
+        stats.prove_rows_per_second,
+        stats.verify_rows_per_second,
+        stats.proof_bytes as f64,
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDelta {
+    pub example: String,
+    pub metric: &'static str,
+    pub old: f64,
+    pub new: f64,
+    higher_is_better: bool,
+}
+
+impl MetricDelta {
+    // Change from old to new in percent, None when old is 0
+    pub fn percent(&self) -> Option<f64> {
+        (self.old != 0.0).then(|| 100.0 * (self.new - self.old) / self.old)
+    }
+
+    // The change in percent with improvements positive, whichever way the
+    // metric runs
+    fn gain(&self) -> Option<f64> {
+        self.percent().map(|percent| if self.higher_is_better { percent } else { -percent })
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.gain().is_some_and(|gain| gain < -threshold)
+    }
+
+    pub fn improved(&self, threshold: f64) -> bool {
+        self.gain().is_some_and(|gain| gain > threshold)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub deltas: Vec<MetricDelta>,
+    // Examples in only one of the files, which have nothing to compare with
+    pub unmatched: Vec<String>,
+}
+
+impl Comparison {
+    pub fn regressions(&self, threshold: f64) -> usize {
+        self.deltas.iter().filter(|delta| delta.regressed(threshold)).count()
+    }
+
+    // One line per metric, marked when it moved by more than `threshold` percent
+    pub fn render(&self, threshold: f64) -> String {
+        let mut out = String::new();
+        for delta in &self.deltas {
+            let change = match delta.percent() {
+                Some(percent) => format!("{:+.1}%", percent),
+                None => "n/a".to_string(),
+            };
+            let flag = if delta.regressed(threshold) {
+                "  REGRESSED"
+            } else if delta.improved(threshold) {
+                "  improved"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "   {:<40} {:<14} {:>14.4} -> {:>14.4} {:>9}{}\n",
+                delta.example, delta.metric, delta.old, delta.new, change, flag
+            ));
+        }
+        for name in &self.unmatched {
+            out.push_str(&format!("   {:<40} in only one file\n", name));
+        }
+        out
+    }
+}
+
+// Every metric of every example in both files. Refuses files whose configs
+// differ, by descriptor or by fingerprint.
+pub fn compare_stats(old: &StatsFile, new: &StatsFile) -> Result<Comparison, StatsError> {
+    if old.config != new.config || old.fingerprint != new.fingerprint {
+        return Err(StatsError::ConfigMismatch {
+            old: format!("{}: {}", old.fingerprint, old.config),
+            new: format!("{}: {}", new.fingerprint, new.config),
+        });
+    }
+    let mut comparison = Comparison { deltas: Vec::new(), unmatched: Vec::new() };
+    for old_example in &old.examples {
+        let Some(new_example) = new.examples.iter().find(|example| example.name == old_example.name) else {
+            comparison.unmatched.push(old_example.name.clone());
+            continue;
+        };
+        let values = metric_values(old_example).into_iter().zip(metric_values(new_example));
+        for ((metric, higher_is_better), (old, new)) in METRICS.into_iter().zip(values) {
+            comparison.deltas.push(MetricDelta {
+                example: old_example.name.clone(),
+                metric,
+                old,
+                new,
+                higher_is_better,
+            });
+        }
+    }
+    let only_new = new.examples.iter().filter(|example| old.examples.iter().all(|old| old.name != example.name));
+    comparison.unmatched.extend(only_new.map(|example| example.name.clone()));
+    Ok(comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(name: &str, prove_seconds: f64, proof_bytes: usize) -> ExampleStats {
+        ExampleStats {
+            name: name.to_string(),
+            rows: 256,
+            threads: 4,
+            proof_bytes,
+            prove_seconds,
+            verify_seconds: 0.01,
+            prove_rows_per_second: rows_per_second(256, prove_seconds),
+            verify_rows_per_second: rows_per_second(256, 0.01),
+        }
+    }
+
+    fn stats_file(examples: Vec<ExampleStats>) -> StatsFile {
+        let config = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::at(SecurityLevel::Test));
+        StatsFile { config, fingerprint: ConfigFingerprint::of(&config).to_string(), examples }
+    }
+
+    #[test]
+    fn deltas_are_computed_per_metric_and_flagged_past_the_threshold() {
+        let dir = std::env::temp_dir().join(format!("fibonacci-stats-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (old_path, new_path) = (dir.join("old.json"), dir.join("new.json"));
+        let old = stats_file(vec![example("fib", 0.5, 1000), example("gone", 1.0, 10)]);
+        let new = stats_file(vec![example("fib", 0.6, 950), example("added", 1.0, 10)]);
+        save_stats(&old_path, &old).unwrap();
+        save_stats(&new_path, &new).unwrap();
+
+        let comparison = compare_stats(&load_stats(&old_path).unwrap(), &load_stats(&new_path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let changes: Vec<(&str, Option<f64>)> = comparison
+            .deltas
+            .iter()
+            .map(|delta| (delta.metric, delta.percent().map(|percent| (percent * 100.0).round() / 100.0)))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("prove time", Some(20.0)),
+                ("verify time", Some(0.0)),
+                ("prove rows/s", Some(-16.67)),
+                ("verify rows/s", Some(0.0)),
+                ("proof bytes", Some(-5.0)),
+            ]
+        );
+        assert_eq!(comparison.unmatched, ["gone", "added"]);
+
+        // Slower proving is a regression on both of its metrics, and smaller
+        // proofs an improvement only below a 5% threshold
+        assert_eq!(comparison.regressions(DEFAULT_THRESHOLD_PERCENT), 2);
+        assert!(!comparison.deltas[4].improved(DEFAULT_THRESHOLD_PERCENT));
+        assert!(comparison.deltas[4].improved(4.0));
+        assert_eq!(comparison.regressions(25.0), 0);
+        let rendered = comparison.render(DEFAULT_THRESHOLD_PERCENT);
+        assert_eq!(rendered.matches("REGRESSED").count(), 2);
+        assert!(rendered.contains("+20.0%"));
+    }
+
+    #[test]
+    fn files_from_different_configs_are_not_compared() {
+        let old = stats_file(vec![example("fib", 0.5, 1000)]);
+        let mut new = old.clone();
+        new.config.num_queries += 1;
+        new.fingerprint = ConfigFingerprint::of(&new.config).to_string();
+        assert!(matches!(compare_stats(&old, &new), Err(StatsError::ConfigMismatch { .. })));
+
+        // Same descriptor, different field
+        let mut new = old.clone();
+        new.fingerprint = "0000000000000000".to_string();
+        assert!(matches!(compare_stats(&old, &new), Err(StatsError::ConfigMismatch { .. })));
+        assert!(compare_stats(&old, &old.clone()).is_ok());
+    }
+
+    #[test]
+    fn a_zero_baseline_has_no_percentage() {
+        let old = stats_file(vec![example("fib", 0.0, 1000)]);
+        let new = stats_file(vec![example("fib", 0.5, 1000)]);
+        let comparison = compare_stats(&old, &new).unwrap();
+        assert_eq!(comparison.deltas[0].percent(), None);
+        assert_eq!(comparison.regressions(DEFAULT_THRESHOLD_PERCENT), 0);
+        assert!(comparison.render(DEFAULT_THRESHOLD_PERCENT).contains("n/a"));
+    }
+}
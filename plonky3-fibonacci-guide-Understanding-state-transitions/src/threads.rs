@@ -0,0 +1,62 @@
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
+
+// Runs `f` inside a dedicated rayon pool of `threads` workers, so the parallel
+// DFT and Merkle hashing in the prover stay within that budget. 0 means one
+// worker per core. Returns f's result and the pool's effective thread count.
+pub fn with_threads<R, F>(threads: usize, f: F) -> Result<(R, usize), ThreadPoolBuildError>
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+    let effective = pool.current_num_threads();
+    Ok((pool.install(f), effective))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_uni_stark::{prove, verify};
+
+    use super::*;
+    use crate::cost::{CostParams, SecurityLevel};
+    use crate::proof_file::encode_proof;
+    use crate::report::measure;
+    use crate::{
+        ConfigDescriptor, DEFAULT_CONFIG_SEED, FibonacciAir, Val, create_default_config, generate_fibonacci_trace,
+    };
+
+    #[test]
+    fn work_runs_inside_the_requested_pool() {
+        assert_eq!(with_threads(2, rayon::current_num_threads).unwrap(), (2, 2));
+        let (inside, effective) = with_threads(0, rayon::current_num_threads).unwrap();
+        assert_eq!(inside, effective);
+        assert!(effective >= 1);
+    }
+
+    #[test]
+    fn one_thread_and_every_core_make_the_same_proof() {
+        let trace = generate_fibonacci_trace::<Val>(100).unwrap();
+        let config = create_default_config(SecurityLevel::Test);
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let prove_on = |threads| {
+            with_threads(threads, || prove(&config, &FibonacciAir, trace.matrix.clone(), &trace.public_values))
+                .unwrap()
+        };
+        let (single, one) = prove_on(1);
+        let (pooled, _) = prove_on(0);
+        assert_eq!(one, 1);
+        for proof in [&single, &pooled] {
+            assert!(verify(&config, &FibonacciAir, proof, &trace.public_values).is_ok());
+        }
+        assert_eq!(encode_proof(&descriptor, &single).unwrap(), encode_proof(&descriptor, &pooled).unwrap());
+
+        let measured = |threads| {
+            let run = || measure("FibonacciAir", &FibonacciAir, trace.matrix.clone(), &trace.public_values);
+            with_threads(threads, run).unwrap().0.stats.unwrap()
+        };
+        let (single, pooled) = (measured(1), measured(0));
+        assert_eq!(single.threads, 1);
+        assert_eq!(pooled.threads, with_threads(0, rayon::current_num_threads).unwrap().1);
+        assert_eq!(single.proof_bytes, pooled.proof_bytes);
+    }
+}
@@ -0,0 +1,325 @@
+use core::fmt;
+use core::mem::{MaybeUninit, align_of, size_of};
+use core::ops::Range;
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+
+use crate::Val;
+
+// Below this height spawning rayon tasks costs more than it saves
+const PARALLEL_MIN_ROWS: usize = 1 << 12;
+
+// Rows handed to each rayon task
+const CHUNK_ROWS: usize = 1 << 10;
+
+// A generated trace plus the facts its generator already knows about it, so callers
+// never have to re-derive the row count or the claimed public values by convention
+#[derive(Debug, Clone)]
+pub struct GeneratedTrace<M, F = Val> {
+    pub matrix: RowMajorMatrix<F>,
+    // Rows carrying real computation; the rest is padding up to a power of two
+    pub real_rows: usize,
+    // Public values the trace was generated against, passed to prove by default
+    pub public_values: Vec<F>,
+    pub meta: M,
+}
+
+// Fibonacci metadata: F(num_steps), or the recurrence's value after num_steps
+// from another start, the a column of the first padding row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FibonacciMeta<F = Val> {
+    pub final_value: F,
+}
+
+// Names an AIR gives its leading columns, in column order. Columns past the
+// end of the list are reachable by index only.
+pub trait ColumnNames {
+    fn column_names(&self) -> &[&'static str];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceViewError {
+    NoSuchColumn(String),
+    RowOutOfRange { row: usize, height: usize },
+}
+
+impl fmt::Display for TraceViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchColumn(name) => write!(f, "no column named {:?}", name),
+            Self::RowOutOfRange { row, height } => write!(f, "row {} is past the {}-row trace", row, height),
+        }
+    }
+}
+
+impl std::error::Error for TraceViewError {}
+
+// Bounds-checked reads from a trace: an out-of-range row or column gives None
+// or an error instead of an index panic, and named columns follow the AIR's
+// layout when it changes
+#[derive(Debug, Clone, Copy)]
+pub struct TraceView<'a, F = Val> {
+    matrix: &'a RowMajorMatrix<F>,
+    names: &'a [&'static str],
+}
+
+impl<'a, F: Copy + Send + Sync> TraceView<'a, F> {
+    // Index access only
+    pub fn new(matrix: &'a RowMajorMatrix<F>) -> Self {
+        Self { matrix, names: &[] }
+    }
+
+    pub fn named(matrix: &'a RowMajorMatrix<F>, air: &'a impl ColumnNames) -> Self {
+        Self { matrix, names: air.column_names() }
+    }
+
+    pub fn height(&self) -> usize {
+        self.matrix.height()
+    }
+
+    pub fn width(&self) -> usize {
+        self.matrix.width()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<F> {
+        if col >= self.width() {
+            return None;
+        }
+        let index = row.checked_mul(self.width())?.checked_add(col)?;
+        self.matrix.values.get(index).copied()
+    }
+
+    // Every row's value in `col`, top to bottom
+    pub fn column(&self, col: usize) -> Option<impl Iterator<Item = F> + 'a> {
+        let width = self.width();
+        let values: &'a [F] = &self.matrix.values;
+        (col < width).then(|| values.iter().skip(col).step_by(width).copied())
+    }
+
+    pub fn column_index(&self, name: &str) -> Result<usize, TraceViewError> {
+        self.names
+            .iter()
+            .position(|&column| column == name)
+            .filter(|&col| col < self.width())
+            .ok_or_else(|| TraceViewError::NoSuchColumn(name.to_string()))
+    }
+
+    pub fn get_named(&self, row: usize, name: &str) -> Result<F, TraceViewError> {
+        let col = self.column_index(name)?;
+        self.get(row, col).ok_or(TraceViewError::RowOutOfRange { row, height: self.height() })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizingError {
+    // A trace needs at least one real row
+    NoRows,
+    // A trace needs at least one column
+    NoColumns,
+    // No power of two >= rows fits in usize
+    HeightOverflow { rows: usize },
+    // height * width field elements do not fit in usize
+    LengthOverflow { height: usize, width: usize },
+}
+
+impl fmt::Display for SizingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoRows => write!(f, "a trace needs at least one real row"),
+            Self::NoColumns => write!(f, "a trace needs at least one column"),
+            Self::HeightOverflow { rows } => {
+                write!(f, "{} rows cannot be padded to a power of two on this target", rows)
+            }
+            Self::LengthOverflow { height, width } => {
+                write!(f, "a {} x {} trace does not fit in memory on this target", height, width)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SizingError {}
+
+// Height the generators pad to when the caller names none. Far above what FRI
+// needs (cost::MIN_TRACE_HEIGHT); --min-height and CostParams.min_height go
+// as low as that.
+pub const DEFAULT_MIN_HEIGHT: usize = 256;
+
+// How far a trace is padded: to at least 2^min_log_height rows. The default
+// pads to DEFAULT_MIN_HEIGHT; CostParams::with_trace_options checks a smaller
+// one against what FRI can fold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceOptions {
+    pub min_log_height: usize,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self { min_log_height: DEFAULT_MIN_HEIGHT.trailing_zeros() as usize }
+    }
+}
+
+impl TraceOptions {
+    // 2^min_log_height, or None when that does not fit a usize
+    pub fn min_height(&self) -> Option<usize> {
+        1usize.checked_shl(u32::try_from(self.min_log_height).ok()?)
+    }
+}
+
+// Padded height for `rows` real rows: the next power of two, at least min_height.
+// Checked, because next_power_of_two wraps to 0 in release builds past usize::MAX / 2.
+pub fn trace_height(rows: usize, min_height: usize) -> Result<usize, SizingError> {
+    let height = rows.checked_next_power_of_two().ok_or(SizingError::HeightOverflow { rows })?;
+    Ok(height.max(min_height))
+}
+
+// Number of field elements in a height x width trace
+pub fn trace_len(height: usize, width: usize) -> Result<usize, SizingError> {
+    height.checked_mul(width).ok_or(SizingError::LengthOverflow { height, width })
+}
+
+// Columns in a row struct R, which must be laid out as consecutive F values.
+// The asserts depend only on the types, so they hold for every call or none.
+fn row_width<F, R>() -> usize {
+    assert_eq!(size_of::<R>() % size_of::<F>(), 0, "rows must consist of field elements");
+    assert_eq!(align_of::<R>(), align_of::<F>(), "rows must consist of field elements");
+    size_of::<R>() / size_of::<F>()
+}
+
+// Builds a `height`-row trace, padding included. `rows(range)` must yield the
+// rows of `range` in order (a generator that stops early is a bug and
+// panics); chunks of rows are generated on rayon workers and written once
+// each into an uninitialized buffer, skipping the zero fill.
+pub fn generate_rows<F, R, I, G>(height: usize, rows: G) -> Result<RowMajorMatrix<F>, SizingError>
+where
+    F: Field,
+    R: Send,
+    I: Iterator<Item = R>,
+    G: Fn(Range<usize>) -> I + Sync,
+{
+    if height < PARALLEL_MIN_ROWS {
+        return generate_rows_serial(height, rows);
+    }
+
+    let width = row_width::<F, R>();
+    let len = trace_len(height, width)?;
+    let mut values: Vec<F> = Vec::with_capacity(len);
+    let spare = &mut values.spare_capacity_mut()[..len];
+    let (prefix, slots, suffix) = unsafe { spare.align_to_mut::<MaybeUninit<R>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(slots.len(), height);
+
+    slots.par_chunks_mut(CHUNK_ROWS).enumerate().for_each(|(chunk, slots)| {
+        let start = chunk * CHUNK_ROWS;
+        let mut written = 0;
+        for (slot, row) in slots.iter_mut().zip(rows(start..start + slots.len())) {
+            slot.write(row);
+            written += 1;
+        }
+        assert_eq!(written, slots.len(), "row generator ended early at row {}", start + written);
+    });
+
+    // SAFETY: each chunk checked that all of its rows were written, and the
+    // chunks cover all `height` rows
+    unsafe { values.set_len(len) };
+    Ok(RowMajorMatrix::new(values, width))
+}
+
+// Safe single-threaded equivalent of generate_rows: zero fill, then overwrite
+pub fn generate_rows_serial<F, R, I, G>(height: usize, rows: G) -> Result<RowMajorMatrix<F>, SizingError>
+where
+    F: Field,
+    I: Iterator<Item = R>,
+    G: Fn(Range<usize>) -> I,
+{
+    let width = row_width::<F, R>();
+    let mut values = F::zero_vec(trace_len(height, width)?);
+    let (prefix, slots, suffix) = unsafe { values.align_to_mut::<R>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(slots.len(), height);
+
+    let mut written = 0;
+    for (slot, row) in slots.iter_mut().zip(rows(0..height)) {
+        *slot = row;
+        written += 1;
+    }
+    assert_eq!(written, height, "row generator ended early at row {}", written);
+    Ok(RowMajorMatrix::new(values, width))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::{FibonacciAir, generate_fibonacci_trace};
+
+    // Row i is [i + 1; 4], so no generated element is zero, the serial path's
+    // fill value and the value a skipped slot is most likely to hold
+    fn sentinel_rows(range: Range<usize>) -> impl Iterator<Item = [Val; 4]> {
+        range.map(|i| [Val::from_usize(i + 1); 4])
+    }
+
+    #[test]
+    fn trace_height_pads_to_a_power_of_two_or_refuses() {
+        assert_eq!(trace_height(0, 256), Ok(256));
+        assert_eq!(trace_height(1024, 256), Ok(1024));
+        assert_eq!(trace_height(1025, 256), Ok(2048));
+        // The largest power of two is its own height; one row more has none
+        assert_eq!(trace_height(usize::MAX / 2 + 1, 256), Ok(usize::MAX / 2 + 1));
+        let rows = usize::MAX / 2 + 2;
+        assert_eq!(trace_height(rows, 256), Err(SizingError::HeightOverflow { rows }));
+        assert_eq!(trace_height(usize::MAX, 256), Err(SizingError::HeightOverflow { rows: usize::MAX }));
+    }
+
+    #[test]
+    fn trace_len_refuses_a_product_past_usize() {
+        assert_eq!(trace_len(0, 4), Ok(0));
+        assert_eq!(trace_len(usize::MAX / 4, 4), Ok(usize::MAX - 3));
+        let height = usize::MAX / 4 + 1;
+        assert_eq!(trace_len(height, 4), Err(SizingError::LengthOverflow { height, width: 4 }));
+        assert_eq!(trace_len(usize::MAX, 2), Err(SizingError::LengthOverflow { height: usize::MAX, width: 2 }));
+    }
+
+    #[test]
+    fn every_element_is_written() {
+        for height in [16, PARALLEL_MIN_ROWS, PARALLEL_MIN_ROWS + CHUNK_ROWS / 2] {
+            let parallel = generate_rows(height, sentinel_rows).unwrap();
+            let serial = generate_rows_serial(height, sentinel_rows).unwrap();
+            assert_eq!(parallel.values.len(), height * 4);
+            assert!(parallel.values.iter().all(|&v| v != Val::ZERO), "height {}", height);
+            assert_eq!(parallel.values, serial.values, "height {}", height);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "row generator ended early")]
+    fn a_short_generator_panics() {
+        let _ = generate_rows(PARALLEL_MIN_ROWS, |range: Range<usize>| sentinel_rows(range).take(1));
+    }
+
+    #[test]
+    fn named_reads_follow_the_air_and_stop_at_the_edges() {
+        let trace = generate_fibonacci_trace::<Val>(20).unwrap();
+        let view = TraceView::named(&trace.matrix, &FibonacciAir);
+        assert_eq!((view.height(), view.width()), (256, 4));
+        // Row 5 holds (F(5), F(6))
+        assert_eq!(view.get_named(5, "a"), Ok(Val::from_u8(5)));
+        assert_eq!(view.get_named(5, "b"), Ok(Val::from_u8(8)));
+        assert_eq!(view.get_named(5, "c"), Err(TraceViewError::NoSuchColumn("c".to_string())));
+        assert_eq!(view.get_named(256, "b"), Err(TraceViewError::RowOutOfRange { row: 256, height: 256 }));
+
+        assert_eq!(view.get(255, 3), Some(Val::from_u8(20)));
+        assert_eq!(view.get(256, 0), None);
+        assert_eq!(view.get(0, 4), None);
+        assert_eq!(view.get(usize::MAX, 0), None);
+        assert_eq!(view.column(3).map(|column| column.count()), Some(256));
+        assert!(view.column(4).is_none());
+
+        // Without names only indices work
+        assert!(TraceView::new(&trace.matrix).column_index("a").is_err());
+    }
+}
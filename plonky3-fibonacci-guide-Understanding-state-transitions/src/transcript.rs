@@ -0,0 +1,276 @@
+// Records every Fiat-Shamir observation and sample of a prove or verify run,
+// so two runs that should agree can be compared entry by entry. Built only
+// with the `transcript-log` feature: the recording challenger is a separate
+// config type and slows every transcript operation down.
+//
+// What enters the transcript, in order, as p3_uni_stark and its FRI PCS drive
+// it; the verifier replays the same sequence from the proof, and a log from
+// --transcript-log is the authority if Plonky3 changes it:
+//   1. observe the trace's degree bits
+//   2. observe commitment: the trace
+//   3. observe each public value
+//   4. sample alpha, which folds the constraints together (one sample per
+//      extension limb)
+//   5. observe commitment: the quotient chunks
+//   6. sample zeta, the out-of-domain point
+//   7. FRI: the values opened at zeta, the batching challenge, then per round
+//      a commitment observed and a folding challenge sampled; the final
+//      polynomial; the proof-of-work witness, checked with sample bits; one
+//      sample bits per query index
+use core::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use p3_air::Air;
+use p3_challenger::{CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger};
+use p3_field::PrimeField64;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_symmetric::Hash;
+use p3_uni_stark::{
+    DebugConstraintBuilder, Proof, ProverConstraintFolder, StarkConfig, SymbolicAirBuilder,
+    VerifierConstraintFolder, prove, verify,
+};
+
+use crate::cost::SecurityLevel;
+use crate::{
+    Challenge, ChallengeMmcs, Challenger, Dft, MyCompress, MyHash, Pcs, Perm, Val, ValMmcs, constants_rng,
+    fri_params_for,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    // Which of our wrappers drove the challenger: "prove" or "verify"
+    pub site: &'static str,
+    // "observe", "observe commitment", "sample" or "sample bits"
+    pub op: &'static str,
+    // Canonical values observed or sampled
+    pub values: Vec<u64>,
+}
+
+impl fmt::Display for TranscriptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {:?}", self.site, self.op, self.values)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranscriptLog {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptLog {
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let values: Vec<String> = entry.values.iter().map(|v| v.to_string()).collect();
+                format!(
+                    "{{\"site\":\"{}\",\"op\":\"{}\",\"values\":[{}]}}",
+                    entry.site,
+                    entry.op,
+                    values.join(",")
+                )
+            })
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+}
+
+// Writes {"prove": [...], "verify": [...]}
+pub fn write_transcript_logs(path: &Path, prove: &TranscriptLog, verify: &TranscriptLog) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "{{\"prove\":{},\"verify\":{}}}", prove.to_json(), verify.to_json())?;
+    out.flush()
+}
+
+// First entry at which two logs disagree on op or values; a missing entry
+// means that log ended first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,
+    pub a: Option<TranscriptEntry>,
+    pub b: Option<TranscriptEntry>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |entry: &Option<TranscriptEntry>| match entry {
+            Some(entry) => entry.to_string(),
+            None => "end of log".to_string(),
+        };
+        write!(f, "entry {}: {} vs {}", self.index, show(&self.a), show(&self.b))
+    }
+}
+
+// Sites are ignored, so a prove log and a verify log of the same proof compare
+// equal when the verifier replays the prover's transcript
+pub fn compare_transcripts(a: &TranscriptLog, b: &TranscriptLog) -> Option<Divergence> {
+    let len = a.entries.len().max(b.entries.len());
+    (0..len).find_map(|index| {
+        let (x, y) = (a.entries.get(index), b.entries.get(index));
+        let same = match (x, y) {
+            (Some(x), Some(y)) => x.op == y.op && x.values == y.values,
+            _ => false,
+        };
+        (!same).then(|| Divergence { index, a: x.cloned(), b: y.cloned() })
+    })
+}
+
+// Wraps a challenger, appending each operation to a log shared by all its
+// clones (the prover clones the config's challenger before using it)
+#[derive(Clone, Debug)]
+pub struct LoggingChallenger<C> {
+    inner: C,
+    site: &'static str,
+    log: Arc<Mutex<Vec<TranscriptEntry>>>,
+}
+
+impl<C> LoggingChallenger<C> {
+    fn record(&self, op: &'static str, values: impl IntoIterator<Item = Val>) {
+        let entry = TranscriptEntry {
+            site: self.site,
+            op,
+            values: values.into_iter().map(|v| v.as_canonical_u64()).collect(),
+        };
+        self.log.lock().expect("transcript log poisoned").push(entry);
+    }
+}
+
+impl<C: CanObserve<Val>> CanObserve<Val> for LoggingChallenger<C> {
+    fn observe(&mut self, value: Val) {
+        self.record("observe", [value]);
+        self.inner.observe(value);
+    }
+}
+
+impl<C: CanObserve<Hash<Val, Val, 8>>> CanObserve<Hash<Val, Val, 8>> for LoggingChallenger<C> {
+    fn observe(&mut self, value: Hash<Val, Val, 8>) {
+        let digest: [Val; 8] = value.into();
+        self.record("observe commitment", digest);
+        self.inner.observe(value);
+    }
+}
+
+impl<C: CanSample<Val>> CanSample<Val> for LoggingChallenger<C> {
+    fn sample(&mut self) -> Val {
+        let value = self.inner.sample();
+        self.record("sample", [value]);
+        value
+    }
+}
+
+impl<C: CanSampleBits<usize>> CanSampleBits<usize> for LoggingChallenger<C> {
+    fn sample_bits(&mut self, bits: usize) -> usize {
+        let value = self.inner.sample_bits(bits);
+        // Recorded as [bits, value]
+        let entry = TranscriptEntry {
+            site: self.site,
+            op: "sample bits",
+            values: vec![bits as u64, value as u64],
+        };
+        self.log.lock().expect("transcript log poisoned").push(entry);
+        value
+    }
+}
+
+impl<C: FieldChallenger<Val>> FieldChallenger<Val> for LoggingChallenger<C> {}
+
+impl<C: GrindingChallenger<Witness = Val> + FieldChallenger<Val>> GrindingChallenger for LoggingChallenger<C> {
+    type Witness = Val;
+
+    // Searches on a copy, then replays the witness through the logged
+    // observe and sample_bits, so the entries match the verifier's check
+    fn grind(&mut self, bits: usize) -> Val {
+        let witness = self.inner.clone().grind(bits);
+        assert!(self.check_witness(bits, witness), "grinding produced an invalid witness");
+        witness
+    }
+}
+
+pub type LoggedConfig = StarkConfig<Pcs, Challenge, LoggingChallenger<Challenger>>;
+
+// create_config_with_seed with its challenger wrapped, and the log it fills
+fn create_logged_config(
+    seed: u64,
+    level: SecurityLevel,
+    site: &'static str,
+) -> (LoggedConfig, Arc<Mutex<Vec<TranscriptEntry>>>) {
+    let mut rng = constants_rng(seed);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(level, challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_params);
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let challenger = LoggingChallenger { inner: Challenger::new(perm), site, log: log.clone() };
+    (LoggedConfig::new(pcs, challenger), log)
+}
+
+fn take_log(log: &Arc<Mutex<Vec<TranscriptEntry>>>) -> TranscriptLog {
+    TranscriptLog { entries: core::mem::take(&mut *log.lock().expect("transcript log poisoned")) }
+}
+
+// prove_logged_at the Test level
+pub fn prove_logged<A>(
+    seed: u64,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    public_values: &Vec<Val>,
+) -> (Proof<LoggedConfig>, TranscriptLog)
+where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, LoggedConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val>>,
+{
+    prove_logged_at(seed, SecurityLevel::Test, air, trace, public_values)
+}
+
+pub fn prove_logged_at<A>(
+    seed: u64,
+    level: SecurityLevel,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    public_values: &Vec<Val>,
+) -> (Proof<LoggedConfig>, TranscriptLog)
+where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, LoggedConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val>>,
+{
+    let (config, log) = create_logged_config(seed, level, "prove");
+    let proof = prove(&config, air, trace, public_values);
+    (proof, take_log(&log))
+}
+
+// verify_logged_at the Test level
+pub fn verify_logged<A>(
+    seed: u64,
+    air: &A,
+    proof: &Proof<LoggedConfig>,
+    public_values: &Vec<Val>,
+) -> (Result<(), String>, TranscriptLog)
+where
+    A: Air<SymbolicAirBuilder<Val>> + for<'a> Air<VerifierConstraintFolder<'a, LoggedConfig>>,
+{
+    verify_logged_at(seed, SecurityLevel::Test, air, proof, public_values)
+}
+
+pub fn verify_logged_at<A>(
+    seed: u64,
+    level: SecurityLevel,
+    air: &A,
+    proof: &Proof<LoggedConfig>,
+    public_values: &Vec<Val>,
+) -> (Result<(), String>, TranscriptLog)
+where
+    A: Air<SymbolicAirBuilder<Val>> + for<'a> Air<VerifierConstraintFolder<'a, LoggedConfig>>,
+{
+    let (config, log) = create_logged_config(seed, level, "verify");
+    let result = verify(&config, air, proof, public_values).map_err(|e| format!("{:?}", e));
+    (result, take_log(&log))
+}
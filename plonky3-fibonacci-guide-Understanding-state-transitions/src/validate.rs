@@ -0,0 +1,528 @@
+use core::fmt;
+use core::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues};
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
+use p3_matrix::stack::VerticalPair;
+
+use crate::Val;
+use crate::cost::MIN_TRACE_HEIGHT;
+use crate::trace::{SizingError, trace_height, trace_len};
+
+// Where a window sits in the trace, which is all the row selectors depend on.
+// A trace has at least two rows, so no row is both first and last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    First,
+    Middle,
+    // Its window wraps around to row 0, so transition constraints are off
+    Last,
+}
+
+impl RowKind {
+    pub fn of(row: usize, height: usize) -> Self {
+        if row == 0 {
+            Self::First
+        } else if row == height - 1 {
+            Self::Last
+        } else {
+            Self::Middle
+        }
+    }
+}
+
+// An AirBuilder over concrete field values for one (local, next) window.
+// Constraints are identified by the order in which eval asserts them.
+pub struct ConstraintChecker<'a, F: Field> {
+    main: VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>,
+    public_values: &'a [F],
+    row_kind: RowKind,
+    // The value of each asserted expression, zero when it holds
+    values: Vec<F>,
+}
+
+impl<'a, F: Field> ConstraintChecker<'a, F> {
+    fn new(local: &'a [F], next: &'a [F], public_values: &'a [F], row_kind: RowKind) -> Self {
+        Self {
+            main: VerticalPair::new(RowMajorMatrixView::new_row(local), RowMajorMatrixView::new_row(next)),
+            public_values,
+            row_kind,
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<'a, F: Field> AirBuilder for ConstraintChecker<'a, F> {
+    type F = F;
+    type Expr = F;
+    type Var = F;
+    type M = VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>;
+
+    fn main(&self) -> Self::M {
+        self.main
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        F::from_bool(self.row_kind == RowKind::First)
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        F::from_bool(self.row_kind == RowKind::Last)
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert_eq!(size, 2, "only two-row windows are supported");
+        F::from_bool(self.row_kind != RowKind::Last)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.values.push(x.into());
+    }
+}
+
+impl<F: Field> AirBuilderWithPublicValues for ConstraintChecker<'_, F> {
+    type PublicVar = F;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.public_values
+    }
+}
+
+// Evaluates every constraint of `air` on one (local, next) window, one value
+// per constraint in assertion order; a constraint holds when its value is
+// zero. Needs only the AIR, so external trace generators can run exactly the
+// checks the prover will without any PCS or config types.
+pub fn evaluate_constraints<A>(air: &A, window: [&[Val]; 2], public_values: &[Val], row_kind: RowKind) -> Vec<Val>
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let [local, next] = window;
+    let mut checker = ConstraintChecker::new(local, next, public_values, row_kind);
+    air.eval(&mut checker);
+    checker.values
+}
+
+// Indices of the constraints failing on `row`, using the same wrap-around
+// window (last row paired with row 0) as the prover's quotient domain
+fn failed_constraints<A>(air: &A, trace: &RowMajorMatrix<Val>, public_values: &[Val], row: usize) -> Vec<usize>
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let height = trace.height();
+    let width = trace.width();
+    let next_row = (row + 1) % height;
+    let local = &trace.values[row * width..(row + 1) * width];
+    let next = &trace.values[next_row * width..(next_row + 1) * width];
+
+    evaluate_constraints(air, [local, next], public_values, RowKind::of(row, height))
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(constraint, _)| constraint)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    pub row: usize,
+    pub constraint: usize,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "constraint {} violated at row {}", self.constraint, self.row)
+    }
+}
+
+// Checks every row and stops at the first violated constraint
+pub fn validate_trace<A>(air: &A, trace: &RowMajorMatrix<Val>, public_values: &[Val]) -> Result<(), ConstraintViolation>
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    for row in 0..trace.height() {
+        if let Some(&constraint) = failed_constraints(air, trace, public_values, row).first() {
+            return Err(ConstraintViolation { row, constraint });
+        }
+    }
+    Ok(())
+}
+
+// Result of a full single pass over a trace with early exit disabled
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationSummary {
+    pub rows_checked: usize,
+    // Violation count per constraint, indexed by constraint
+    pub violations: Vec<usize>,
+    // The first few offending rows per constraint
+    pub sample_rows: Vec<Vec<usize>>,
+}
+
+impl ValidationSummary {
+    pub fn total_violations(&self) -> usize {
+        self.violations.iter().sum()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.total_violations() == 0
+    }
+}
+
+impl fmt::Display for ValidationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "   {:<12} {:>10}   first rows", "constraint", "violations")?;
+        for (constraint, (count, rows)) in self.violations.iter().zip(&self.sample_rows).enumerate() {
+            writeln!(f, "   {:<12} {:>10}   {:?}", constraint, count, rows)?;
+        }
+        write!(f, "   {} violations across {} rows", self.total_violations(), self.rows_checked)
+    }
+}
+
+// Checks every row, counting violations per constraint and keeping the first
+// `max_samples` offending row indices of each
+pub fn validate_trace_full<A>(
+    air: &A,
+    trace: &RowMajorMatrix<Val>,
+    public_values: &[Val],
+    max_samples: usize,
+) -> ValidationSummary
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let mut summary = ValidationSummary { rows_checked: trace.height(), ..Default::default() };
+    for row in 0..trace.height() {
+        for constraint in failed_constraints(air, trace, public_values, row) {
+            if constraint >= summary.violations.len() {
+                summary.violations.resize(constraint + 1, 0);
+                summary.sample_rows.resize(constraint + 1, Vec::new());
+            }
+            summary.violations[constraint] += 1;
+            if summary.sample_rows[constraint].len() < max_samples {
+                summary.sample_rows[constraint].push(row);
+            }
+        }
+    }
+    summary
+}
+
+// The smallest window found to contain a violation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShrunkFailure {
+    pub rows: Range<usize>,
+    pub violation: ConstraintViolation,
+}
+
+impl ShrunkFailure {
+    // The window's rows cut out of `trace`, the one that was shrunk, and
+    // padded with copies of their last row to a power-of-two height the prover
+    // accepts. The rows move to the top, so the public values and first-row
+    // constraints no longer line up with them; the carried-over violation is
+    // still evaluated on the same two rows unless the window wrapped around.
+    pub fn reproducer(&self, trace: &RowMajorMatrix<Val>) -> Result<RowMajorMatrix<Val>, SizingError> {
+        let width = trace.width();
+        let height = trace_height(self.rows.len(), MIN_TRACE_HEIGHT)?;
+        let mut values = Vec::with_capacity(trace_len(height, width)?);
+        values.extend_from_slice(&trace.values[self.rows.start * width..self.rows.end * width]);
+        let last = &trace.values[(self.rows.end - 1) * width..self.rows.end * width];
+        for _ in self.rows.len()..height {
+            values.extend_from_slice(last);
+        }
+        Ok(RowMajorMatrix::new(values, width))
+    }
+}
+
+impl fmt::Display for ShrunkFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rows {}..{}: {}", self.rows.start, self.rows.end, self.violation)
+    }
+}
+
+// First violation among the windows of `rows` that lie entirely inside it.
+// Rows keep their original positions, so first/last-row constraints mean the
+// same as in the full trace and no boundary seeds need regenerating. The
+// last row's wrap-around window counts as inside any range that ends the trace.
+fn window_violation<A>(
+    air: &A,
+    trace: &RowMajorMatrix<Val>,
+    public_values: &[Val],
+    rows: Range<usize>,
+) -> Option<ConstraintViolation>
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let height = trace.height();
+    rows.clone()
+        .filter(|&row| row + 1 < rows.end || row + 1 == height)
+        .find_map(|row| {
+            let constraint = *failed_constraints(air, trace, public_values, row).first()?;
+            Some(ConstraintViolation { row, constraint })
+        })
+}
+
+// Bisects a failing trace down to a window of at most two rows that still
+// fails. Halves overlap by one row so a transition across the midpoint stays
+// inside one of them. Each step checks half the previous window, so the whole
+// search costs about two passes over the trace. None if the trace is valid.
+pub fn shrink_failing_trace<A>(
+    air: &A,
+    trace: &RowMajorMatrix<Val>,
+    public_values: &[Val],
+) -> Option<ShrunkFailure>
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let mut rows = 0..trace.height();
+    let mut violation = window_violation(air, trace, public_values, rows.clone())?;
+
+    while rows.len() > 2 {
+        let mid = rows.start + rows.len() / 2;
+        let halves = [rows.start..mid + 1, mid..rows.end];
+        let Some((half, found)) = halves
+            .into_iter()
+            .find_map(|half| Some((half.clone(), window_violation(air, trace, public_values, half)?)))
+        else {
+            break;
+        };
+        rows = half;
+        violation = found;
+    }
+    Some(ShrunkFailure { rows, violation })
+}
+
+// One trace in an attack corpus, and whether a correct AIR accepts it
+pub struct Attack {
+    pub name: &'static str,
+    pub trace: RowMajorMatrix<Val>,
+    pub public_values: Vec<Val>,
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttackReport {
+    // The exercise or example the corpus was run against
+    pub name: &'static str,
+    // Set if eval hit a todo!() slot or otherwise panicked
+    pub unfinished: Option<String>,
+    // Malicious traces the AIR accepted
+    pub slipped_through: Vec<&'static str>,
+    // Valid traces the AIR rejected
+    pub wrongly_rejected: Vec<&'static str>,
+}
+
+impl AttackReport {
+    pub fn passed(&self) -> bool {
+        self.unfinished.is_none() && self.slipped_through.is_empty() && self.wrongly_rejected.is_empty()
+    }
+}
+
+impl fmt::Display for AttackReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(msg) = &self.unfinished {
+            return write!(f, "{}: unfinished ({})", self.name, msg);
+        }
+        if self.passed() {
+            return write!(f, "{}: passed", self.name);
+        }
+        write!(
+            f,
+            "{}: slipped through {:?}, wrongly rejected {:?}",
+            self.name, self.slipped_through, self.wrongly_rejected
+        )
+    }
+}
+
+// Runs `air` over every trace in `attacks`, catching panics from eval (e.g.
+// an exercise's unfinished todo!() slot) so one AIR cannot stop the rest. The
+// panic hook is left alone: a caller that wants the message silenced, as the
+// binary does for --exercises, sets its own.
+pub fn run_attacks<A>(name: &'static str, air: &A, attacks: Vec<Attack>) -> AttackReport
+where
+    A: for<'a> Air<ConstraintChecker<'a, Val>>,
+{
+    let mut report = AttackReport { name, ..Default::default() };
+    for attack in attacks {
+        let checked = panic::catch_unwind(AssertUnwindSafe(|| {
+            validate_trace(air, &attack.trace, &attack.public_values).is_ok()
+        }));
+        match checked {
+            Ok(true) if !attack.valid => report.slipped_through.push(attack.name),
+            Ok(false) if attack.valid => report.wrongly_rejected.push(attack.name),
+            Ok(_) => {}
+            Err(payload) => {
+                let msg = payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| payload.downcast_ref::<&str>().map(|msg| msg.to_string()))
+                    .unwrap_or_else(|| "eval panicked".to_string());
+                report.unfinished = Some(msg);
+                break;
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_air::BaseAir;
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::{FibonacciAir, NUM_FIBONACCI_COLS, generate_fibonacci_trace_padded};
+
+    // 20 steps over 32 rows, and the same with row 10's b bumped, which
+    // breaks the windows ending and starting on row 10
+    fn traces() -> (RowMajorMatrix<Val>, RowMajorMatrix<Val>, Vec<Val>) {
+        let trace = generate_fibonacci_trace_padded::<Val>(20, MIN_TRACE_HEIGHT).unwrap();
+        let mut broken = trace.matrix.clone();
+        broken.values[10 * NUM_FIBONACCI_COLS + 1] += Val::ONE;
+        (trace.matrix, broken, trace.public_values)
+    }
+
+    #[test]
+    fn row_kinds_follow_the_position() {
+        let kinds = [0, 1, 30, 31].map(|row| RowKind::of(row, 32));
+        assert_eq!(kinds, [RowKind::First, RowKind::Middle, RowKind::Middle, RowKind::Last]);
+    }
+
+    #[test]
+    fn an_honest_window_evaluates_every_constraint_to_zero() {
+        let (honest, _, public_values) = traces();
+        let (local, next) = honest.values[..2 * NUM_FIBONACCI_COLS].split_at(NUM_FIBONACCI_COLS);
+        let window = [local, next];
+        let values = evaluate_constraints(&FibonacciAir, window, &public_values, RowKind::First);
+        assert!(!values.is_empty());
+        assert!(values.iter().all(|value| value.is_zero()));
+        assert_eq!(validate_trace(&FibonacciAir, &honest, &public_values), Ok(()));
+    }
+
+    #[test]
+    fn an_edited_cell_is_found_and_counted() {
+        let (_, broken, public_values) = traces();
+        let first = validate_trace(&FibonacciAir, &broken, &public_values).unwrap_err();
+        assert_eq!(first.row, 9);
+
+        let summary = validate_trace_full(&FibonacciAir, &broken, &public_values, 1);
+        assert_eq!(summary.rows_checked, 32);
+        assert!(!summary.is_valid());
+        let flagged: Vec<usize> = summary.sample_rows.iter().flatten().copied().collect();
+        assert!(flagged.iter().all(|&row| row == 9 || row == 10), "{:?}", flagged);
+        assert!(summary.sample_rows.iter().all(|rows| rows.len() <= 1));
+    }
+
+    // eval_fibonacci asserts three first-row constraints, the two last-row
+    // bindings, three on is_real and one on the count before these
+    const RECURRENCE: usize = 9;
+    const PROPAGATION: usize = 10;
+
+    #[test]
+    fn scattered_edits_are_counted_per_constraint() {
+        let trace = generate_fibonacci_trace_padded::<Val>(1000, MIN_TRACE_HEIGHT).unwrap();
+        let mut broken = trace.matrix;
+        // Rows 7 + 9i: a on even i breaks the propagation into the row and the
+        // recurrence out of it; b on odd i breaks the recurrence into the row
+        // and both constraints out of it
+        let (mut recurrence, mut propagation) = (Vec::new(), Vec::new());
+        for i in 0..100 {
+            let row = 7 + 9 * i;
+            broken.values[row * NUM_FIBONACCI_COLS + i % 2] += Val::ONE;
+            if i % 2 == 0 {
+                propagation.push(row - 1);
+                recurrence.push(row);
+            } else {
+                recurrence.extend([row - 1, row]);
+                propagation.push(row);
+            }
+        }
+
+        let summary = validate_trace_full(&FibonacciAir, &broken, &trace.public_values, usize::MAX);
+        let mut violations = vec![0; RECURRENCE];
+        violations.extend([150, 100]);
+        assert_eq!(summary.violations, violations);
+        assert!(summary.sample_rows[..RECURRENCE].iter().all(Vec::is_empty));
+        assert_eq!(summary.sample_rows[RECURRENCE], recurrence);
+        assert_eq!(summary.sample_rows[PROPAGATION], propagation);
+
+        let sampled = validate_trace_full(&FibonacciAir, &broken, &trace.public_values, 4);
+        assert_eq!(sampled.violations, summary.violations);
+        assert_eq!(sampled.sample_rows[RECURRENCE], [7, 15, 16, 25]);
+        assert_eq!(sampled.sample_rows[PROPAGATION], [6, 16, 24, 34]);
+        assert_eq!(
+            validate_trace(&FibonacciAir, &broken, &trace.public_values),
+            Err(ConstraintViolation { row: 6, constraint: PROPAGATION })
+        );
+    }
+
+    #[test]
+    fn an_edited_cell_shrinks_to_its_window() {
+        let (_, broken, public_values) = traces();
+        let shrunk = shrink_failing_trace(&FibonacciAir, &broken, &public_values).unwrap();
+        assert!(shrunk.rows.len() <= 2 && shrunk.rows.contains(&shrunk.violation.row), "{}", shrunk);
+        assert!([9, 10].contains(&shrunk.violation.row), "{}", shrunk);
+    }
+
+    #[test]
+    fn a_bad_transition_deep_in_a_large_trace_shrinks_to_a_padded_reproducer() {
+        let trace = generate_fibonacci_trace_padded::<Val>((1 << 20) - 1, MIN_TRACE_HEIGHT).unwrap();
+        let mut broken = trace.matrix;
+        assert_eq!(broken.height(), 1 << 20);
+        let bad_row = 700_000;
+        broken.values[bad_row * NUM_FIBONACCI_COLS + 1] += Val::ONE;
+
+        let shrunk = shrink_failing_trace(&FibonacciAir, &broken, &trace.public_values).unwrap();
+        assert!(shrunk.rows.len() <= 2 && shrunk.rows.contains(&shrunk.violation.row), "{}", shrunk);
+        assert!([bad_row - 1, bad_row].contains(&shrunk.violation.row), "{}", shrunk);
+        assert_eq!(shrunk.violation.constraint, RECURRENCE);
+
+        let reproducer = shrunk.reproducer(&broken).unwrap();
+        assert!(reproducer.height().is_power_of_two() && reproducer.height() >= MIN_TRACE_HEIGHT);
+        assert_eq!(reproducer.width(), NUM_FIBONACCI_COLS);
+        let cut = &broken.values[shrunk.rows.start * NUM_FIBONACCI_COLS..shrunk.rows.end * NUM_FIBONACCI_COLS];
+        assert_eq!(&reproducer.values[..cut.len()], cut);
+        let summary = validate_trace_full(&FibonacciAir, &reproducer, &trace.public_values, usize::MAX);
+        assert!(summary.sample_rows[RECURRENCE].contains(&(shrunk.violation.row - shrunk.rows.start)));
+    }
+
+    #[test]
+    fn a_valid_trace_has_nothing_to_shrink() {
+        let (honest, _, public_values) = traces();
+        assert_eq!(shrink_failing_trace(&FibonacciAir, &honest, &public_values), None);
+        assert!(validate_trace_full(&FibonacciAir, &honest, &public_values, 4).is_valid());
+    }
+
+    struct Unfinished;
+
+    impl<F> BaseAir<F> for Unfinished {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl<AB: AirBuilder> Air<AB> for Unfinished {
+        fn eval(&self, _builder: &mut AB) {
+            todo!("the slot");
+        }
+    }
+
+    #[test]
+    fn attack_reports_sort_each_outcome() {
+        let (honest, broken, public_values) = traces();
+        let attack = |name, trace: &RowMajorMatrix<Val>, valid| Attack {
+            name,
+            trace: trace.clone(),
+            public_values: public_values.clone(),
+            valid,
+        };
+        let labelled = vec![attack("honest", &honest, true), attack("bumped", &broken, false)];
+        let sound = run_attacks("sound", &FibonacciAir, labelled);
+        assert!(sound.passed(), "{}", sound);
+
+        let mislabelled = vec![attack("honest", &honest, false), attack("bumped", &broken, true)];
+        let report = run_attacks("mislabelled", &FibonacciAir, mislabelled);
+        assert_eq!((report.slipped_through, report.wrongly_rejected), (vec!["honest"], vec!["bumped"]));
+
+        let unfinished = run_attacks("todo", &Unfinished, vec![attack("honest", &honest, true)]);
+        assert!(unfinished.unfinished.as_deref().is_some_and(|msg| msg.contains("the slot")), "{}", unfinished);
+    }
+}
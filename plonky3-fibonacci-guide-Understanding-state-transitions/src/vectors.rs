@@ -0,0 +1,311 @@
+// Test vectors for verifiers written outside Rust: for each security level,
+// one JSON file holding the config's parameters, the Poseidon2 round
+// constants, a tiny Fibonacci trace, its commitments, the Fiat-Shamir
+// transcript phase by phase and the proof. Every number is a decimal string,
+// so no JSON parser rounds it. Built with the `transcript-log` feature: the
+// transcript comes from transcript.rs's recording challenger.
+//
+// Field elements outside `proof` are canonical. `proof` is the proof as
+// Plonky3's serde impls write it, numbers turned into strings and nothing
+// else changed, so its BabyBear values are in the Montgomery form p3 stores.
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use p3_field::PrimeField64;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use stark_config::{LOG_FINAL_POLY_LEN, poseidon2_constants};
+
+use crate::cost::{MIN_TRACE_HEIGHT, SecurityLevel};
+use crate::trace::SizingError;
+use crate::transcript::{TranscriptEntry, compare_transcripts, prove_logged_at, verify_logged_at};
+use crate::{DEFAULT_CONFIG_SEED, FIBONACCI_AIR_ID, FibonacciAir, Val, generate_fibonacci_trace_padded};
+
+// Steps in the vectors' trace, padded to MIN_TRACE_HEIGHT rows: the smallest
+// trace FRI folds down to its final polynomial
+pub const VECTOR_STEPS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    pub air: String,
+    pub config: VectorConfig,
+    pub poseidon2: Poseidon2Vectors,
+    // Row by row, [a, b, is_real, count]
+    pub trace: Vec<Vec<String>>,
+    pub public_values: Vec<String>,
+    pub commitments: VectorCommitments,
+    pub transcript: Vec<TranscriptPhase>,
+    pub proof: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorConfig {
+    // SecurityLevel's name: "test", "80" or "100"
+    pub level: String,
+    pub seed: String,
+    pub field_order: String,
+    pub extension_degree: String,
+    pub log_blowup: String,
+    pub log_final_poly_len: String,
+    pub num_queries: String,
+    pub proof_of_work_bits: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poseidon2Vectors {
+    pub width: String,
+    pub initial_external: Vec<Vec<String>>,
+    pub internal: Vec<String>,
+    pub terminal_external: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorCommitments {
+    pub trace: Vec<String>,
+    pub quotient_chunks: Vec<String>,
+}
+
+// What the challenger absorbed, then what it squeezed out before absorbing
+// again. A verifier that replays `observed` must sample exactly `sampled`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptPhase {
+    pub observed: Vec<VectorEntry>,
+    pub sampled: Vec<VectorEntry>,
+}
+
+// One challenger operation, named as in transcript.rs; "sample bits" values
+// are [bits, index]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorEntry {
+    pub op: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum VectorError {
+    Trace(SizingError),
+    // The logged verifier rejected the proof it is meant to describe
+    Verification(String),
+    // The verifier's transcript is not the prover's
+    Transcript(String),
+    Json(serde_json::Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for VectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trace(e) => write!(f, "vector trace: {}", e),
+            Self::Verification(e) => write!(f, "the vector proof did not verify: {}", e),
+            Self::Transcript(divergence) => write!(f, "prove and verify transcripts diverge at {}", divergence),
+            Self::Json(e) => write!(f, "vector JSON: {}", e),
+            Self::Io(e) => write!(f, "could not write vectors: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VectorError {}
+
+impl From<serde_json::Error> for VectorError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<io::Error> for VectorError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn decimal(value: impl ToString) -> String {
+    value.to_string()
+}
+
+fn canonical(values: &[Val]) -> Vec<String> {
+    values.iter().map(|v| decimal(v.as_canonical_u64())).collect()
+}
+
+// Every JSON number in `value` as its decimal string
+fn decimal_strings(value: Value) -> Value {
+    match value {
+        Value::Number(n) => Value::String(n.to_string()),
+        Value::Array(items) => Value::Array(items.into_iter().map(decimal_strings).collect()),
+        Value::Object(fields) => Value::Object(fields.into_iter().map(|(k, v)| (k, decimal_strings(v))).collect()),
+        other => other,
+    }
+}
+
+// A new phase starts at each observation that follows a sample
+fn transcript_phases(entries: &[TranscriptEntry]) -> Vec<TranscriptPhase> {
+    let mut phases: Vec<TranscriptPhase> = Vec::new();
+    for entry in entries {
+        let sample = entry.op.starts_with("sample");
+        let vector = VectorEntry { op: entry.op.to_string(), values: entry.values.iter().map(decimal).collect() };
+        match phases.last_mut() {
+            Some(phase) if sample || phase.sampled.is_empty() => {
+                if sample { phase.sampled.push(vector) } else { phase.observed.push(vector) }
+            }
+            _ => {
+                let (observed, sampled) = if sample { (vec![], vec![vector]) } else { (vec![vector], vec![]) };
+                phases.push(TranscriptPhase { observed, sampled });
+            }
+        }
+    }
+    phases
+}
+
+fn vector_config(level: SecurityLevel) -> VectorConfig {
+    let fri = level.fri_choice();
+    VectorConfig {
+        level: level.to_string(),
+        seed: decimal(DEFAULT_CONFIG_SEED),
+        field_order: decimal(Val::ORDER_U64),
+        extension_degree: decimal(4),
+        log_blowup: decimal(fri.log_blowup),
+        log_final_poly_len: decimal(LOG_FINAL_POLY_LEN),
+        num_queries: decimal(fri.num_queries),
+        proof_of_work_bits: decimal(fri.pow_bits),
+    }
+}
+
+fn poseidon2_vectors() -> Poseidon2Vectors {
+    let constants = poseidon2_constants(DEFAULT_CONFIG_SEED);
+    Poseidon2Vectors {
+        width: decimal(16),
+        initial_external: constants.initial_external.iter().map(|round| canonical(round)).collect(),
+        internal: canonical(&constants.internal),
+        terminal_external: constants.terminal_external.iter().map(|round| canonical(round)).collect(),
+    }
+}
+
+// Proves the vector trace at `level` under the default seed through the
+// logged config, and refuses to describe a proof its own verifier rejects or
+// replays differently
+pub fn export_vectors(level: SecurityLevel) -> Result<TestVectors, VectorError> {
+    let trace = generate_fibonacci_trace_padded::<Val>(VECTOR_STEPS, MIN_TRACE_HEIGHT).map_err(VectorError::Trace)?;
+    let pis = &trace.public_values;
+    let rows = trace.matrix.rows().map(|row| row.map(|v| decimal(v.as_canonical_u64())).collect()).collect();
+
+    let (proof, prove_log) = prove_logged_at(DEFAULT_CONFIG_SEED, level, &FibonacciAir, trace.matrix.clone(), pis);
+    let (verified, verify_log) = verify_logged_at(DEFAULT_CONFIG_SEED, level, &FibonacciAir, &proof, pis);
+    verified.map_err(VectorError::Verification)?;
+    if let Some(divergence) = compare_transcripts(&prove_log, &verify_log) {
+        return Err(VectorError::Transcript(divergence.to_string()));
+    }
+
+    let trace_commitment: [Val; 8] = proof.commitments.trace.into();
+    let quotient_commitment: [Val; 8] = proof.commitments.quotient_chunks.into();
+    Ok(TestVectors {
+        air: FIBONACCI_AIR_ID.to_string(),
+        config: vector_config(level),
+        poseidon2: poseidon2_vectors(),
+        trace: rows,
+        public_values: canonical(pis),
+        commitments: VectorCommitments {
+            trace: canonical(&trace_commitment),
+            quotient_chunks: canonical(&quotient_commitment),
+        },
+        transcript: transcript_phases(&prove_log.entries),
+        proof: decimal_strings(serde_json::to_value(&proof)?),
+    })
+}
+
+// vectors-test.json, vectors-80.json, vectors-100.json
+pub fn vectors_file_name(level: SecurityLevel) -> String {
+    format!("vectors-{}.json", level)
+}
+
+pub fn vectors_to_json(vectors: &TestVectors) -> Result<String, VectorError> {
+    let mut json = serde_json::to_string_pretty(vectors)?;
+    json.push('\n');
+    Ok(json)
+}
+
+pub fn vectors_from_json(json: &str) -> Result<TestVectors, VectorError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+// One file per security level in `dir`, returning the paths written
+pub fn write_vectors(dir: &Path) -> Result<Vec<PathBuf>, VectorError> {
+    fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    for level in SecurityLevel::ALL {
+        let path = dir.join(vectors_file_name(level));
+        fs::write(&path, vectors_to_json(&export_vectors(level)?)?)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checked_in(level: SecurityLevel) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(vectors_file_name(level));
+        fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "{}: {}; write it with cargo run --features regen-fixtures -- regen-fixtures --apply",
+                path.display(),
+                e
+            )
+        })
+    }
+
+    #[test]
+    fn vectors_round_trip_through_json() {
+        let vectors = export_vectors(SecurityLevel::Test).unwrap();
+        let json = vectors_to_json(&vectors).unwrap();
+        assert_eq!(vectors_from_json(&json).unwrap(), vectors);
+
+        // Nothing but strings at the leaves
+        fn only_strings(value: &Value) -> bool {
+            match value {
+                Value::String(_) => true,
+                Value::Array(items) => items.iter().all(only_strings),
+                Value::Object(fields) => fields.values().all(only_strings),
+                _ => false,
+            }
+        }
+        assert!(only_strings(&serde_json::from_str(&json).unwrap()));
+    }
+
+    #[test]
+    fn a_phase_ends_at_the_first_observation_after_a_sample() {
+        let entry = |op, value| TranscriptEntry { site: "prove", op, values: vec![value] };
+        let entries = [
+            entry("observe", 1),
+            entry("observe commitment", 2),
+            entry("sample", 3),
+            entry("sample", 4),
+            entry("observe", 5),
+            entry("sample bits", 6),
+        ];
+        let phases = transcript_phases(&entries);
+        assert_eq!(phases.len(), 2);
+        assert_eq!((phases[0].observed.len(), phases[0].sampled.len()), (2, 2));
+        assert_eq!(phases[1].sampled[0].values, ["6"]);
+    }
+
+    // A changed transcript, hash, constant or proof layout fails here until
+    // the fixtures are regenerated
+    #[test]
+    fn checked_in_vectors_match_a_fresh_export() {
+        for level in SecurityLevel::ALL {
+            let fixture = vectors_from_json(&checked_in(level)).unwrap();
+            let fresh = export_vectors(level).unwrap();
+            assert_eq!(fresh.config, fixture.config, "{}: config", level);
+            assert_eq!(fresh.poseidon2, fixture.poseidon2, "{}: Poseidon2 constants", level);
+            assert_eq!(fresh.trace, fixture.trace, "{}: trace", level);
+            assert_eq!(fresh.commitments, fixture.commitments, "{}: commitments", level);
+            let diverged = fresh.transcript.iter().zip(&fixture.transcript).position(|(a, b)| a != b);
+            assert_eq!(diverged, None, "{}: first differing transcript phase", level);
+            assert_eq!(fresh.transcript.len(), fixture.transcript.len(), "{}: transcript phases", level);
+            assert_eq!(fresh, fixture, "{}: proof", level);
+        }
+    }
+}
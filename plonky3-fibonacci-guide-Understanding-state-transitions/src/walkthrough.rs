@@ -0,0 +1,1084 @@
+// The binary's default run: the main proof narrated step by step, with the
+// smaller demos it stops at along the way, plus the --quiet and --start
+// variants of the same proof
+use core::str::FromStr;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::Matrix;
+use p3_uni_stark::{prove, verify};
+
+use crate::air_id::{AirId, IdentifiedProof, check_air_id, prove_identified, verify_identified};
+use crate::blake3::{prove_fibonacci_blake3, verify_as_poseidon2, verify_fibonacci_blake3};
+use crate::cache::{DEFAULT_CACHE_CAPACITY, ProofCache, prove_cached};
+use crate::canonical::{Reduction, parse_with};
+use crate::claims::{FibonacciClaims, format_public_values};
+use crate::coefficients::{
+    CoefficientAir, find_coefficients, generate_coefficient_trace, generate_reachable_trace, prove_reachable,
+    recurrence_final, verify_reachable,
+};
+use crate::cost::{CostParams, SecurityLevel, estimate_proving_cost, grinding_time};
+use crate::counter::{CounterAir, generate_counter_trace};
+use crate::diff::write_trace;
+use crate::exact::{DEFAULT_EXACT_BOUND, FibonacciValue, fibonacci_value};
+use crate::journal::{Journal, journal_head, verify_journal};
+use crate::keccak::{prove_fibonacci_keccak, verify_fibonacci_keccak};
+use crate::last_row::{LastRowBinding, LastRowDemoAir, PaddingStrategy, generate_last_row_trace};
+use crate::lint::{DanglingColumnAir, lint_air};
+use crate::observer::{CollectingObserver, PhaseId, ProofObserver, TracingObserver};
+use crate::parity::{ParityAir, generate_parity_trace};
+use crate::proof_file::{
+    ConfigFingerprint, DEFAULT_PROOF_PATH, encode_proof, encoded_size, load_described_proof_from_reader, load_proof,
+    load_proof_from_reader, proof_from_string, proof_to_string, save_proof,
+};
+use crate::provenance::PROVENANCE;
+use crate::sequence::{check_sequence, sequence_values, write_sequence};
+use crate::threads::with_threads;
+use crate::trace::TraceView;
+use crate::validate::{shrink_failing_trace, validate_trace, validate_trace_full};
+use crate::{
+    ConfigDescriptor, FIBONACCI_AIR_ID, FIBONACCI_LOG_QUOTIENT_DEGREE, FibonacciAir, MyConfig, NUM_FIBONACCI_COLS,
+    PublicValues, Val, create_config, create_config_from, create_default_config, generate_fibonacci_trace,
+    generate_fibonacci_trace_padded, generate_recurrence_trace_padded, prove_fibonacci_trace, verify_described,
+    verify_sequence,
+};
+
+// What the main proof is built from: --steps, --seed, --security,
+// --log-blowup, --pow-bits and --min-height, checked together before
+// anything reaches the prover
+pub struct ProverOptions {
+    pub num_steps: usize,
+    pub seed: u64,
+    pub params: CostParams,
+    // --hash: also prove the statement under another Merkle hash
+    pub hash: CommitmentHash,
+    // --cache-dir: where --quiet looks for the proof before proving it
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl ProverOptions {
+    // Refuses a step count the parameters cannot prove, before the prover does
+    pub fn new(num_steps: usize, seed: u64, params: CostParams, hash: CommitmentHash) -> Result<Self, String> {
+        // One padding row past the last step holds the result
+        params
+            .check(num_steps.saturating_add(1), FIBONACCI_LOG_QUOTIENT_DEGREE)
+            .map_err(|e| format!("Cannot prove {} steps: {}", num_steps, e))?;
+        Ok(Self { num_steps, seed, params, hash, cache_dir: None })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitmentHash {
+    #[default]
+    Poseidon2,
+    Keccak,
+    Blake3,
+}
+
+impl FromStr for CommitmentHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "poseidon2" => Ok(Self::Poseidon2),
+            "keccak" => Ok(Self::Keccak),
+            "blake3" => Ok(Self::Blake3),
+            other => Err(format!("unknown hash {:?}; expected poseidon2, keccak or blake3", other)),
+        }
+    }
+}
+
+// What the walkthrough does besides the main proof, one field per flag
+pub struct WalkthroughOptions {
+    // --threads: prover threads, 0 for every core
+    pub threads: usize,
+    // --exact-bound: sample values past this index are shown mod p only
+    pub exact_bound: usize,
+    // --check-full: every constraint violation, not just the first
+    pub check_full: bool,
+    // --shrink: the smallest failing window, and the 2^20-row shrink demo
+    pub shrink: bool,
+    // --emit-trace, --emit-sequence: write the trace or its values as CSV
+    pub emit_trace: Option<PathBuf>,
+    pub emit_sequence: Option<PathBuf>,
+    // --sequence: check a sequence file against the proved result
+    pub sequence: Option<PathBuf>,
+    // --strict: refuse to prove an AIR with lint findings
+    pub strict: bool,
+    // --trace-phases: report phases through the tracing observer
+    pub trace_phases: bool,
+    // --journal: append the proof to a hash-chained journal
+    pub journal: Option<PathBuf>,
+}
+
+impl Default for WalkthroughOptions {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            exact_bound: DEFAULT_EXACT_BOUND,
+            check_full: false,
+            shrink: false,
+            emit_trace: None,
+            emit_sequence: None,
+            sequence: None,
+            strict: false,
+            trace_phases: false,
+            journal: None,
+        }
+    }
+}
+
+// --quiet: the main proof without commentary, as one line for scripts
+pub fn run_quiet(options: &ProverOptions) -> Result<(), String> {
+    if let Some(dir) = &options.cache_dir {
+        return run_quiet_cached(options, dir);
+    }
+    let trace = generate_fibonacci_trace_padded::<Val>(options.num_steps, options.params.min_height)
+        .map_err(|e| e.to_string())?;
+    let rows = trace.matrix.height();
+    let config = create_config(options.seed, &options.params);
+    let start = Instant::now();
+    let (proof, public_values) = prove_fibonacci_trace(&config, trace, &mut ());
+    let proving = start.elapsed();
+    let start = Instant::now();
+    verify_identified(&config, &FibonacciAir, &proof, &public_values).map_err(|e| e.to_string())?;
+    println!(
+        "steps={} rows={} log_blowup={} prove={:?} verify={:?} result={}",
+        options.num_steps,
+        rows,
+        options.params.log_blowup,
+        proving,
+        start.elapsed(),
+        public_values[2]
+    );
+    Ok(())
+}
+
+// --quiet --cache-dir DIR: the proof from DIR when an earlier run left it
+// there, proved and stored otherwise, and verified from its bytes either way
+fn run_quiet_cached(options: &ProverOptions, dir: &Path) -> Result<(), String> {
+    let descriptor = ConfigDescriptor::new(options.seed, &options.params);
+    let mut cache = ProofCache::with_dir(DEFAULT_CACHE_CAPACITY, dir);
+    let start = Instant::now();
+    let cached = prove_cached(&mut cache, &descriptor, options.num_steps).map_err(|e| e.to_string())?;
+    let proving = start.elapsed();
+    let start = Instant::now();
+    verify_described(&cached.bytes, &cached.public_values).map_err(|e| e.to_string())?;
+    let stats = cache.stats();
+    println!(
+        "steps={} log_blowup={} prove={:?} verify={:?} result={} cache_hits={} cache_misses={}",
+        options.num_steps,
+        options.params.log_blowup,
+        proving,
+        start.elapsed(),
+        cached.public_values[2],
+        stats.hits(),
+        stats.misses
+    );
+    Ok(())
+}
+
+// --start A B: the Fibonacci recurrence from (A, B) instead of (0, 1), e.g.
+// --start 2 1 --steps 10 for the Lucas number L(10) = 123. The proof is
+// checked against its own start and against (0, 1), which it must not prove.
+pub fn run_start_demo(options: &ProverOptions, start: [&str; 2], reduction: Reduction) -> Result<(), String> {
+    let parse_value = |name: &str, text: &str| -> Result<Val, String> {
+        let value = text.trim().parse::<u64>().map_err(|_| format!("{}: {:?} is not an integer", name, text))?;
+        parse_with(value, reduction).map_err(|e| format!("{}: {}", name, e))
+    };
+    let (a0, b0) = (parse_value("A", start[0])?, parse_value("B", start[1])?);
+    let trace = generate_recurrence_trace_padded(a0, b0, options.num_steps, options.params.min_height)
+        .map_err(|e| format!("Could not generate the trace: {}", e))?;
+    let head: Vec<u64> = sequence_values(&trace.matrix, trace.real_rows).take(10).collect();
+    println!(" Recurrence from ({}, {}), {} steps:", a0, b0, options.num_steps);
+    println!("   Sequence head: {:?}", head);
+    println!("   Value after {} steps: {} (mod p)", options.num_steps, trace.meta.final_value);
+
+    let config = create_config(options.seed, &options.params);
+    let (proof, public_values) = prove_fibonacci_trace(&config, trace, &mut ());
+    let mut from_zero_one = public_values.clone();
+    from_zero_one[..2].copy_from_slice(&[Val::ZERO, Val::ONE]);
+    let cases = [
+        ("claimed from its own start", public_values, true),
+        ("claimed from (0, 1)", from_zero_one, (a0, b0) == (Val::ZERO, Val::ONE)),
+    ];
+    for (name, claim, expected) in cases {
+        match (verify_identified(&config, &FibonacciAir, &proof, &claim), expected) {
+            (Ok(_), true) => println!("   {}: accepted", name),
+            (Ok(_), false) => println!("   {}: unexpectedly accepted", name),
+            (Err(e), true) => println!("   {}: unexpectedly rejected ({})", name, e),
+            (Err(e), false) => println!("   {}: rejected ({})", name, e),
+        }
+    }
+    Ok(())
+}
+
+// The main proof, F(options.num_steps) from (0, 1), from trace to verified
+// proof with each stage explained, then every demo that needs no flag of its own
+pub fn run_walkthrough(options: &ProverOptions, walkthrough: &WalkthroughOptions) {
+    println!(" Plonky3 Fibonacci Proof System");
+    println!("   Proving: F(n) = F(n-1) + F(n-2)");
+    println!("   Computing: F(0)=0, F(1)=1, F(2)=1, F(3)=2, F(4)=3, F(5)=5...");
+    println!();
+
+    // Generate Fibonacci sequence up to F(--steps), F(100) by default
+    let num_steps = options.num_steps;
+    let air = FibonacciAir;
+    let trace = match generate_fibonacci_trace_padded::<Val>(num_steps, options.params.min_height) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!(" Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    let config = create_config(options.seed, &options.params);
+
+    // Display some values from the trace
+    println!(" Generated execution trace:");
+    println!("   Computing {} Fibonacci numbers", trace.real_rows);
+    println!("   Trace padded to {} rows (power of 2)", trace.matrix.height());
+
+    // Calculate and display some Fibonacci values; past F(46) the trace holds
+    // them mod p, so each is shown with its exact integer
+    let exact_bound = walkthrough.exact_bound;
+    let view = TraceView::named(&trace.matrix, &air);
+    println!("\n   Sample values:");
+    for i in [0, 1, 2, 3, 4, 5, 10, 20, 50, 99] {
+        if i < num_steps {
+            match fibonacci_value(&trace.matrix, i) {
+                Ok(a) => println!("   {}", FibonacciValue::new(i, a, exact_bound)),
+                Err(e) => println!("   F({}): {}", i, e),
+            }
+        }
+    }
+    println!("   Final value: {}", FibonacciValue::new(num_steps, trace.meta.final_value, exact_bound));
+    println!();
+
+    // Reads through the view are bounds-checked, by name or by index
+    println!("   Trace access:");
+    match view.get_named(5, "b") {
+        Ok(b) => println!("   Row 5, column b: {}", b),
+        Err(e) => println!("   Row 5, column b: {}", e),
+    }
+    match view.get_named(0, "c") {
+        Ok(c) => println!("   Column c: unexpectedly read {}", c),
+        Err(e) => println!("   Column c: {}", e),
+    }
+    println!("   Row {} (one past the end): {:?}", view.height(), view.get(view.height(), 0));
+    let real_rows = view.column(2).map(|is_real| is_real.filter(|v| *v == Val::ONE).count());
+    println!("   Rows with is_real = 1 (column 2): {:?}", real_rows);
+    let past_end = view.column(view.width()).map(|column| column.count());
+    println!("   Column {} (one past the end): {:?}", view.width(), past_end);
+    println!();
+
+    println!("   Constraints:");
+    println!("   1. Transition: next.b = local.a + local.b (Fibonacci rule)");
+    println!("   2. Propagation: next.a = local.b (state shift)");
+    println!("      both out of real rows; padding rows (is_real = 0) copy the row before");
+    println!("   3. Boundary: the first row is the public (F(0), F(1)) = {:?}", &trace.public_values[..2]);
+    let claimed = FibonacciValue::new(num_steps, trace.public_values[2], exact_bound);
+    println!("   4. Output: the last row ends on the public {}", claimed);
+    println!("   5. Length: count ends on the public num_steps = {}", trace.public_values[3]);
+    println!();
+
+    match validate_trace(&air, &trace.matrix, &trace.public_values) {
+        Ok(()) => println!(" Trace check: all constraints hold"),
+        Err(violation) => println!(" Trace check: {}", violation),
+    }
+    run_boundary_demo();
+    if walkthrough.check_full {
+        println!(" Full constraint check:");
+        println!("{}", validate_trace_full(&air, &trace.matrix, &trace.public_values, 5));
+    }
+    if walkthrough.shrink {
+        match shrink_failing_trace(&air, &trace.matrix, &trace.public_values) {
+            Some(failure) => println!(" Smallest failing window: {}", failure),
+            None => println!(" Smallest failing window: none, the trace is valid"),
+        }
+        run_shrink_demo();
+    }
+    println!();
+
+    if let Some(path) = &walkthrough.emit_trace {
+        match write_trace(path, &trace.matrix) {
+            Ok(()) => println!(" Trace written to {}", path.display()),
+            Err(e) => println!(" Could not write trace to {}: {}", path.display(), e),
+        }
+    }
+    if let Some(path) = &walkthrough.emit_sequence {
+        match write_sequence(path, sequence_values(&trace.matrix, trace.real_rows)) {
+            Ok(()) => println!(" Sequence written to {}", path.display()),
+            Err(e) => println!(" Could not write sequence to {}: {}", path.display(), e),
+        }
+    }
+    println!();
+
+    // Every shipped AIR must lint clean; the dangling one shows what a finding looks like
+    println!(" Constraint lint:");
+    let fibonacci_lint = lint_air(&air);
+    println!("   FibonacciAir: {}", fibonacci_lint);
+    println!("   CoefficientAir: {}", lint_air(&CoefficientAir));
+    println!("   DanglingColumnAir: {}", lint_air(&DanglingColumnAir));
+    if walkthrough.strict && !fibonacci_lint.is_clean() {
+        println!(" Refusing to prove under --strict: {}", fibonacci_lint);
+        return;
+    }
+    println!();
+
+    match estimate_proving_cost(trace.matrix.height(), NUM_FIBONACCI_COLS, &options.params) {
+        Ok(estimate) => {
+            println!(" Estimated cost:");
+            println!("   Prover time: {:?} .. {:?}", estimate.time_range.0, estimate.time_range.1);
+            println!(
+                "   Peak memory: ~{} KiB, proof size: ~{} KiB",
+                estimate.peak_mem_bytes / 1024,
+                estimate.proof_bytes / 1024
+            );
+        }
+        Err(e) => println!(" Estimated cost: {}", e),
+    }
+    println!();
+
+    println!(" Generating STARK proof...");
+    let mut phases = CollectingObserver::default();
+    let mut tracing = TracingObserver::default();
+    let observer: &mut (dyn ProofObserver + Send) = if walkthrough.trace_phases {
+        &mut tracing
+    } else {
+        &mut phases
+    };
+    let start = Instant::now();
+    let proved = with_threads(walkthrough.threads, || prove_fibonacci_trace(&config, trace, observer));
+    let ((proof, public_values), used_threads) = match proved {
+        Ok(result) => result,
+        Err(e) => {
+            println!(" Could not build a {}-thread pool: {}", walkthrough.threads, e);
+            return;
+        }
+    };
+
+    println!(" Proof generated successfully! ({:?} on {} threads)", start.elapsed(), used_threads);
+    let pow_bits = options.params.pow_bits;
+    if pow_bits > 0 {
+        println!("   Grinding, {} bits: ~{:?} of that", pow_bits, grinding_time(pow_bits));
+    }
+    println!("   Provenance: {} [{:016x}]", PROVENANCE, proof.provenance);
+    for timing in &phases.phases {
+        println!("   {}{}: {:?}", "  ".repeat(timing.depth), timing.phase, timing.duration);
+    }
+    if !phases.phases.is_empty() {
+        let nested = phases.duration(PhaseId::AirId) + phases.duration(PhaseId::Stark);
+        println!("   Nested phases fit in total: {}", nested <= phases.duration(PhaseId::Total));
+    }
+    println!();
+
+    println!(" Verifying proof...");
+    let verify_result = verify_identified(&config, &air, &proof, &public_values);
+
+    match verify_result {
+        Ok(_) => {
+            println!(" Proof verified successfully!");
+            println!();
+            println!(" What was proven:");
+            println!("   - The prover knows a valid Fibonacci sequence");
+            println!("   - Every step satisfies F(n) = F(n-1) + F(n-2)");
+            println!("   - The sequence starts with F(0)=0, F(1)=1");
+            println!("   - All {} steps are correctly computed", num_steps);
+            println!("   - The claimed output is {}", claimed);
+            // Checked against what the proof established, not the trace it came from
+            if let Some(path) = &walkthrough.sequence {
+                let checked = FibonacciClaims::from_public_values(&public_values)
+                    .map_err(|e| e.to_string())
+                    .and_then(|claims| check_sequence(path, &claims).map_err(|e| e.to_string()));
+                match checked {
+                    Ok(entries) => println!(" Sequence file {}: {} entries match the proof", path.display(), entries),
+                    Err(e) => println!(" Sequence file {}: {}", path.display(), e),
+                }
+            }
+        },
+        Err(e) => {
+            println!(" Verification failed: {}", e);
+            return;
+        }
+    }
+    println!();
+
+    // The same proof presented with the result off by one
+    let mut off_by_one = public_values.clone();
+    off_by_one[2] += Val::ONE;
+    match verify_identified(&config, &air, &proof, &off_by_one) {
+        Ok(_) => println!(" Claim F({}) = {} (mod p): unexpectedly accepted", num_steps, off_by_one[2]),
+        Err(e) => println!(" Claim F({}) = {} (mod p): rejected ({})", num_steps, off_by_one[2], e),
+    }
+    println!();
+
+    run_step_count_demo(&config, &proof, &public_values);
+    println!();
+
+    #[cfg(feature = "goldilocks")]
+    {
+        run_goldilocks_demo(num_steps);
+        println!();
+    }
+
+    #[cfg(feature = "koala-bear")]
+    {
+        run_koala_bear_demo(num_steps);
+        println!();
+    }
+
+    #[cfg(feature = "mersenne-31")]
+    {
+        run_mersenne_31_demo(num_steps);
+        println!();
+    }
+
+    match options.hash {
+        CommitmentHash::Poseidon2 => {}
+        CommitmentHash::Keccak => {
+            run_keccak_demo(num_steps);
+            println!();
+        }
+        CommitmentHash::Blake3 => {
+            run_blake3_demo(num_steps);
+            println!();
+        }
+    }
+
+    run_proof_file_demo(options, &config, &proof, &public_values);
+    println!();
+
+    run_empty_demo(&config);
+    println!();
+
+    // A config rebuilt from the same seed and parameters must accept the proof;
+    // a different seed must not
+    println!(" Config compatibility:");
+    let rebuilt = create_config(options.seed, &options.params);
+    match verify_identified(&rebuilt, &air, &proof, &public_values) {
+        Ok(_) => println!("   Independently rebuilt config (seed {}): accepted", options.seed),
+        Err(e) => println!("   Independently rebuilt config (seed {}): rejected ({})", options.seed, e),
+    }
+    let other_seed = options.seed.wrapping_add(1);
+    let other = create_config(other_seed, &options.params);
+    match verify_identified(&other, &air, &proof, &public_values) {
+        Ok(_) => println!("   Config with seed {}: unexpectedly accepted", other_seed),
+        Err(e) => println!("   Config with seed {}: rejected ({})", other_seed, e),
+    }
+    println!();
+
+    // A future revision of the AIR must refuse this proof before any cryptography
+    println!(" AIR identity: {}", FIBONACCI_AIR_ID);
+    match check_air_id(&AirId::new("fibonacci", 6, 0, 0), proof.air_id) {
+        Ok(()) => println!("   fibonacci@6.0.0: unexpectedly accepted"),
+        Err(e) => println!("   fibonacci@6.0.0: {}", e),
+    }
+    println!();
+
+    if let Some(path) = &walkthrough.journal {
+        let descriptor = ConfigDescriptor::new(options.seed, &options.params);
+        let appended =
+            Journal::open(path).and_then(|mut journal| journal.append(&descriptor, &proof, &public_values));
+        match appended.and_then(|head| Ok((verify_journal(path, &head)?, head))) {
+            Ok((records, head)) => println!(" Journal: {} records verified, head {:?}...", records, &head[..2]),
+            Err(e) => println!(" Journal: {}", e),
+        }
+        run_journal_tamper_demo(&descriptor, &proof, &public_values);
+        println!();
+    }
+
+    run_coefficient_demo();
+    println!();
+
+    run_last_row_demo();
+    println!();
+
+    run_parity_demo();
+    println!();
+    run_counter_demo();
+}
+
+fn run_coefficient_demo() {
+    println!(" Hidden coefficients: x(n+1) = p * x(n) + q * x(n-1), p and q in [0, 15]");
+
+    let (a0, b0) = (Val::ZERO, Val::ONE);
+    let num_rows = 256;
+
+    // Pell numbers: only (p, q) = (2, 1) reaches this final value
+    let target = recurrence_final(a0, b0, 2, 1, num_rows);
+    println!("   Coefficients reaching the target: {:?}", find_coefficients(a0, b0, target, num_rows));
+    match prove_reachable(a0, b0, target, num_rows) {
+        Ok(proof) => match verify_reachable(a0, b0, target, num_rows, &proof) {
+            Ok(claims) => println!("   Proof verified: {}", claims),
+            Err(e) => println!("   Verification failed: {}", e),
+        },
+        Err(e) => println!("   Prover refused: {}", e),
+    }
+    // A vector missing the final value is a shape error, not a misread
+    match FibonacciClaims::from_coefficient_public_values(&[a0, b0], num_rows) {
+        Ok(claims) => println!("   Truncated public values unexpectedly read as {}", claims),
+        Err(e) => println!("   Truncated public values: {}", e),
+    }
+
+    // Coefficient 16 is out of range, and nothing in range lands on its value
+    let unreachable = recurrence_final(a0, b0, 16, 1, num_rows);
+    match generate_reachable_trace(a0, b0, unreachable, num_rows) {
+        Ok(trace) => println!("   Unexpectedly reached with {:?}", trace.meta),
+        Err(e) => println!("   Out-of-range target: {}", e),
+    }
+
+    // Switching p halfway through breaks the constancy constraint
+    let mut trace = generate_coefficient_trace(a0, b0, 2, 1, num_rows).expect("valid coefficients");
+    let width = trace.matrix.width();
+    let switched = generate_coefficient_trace(a0, b0, 3, 1, num_rows).expect("valid coefficients");
+    let half = num_rows / 2 * width;
+    trace.matrix.values[half..].copy_from_slice(&switched.matrix.values[half..]);
+    match validate_trace(&CoefficientAir, &trace.matrix, &trace.public_values) {
+        Ok(()) => println!("   Mid-trace switch unexpectedly accepted"),
+        Err(violation) => println!("   Mid-trace switch caught: {}", violation),
+    }
+}
+
+// A trace that starts one step late, at (F(1), F(2)) = (1, 1), checked
+// against public values claiming (0, 1) and against its own start
+fn run_boundary_demo() {
+    // Mostly padding: every padded row must satisfy the constraints too, and
+    // step counts either side of a power of two pad to the next one
+    for steps in [0, 1, 3, 100, 255, 256, 257] {
+        if let Ok(trace) = generate_fibonacci_trace::<Val>(steps) {
+            match validate_trace(&FibonacciAir, &trace.matrix, &trace.public_values) {
+                Ok(()) => println!(" {} steps in {} rows: all constraints hold", steps, trace.matrix.height()),
+                Err(violation) => println!(" {} steps in {} rows: {}", steps, trace.matrix.height(), violation),
+            }
+        }
+    }
+
+    let Ok(trace) = generate_fibonacci_trace::<Val>(101) else {
+        return;
+    };
+    let mut shifted = trace.matrix.clone();
+    shifted.values.drain(..NUM_FIBONACCI_COLS);
+    let last = shifted.values[shifted.values.len() - NUM_FIBONACCI_COLS..].to_vec();
+    shifted.values.extend(last);
+    // One step fewer from the new start
+    for row in shifted.values.chunks_exact_mut(NUM_FIBONACCI_COLS) {
+        row[3] -= Val::ONE;
+    }
+
+    println!(" Boundary check, trace starting at (1, 1):");
+    let (result, steps) = (trace.meta.final_value, Val::from_u64(100));
+    for claim in [[Val::ZERO, Val::ONE, result, steps], [Val::ONE, Val::ONE, result, steps]] {
+        match validate_trace(&FibonacciAir, &shifted, &claim) {
+            Ok(()) => println!("   claiming {:?}: all constraints hold", claim),
+            Err(violation) => println!("   claiming {:?}: {}", claim, violation),
+        }
+    }
+}
+
+// Zero steps prove as the canonical all-padding trace. The proof verifies as
+// the empty computation and under no other claim, and a one-step proof cannot
+// be passed off as empty.
+fn run_empty_demo(config: &MyConfig) {
+    let traces = (generate_fibonacci_trace::<Val>(0), generate_fibonacci_trace::<Val>(1));
+    let (Ok(empty), Ok(one_step)) = traces else {
+        println!(" Could not generate the empty trace");
+        return;
+    };
+    let claims = match FibonacciClaims::from_public_values(&empty.public_values) {
+        Ok(claims) => claims,
+        Err(e) => {
+            println!(" Empty claims: {}", e);
+            return;
+        }
+    };
+    println!(" Empty computation, {} padding rows: {}", empty.matrix.height(), claims);
+    let proof = prove_identified(config, &FibonacciAir, empty.matrix, &empty.public_values);
+    let one_step_proof = prove_identified(config, &FibonacciAir, one_step.matrix, &one_step.public_values);
+
+    let as_one_step = FibonacciClaims { result: Val::ONE, num_steps: 1, ..claims };
+    let cases = [
+        ("empty proof as empty", &proof, claims, true),
+        ("empty proof as one step", &proof, as_one_step, false),
+        ("one-step proof as empty", &one_step_proof, claims, false),
+    ];
+    for (name, proof, claims, expected) in cases {
+        match (verify_sequence(config, proof, &claims), expected) {
+            (Ok(_), true) => println!("   {}: accepted", name),
+            (Ok(_), false) => println!("   {}: unexpectedly accepted", name),
+            (Err(e), true) => println!("   {}: unexpectedly rejected ({})", name, e),
+            (Err(e), false) => println!("   {}: rejected ({})", name, e),
+        }
+    }
+}
+
+// The proof's step count is a public value bound by the count column: the
+// honest count is accepted, one step either way is not, and neither is a count
+// longer than the trace has rows. The claimed result is left as proved, so only
+// the step count is wrong.
+fn run_step_count_demo(config: &MyConfig, proof: &IdentifiedProof, public_values: &[Val]) {
+    let height = 1u64 << proof.proof.degree_bits;
+    println!(" Step count claims ({} rows):", height);
+    let claimed = public_values[3];
+    let cases = [
+        ("as proved", claimed, true),
+        ("one step fewer", claimed - Val::ONE, false),
+        ("one step more", claimed + Val::ONE, false),
+        ("as many steps as rows", Val::from_u64(height), false),
+    ];
+    for (name, num_steps, expected) in cases {
+        let mut claim = public_values.to_vec();
+        claim[3] = num_steps;
+        match (verify_identified(config, &FibonacciAir, proof, &claim), expected) {
+            (Ok(_), true) => println!("   {} ({}): accepted", name, num_steps),
+            (Ok(_), false) => println!("   {} ({}): unexpectedly accepted", name, num_steps),
+            (Err(e), true) => println!("   {} ({}): unexpectedly rejected ({})", name, num_steps, e),
+            (Err(e), false) => println!("   {} ({}): rejected ({})", name, num_steps, e),
+        }
+    }
+}
+
+// Plants one bad transition deep in a 2^20-row trace and shrinks to it
+fn run_shrink_demo() {
+    let mut trace = generate_fibonacci_trace::<Val>(1 << 20).expect("2^20 steps fit in memory");
+    let bad_row = 700_000;
+    trace.matrix.values[bad_row * NUM_FIBONACCI_COLS + 1] += Val::ONE;
+
+    println!(" Planted bad value at row {} of {}:", bad_row, trace.matrix.height());
+    match shrink_failing_trace(&FibonacciAir, &trace.matrix, &trace.public_values) {
+        Some(failure) => {
+            println!("   Shrunk to {}", failure);
+            match failure.reproducer(&trace.matrix) {
+                Ok(reproducer) => println!("   Reproducer: {} rows, padded to a power of two", reproducer.height()),
+                Err(e) => println!("   No reproducer: {}", e),
+            }
+        }
+        None => println!("   Not detected"),
+    }
+}
+
+// Pins which row when_last_row sees for exact and padded traces
+fn run_last_row_demo() {
+    println!(" when_last_row semantics (public value = number of real rows):");
+    let cases = [
+        ("exact fill, 256 rows", 256, PaddingStrategy::Zero),
+        ("100 rows, zero padding", 100, PaddingStrategy::Zero),
+        ("100 rows, continuation padding", 100, PaddingStrategy::Continue),
+    ];
+    for (name, real_rows, padding) in cases {
+        let trace = generate_last_row_trace::<Val>(real_rows, padding).expect("non-empty trace");
+        for binding in [LastRowBinding::Physical, LastRowBinding::LastReal] {
+            let air = LastRowDemoAir { binding };
+            let result = match validate_trace(&air, &trace.matrix, &trace.public_values) {
+                Ok(()) => "holds".to_string(),
+                Err(violation) => violation.to_string(),
+            };
+            println!("   {:<32} {:?}: {}", name, binding, result);
+        }
+    }
+
+    // The real-row binding survives a full prove/verify on a zero-padded trace
+    let air = LastRowDemoAir { binding: LastRowBinding::LastReal };
+    let trace = generate_last_row_trace::<Val>(100, PaddingStrategy::Zero).expect("non-empty trace");
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, &air, trace.matrix, &trace.public_values);
+    match verify(&config, &air, &proof, &trace.public_values) {
+        Ok(()) => println!("   LastReal binding on zero padding: proof verified"),
+        Err(e) => println!("   LastReal binding on zero padding: verification failed: {:?}", e),
+    }
+}
+
+fn run_parity_demo() {
+    println!(" Parity column: b = 2 * half + parity, half range-checked");
+    let trace = match generate_parity_trace::<Val>(256) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   {}", e);
+            return;
+        }
+    };
+    // Row i holds F(i + 1)
+    let view = TraceView::named(&trace.matrix, &ParityAir);
+    let parity_col = view.column_index("parity").expect("ParityAir names its parity column");
+
+    // Integer parity follows 1, 1, 0 from F(1) while F(n) < p (n <= 46)
+    let period_three = (0..46).all(|row| view.get(row, parity_col) == Some(Val::from_bool(row % 3 != 2)));
+    println!("   F(1)..F(46) follow the period-3 parity pattern: {}", period_three);
+    println!("   Public parity of F(256) mod p: {}", trace.public_values[0]);
+
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, &ParityAir, trace.matrix.clone(), &trace.public_values);
+    match verify(&config, &ParityAir, &proof, &trace.public_values) {
+        Ok(()) => println!("   Proof verified"),
+        Err(e) => println!("   Verification failed: {:?}", e),
+    }
+
+    let width = trace.matrix.width();
+    let mut flipped = trace.matrix;
+    flipped.values[10 * width + parity_col] = Val::ONE - flipped.values[10 * width + parity_col];
+    match validate_trace(&ParityAir, &flipped, &trace.public_values) {
+        Ok(()) => println!("   Flipped parity bit unexpectedly accepted"),
+        Err(violation) => println!("   Flipped parity bit rejected: {}", violation),
+    }
+}
+
+// Width 1 end to end: parallel generation, lint, constraint check, prove/verify
+fn run_counter_demo() {
+    println!(" Width-1 counter: x(0) = 0, x(i + 1) = x(i) + 1");
+    let trace = match generate_counter_trace::<Val>(1 << 12) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   {}", e);
+            return;
+        }
+    };
+    println!(
+        "   {} x {} trace, public count {}",
+        trace.matrix.height(),
+        trace.matrix.width(),
+        trace.public_values[0]
+    );
+    println!("   Lint: {}", lint_air(&CounterAir));
+    match validate_trace(&CounterAir, &trace.matrix, &trace.public_values) {
+        Ok(()) => println!("   Trace check: all constraints hold"),
+        Err(violation) => println!("   Trace check: {}", violation),
+    }
+
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, &CounterAir, trace.matrix.clone(), &trace.public_values);
+    match verify(&config, &CounterAir, &proof, &trace.public_values) {
+        Ok(()) => println!("   Proof verified"),
+        Err(e) => println!("   Verification failed: {:?}", e),
+    }
+
+    let mut skipped = trace.matrix;
+    skipped.values[1000] += Val::ONE;
+    match shrink_failing_trace(&CounterAir, &skipped, &trace.public_values) {
+        Some(failure) => println!("   Skipped count found at: {}", failure),
+        None => println!("   Skipped count unexpectedly accepted"),
+    }
+}
+
+// Hands out at most `chunk` bytes per read
+struct ChunkedReader<'a> {
+    bytes: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for ChunkedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.bytes.len());
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        self.bytes = &self.bytes[n..];
+        Ok(n)
+    }
+}
+
+struct DroppedConnection;
+
+impl Read for DroppedConnection {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"))
+    }
+}
+
+// The prover's half of a hand-off: proves under `descriptor` and returns
+// only what would be sent, the encoded proof and its public values
+fn prove_for_handoff(descriptor: &ConfigDescriptor, num_steps: usize) -> Result<(Vec<u8>, PublicValues), String> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps).map_err(|e| e.to_string())?;
+    let (proof, public_values) = prove_fibonacci_trace(&create_config_from(descriptor), trace, &mut ());
+    let bytes = encode_proof(descriptor, &proof).map_err(|e| e.to_string())?;
+    Ok((bytes, public_values))
+}
+
+// Writes the proof to DEFAULT_PROOF_PATH, reads it back and verifies the copy,
+// then shows a truncated copy is refused with an error
+fn run_proof_file_demo(options: &ProverOptions, config: &MyConfig, proof: &IdentifiedProof, public_values: &[Val]) {
+    let path = Path::new(DEFAULT_PROOF_PATH);
+    let descriptor = ConfigDescriptor::new(options.seed, &options.params);
+    let fingerprint = ConfigFingerprint::of(&descriptor);
+    if let Err(e) = save_proof(path, &descriptor, proof) {
+        println!(" Could not write {}: {}", path.display(), e);
+        return;
+    }
+    let size = std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    println!(" Proof written to {} ({} bytes, config {})", path.display(), size, fingerprint);
+    println!(
+        "   Check it with: verify {} --public-values {}",
+        path.display(),
+        format_public_values(public_values)
+    );
+    match load_proof::<IdentifiedProof>(path, fingerprint) {
+        Ok(loaded) => match verify_identified(config, &FibonacciAir, &loaded, public_values) {
+            Ok(_) => println!("   Reloaded proof: verified"),
+            Err(e) => println!("   Reloaded proof: rejected ({})", e),
+        },
+        Err(e) => println!("   Reloaded proof: {}", e),
+    }
+
+    let truncated = std::env::temp_dir().join(format!("fibonacci-truncated-{}.proof", std::process::id()));
+    let written = std::fs::read(path).and_then(|bytes| std::fs::write(&truncated, &bytes[..bytes.len() / 2]));
+    match written {
+        Ok(()) => match load_proof::<IdentifiedProof>(&truncated, fingerprint) {
+            Ok(_) => println!("   Truncated proof: unexpectedly loaded"),
+            Err(e) => println!("   Truncated proof: {}", e),
+        },
+        Err(e) => println!("   Scratch copy: {}", e),
+    }
+    let _ = std::fs::remove_file(&truncated);
+
+    // Streamed the way a network body arrives: a few bytes per read, and a
+    // connection that drops halfway
+    if let Ok(bytes) = std::fs::read(path) {
+        let chunked = ChunkedReader { bytes: &bytes, chunk: 7 };
+        match load_proof_from_reader::<IdentifiedProof>(chunked, fingerprint) {
+            Ok(loaded) => match verify_identified(config, &FibonacciAir, &loaded, public_values) {
+                Ok(_) => println!("   Streamed 7 bytes per read: verified"),
+                Err(e) => println!("   Streamed 7 bytes per read: rejected ({})", e),
+            },
+            Err(e) => println!("   Streamed 7 bytes per read: {}", e),
+        }
+        let dropped = bytes[..bytes.len() / 2].chain(DroppedConnection);
+        match load_proof_from_reader::<IdentifiedProof>(dropped, fingerprint) {
+            Ok(_) => println!("   Connection dropped halfway: unexpectedly loaded"),
+            Err(e) => println!("   Connection dropped halfway: {}", e),
+        }
+    }
+
+    // A proof made with twice the blowup is refused from its envelope alone,
+    // before any of it reaches FRI
+    let wider = CostParams { log_blowup: options.params.log_blowup + 1, ..options.params };
+    let scratch = std::env::temp_dir().join(format!("fibonacci-wider-{}.proof", std::process::id()));
+    if let Ok(trace) = generate_fibonacci_trace::<Val>(8) {
+        let (wider_proof, _) = prove_fibonacci_trace(&create_config(options.seed, &wider), trace, &mut ());
+        let saved = save_proof(&scratch, &ConfigDescriptor::new(options.seed, &wider), &wider_proof);
+        let name =
+            format!("Blowup {} proof, blowup {} verifier", 1 << wider.log_blowup, 1 << options.params.log_blowup);
+        match saved.and_then(|()| load_proof::<IdentifiedProof>(&scratch, fingerprint)) {
+            Ok(_) => println!("   {}: unexpectedly loaded", name),
+            Err(e) => println!("   {}: {}", name, e),
+        }
+    }
+    let _ = std::fs::remove_file(&scratch);
+
+    // A prover and a verifier sharing nothing but the bytes. The descriptor
+    // travels with the proof, so a non-default seed and FRI setup need no
+    // flags; editing it breaks the proof, as the verifier's challenges change.
+    let handoff = ConfigDescriptor { perm_seed: 7, log_blowup: 2, num_queries: 3, pow_bits: 2, min_log_height: 8 };
+    match prove_for_handoff(&handoff, 20) {
+        Ok((bytes, public_values)) => {
+            match verify_described(&bytes, &public_values) {
+                Ok(descriptor) => println!("   Hand-off under {}: verified", descriptor),
+                Err(e) => println!("   Hand-off: rejected ({})", e),
+            }
+            let reseeded = load_described_proof_from_reader::<IdentifiedProof>(bytes.as_slice())
+                .and_then(|(_, proof)| encode_proof(&ConfigDescriptor { perm_seed: 8, ..handoff }, &proof));
+            match reseeded.map(|bytes| verify_described(&bytes, &public_values)) {
+                Ok(Ok(_)) => println!("   Hand-off with the seed edited: unexpectedly verified"),
+                Ok(Err(e)) => println!("   Hand-off with the seed edited: {}", e),
+                Err(e) => println!("   Hand-off with the seed edited: {}", e),
+            }
+        }
+        Err(e) => println!("   Hand-off: {}", e),
+    }
+
+    // The same proof as pasteable text, then with one body digit changed and
+    // with no armor at all
+    let armored = match proof_to_string(&FIBONACCI_AIR_ID, &descriptor, proof) {
+        Ok(armored) => armored,
+        Err(e) => {
+            println!("   Armored proof: {}", e);
+            return;
+        }
+    };
+    let lines: Vec<&str> = armored.lines().collect();
+    println!("   Armored proof: {} lines, header {:?}", lines.len(), lines[0]);
+    match proof_from_string::<IdentifiedProof>(&armored, fingerprint) {
+        Ok((air, loaded)) => match verify_identified(config, &FibonacciAir, &loaded, public_values) {
+            Ok(_) => println!("   Armored round trip ({}): verified", air),
+            Err(e) => println!("   Armored round trip ({}): rejected ({})", air, e),
+        },
+        Err(e) => println!("   Armored round trip: {}", e),
+    }
+    let digit = if lines[1].starts_with('0') { "1" } else { "0" };
+    let flipped = format!("{}{}", digit, &lines[1][1..]);
+    let corrupted = armored.replacen(lines[1], &flipped, 1);
+    for (name, text) in [("One body digit changed", corrupted.as_str()), ("Not a proof", "hello")] {
+        match proof_from_string::<IdentifiedProof>(text, fingerprint) {
+            Ok(_) => println!("   {}: unexpectedly decoded", name),
+            Err(e) => println!("   {}: {}", name, e),
+        }
+    }
+}
+
+// Ten records in a scratch journal, then one edited and one removed: each
+// break is reported at the first record that no longer links
+fn run_journal_tamper_demo(descriptor: &ConfigDescriptor, proof: &IdentifiedProof, public_values: &[Val]) {
+    let dir = std::env::temp_dir().join(format!("fibonacci-journal-{}", std::process::id()));
+    let path = dir.join("journal.log");
+    let _ = std::fs::remove_dir_all(&dir);
+    let written = std::fs::create_dir_all(&dir).map_err(Into::into).and_then(|()| {
+        let mut journal = Journal::open(&path)?;
+        (0..10).try_for_each(|_| journal.append(descriptor, proof, public_values).map(|_| ()))
+    });
+    let contents = match written.and_then(|()| Ok(std::fs::read_to_string(&path)?)) {
+        Ok(contents) => contents,
+        Err(e) => {
+            println!("   Scratch journal: {}", e);
+            return;
+        }
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let mut edited = lines.clone();
+    let altered = edited[5].replacen(',', "9,", 1);
+    edited[5] = &altered;
+    let mut removed = lines.clone();
+    removed.remove(5);
+    for (name, records) in [("intact", lines), ("record 5 edited", edited), ("record 5 removed", removed)] {
+        let result = std::fs::write(&path, records.join("\n") + "\n")
+            .map_err(Into::into)
+            .and_then(|()| journal_head(&path));
+        match result {
+            Ok((count, _)) => println!("   {}: {} records link", name, count),
+            Err(e) => println!("   {}: {}", name, e),
+        }
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// The main proof's statement again over Goldilocks, then F(93), the largest
+// Fibonacci number below 2^64: exact in Goldilocks, reduced mod p in BabyBear
+#[cfg(feature = "goldilocks")]
+fn run_goldilocks_demo(num_steps: usize) {
+    use p3_field::PrimeField64;
+    use p3_goldilocks::Goldilocks;
+
+    use crate::goldilocks::{prove_fibonacci_goldilocks, verify_fibonacci_goldilocks};
+
+    println!(" Goldilocks field:");
+    match prove_fibonacci_goldilocks(num_steps) {
+        Ok((proof, public_values)) => match verify_fibonacci_goldilocks(&proof, &public_values) {
+            Ok(()) => println!("   F({}) = {}: proved and verified", num_steps, public_values[2]),
+            Err(e) => println!("   F({}): verification failed: {}", num_steps, e),
+        },
+        Err(e) => println!("   Could not generate the trace: {}", e),
+    }
+    let finals = generate_fibonacci_trace::<Val>(93)
+        .and_then(|baby_bear| Ok((baby_bear, generate_fibonacci_trace::<Goldilocks>(93)?)));
+    match finals {
+        Ok((baby_bear, goldilocks)) => {
+            let b = baby_bear.meta.final_value.as_canonical_u64();
+            let g = goldilocks.meta.final_value.as_canonical_u64();
+            println!("   F(93) in BabyBear:   {}", b);
+            println!("   F(93) in Goldilocks: {} (exact: {})", g, g == 12_200_160_415_121_876_738);
+            println!("   The fields agree on F(93): {}", b == g);
+        }
+        Err(e) => println!("   Could not generate the F(93) traces: {}", e),
+    }
+}
+
+#[cfg(feature = "koala-bear")]
+fn run_koala_bear_demo(num_steps: usize) {
+    use crate::koala_bear::{prove_fibonacci_koala_bear, verify_fibonacci_koala_bear};
+
+    println!(" KoalaBear field:");
+    match prove_fibonacci_koala_bear(num_steps) {
+        Ok((proof, public_values)) => match verify_fibonacci_koala_bear(&proof, &public_values) {
+            Ok(()) => println!("   F({}) = {} (mod p): proved and verified", num_steps, public_values[2]),
+            Err(e) => println!("   F({}): verification failed: {}", num_steps, e),
+        },
+        Err(e) => println!("   Could not generate the trace: {}", e),
+    }
+}
+
+// The main proof's statement over Mersenne31 with the circle PCS, and the size
+// of that proof next to the same statement under the BabyBear two-adic config
+#[cfg(feature = "mersenne-31")]
+fn run_mersenne_31_demo(num_steps: usize) {
+    use crate::mersenne_31::{prove_fibonacci_mersenne_31, verify_fibonacci_mersenne_31};
+
+    println!(" Mersenne31 field, circle PCS:");
+    let (proof, public_values) = match prove_fibonacci_mersenne_31(num_steps) {
+        Ok(proven) => proven,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    match verify_fibonacci_mersenne_31(&proof, &public_values) {
+        Ok(()) => println!("   F({}) = {} (mod p): proved and verified", num_steps, public_values[2]),
+        Err(e) => println!("   F({}): verification failed: {}", num_steps, e),
+    }
+
+    let config = create_default_config(SecurityLevel::Test);
+    let baby_bear = match generate_fibonacci_trace::<Val>(num_steps) {
+        Ok(trace) => prove(&config, &FibonacciAir, trace.matrix, &trace.public_values),
+        Err(e) => {
+            println!("   Could not generate the BabyBear trace: {}", e);
+            return;
+        }
+    };
+    match (encoded_size(&baby_bear), encoded_size(&proof)) {
+        (Ok(two_adic), Ok(circle)) => {
+            println!("   Proof size, BabyBear two-adic: {} bytes", two_adic);
+            println!("   Proof size, Mersenne31 circle: {} bytes", circle);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("   Could not size the proofs: {}", e),
+    }
+}
+
+// --hash keccak: the main proof's statement under Keccak-256 Merkle trees and
+// challenger, with its size next to the same statement under Poseidon2
+fn run_keccak_demo(num_steps: usize) {
+    println!(" Keccak-256 commitments:");
+    let (proof, public_values) = match prove_fibonacci_keccak(num_steps) {
+        Ok(proven) => proven,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    match verify_fibonacci_keccak(&proof, &public_values) {
+        Ok(()) => println!("   F({}) = {} (mod p): proved and verified", num_steps, public_values[2]),
+        Err(e) => println!("   F({}): verification failed: {}", num_steps, e),
+    }
+
+    let config = create_default_config(SecurityLevel::Test);
+    let poseidon2 = match generate_fibonacci_trace::<Val>(num_steps) {
+        Ok(trace) => prove(&config, &FibonacciAir, trace.matrix, &trace.public_values),
+        Err(e) => {
+            println!("   Could not generate the Poseidon2 trace: {}", e);
+            return;
+        }
+    };
+    match (encoded_size(&poseidon2), encoded_size(&proof)) {
+        (Ok(poseidon2), Ok(keccak)) => {
+            println!("   Proof size, Poseidon2: {} bytes", poseidon2);
+            println!("   Proof size, Keccak-256: {} bytes", keccak);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("   Could not size the proofs: {}", e),
+    }
+}
+
+// --hash blake3: the main proof's statement under Blake3 Merkle trees and
+// challenger, then the same proof in front of the Poseidon2 verifier
+fn run_blake3_demo(num_steps: usize) {
+    println!(" Blake3 commitments:");
+    let (proof, public_values) = match prove_fibonacci_blake3(num_steps) {
+        Ok(proven) => proven,
+        Err(e) => {
+            println!("   Could not generate the trace: {}", e);
+            return;
+        }
+    };
+    match verify_fibonacci_blake3(&proof, &public_values) {
+        Ok(()) => println!("   F({}) = {} (mod p): proved and verified", num_steps, public_values[2]),
+        Err(e) => println!("   F({}): verification failed: {}", num_steps, e),
+    }
+    match verify_as_poseidon2(&proof, &public_values) {
+        Ok(()) => println!("   Poseidon2 verifier: unexpectedly accepted"),
+        Err(e) => println!("   Poseidon2 verifier: rejected ({})", e),
+    }
+}
@@ -0,0 +1,131 @@
+// MyConfig made zero-knowledge. MyConfig commits the low-degree extension of
+// the raw trace, so every opened row is a function of the witness alone;
+// stark_config's ZkConfig randomizes the trace polynomials, commits extra
+// random codewords and salts every Merkle leaf, so neither the commitments nor
+// the openings depend on the witness alone. p3-uni-stark sees the PCS is
+// hiding and pads the trace itself: the AIR and the trace generator are
+// unchanged, and a verifier needs no blinding.
+//
+// prove_fibonacci_with takes the choice as a flag and draws the blinding from
+// the OS, so a blinded proof hides the witness; BlindingSource::Seeded is for
+// the self-test, which needs the same proof on every run. As with the keccak
+// module, proof files and AIR ids stay on MyConfig.
+use p3_uni_stark::{Proof, prove, verify};
+pub use stark_config::{BlindingSource, NUM_RANDOM_CODEWORDS, ZkChallengeMmcs, ZkConfig, ZkPcs, ZkValMmcs};
+use stark_config::FriChoice;
+
+use crate::air_id::{AirIdError, IdentifiedProof};
+use crate::cost::SecurityLevel;
+use crate::journal::Digest;
+use crate::trace::SizingError;
+use crate::{
+    DEFAULT_CONFIG_SEED, FibonacciAir, PublicValues, Val, VerifyError, generate_fibonacci_trace, prove_fibonacci,
+    verify_fibonacci,
+};
+
+// create_default_config(level), hiding. The randomized trace is committed
+// over twice the rows, so the blowup is one step above the level's.
+pub fn create_zk_config(level: SecurityLevel, blinding: BlindingSource) -> ZkConfig {
+    let fri = level.fri_choice();
+    let fri = FriChoice { log_blowup: fri.log_blowup + 1, ..fri };
+    stark_config::create_zk_config(DEFAULT_CONFIG_SEED, fri, blinding)
+}
+
+// A proof from prove_fibonacci_with
+pub enum FibonacciProof {
+    Plain(IdentifiedProof),
+    Blinded(Proof<ZkConfig>),
+}
+
+impl FibonacciProof {
+    pub fn trace_commitment(&self) -> Digest {
+        match self {
+            Self::Plain(proof) => proof.proof.commitments.trace.clone().into(),
+            Self::Blinded(proof) => proof.commitments.trace.clone().into(),
+        }
+    }
+}
+
+// prove_fibonacci under the hiding config, blinded from `blinding`, returning
+// the public values [F(0), F(1), F(num_steps), num_steps]
+pub fn prove_fibonacci_zk(
+    num_steps: usize,
+    blinding: BlindingSource,
+) -> Result<(Proof<ZkConfig>, PublicValues), SizingError> {
+    let trace = generate_fibonacci_trace::<Val>(num_steps)?;
+    let config = create_zk_config(SecurityLevel::Test, blinding);
+    let proof = prove(&config, &FibonacciAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+// prove_fibonacci, or with `enable_zk` the same statement blinded from the OS
+// RNG. Two blinded proofs of one trace share no commitment; two plain ones
+// encode byte for byte the same.
+pub fn prove_fibonacci_with(num_steps: usize, enable_zk: bool) -> Result<(FibonacciProof, PublicValues), SizingError> {
+    if enable_zk {
+        let (proof, public_values) = prove_fibonacci_zk(num_steps, BlindingSource::Os)?;
+        Ok((FibonacciProof::Blinded(proof), public_values))
+    } else {
+        let (proof, public_values) = prove_fibonacci(num_steps)?;
+        Ok((FibonacciProof::Plain(proof), public_values))
+    }
+}
+
+// The verifier samples no blinding, so any source rebuilds its config
+pub fn verify_fibonacci_zk(proof: &Proof<ZkConfig>, public_values: &[Val]) -> Result<(), String> {
+    let config = create_zk_config(SecurityLevel::Test, BlindingSource::Seeded(0));
+    verify(&config, &FibonacciAir, proof, public_values).map_err(|e| format!("{:?}", e))
+}
+
+// Either kind of proof from prove_fibonacci_with, so a caller verifies a
+// blinded proof as it would a plain one
+pub fn verify_fibonacci_proof(proof: &FibonacciProof, public_values: &[Val]) -> Result<(), VerifyError> {
+    match proof {
+        FibonacciProof::Plain(proof) => verify_fibonacci(proof, public_values),
+        FibonacciProof::Blinded(proof) => {
+            verify_fibonacci_zk(proof, public_values).map_err(|e| VerifyError::Proof(AirIdError::Verification(e)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::ConfigDescriptor;
+    use crate::cost::CostParams;
+    use crate::proof_file::encode_proof;
+
+    #[test]
+    fn blinded_proofs_verify_and_share_no_commitment() {
+        let (first, public_values) = prove_fibonacci_with(100, true).unwrap();
+        let (second, _) = prove_fibonacci_with(100, true).unwrap();
+        assert_eq!(verify_fibonacci_proof(&first, &public_values), Ok(()));
+        assert_eq!(verify_fibonacci_proof(&second, &public_values), Ok(()));
+        assert_ne!(first.trace_commitment(), second.trace_commitment());
+    }
+
+    #[test]
+    fn plain_proofs_are_byte_identical() {
+        let descriptor = ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test());
+        let encode = |proof: FibonacciProof| match proof {
+            FibonacciProof::Plain(proof) => encode_proof(&descriptor, &proof).unwrap(),
+            FibonacciProof::Blinded(_) => panic!("proved with zk disabled"),
+        };
+        let (first, public_values) = prove_fibonacci_with(100, false).unwrap();
+        assert_eq!(verify_fibonacci_proof(&first, &public_values), Ok(()));
+        let second = prove_fibonacci_with(100, false).unwrap().0;
+        assert_eq!(encode(first), encode(second));
+    }
+
+    #[test]
+    fn a_seeded_blinding_repeats_and_a_wrong_claim_is_refused() {
+        let (first, public_values) = prove_fibonacci_zk(100, BlindingSource::Seeded(7)).unwrap();
+        let (second, _) = prove_fibonacci_zk(100, BlindingSource::Seeded(7)).unwrap();
+        assert_eq!(first.commitments.trace, second.commitments.trace);
+        let mut wrong = public_values.clone();
+        wrong[2] += Val::ONE;
+        assert!(verify_fibonacci_zk(&first, &wrong).is_err());
+    }
+}
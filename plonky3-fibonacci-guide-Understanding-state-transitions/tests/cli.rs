@@ -0,0 +1,135 @@
+// The binary as a user runs it: saved proofs verified and inspected through
+// the subcommands, and, built with each feature set in turn, a flag whose
+// feature is missing naming the feature to recompile with while the same flag
+// runs when the feature is in.
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use fibonacci_proof::claims::format_public_values;
+use fibonacci_proof::cost::CostParams;
+use fibonacci_proof::proof_file::save_proof;
+use fibonacci_proof::provenance::PROVENANCE;
+use fibonacci_proof::{ConfigDescriptor, DEFAULT_CONFIG_SEED, prove_fibonacci};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fibonacci_proof")).args(args).output().expect("the binary should start")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// A proof of F(20) saved under the test parameters, with its public values in
+// the form --public-values takes
+fn saved_proof(name: &str) -> (PathBuf, String) {
+    let path = std::env::temp_dir().join(format!("fibonacci-{}-{}.proof", name, std::process::id()));
+    let (proof, public_values) = prove_fibonacci(20).unwrap();
+    save_proof(&path, &ConfigDescriptor::new(DEFAULT_CONFIG_SEED, &CostParams::test()), &proof).unwrap();
+    (path, format_public_values(&public_values))
+}
+
+// (feature, flag, arguments that exercise it cheaply)
+const GATED: [(&str, &str, &[&str]); 4] = [
+    ("exercises", "--exercises", &["--exercises"]),
+    ("tutorial", "--stage", &["--stage", "1"]),
+    ("koala-bear", "--compare-fields", &["--compare-fields"]),
+    ("regen-fixtures", "regen-fixtures", &["regen-fixtures"]),
+];
+
+fn enabled(feature: &str) -> bool {
+    match feature {
+        "exercises" => cfg!(feature = "exercises"),
+        "tutorial" => cfg!(feature = "tutorial"),
+        "koala-bear" => cfg!(feature = "koala-bear"),
+        "regen-fixtures" => cfg!(feature = "regen-fixtures"),
+        _ => unreachable!("{} is not in GATED", feature),
+    }
+}
+
+#[test]
+fn gated_flags_explain_a_missing_feature_or_run() {
+    for (feature, flag, args) in GATED {
+        let output = run(args);
+        let explanation = format!("{} is not in this build; recompile with --features {}", flag, feature);
+        if enabled(feature) {
+            assert!(!stdout(&output).contains(&explanation), "{}: {}", flag, stdout(&output));
+        } else {
+            assert!(!output.status.success(), "{} succeeded without {}", flag, feature);
+            assert!(stdout(&output).contains(&explanation), "{}: {}", flag, stdout(&output));
+        }
+    }
+}
+
+#[test]
+fn version_verbose_lists_the_features() {
+    let output = run(&["--version", "--verbose"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with(&format!("fibonacci_proof {}", env!("CARGO_PKG_VERSION"))), "{}", text);
+    let mark = if cfg!(feature = "tutorial") { "+tutorial" } else { "-tutorial" };
+    assert!(text.contains(mark), "{}", text);
+
+    let plain = stdout(&run(&["--version"]));
+    assert_eq!(plain.lines().count(), 1, "{}", plain);
+}
+
+#[test]
+fn inspect_shows_the_provenance_digest() {
+    let (path, _) = saved_proof("inspect");
+    let output = run(&["inspect", path.to_str().unwrap()]);
+    let _ = std::fs::remove_file(&path);
+    assert!(output.status.success(), "{}", stdout(&output));
+    let text = stdout(&output);
+    assert!(text.contains(&format!("Provenance digest: {:016x}", PROVENANCE.digest())), "{}", text);
+    assert!(text.contains("Made by this build"), "{}", text);
+}
+
+#[test]
+fn verify_accepts_a_saved_proof() {
+    let (path, public_values) = saved_proof("valid");
+    let output = run(&["verify", path.to_str().unwrap(), "--public-values", &public_values]);
+    let _ = std::fs::remove_file(&path);
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(stdout(&output).contains(&format!("Verified {}", path.display())), "{}", stdout(&output));
+}
+
+#[test]
+fn verify_rejects_a_corrupted_or_misclaimed_proof() {
+    let (path, public_values) = saved_proof("corrupt");
+    let path_arg = path.to_str().unwrap();
+    let wrong_claim = run(&["verify", path_arg, "--public-values", "0,1,7,20"]);
+    let mut bytes = std::fs::read(&path).unwrap();
+    // Inside the proof body, past the envelope header
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 1;
+    std::fs::write(&path, &bytes).unwrap();
+    let flipped = run(&["verify", path_arg, "--public-values", &public_values]);
+    std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    let truncated = run(&["verify", path_arg, "--public-values", &public_values]);
+    let _ = std::fs::remove_file(&path);
+    for (case, output) in [("wrong claim", wrong_claim), ("flipped byte", flipped), ("truncated", truncated)] {
+        assert!(!output.status.success(), "{}: {}", case, stdout(&output));
+        assert!(stdout(&output).contains(&format!("Rejected {}", path.display())), "{}: {}", case, stdout(&output));
+    }
+}
+
+#[test]
+fn soak_is_a_subcommand_whose_exit_status_follows_the_result() {
+    // Zero minutes takes no samples, so it passes wherever memory can be read
+    let output = run(&["soak", "--minutes", "0"]);
+    let measurable = cfg!(any(target_os = "linux", feature = "mem-stats"));
+    assert_eq!(output.status.success(), measurable, "{}", stdout(&output));
+    let verdict = if measurable { "Soak passed" } else { "Soak failed: no memory source" };
+    assert!(stdout(&output).contains(verdict), "{}", stdout(&output));
+
+    assert!(!run(&["--soak", "0"]).status.success());
+}
+
+#[cfg(feature = "exercises")]
+#[test]
+fn exercises_fail_until_every_slot_is_filled() {
+    let output = run(&["--exercises"]);
+    let text = stdout(&output);
+    assert_eq!(output.status.success(), cfg!(feature = "solutions"), "{}", text);
+    assert!(text.contains("1. boundary constraint") && text.contains("2. padding selector"), "{}", text);
+}
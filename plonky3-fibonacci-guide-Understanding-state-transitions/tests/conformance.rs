@@ -0,0 +1,46 @@
+// The conformance suite as one test per registered example, so a failure
+// names its example and the others still run. A new example fails
+// every_registered_example_has_a_case until it is listed here.
+use fibonacci_proof::report::EXAMPLES;
+
+fn check(name: &str) {
+    let example = EXAMPLES
+        .iter()
+        .find(|example| example.name == name)
+        .unwrap_or_else(|| panic!("{} is not in report::EXAMPLES", name));
+    let report = (example.conformance)(example.name);
+    assert!(report.passed(), "{}", report);
+}
+
+macro_rules! conformance_cases {
+    ($($test:ident => $name:expr,)*) => {
+        const CASES: &[&str] = &[$($name),*];
+        $(
+            #[test]
+            fn $test() {
+                check($name);
+            }
+        )*
+    };
+}
+
+conformance_cases! {
+    fibonacci => "FibonacciAir, 100 steps",
+    coefficients => "CoefficientAir, p = q = 1, 256 rows",
+    counter => "CounterAir, 256 rows",
+    parity => "ParityAir, 256 rows",
+    last_row => "LastRowDemoAir, last real row, zero padding",
+    dataset => "DatasetAir, sample transactions",
+    tribonacci => "RecurrenceAir<3> (Tribonacci), 20 steps",
+    lucas => "LucasAir, Pell numbers, 20 steps",
+    multi_fibonacci => "MultiFibonacciAir, 8 sequences, 256 rows",
+    big_fibonacci => "BigFibonacciAir, 100 steps",
+    indexed_fibonacci => "IndexedFibonacciAir, 100 steps",
+}
+
+#[test]
+fn every_registered_example_has_a_case() {
+    for example in EXAMPLES {
+        assert!(CASES.contains(&example.name), "{} has no conformance case", example.name);
+    }
+}
@@ -0,0 +1,71 @@
+// The fast test profile: test_config at the smallest provable height, which
+// the rest of the suite proves under, and a few full-size proofs that only run
+// with --ignored.
+use std::time::{Duration, Instant};
+
+use fibonacci_proof::air_id::verify_identified;
+use fibonacci_proof::cost::{CostParams, MIN_TRACE_HEIGHT, SecurityLevel};
+use fibonacci_proof::{
+    DEFAULT_CONFIG_SEED, FIBONACCI_LOG_QUOTIENT_DEGREE, FibonacciAir, Val, create_config_with_seed,
+    generate_fibonacci_trace_padded, prove_fibonacci_trace, test_config,
+};
+use p3_matrix::Matrix;
+use test_support::assert_proof_roundtrip;
+
+// Far above what a 256-row proof takes in a debug build; a profile change
+// that lands near it has made every test that proves slower
+const PROFILE_BOUND: Duration = Duration::from_secs(20);
+
+#[test]
+fn minimal_params_are_provable() {
+    let params = CostParams::minimal();
+    assert_eq!(params.check(1, FIBONACCI_LOG_QUOTIENT_DEGREE), Ok(MIN_TRACE_HEIGHT));
+}
+
+#[test]
+fn smallest_trace_proves_under_test_config() {
+    let trace = generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap();
+    assert_eq!(trace.matrix.height(), MIN_TRACE_HEIGHT);
+    assert_proof_roundtrip(&FibonacciAir, trace.matrix, &trace.public_values);
+}
+
+#[test]
+fn test_config_proofs_do_not_verify_at_a_security_level() {
+    let trace = generate_fibonacci_trace_padded::<Val>(15, MIN_TRACE_HEIGHT).unwrap();
+    let (proof, public_values) = prove_fibonacci_trace(&test_config(), trace, &mut ());
+    let config = create_config_with_seed(DEFAULT_CONFIG_SEED, SecurityLevel::Test);
+    assert!(verify_identified(&config, &FibonacciAir, &proof, &public_values).is_err());
+}
+
+#[test]
+fn test_profile_proves_256_rows_quickly() {
+    let trace = generate_fibonacci_trace_padded::<Val>(255, MIN_TRACE_HEIGHT).unwrap();
+    assert_eq!(trace.matrix.height(), 256);
+    let config = test_config();
+    let start = Instant::now();
+    let (proof, public_values) = prove_fibonacci_trace(&config, trace, &mut ());
+    assert_eq!(verify_identified(&config, &FibonacciAir, &proof, &public_values), Ok(None));
+    let elapsed = start.elapsed();
+    assert!(elapsed < PROFILE_BOUND, "256 rows took {:?} under test_config", elapsed);
+}
+
+// Full size: 2^16 rows at each SecurityLevel
+#[test]
+#[ignore = "full-size proofs take minutes in a debug build"]
+fn every_security_level_proves_at_full_size() {
+    for level in SecurityLevel::ALL {
+        let trace = generate_fibonacci_trace_padded::<Val>((1 << 16) - 1, MIN_TRACE_HEIGHT).unwrap();
+        let config = create_config_with_seed(DEFAULT_CONFIG_SEED, level);
+        let (proof, public_values) = prove_fibonacci_trace(&config, trace, &mut ());
+        assert_eq!(verify_identified(&config, &FibonacciAir, &proof, &public_values), Ok(None), "{}", level);
+    }
+}
+
+#[test]
+#[ignore = "full-size proofs take minutes in a debug build"]
+fn a_million_steps_prove_under_test_config() {
+    let trace = generate_fibonacci_trace_padded::<Val>(1 << 20, MIN_TRACE_HEIGHT).unwrap();
+    let config = test_config();
+    let (proof, public_values) = prove_fibonacci_trace(&config, trace, &mut ());
+    assert_eq!(verify_identified(&config, &FibonacciAir, &proof, &public_values), Ok(None));
+}
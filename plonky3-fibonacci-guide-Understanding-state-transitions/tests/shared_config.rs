@@ -0,0 +1,26 @@
+// Both examples build their configs through stark_config: a proof from either
+// crate verifies under stark_config::create_config at the prover's seed, and
+// under no other seed.
+use fibonacci_proof::air_id::verify_identified;
+use fibonacci_proof::{DEFAULT_CONFIG_SEED, FibonacciAir, prove_fibonacci};
+use p3_uni_stark::verify;
+use simple_arithmetic_proof::{ArithmeticAir, prove_arithmetic};
+use stark_config::{FriChoice, create_config};
+
+#[test]
+fn a_fibonacci_proof_verifies_under_the_shared_constructor_at_its_seed() {
+    let (proof, public_values) = prove_fibonacci(20).unwrap();
+    let same = create_config(DEFAULT_CONFIG_SEED, FriChoice::TEST);
+    assert!(verify_identified(&same, &FibonacciAir, &proof, &public_values).is_ok());
+    let other = create_config(DEFAULT_CONFIG_SEED + 1, FriChoice::TEST);
+    assert!(verify_identified(&other, &FibonacciAir, &proof, &public_values).is_err());
+}
+
+#[test]
+fn an_arithmetic_proof_verifies_under_the_shared_constructor_at_its_seed() {
+    let (proof, public_values) = prove_arithmetic().unwrap();
+    let same = create_config(DEFAULT_CONFIG_SEED, FriChoice::TEST);
+    assert!(verify(&same, &ArithmeticAir, &proof, &public_values).is_ok());
+    let other = create_config(DEFAULT_CONFIG_SEED + 1, FriChoice::TEST);
+    assert!(verify(&other, &ArithmeticAir, &proof, &public_values).is_err());
+}
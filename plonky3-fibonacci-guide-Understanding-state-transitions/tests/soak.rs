@@ -0,0 +1,33 @@
+// The short soak: ten prove/verify rounds with memory read after each. Memory
+// in use is per process, so this lives in its own test binary where no other
+// test allocates alongside it. Under mem-stats the counting allocator is
+// installed and the rounds are measured by its counters; otherwise by RSS.
+use fibonacci_proof::soak::{MemorySource, SoakError, soak_iterations};
+
+#[cfg(feature = "mem-stats")]
+#[global_allocator]
+static ALLOCATOR: fibonacci_proof::soak::CountingAllocator = fibonacci_proof::soak::CountingAllocator;
+
+// Rows per round; small enough for a debug build, large enough to allocate
+// fresh DFT and Merkle buffers each time
+const STEPS: usize = 1 << 10;
+
+// Growth tolerated per round after warmup, below the size of one round's
+// LDE, so a buffer kept alive from every round shows up
+const MAX_SLOPE: f64 = 64.0 * 1024.0;
+
+#[test]
+fn ten_rounds_do_not_grow_memory() {
+    let source = MemorySource::detect();
+    if cfg!(feature = "mem-stats") {
+        assert_eq!(source, Ok(MemorySource::Allocator));
+    } else if cfg!(target_os = "linux") {
+        assert_eq!(source, Ok(MemorySource::Resident));
+    } else {
+        assert_eq!(source, Err(SoakError::Unsupported));
+        assert_eq!(soak_iterations(STEPS, 10, 3, MAX_SLOPE), Err(SoakError::Unsupported));
+        return;
+    }
+    let in_use = soak_iterations(STEPS, 10, 3, MAX_SLOPE).unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(in_use.len(), 10);
+}
@@ -0,0 +1,299 @@
+// The STARK config both examples prove under: BabyBear, Poseidon2 Merkle trees
+// and transcript, and FRI over the degree-4 extension. Each example re-exports
+// these types, so a proof made by one verifies under a config the other builds
+// from the same seed and FriChoice.
+use core::fmt;
+use core::str::FromStr;
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::{CanObserve, DuplexChallenger};
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::Field;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::{FriParameters, HidingFriPcs, TwoAdicFriPcs};
+use p3_merkle_tree::{MerkleTreeHidingMmcs, MerkleTreeMmcs};
+use p3_poseidon2::ExternalLayerConstants;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::StarkConfig;
+use rand::distr::StandardUniform;
+use rand::{Rng, SeedableRng};
+use rand::rngs::SmallRng;
+use rand_chacha::ChaCha20Rng;
+
+pub type Val = BabyBear;
+pub type Perm = Poseidon2BabyBear<16>;
+pub type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+pub type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+pub type ValMmcs = MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+pub type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+pub type Dft = Radix2DitParallel<Val>;
+pub type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+pub type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+// MyConfig made zero-knowledge: HidingFriPcs randomizes the trace polynomials
+// and commits extra random codewords, and MerkleTreeHidingMmcs salts every
+// Merkle leaf. p3-uni-stark pads the trace itself, so AIRs and trace
+// generators are unchanged and a verifier needs no blinding.
+pub type ZkValMmcs =
+    MerkleTreeHidingMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, SmallRng, 8, 4>;
+pub type ZkChallengeMmcs = ExtensionMmcs<Val, Challenge, ZkValMmcs>;
+pub type ZkPcs = HidingFriPcs<Val, Dft, ZkValMmcs, ZkChallengeMmcs, SmallRng>;
+pub type ZkConfig = StarkConfig<ZkPcs, Challenge, Challenger>;
+
+// Seed shared by both examples so a proof from one verifies under the other's config
+pub const DEFAULT_CONFIG_SEED: u64 = 42;
+
+// FRI stops folding at a final polynomial of 2^LOG_FINAL_POLY_LEN coefficients
+pub const LOG_FINAL_POLY_LEN: usize = 4;
+
+// Random codewords HidingFriPcs commits next to the trace
+pub const NUM_RANDOM_CODEWORDS: usize = 4;
+
+// The FRI parameters a config is built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriChoice {
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub pow_bits: usize,
+}
+
+impl FriChoice {
+    // What create_test_fri_params used to give: fast, and a few bits of security
+    pub const TEST: Self = Self { log_blowup: 1, num_queries: 2, pow_bits: 1 };
+
+    // The smallest parameters that still run every FRI code path: one query
+    // at a blowup of 2 and no grinding. Insecure; for tests only.
+    pub const MINIMAL: Self = Self { log_blowup: 1, num_queries: 1, pow_bits: 0 };
+
+    pub fn fri_params<M>(self, mmcs: M) -> FriParameters<M> {
+        FriParameters {
+            log_blowup: self.log_blowup,
+            log_final_poly_len: LOG_FINAL_POLY_LEN,
+            num_queries: self.num_queries,
+            proof_of_work_bits: self.pow_bits,
+            mmcs,
+        }
+    }
+}
+
+// Named FRI parameter sets, shared by both examples. Test is fast and gives a
+// few bits of security. Bits80 and Bits100 meet their target in conjectured
+// security (log_blowup bits per query, plus the grinding bits) with a blowup
+// of 4, which leaves room for AIRs of log quotient degree up to 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityLevel {
+    Test,
+    Bits80,
+    Bits100,
+}
+
+impl SecurityLevel {
+    pub const ALL: [Self; 3] = [Self::Test, Self::Bits80, Self::Bits100];
+
+    pub const fn fri_choice(self) -> FriChoice {
+        match self {
+            Self::Test => FriChoice::TEST,
+            Self::Bits80 => FriChoice { log_blowup: 2, num_queries: 32, pow_bits: 16 },
+            Self::Bits100 => FriChoice { log_blowup: 2, num_queries: 42, pow_bits: 16 },
+        }
+    }
+
+    pub const fn conjectured_security_bits(self) -> usize {
+        let fri = self.fri_choice();
+        fri.log_blowup * fri.num_queries + fri.pow_bits
+    }
+}
+
+impl fmt::Display for SecurityLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Test => "test",
+            Self::Bits80 => "80",
+            Self::Bits100 => "100",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for SecurityLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "test" => Ok(Self::Test),
+            "80" => Ok(Self::Bits80),
+            "100" => Ok(Self::Bits100),
+            _ => Err("expected test, 80 or 100".to_string()),
+        }
+    }
+}
+
+// The RNG the Poseidon2 round constants are sampled from. ChaCha20 is
+// specified independently of rand and of the platform, so the constants, and
+// with them every proof, depend on the seed alone.
+pub fn constants_rng(seed: u64) -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(seed)
+}
+
+// Poseidon2's full and partial rounds for BabyBear at width 16 and 128-bit
+// security, the numbers Perm::new_from_rng_128 looks up
+pub const POSEIDON2_ROUNDS_F: usize = 8;
+pub const POSEIDON2_ROUNDS_P: usize = 13;
+
+// The round constants behind Perm for one seed: the external rounds before and
+// after the partial rounds, one state-wide vector each, and one constant per
+// partial round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poseidon2Constants {
+    pub initial_external: Vec<[Val; 16]>,
+    pub internal: Vec<Val>,
+    pub terminal_external: Vec<[Val; 16]>,
+}
+
+impl Poseidon2Constants {
+    pub fn perm(&self) -> Perm {
+        let external = ExternalLayerConstants::new(self.initial_external.clone(), self.terminal_external.clone());
+        Perm::new(external, self.internal.clone())
+    }
+}
+
+// Drawn from constants_rng(seed) in the order Perm::new_from_rng_128 draws
+// them, so poseidon2_constants(seed).perm() is the permutation every config
+// built from `seed` uses
+pub fn poseidon2_constants(seed: u64) -> Poseidon2Constants {
+    let mut rng = constants_rng(seed);
+    let external = ExternalLayerConstants::<Val, 16>::new_from_rng(POSEIDON2_ROUNDS_F, &mut rng);
+    let internal = (&mut rng).sample_iter(StandardUniform).take(POSEIDON2_ROUNDS_P).collect();
+    Poseidon2Constants {
+        initial_external: external.get_initial_constants().clone(),
+        internal,
+        terminal_external: external.get_terminal_constants().clone(),
+    }
+}
+
+// The seed determines the Poseidon2 constants, so configs built from
+// different seeds cannot verify each other's proofs
+pub fn create_config(seed: u64, fri: FriChoice) -> MyConfig {
+    create_config_with_prefix(seed, fri, &[])
+}
+
+// The config tests prove under: FriChoice::MINIMAL at the default seed.
+// Proofs under it exercise the prover and verifier, not their security, and do
+// not verify under any SecurityLevel.
+pub fn test_config() -> MyConfig {
+    create_config(DEFAULT_CONFIG_SEED, FriChoice::MINIMAL)
+}
+
+// create_config with a challenger that has already observed `prefix`, so it
+// comes before everything prove and verify absorb. Each example's domain
+// module passes a hashed domain string.
+pub fn create_config_with_prefix(seed: u64, fri: FriChoice, prefix: &[Val]) -> MyConfig {
+    let perm = Perm::new_from_rng_128(&mut constants_rng(seed));
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri.fri_params(challenge_mmcs));
+    let mut challenger = Challenger::new(perm);
+    challenger.observe_slice(prefix);
+    MyConfig::new(pcs, challenger)
+}
+
+// Where a ZkConfig draws its blinding from. Only the prover uses it: the
+// verifier samples no blinding, so any source rebuilds its config. A proof
+// only hides the witness when the blinding is unpredictable, so Seeded is for
+// reproducible tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlindingSource {
+    Os,
+    Seeded(u64),
+}
+
+impl BlindingSource {
+    // One RNG for the Merkle salts, one for the trace randomization
+    fn rngs(self) -> (SmallRng, SmallRng) {
+        match self {
+            Self::Os => (SmallRng::from_os_rng(), SmallRng::from_os_rng()),
+            Self::Seeded(seed) => (SmallRng::seed_from_u64(seed), SmallRng::seed_from_u64(seed.wrapping_add(1))),
+        }
+    }
+}
+
+// create_config, hiding. The randomized trace is committed over twice the
+// rows, so `fri` needs a log_blowup one above what the AIR needs without it.
+pub fn create_zk_config(seed: u64, fri: FriChoice, blinding: BlindingSource) -> ZkConfig {
+    let perm = Perm::new_from_rng_128(&mut constants_rng(seed));
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let (salt_rng, trace_rng) = blinding.rngs();
+    let val_mmcs = ZkValMmcs::new(hash, compress, salt_rng);
+    let challenge_mmcs = ZkChallengeMmcs::new(val_mmcs.clone());
+    let pcs = ZkPcs::new(Dft::default(), val_mmcs, fri.fri_params(challenge_mmcs), NUM_RANDOM_CODEWORDS, trace_rng);
+    ZkConfig::new(pcs, Challenger::new(perm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn security_level_names_round_trip() {
+        for level in SecurityLevel::ALL {
+            assert_eq!(level.to_string().parse::<SecurityLevel>(), Ok(level));
+        }
+        assert!("90".parse::<SecurityLevel>().is_err());
+    }
+
+    #[test]
+    fn each_level_meets_its_target() {
+        assert!(SecurityLevel::Bits80.conjectured_security_bits() >= 80);
+        assert!(SecurityLevel::Bits100.conjectured_security_bits() >= 100);
+        let queries = SecurityLevel::ALL.map(|level| level.fri_choice().num_queries);
+        assert!(queries[0] < queries[1] && queries[1] < queries[2], "{:?}", queries);
+        assert_eq!(SecurityLevel::Test.fri_choice(), FriChoice::TEST);
+    }
+
+    #[test]
+    fn poseidon2_constants_rebuild_the_config_permutation() {
+        use p3_baby_bear::BABYBEAR_S_BOX_DEGREE;
+        use p3_field::PrimeCharacteristicRing;
+        use p3_poseidon2::poseidon2_round_numbers_128;
+        use p3_symmetric::Permutation;
+
+        assert_eq!(
+            poseidon2_round_numbers_128::<Val>(16, BABYBEAR_S_BOX_DEGREE),
+            Ok((POSEIDON2_ROUNDS_F, POSEIDON2_ROUNDS_P))
+        );
+        let constants = poseidon2_constants(DEFAULT_CONFIG_SEED);
+        assert_eq!(constants.initial_external.len(), POSEIDON2_ROUNDS_F / 2);
+        assert_eq!(constants.terminal_external.len(), POSEIDON2_ROUNDS_F / 2);
+        assert_eq!(constants.internal.len(), POSEIDON2_ROUNDS_P);
+
+        let state: [Val; 16] = core::array::from_fn(|i| Val::from_usize(i));
+        let sampled = Perm::new_from_rng_128(&mut constants_rng(DEFAULT_CONFIG_SEED));
+        assert_eq!(constants.perm().permute(state), sampled.permute(state));
+        assert_ne!(poseidon2_constants(1), constants);
+    }
+
+    // The first constants of the default seed's first external round and of
+    // its partial rounds. A change here changes every proof; if it is meant,
+    // bump every AIR id and regenerate the fixtures.
+    #[test]
+    fn the_default_round_constants_are_pinned() {
+        use p3_field::PrimeCharacteristicRing;
+
+        let constants = poseidon2_constants(DEFAULT_CONFIG_SEED);
+        let pinned = |values: [u32; 4]| values.map(Val::from_u32);
+        assert_eq!(constants.initial_external[0][..4], pinned([1416725253, 992490477, 616989865, 1852004523]));
+        assert_eq!(constants.internal[..4], pinned([738634731, 426486489, 1562227959, 1351705281]));
+    }
+
+    #[test]
+    fn named_levels_meet_their_target() {
+        assert!(SecurityLevel::Bits80.conjectured_security_bits() >= 80);
+        assert!(SecurityLevel::Bits100.conjectured_security_bits() >= 100);
+        assert_eq!(SecurityLevel::Test.fri_choice(), FriChoice::TEST);
+    }
+}
@@ -0,0 +1,255 @@
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_uni_stark::{Proof, prove, verify};
+
+use crate::trace::{ArithmeticMeta, GeneratedTrace, LengthOverflow, generate_rows};
+use crate::{MyConfig, SecurityLevel, Val, create_default_config};
+
+// Number of bits in the range decomposition. Bounds must fit in this many bits so
+// that a + (bound - a) = bound holds over the integers, not just mod p.
+pub const BOUND_BITS: usize = 16;
+
+// Columns: a, the bits of a, and the bits of the slack (bound - a)
+const NUM_BOUNDED_COLS: usize = 1 + 2 * BOUND_BITS;
+
+// Proves a + c * d = e for a private a in [0, bound]
+#[derive(Debug, Clone)]
+pub struct BoundedArithmeticAir;
+
+impl<F> BaseAir<F> for BoundedArithmeticAir {
+    fn width(&self) -> usize {
+        NUM_BOUNDED_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for BoundedArithmeticAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &BoundedArithmeticRow<AB::Var> = (*local).borrow();
+
+        let pis = builder.public_values();
+        let (bound, limb_count, c, d, e) = (pis[0], pis[1], pis[2], pis[3], pis[4]);
+        let bound: AB::Expr = bound.into();
+        let a: AB::Expr = local.a.clone().into();
+        let c: AB::Expr = c.into();
+        let d: AB::Expr = d.into();
+
+        // The decomposition width is part of the statement
+        builder.assert_eq(limb_count, AB::Expr::from_usize(BOUND_BITS));
+
+        // a = sum(a_bits[i] * 2^i) and bound - a = sum(slack_bits[i] * 2^i),
+        // so both a and bound - a lie in [0, 2^BOUND_BITS)
+        let mut a_recomposed = AB::Expr::ZERO;
+        let mut slack_recomposed = AB::Expr::ZERO;
+        for i in 0..BOUND_BITS {
+            let a_bit: AB::Expr = local.a_bits[i].clone().into();
+            let slack_bit: AB::Expr = local.slack_bits[i].clone().into();
+            builder.assert_bool(a_bit.clone());
+            builder.assert_bool(slack_bit.clone());
+            a_recomposed += a_bit * AB::Expr::from_u64(1 << i);
+            slack_recomposed += slack_bit * AB::Expr::from_u64(1 << i);
+        }
+        builder.assert_eq(a.clone(), a_recomposed);
+        builder.assert_eq(bound - a.clone(), slack_recomposed);
+
+        // The arithmetic relation itself, with c, d, e taken from the public values
+        builder.assert_eq(a + c * d, e);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct BoundedArithmeticRow<F> {
+    pub a: F,
+    pub a_bits: [F; BOUND_BITS],
+    pub slack_bits: [F; BOUND_BITS],
+}
+
+impl<F> Borrow<BoundedArithmeticRow<F>> for [F] {
+    fn borrow(&self) -> &BoundedArithmeticRow<F> {
+        debug_assert_eq!(self.len(), NUM_BOUNDED_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<BoundedArithmeticRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundedArithmeticError {
+    // The bound does not fit in BOUND_BITS bits
+    BoundTooLarge { bound: u64 },
+    // There is no non-negative a with a + c * d = e
+    NoWitness { c: u64, d: u64, e: u64 },
+    // The witness exists but lies outside [0, bound]
+    OutOfRange { a: u64, bound: u64 },
+    Trace(LengthOverflow),
+    Verification(String),
+}
+
+impl fmt::Display for BoundedArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BoundTooLarge { bound } => {
+                write!(f, "bound {} does not fit in {} bits", bound, BOUND_BITS)
+            }
+            Self::NoWitness { c, d, e } => {
+                write!(f, "no a >= 0 satisfies a + {} * {} = {}", c, d, e)
+            }
+            Self::OutOfRange { a, bound } => write!(f, "witness a = {} exceeds bound {}", a, bound),
+            Self::Trace(e) => write!(f, "{}", e),
+            Self::Verification(msg) => write!(f, "verification failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BoundedArithmeticError {}
+
+fn check_bound(bound: u64) -> Result<(), BoundedArithmeticError> {
+    if bound >> BOUND_BITS != 0 {
+        return Err(BoundedArithmeticError::BoundTooLarge { bound });
+    }
+    Ok(())
+}
+
+// Public values: [bound, limb_count, c, d, e]. The witness a is never public.
+pub fn bounded_public_values<F: PrimeCharacteristicRing>(bound: u64, c: u64, d: u64, e: u64) -> Vec<F> {
+    vec![
+        F::from_u64(bound),
+        F::from_usize(BOUND_BITS),
+        F::from_u64(c),
+        F::from_u64(d),
+        F::from_u64(e),
+    ]
+}
+
+// Derives the private a = e - c * d and lays out its range decomposition
+pub fn generate_bounded_arithmetic_trace<F: PrimeField64>(
+    bound: u64,
+    c: u64,
+    d: u64,
+    e: u64,
+) -> Result<GeneratedTrace<ArithmeticMeta<F>, F>, BoundedArithmeticError> {
+    check_bound(bound)?;
+    let a = c
+        .checked_mul(d)
+        .and_then(|cd| e.checked_sub(cd))
+        .ok_or(BoundedArithmeticError::NoWitness { c, d, e })?;
+    if a > bound {
+        return Err(BoundedArithmeticError::OutOfRange { a, bound });
+    }
+    let slack = bound - a;
+
+    let n = 256; // Same minimum height as the plain arithmetic trace
+    let row = BoundedArithmeticRow {
+        a: F::from_u64(a),
+        a_bits: core::array::from_fn(|i| F::from_u64((a >> i) & 1)),
+        slack_bits: core::array::from_fn(|i| F::from_u64((slack >> i) & 1)),
+    };
+    let row = &row;
+    let trace = generate_rows(n, |range: Range<usize>| range.map(move |_| row.clone()))
+        .map_err(BoundedArithmeticError::Trace)?;
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: n,
+        public_values: bounded_public_values(bound, c, d, e),
+        meta: ArithmeticMeta { results: vec![F::from_u64(e); n] },
+    })
+}
+
+// Proves that some a in [0, bound] satisfies a + c * d = e. The prover derives a
+// from the public values; the verifier only ever sees bound, c, d and e.
+pub fn prove_bounded_arithmetic(
+    bound: u64,
+    c: u64,
+    d: u64,
+    e: u64,
+) -> Result<Proof<MyConfig>, BoundedArithmeticError> {
+    let trace = generate_bounded_arithmetic_trace::<Val>(bound, c, d, e)?;
+    let config = create_default_config(SecurityLevel::Test);
+    Ok(prove(&config, &BoundedArithmeticAir, trace.matrix, &trace.public_values))
+}
+
+pub fn verify_bounded_arithmetic(
+    bound: u64,
+    c: u64,
+    d: u64,
+    e: u64,
+    proof: &Proof<MyConfig>,
+) -> Result<(), BoundedArithmeticError> {
+    check_bound(bound)?;
+    let config = create_default_config(SecurityLevel::Test);
+    verify(&config, &BoundedArithmeticAir, proof, &bounded_public_values::<Val>(bound, c, d, e))
+        .map_err(|err| BoundedArithmeticError::Verification(format!("{:?}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::assert_proof_roundtrip;
+    use test_support::checker::prover_accepts;
+
+    use super::*;
+
+    #[test]
+    fn a_witness_inside_the_bound_proves_without_revealing_it() {
+        let proof = prove_bounded_arithmetic(100, 4, 5, 23).unwrap();
+        assert_eq!(verify_bounded_arithmetic(100, 4, 5, 23, &proof), Ok(()));
+        assert!(matches!(
+            verify_bounded_arithmetic(100, 4, 5, 24, &proof),
+            Err(BoundedArithmeticError::Verification(_))
+        ));
+    }
+
+    #[test]
+    fn only_the_bound_and_the_relation_are_public() {
+        let trace = generate_bounded_arithmetic_trace::<Val>(100, 4, 5, 23).unwrap();
+        // a = 3 is not among them
+        assert_eq!(trace.public_values, [100, BOUND_BITS as u64, 4, 5, 23].map(Val::from_u64));
+    }
+
+    #[test]
+    fn both_ends_of_the_range_prove() {
+        // a = 0, so e = c * d
+        let proof = prove_bounded_arithmetic(100, 4, 5, 20).unwrap();
+        assert_eq!(verify_bounded_arithmetic(100, 4, 5, 20, &proof), Ok(()));
+        // a = bound, with no slack left
+        let proof = prove_bounded_arithmetic(100, 4, 5, 120).unwrap();
+        assert_eq!(verify_bounded_arithmetic(100, 4, 5, 120, &proof), Ok(()));
+    }
+
+    #[test]
+    fn a_witness_one_past_the_bound_is_rejected_by_the_air() {
+        // a = 101 with its bits and the relation consistent, built under bound
+        // 101 and then claimed under bound 100
+        let forged = generate_bounded_arithmetic_trace::<Val>(101, 4, 5, 121).unwrap();
+        assert_eq!(prover_accepts(&BoundedArithmeticAir, forged.matrix.clone(), &forged.public_values), Ok(()));
+        let claimed = bounded_public_values::<Val>(100, 4, 5, 121);
+        assert!(prover_accepts(&BoundedArithmeticAir, forged.matrix, &claimed).is_err());
+    }
+
+    #[test]
+    fn the_trace_round_trips_at_the_tightest_bound() {
+        let trace = generate_bounded_arithmetic_trace::<Val>(3, 4, 5, 23).unwrap();
+        assert_eq!(trace.public_values, bounded_public_values::<Val>(3, 4, 5, 23));
+        assert_proof_roundtrip(&BoundedArithmeticAir, trace.matrix, &trace.public_values);
+    }
+
+    #[test]
+    fn statements_without_an_in_range_witness_are_refused() {
+        let generate = |bound, c, d, e| generate_bounded_arithmetic_trace::<Val>(bound, c, d, e).err();
+        assert_eq!(generate(2, 4, 5, 23), Some(BoundedArithmeticError::OutOfRange { a: 3, bound: 2 }));
+        assert_eq!(generate(100, 4, 5, 19), Some(BoundedArithmeticError::NoWitness { c: 4, d: 5, e: 19 }));
+        let too_large = Some(BoundedArithmeticError::BoundTooLarge { bound: 1 << BOUND_BITS });
+        assert_eq!(generate(1 << BOUND_BITS, 4, 5, 23), too_large);
+        // c * d overflows rather than wrapping into a witness
+        let overflow = Some(BoundedArithmeticError::NoWitness { c: u64::MAX, d: 2, e: 23 });
+        assert_eq!(generate(100, u64::MAX, 2, 23), overflow);
+    }
+}
@@ -0,0 +1,85 @@
+// What a verified ArithmeticAir proof establishes. The AIR takes no public
+// values, so the claim is only the row count, which the proof carries as its
+// degree; every verify wrapper decodes it the same way whichever config or
+// field proved it.
+use core::fmt;
+
+use p3_uni_stark::{Proof, StarkGenericConfig};
+
+use crate::VerifyError;
+
+// ArithmeticAir has no public inputs
+pub const NUM_ARITHMETIC_CLAIM_VALUES: usize = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArithmeticClaims {
+    // Rows on each of which a + c * d = e holds
+    pub num_rows: usize,
+}
+
+impl ArithmeticClaims {
+    pub fn from_public_values<F>(public_values: &[F], num_rows: usize) -> Result<Self, VerifyError> {
+        if public_values.len() != NUM_ARITHMETIC_CLAIM_VALUES {
+            return Err(VerifyError::WrongPublicValueCount {
+                expected: NUM_ARITHMETIC_CLAIM_VALUES,
+                found: public_values.len(),
+            });
+        }
+        Ok(Self { num_rows })
+    }
+
+    // Takes the row count from the proof's degree, under any config. The
+    // degree is read before verifying, so one too large to shift is an error
+    // rather than a panic or a wrapped count.
+    pub fn from_proof<F, SC: StarkGenericConfig>(public_values: &[F], proof: &Proof<SC>) -> Result<Self, VerifyError> {
+        let degree_bits = proof.degree_bits;
+        let num_rows = u32::try_from(degree_bits)
+            .ok()
+            .and_then(|bits| 1usize.checked_shl(bits))
+            .ok_or(VerifyError::DegreeTooLarge { degree_bits })?;
+        Self::from_public_values(public_values, num_rows)
+    }
+
+    pub fn to_public_values<F>(&self) -> Vec<F> {
+        vec![]
+    }
+}
+
+impl fmt::Display for ArithmeticClaims {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a + c * d = e holds on each of {} rows", self.num_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::{Val, prove_arithmetic, verify_arithmetic};
+
+    #[test]
+    fn claims_round_trip_through_the_empty_layout() {
+        let claims = ArithmeticClaims { num_rows: 256 };
+        let public_values: Vec<Val> = claims.to_public_values();
+        assert_eq!(ArithmeticClaims::from_public_values(&public_values, 256), Ok(claims));
+        assert_eq!(claims.to_string(), "a + c * d = e holds on each of 256 rows");
+    }
+
+    #[test]
+    fn any_public_value_is_a_shape_error() {
+        assert_eq!(
+            ArithmeticClaims::from_public_values(&[Val::ONE], 256),
+            Err(VerifyError::WrongPublicValueCount { expected: 0, found: 1 })
+        );
+    }
+
+    #[test]
+    fn a_degree_too_large_to_count_is_refused_before_verifying() {
+        let (mut proof, public_values) = prove_arithmetic().unwrap();
+        for degree_bits in [usize::BITS as usize, 200, usize::MAX] {
+            proof.degree_bits = degree_bits;
+            assert_eq!(verify_arithmetic(&proof, &public_values), Err(VerifyError::DegreeTooLarge { degree_bits }));
+        }
+    }
+}
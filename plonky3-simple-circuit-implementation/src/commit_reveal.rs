@@ -0,0 +1,269 @@
+use core::borrow::Borrow;
+use core::fmt;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::GenericPoseidon2LinearLayersBabyBear;
+use p3_field::PrimeCharacteristicRing;
+use p3_matrix::Matrix;
+use p3_poseidon2_air::{Poseidon2Air, Poseidon2Cols, RoundConstants};
+use p3_uni_stark::{Proof, prove, verify};
+use rand::Rng;
+use stark_config::{FriChoice, create_zk_config};
+
+use crate::hashing::hash_bytes_to_fields;
+use crate::trace::GeneratedTrace;
+use crate::{BlindingSource, DEFAULT_CONFIG_SEED, Val, ZkConfig, constants_rng};
+
+// Poseidon2 over BabyBear with a width-16 state, matching Poseidon2BabyBear<16>
+const WIDTH: usize = 16;
+const SBOX_DEGREE: u64 = 7;
+const SBOX_REGISTERS: usize = 1;
+const HALF_FULL_ROUNDS: usize = 4;
+const PARTIAL_ROUNDS: usize = 13;
+
+// The committed value takes one state slot and the blinding the rest of the rate
+pub const BLINDING_LEN: usize = 7;
+pub const DIGEST_LEN: usize = 8;
+const RATE: usize = 1 + BLINDING_LEN;
+
+// Public values: [value, c_0, ..., c_7]. The blinding is never public.
+const NUM_COMMIT_PUBLIC_VALUES: usize = 1 + DIGEST_LEN;
+
+type HashAir = Poseidon2Air<
+    Val,
+    GenericPoseidon2LinearLayersBabyBear,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+>;
+type HashCols<T> = Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>;
+
+pub type Commitment = [Val; DIGEST_LEN];
+pub type Blinding = [Val; BLINDING_LEN];
+
+// Every row is a Poseidon2 permutation; the first row's permutation is bound to
+// the opening: input = (value, blinding, 0...) and output prefix = c
+#[derive(Debug)]
+pub struct CommitRevealAir {
+    hash: HashAir,
+}
+
+impl CommitRevealAir {
+    // Round constants are derived from the default config seed so every party
+    // builds the same hash
+    pub fn new() -> Self {
+        let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+        Self { hash: HashAir::new(RoundConstants::from_rng(&mut rng)) }
+    }
+}
+
+impl Default for CommitRevealAir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BaseAir<Val> for CommitRevealAir {
+    fn width(&self) -> usize {
+        BaseAir::<Val>::width(&self.hash)
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues<F = Val>> Air<AB> for CommitRevealAir {
+    fn eval(&self, builder: &mut AB) {
+        // Permutation constraints on every row
+        self.hash.eval(builder);
+
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &HashCols<AB::Var> = (*local).borrow();
+
+        let pis = builder.public_values();
+        let value = pis[0];
+        let commitment: [AB::PublicVar; DIGEST_LEN] = core::array::from_fn(|i| pis[1 + i]);
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local.inputs[0].clone(), value);
+        for input in &local.inputs[RATE..] {
+            when_first_row.assert_zero(input.clone());
+        }
+        let output = &local.ending_full_rounds[HALF_FULL_ROUNDS - 1].post;
+        for (out, c) in output.iter().zip(commitment) {
+            when_first_row.assert_eq(out.clone(), c);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitRevealError {
+    // A public-value vector that is not [value, c_0, ..., c_7]
+    WrongPublicValueCount { expected: usize, found: usize },
+    Verification(String),
+}
+
+impl fmt::Display for CommitRevealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongPublicValueCount { expected, found } => {
+                write!(f, "expected {} public values, found {}", expected, found)
+            }
+            Self::Verification(msg) => write!(f, "opening rejected: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CommitRevealError {}
+
+// What a verified opening establishes: `commitment` opens to `value`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpeningClaims {
+    pub value: Val,
+    pub commitment: Commitment,
+}
+
+impl OpeningClaims {
+    pub fn from_public_values(public_values: &[Val]) -> Result<Self, CommitRevealError> {
+        let Some((&value, commitment)) = public_values.split_first() else {
+            return Err(CommitRevealError::WrongPublicValueCount {
+                expected: NUM_COMMIT_PUBLIC_VALUES,
+                found: 0,
+            });
+        };
+        let commitment: Commitment =
+            commitment.try_into().map_err(|_| CommitRevealError::WrongPublicValueCount {
+                expected: NUM_COMMIT_PUBLIC_VALUES,
+                found: public_values.len(),
+            })?;
+        Ok(Self { value, commitment })
+    }
+
+    pub fn to_public_values(&self) -> Vec<Val> {
+        commitment_public_values(self.value, &self.commitment)
+    }
+}
+
+impl fmt::Display for OpeningClaims {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "commitment {:?} opens to {}", self.commitment, self.value)
+    }
+}
+
+fn hash_input(value: Val, blinding: &Blinding) -> [Val; WIDTH] {
+    let mut input = [Val::ZERO; WIDTH];
+    input[0] = value;
+    input[1..RATE].copy_from_slice(blinding);
+    input
+}
+
+// Computes c by running the same permutation trace generator the AIR checks,
+// so the commitment can never drift from what the proof binds
+fn hash_commitment(air: &CommitRevealAir, value: Val, blinding: &Blinding) -> Commitment {
+    let trace = air.hash.generate_trace_rows(vec![hash_input(value, blinding)], 0);
+    // One input is one row: generate_trace_rows pads to a power of two, never
+    // below the input count
+    debug_assert_eq!(trace.height(), 1);
+    let cols: &HashCols<Val> = trace.values[..trace.width()].borrow();
+    core::array::from_fn(|i| cols.ending_full_rounds[HALF_FULL_ROUNDS - 1].post[i])
+}
+
+pub fn commitment_public_values(value: Val, commitment: &Commitment) -> Vec<Val> {
+    let mut public_values = Vec::with_capacity(NUM_COMMIT_PUBLIC_VALUES);
+    public_values.push(value);
+    public_values.extend_from_slice(commitment);
+    public_values
+}
+
+// Commits to `value` under fresh random blinding
+pub fn commit(value: Val) -> (Commitment, Blinding) {
+    let mut rng = rand::rng();
+    let blinding: Blinding = core::array::from_fn(|_| rng.random());
+    (hash_commitment(&CommitRevealAir::new(), value, &blinding), blinding)
+}
+
+// Commits to a byte message through its single-element hash, which becomes the
+// public value an opening reveals
+pub fn commit_message(message: &[u8]) -> (Val, Commitment, Blinding) {
+    let value = hash_bytes_to_fields(message, 1)[0];
+    let (commitment, blinding) = commit(value);
+    (value, commitment, blinding)
+}
+
+pub fn generate_commit_reveal_trace(
+    air: &CommitRevealAir,
+    value: Val,
+    blinding: &Blinding,
+) -> GeneratedTrace<Commitment> {
+    // Same minimum height as the other examples; every row hashes the opening
+    let n = 256;
+    let matrix = air.hash.generate_trace_rows(vec![hash_input(value, blinding); n], 0);
+    let commitment = hash_commitment(air, value, blinding);
+
+    GeneratedTrace {
+        matrix,
+        real_rows: 1,
+        public_values: commitment_public_values(value, &commitment),
+        meta: commitment,
+    }
+}
+
+// The test parameters with room for hiding: the Poseidon2 constraints have
+// degree 3, so the quotient is already twice the trace before the hiding PCS
+// doubles it
+const OPENING_FRI: FriChoice = FriChoice { log_blowup: 3, ..FriChoice::TEST };
+
+// Openings are proved under the hiding config: every row of the trace holds
+// the blinding, which MyConfig's commitments and FRI openings would reveal
+fn opening_config(blinding: BlindingSource) -> ZkConfig {
+    create_zk_config(DEFAULT_CONFIG_SEED, OPENING_FRI, blinding)
+}
+
+// Proves knowledge of a blinding that opens `c` to `value`
+pub fn prove_opening(value: Val, blinding: &Blinding) -> (Commitment, Proof<ZkConfig>) {
+    let air = CommitRevealAir::new();
+    let trace = generate_commit_reveal_trace(&air, value, blinding);
+    let config = opening_config(BlindingSource::Os);
+    let proof = prove(&config, &air, trace.matrix, &trace.public_values);
+    (trace.meta, proof)
+}
+
+pub fn verify_opening(
+    commitment: &Commitment,
+    value: Val,
+    proof: &Proof<ZkConfig>,
+) -> Result<OpeningClaims, CommitRevealError> {
+    let claims = OpeningClaims { value, commitment: *commitment };
+    let air = CommitRevealAir::new();
+    // The verifier samples no blinding
+    let config = opening_config(BlindingSource::Seeded(0));
+    verify(&config, &air, proof, &claims.to_public_values())
+        .map_err(|err| CommitRevealError::Verification(format!("{:?}", err)))?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opening_verifies_only_for_the_committed_value() {
+        let value = Val::from_u64(1234);
+        let (commitment, blinding) = commit(value);
+        let (proven, proof) = prove_opening(value, &blinding);
+        assert_eq!(proven, commitment);
+        assert!(verify_opening(&commitment, value, &proof).is_ok());
+        assert!(verify_opening(&commitment, value + Val::ONE, &proof).is_err());
+    }
+
+    #[test]
+    fn blinding_stays_out_of_the_public_values_and_commitments() {
+        let value = Val::from_u64(1234);
+        let (commitment, blinding) = commit(value);
+        let public_values = commitment_public_values(value, &commitment);
+        assert!(blinding.iter().all(|b| !public_values.contains(b)));
+        // Fresh blinding per proof: the same opening never commits the same trace twice
+        let (_, first) = prove_opening(value, &blinding);
+        let (_, second) = prove_opening(value, &blinding);
+        assert_ne!(first.commitments.trace, second.commitments.trace);
+    }
+}
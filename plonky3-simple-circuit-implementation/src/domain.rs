@@ -0,0 +1,64 @@
+// Fiat-Shamir domain separation. Every config built from the same seed starts
+// its transcript in the same state, so a proof made for one application would
+// verify for any other using the same AIR. A domain string, hashed to field
+// elements and observed by the challenger before anything else, gives each
+// application its own transcript: under any other domain the challenges
+// differ and the proof is rejected. hash_bytes_to_fields prefixes the byte
+// length, so the empty domain is distinct from having none.
+use p3_uni_stark::{Proof, prove, verify};
+
+use crate::claims::ArithmeticClaims;
+use crate::hashing::hash_bytes_to_fields;
+use crate::trace::LengthOverflow;
+use crate::{
+    ArithmeticAir, DEFAULT_CONFIG_SEED, MyConfig, PublicValues, SecurityLevel, Val, VerifyError,
+    create_config_with_prefix, generate_arithmetic_trace,
+};
+
+// Elements of the domain digest the challenger observes
+const DOMAIN_DIGEST_LEN: usize = 8;
+
+// create_default_config with the challenger bound to `domain`
+pub fn create_domain_config(domain: &str) -> MyConfig {
+    let digest = hash_bytes_to_fields(domain.as_bytes(), DOMAIN_DIGEST_LEN);
+    create_config_with_prefix(DEFAULT_CONFIG_SEED, SecurityLevel::Test, &digest)
+}
+
+// prove_arithmetic under `domain`
+pub fn prove_arithmetic_in(domain: &str) -> Result<(Proof<MyConfig>, PublicValues), LengthOverflow> {
+    let trace = generate_arithmetic_trace::<Val>()?;
+    let proof = prove(&create_domain_config(domain), &ArithmeticAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+// verify_arithmetic under `domain`; a proof made under any other domain, or
+// under none, is rejected
+pub fn verify_arithmetic_in(
+    domain: &str,
+    proof: &Proof<MyConfig>,
+    public_values: &PublicValues,
+) -> Result<ArithmeticClaims, VerifyError> {
+    let claims = ArithmeticClaims::from_proof(public_values, proof)?;
+    verify(&create_domain_config(domain), &ArithmeticAir, proof, public_values)
+        .map_err(|e| VerifyError::Proof(format!("{:?}", e)))?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_arithmetic;
+
+    #[test]
+    fn only_the_proving_domain_verifies() {
+        for domain in ["arith-v1", ""] {
+            let (proof, public_values) = prove_arithmetic_in(domain).unwrap();
+            for verifier in ["arith-v1", "arith-v2", ""] {
+                let verified = verify_arithmetic_in(verifier, &proof, &public_values);
+                let case = format!("proved under {:?}, verified under {:?}", domain, verifier);
+                assert_eq!(verified.is_ok(), verifier == domain, "{}", case);
+            }
+            assert!(verify_arithmetic(&proof, &public_values).is_err(), "proved under {:?}", domain);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// The one place byte orders are decided. Every path that turns field
+// elements into bytes or bytes into field elements goes through here, so
+// external implementations only need to match this file.
+use core::fmt;
+use p3_field::{BasedVectorSpace, PrimeCharacteristicRing, PrimeField32};
+
+use crate::{Challenge, Val};
+
+// Canonical u32 of a Val, little-endian
+pub const VAL_BYTES: usize = 4;
+// Four Val basis coefficients, lowest degree first
+pub const CHALLENGE_BYTES: usize = 4 * VAL_BYTES;
+// A Challenge as public values: its four Val basis coefficients, lowest
+// degree first, the same order as its bytes
+pub const CHALLENGE_LIMBS: usize = 4;
+// Bytes packed into one element by pack_bytes; four would not fit below 2^31
+pub const PACKED_BYTES_PER_ELEMENT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingError {
+    WrongLength { expected: usize, found: usize },
+    // A public-value slice that is not CHALLENGE_LIMBS elements
+    WrongLimbCount { expected: usize, found: usize },
+    // A u32 at or above the BabyBear modulus
+    NonCanonical(u32),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength { expected, found } => {
+                write!(f, "expected {} bytes, found {}", expected, found)
+            }
+            Self::WrongLimbCount { expected, found } => {
+                write!(f, "expected {} limbs, found {}", expected, found)
+            }
+            Self::NonCanonical(value) => write!(f, "{} is not a canonical BabyBear element", value),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+pub fn val_to_le_bytes(value: Val) -> [u8; VAL_BYTES] {
+    value.as_canonical_u32().to_le_bytes()
+}
+
+// Rejects non-canonical encodings so every element has exactly one byte form
+pub fn val_from_le_bytes(bytes: &[u8]) -> Result<Val, EncodingError> {
+    let bytes: [u8; VAL_BYTES] = bytes
+        .try_into()
+        .map_err(|_| EncodingError::WrongLength { expected: VAL_BYTES, found: bytes.len() })?;
+    let value = u32::from_le_bytes(bytes);
+    if value >= Val::ORDER_U32 {
+        return Err(EncodingError::NonCanonical(value));
+    }
+    Ok(Val::from_u32(value))
+}
+
+pub fn challenge_to_le_bytes(value: Challenge) -> [u8; CHALLENGE_BYTES] {
+    let mut out = [0u8; CHALLENGE_BYTES];
+    let coefficients: &[Val] = value.as_basis_coefficients_slice();
+    for (chunk, coefficient) in out.chunks_exact_mut(VAL_BYTES).zip(coefficients) {
+        chunk.copy_from_slice(&val_to_le_bytes(*coefficient));
+    }
+    out
+}
+
+pub fn challenge_from_le_bytes(bytes: &[u8]) -> Result<Challenge, EncodingError> {
+    if bytes.len() != CHALLENGE_BYTES {
+        return Err(EncodingError::WrongLength { expected: CHALLENGE_BYTES, found: bytes.len() });
+    }
+    let coefficients = bytes
+        .chunks_exact(VAL_BYTES)
+        .map(val_from_le_bytes)
+        .collect::<Result<Vec<_>, _>>()?;
+    challenge_from_limbs(&coefficients)
+}
+
+pub fn challenge_to_limbs(value: Challenge) -> [Val; CHALLENGE_LIMBS] {
+    let mut limbs = [Val::ZERO; CHALLENGE_LIMBS];
+    for (limb, coefficient) in limbs.iter_mut().zip(value.as_basis_coefficients_slice()) {
+        *limb = *coefficient;
+    }
+    limbs
+}
+
+pub fn challenge_from_limbs(limbs: &[Val]) -> Result<Challenge, EncodingError> {
+    // None exactly when the length is not CHALLENGE_LIMBS
+    Challenge::from_basis_coefficients_slice(limbs)
+        .ok_or(EncodingError::WrongLimbCount { expected: CHALLENGE_LIMBS, found: limbs.len() })
+}
+
+// A Challenge-typed claim. Public values are base-field only, so the prover
+// appends to_public_values to its claim vector and the verifier reads the
+// same CHALLENGE_LIMBS elements back with from_public_values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionClaim(pub Challenge);
+
+impl ExtensionClaim {
+    pub fn from_public_values(public_values: &[Val]) -> Result<Self, EncodingError> {
+        challenge_from_limbs(public_values).map(Self)
+    }
+
+    pub fn to_public_values(&self) -> Vec<Val> {
+        challenge_to_limbs(self.0).to_vec()
+    }
+}
+
+impl fmt::Display for ExtensionClaim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", challenge_to_limbs(self.0))
+    }
+}
+
+// Packs arbitrary bytes into field elements: the byte length first, then each
+// 3-byte chunk as a little-endian integer, the final chunk zero-extended. The
+// length prefix keeps "ab" and "ab\0" apart.
+pub fn pack_bytes(bytes: &[u8]) -> impl Iterator<Item = Val> + '_ {
+    let chunks = bytes.chunks(PACKED_BYTES_PER_ELEMENT).map(|chunk| {
+        let mut padded = [0u8; VAL_BYTES];
+        padded[..chunk.len()].copy_from_slice(chunk);
+        Val::from_u32(u32::from_le_bytes(padded))
+    });
+    core::iter::once(Val::from_u64(bytes.len() as u64)).chain(chunks)
+}
+
+// Reference encodings for external implementations: (canonical value, bytes)
+pub const VAL_TEST_VECTORS: [(u32, [u8; VAL_BYTES]); 4] = [
+    (0, [0x00, 0x00, 0x00, 0x00]),
+    (1, [0x01, 0x00, 0x00, 0x00]),
+    (0x1234_5678, [0x78, 0x56, 0x34, 0x12]),
+    // p - 1, the largest canonical element
+    (0x7800_0000, [0x00, 0x00, 0x00, 0x78]),
+];
+
+// (input bytes, packed elements as canonical u32s)
+pub const PACK_TEST_VECTORS: [(&[u8], &[u32]); 4] = [
+    (b"", &[0]),
+    (b"a", &[1, 0x61]),
+    (b"abc", &[3, 0x63_6261]),
+    (b"abcd", &[4, 0x63_6261, 0x64]),
+];
+
+// Checks every test vector in both directions, returning the first mismatch
+pub fn check_test_vectors() -> Result<(), String> {
+    for (value, bytes) in VAL_TEST_VECTORS {
+        if val_to_le_bytes(Val::from_u32(value)) != bytes {
+            return Err(format!("{} does not encode to {:?}", value, bytes));
+        }
+        if val_from_le_bytes(&bytes) != Ok(Val::from_u32(value)) {
+            return Err(format!("{:?} does not decode to {}", bytes, value));
+        }
+    }
+    for (input, expected) in PACK_TEST_VECTORS {
+        let packed: Vec<u32> = pack_bytes(input).map(|v| v.as_canonical_u32()).collect();
+        if packed != expected {
+            return Err(format!("{:?} packs to {:?}, expected {:?}", input, packed, expected));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_published_vectors_hold() {
+        assert_eq!(check_test_vectors(), Ok(()));
+    }
+
+    #[test]
+    fn only_canonical_four_byte_encodings_decode() {
+        let p = Val::ORDER_U32;
+        assert_eq!(val_from_le_bytes(&p.to_le_bytes()), Err(EncodingError::NonCanonical(p)));
+        assert_eq!(val_from_le_bytes(&[1, 0, 0]), Err(EncodingError::WrongLength { expected: 4, found: 3 }));
+        assert_eq!(val_from_le_bytes(&(p - 1).to_le_bytes()), Ok(-Val::ONE));
+    }
+
+    #[test]
+    fn challenges_round_trip_through_bytes_and_limbs() {
+        let limbs = [7, 0, 0x7800_0000, 1].map(Val::from_u32);
+        let challenge = challenge_from_limbs(&limbs).unwrap();
+        assert_eq!(challenge_to_limbs(challenge), limbs);
+        let bytes = challenge_to_le_bytes(challenge);
+        assert_eq!(bytes[..VAL_BYTES], val_to_le_bytes(limbs[0]));
+        assert_eq!(challenge_from_le_bytes(&bytes), Ok(challenge));
+        assert_eq!(
+            challenge_from_le_bytes(&bytes[1..]),
+            Err(EncodingError::WrongLength { expected: CHALLENGE_BYTES, found: CHALLENGE_BYTES - 1 })
+        );
+
+        let claim = ExtensionClaim(challenge);
+        assert_eq!(ExtensionClaim::from_public_values(&claim.to_public_values()), Ok(claim));
+        let short = ExtensionClaim::from_public_values(&limbs[..3]);
+        assert_eq!(short, Err(EncodingError::WrongLimbCount { expected: CHALLENGE_LIMBS, found: 3 }));
+    }
+
+    #[test]
+    fn the_length_prefix_keeps_trailing_zeros_apart() {
+        let packed = |bytes: &[u8]| pack_bytes(bytes).collect::<Vec<_>>();
+        assert_ne!(packed(b"ab"), packed(b"ab\0"));
+        assert_eq!(packed(b"abcdef").len(), 3);
+    }
+}
@@ -0,0 +1,99 @@
+use core::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use p3_air::Air;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{
+    DebugConstraintBuilder, Proof, ProverConstraintFolder, SymbolicAirBuilder,
+    VerifierConstraintFolder, prove, verify,
+};
+
+use crate::{MyConfig, Val};
+
+// Where an invalid witness was stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureStage {
+    // The prover panicked, e.g. its debug constraint check or the quotient
+    // no longer dividing evenly
+    Proving(String),
+    // A proof came out but the verifier rejected it
+    Verification(String),
+}
+
+impl fmt::Display for FailureStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Proving(msg) => write!(f, "caught while proving: {}", msg),
+            Self::Verification(msg) => write!(f, "caught at verification: {}", msg),
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "prover panicked".to_string(),
+        },
+    }
+}
+
+// Proves a trace that is expected to be invalid and reports the stage that
+// caught it. Prover panics become FailureStage::Proving instead of aborting.
+// Returns the proof as the error if it verifies, which would be a soundness bug.
+pub fn prove_expect_failure<A>(
+    config: &MyConfig,
+    air: &A,
+    trace: RowMajorMatrix<Val>,
+    public_values: &Vec<Val>,
+) -> Result<FailureStage, Box<Proof<MyConfig>>>
+where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val>>,
+{
+    // Keep the default hook from printing the expected panic
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let proved = panic::catch_unwind(AssertUnwindSafe(|| prove(config, air, trace, public_values)));
+    panic::set_hook(hook);
+
+    let proof = match proved {
+        Ok(proof) => proof,
+        Err(payload) => return Ok(FailureStage::Proving(panic_message(payload))),
+    };
+    match verify(config, air, &proof, public_values) {
+        Ok(()) => Err(Box::new(proof)),
+        Err(err) => Ok(FailureStage::Verification(format!("{:?}", err))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::{ArithmeticAir, NUM_ARITHMETIC_COLS, generate_arithmetic_trace, test_config};
+
+    #[test]
+    fn a_wrong_e_is_caught() {
+        let mut trace = generate_arithmetic_trace::<Val>().unwrap();
+        trace.matrix.values[7 * NUM_ARITHMETIC_COLS + 3] += Val::ONE;
+        let stage = prove_expect_failure(&test_config(), &ArithmeticAir, trace.matrix, &trace.public_values)
+            .unwrap_or_else(|_| panic!("a trace with 3 + 4 * 5 = 24 verified"));
+        // Debug builds check every constraint before committing
+        if cfg!(debug_assertions) {
+            assert!(matches!(stage, FailureStage::Proving(_)), "{}", stage);
+        }
+    }
+
+    #[test]
+    fn an_honest_trace_comes_back_as_its_proof() {
+        let trace = generate_arithmetic_trace::<Val>().unwrap();
+        let proved = prove_expect_failure(&test_config(), &ArithmeticAir, trace.matrix, &trace.public_values);
+        let proof = proved.expect_err("the honest trace was reported as a failure");
+        assert!(verify(&test_config(), &ArithmeticAir, &proof, &trace.public_values).is_ok());
+    }
+}
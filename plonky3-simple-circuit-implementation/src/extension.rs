@@ -0,0 +1,174 @@
+// A claim that lives in the extension field: knowledge of x with x * x = y,
+// where x and y are Challenge elements and y is public. Each element takes
+// CHALLENGE_LIMBS base columns, and y reaches the verifier as an
+// ExtensionClaim, four public values in the order encoding.rs fixes.
+use core::fmt;
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_field::PrimeCharacteristicRing;
+use p3_field::extension::BinomiallyExtendable;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{Proof, prove, verify};
+
+use crate::encoding::{CHALLENGE_LIMBS, EncodingError, ExtensionClaim, challenge_to_limbs};
+use crate::trace::GeneratedTrace;
+use crate::{Challenge, MyConfig, SecurityLevel, Val, create_default_config};
+
+// [x_0..x_3, y_0..y_3]
+pub const NUM_EXTENSION_COLS: usize = 2 * CHALLENGE_LIMBS;
+
+// Product in Val[X] / (X^4 - W) on limbs, for field elements and symbolic
+// expressions alike: a coefficient that lands on X^(4+k) wraps to W * X^k
+fn extension_mul<R: PrimeCharacteristicRing>(
+    a: &[R; CHALLENGE_LIMBS],
+    b: &[R; CHALLENGE_LIMBS],
+    w: R,
+) -> [R; CHALLENGE_LIMBS] {
+    core::array::from_fn(|k| {
+        let mut acc = R::ZERO;
+        for i in 0..CHALLENGE_LIMBS {
+            let j = (k + CHALLENGE_LIMBS - i) % CHALLENGE_LIMBS;
+            let term = a[i].clone() * b[j].clone();
+            acc += if i + j >= CHALLENGE_LIMBS { term * w.clone() } else { term };
+        }
+        acc
+    })
+}
+
+// Every row holds (x, x * x); the first row's square is bound to the claim
+#[derive(Debug, Clone)]
+pub struct ExtensionSquareAir;
+
+impl<F> BaseAir<F> for ExtensionSquareAir {
+    fn width(&self) -> usize {
+        NUM_EXTENSION_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues<F = Val>> Air<AB> for ExtensionSquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &[AB::Var] = &local;
+
+        let x: [AB::Expr; CHALLENGE_LIMBS] = core::array::from_fn(|i| local[i].clone().into());
+        let w = AB::Expr::from(<Val as BinomiallyExtendable<CHALLENGE_LIMBS>>::W);
+        let square = extension_mul(&x, &x, w);
+        for (i, limb) in square.into_iter().enumerate() {
+            builder.assert_eq(local[CHALLENGE_LIMBS + i].clone(), limb);
+        }
+
+        let pis = builder.public_values();
+        let claim: [AB::PublicVar; CHALLENGE_LIMBS] = core::array::from_fn(|i| pis[i]);
+        let mut when_first_row = builder.when_first_row();
+        for (i, limb) in claim.into_iter().enumerate() {
+            when_first_row.assert_eq(local[CHALLENGE_LIMBS + i].clone(), limb);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtensionError {
+    // Public values that do not decode to an ExtensionClaim
+    Claim(EncodingError),
+    Verification(String),
+}
+
+impl fmt::Display for ExtensionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Claim(e) => write!(f, "malformed claim: {}", e),
+            Self::Verification(msg) => write!(f, "square root rejected: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ExtensionError {}
+
+impl From<EncodingError> for ExtensionError {
+    fn from(e: EncodingError) -> Self {
+        Self::Claim(e)
+    }
+}
+
+// The square is computed with Challenge arithmetic rather than
+// extension_mul, so the proof also checks the AIR's limb formula against
+// Plonky3's own multiplication
+pub fn generate_square_trace(x: Challenge) -> GeneratedTrace<ExtensionClaim> {
+    // Same minimum height as the other examples; every row repeats (x, y)
+    let n = 256;
+    let claim = ExtensionClaim(x * x);
+    let mut row = challenge_to_limbs(x).to_vec();
+    row.extend(claim.to_public_values());
+    let values = (0..n).flat_map(|_| row.iter().copied()).collect();
+
+    GeneratedTrace {
+        matrix: RowMajorMatrix::new(values, NUM_EXTENSION_COLS),
+        real_rows: 1,
+        public_values: claim.to_public_values(),
+        meta: claim,
+    }
+}
+
+// Proves knowledge of x, returning the claimed square it binds
+pub fn prove_square_root(x: Challenge) -> (ExtensionClaim, Proof<MyConfig>) {
+    let trace = generate_square_trace(x);
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, &ExtensionSquareAir, trace.matrix, &trace.public_values);
+    (trace.meta, proof)
+}
+
+// Decodes the claim before any cryptography, so a wrong-length vector is a
+// Claim error and only well-formed claims reach verify
+pub fn verify_square_root(
+    public_values: &[Val],
+    proof: &Proof<MyConfig>,
+) -> Result<ExtensionClaim, ExtensionError> {
+    let claim = ExtensionClaim::from_public_values(public_values)?;
+    let config = create_default_config(SecurityLevel::Test);
+    verify(&config, &ExtensionSquareAir, proof, &claim.to_public_values())
+        .map_err(|err| ExtensionError::Verification(format!("{:?}", err)))?;
+    Ok(claim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::challenge_from_limbs;
+
+    fn limbs(coefficients: [u32; CHALLENGE_LIMBS]) -> Challenge {
+        challenge_from_limbs(&coefficients.map(Val::from_u32)).unwrap()
+    }
+
+    #[test]
+    fn claims_round_trip_through_public_values() {
+        // Limbs are the basis coefficients, lowest degree first; [0, 0, 0, 1] is X^3
+        for coefficients in [[0, 0, 0, 0], [1, 0, 0, 0], [0, 0, 0, 1], [5, 6, 7, 8]] {
+            let claim = ExtensionClaim(limbs(coefficients));
+            assert_eq!(claim.to_public_values(), coefficients.map(Val::from_u32));
+            assert_eq!(ExtensionClaim::from_public_values(&claim.to_public_values()), Ok(claim));
+        }
+    }
+
+    #[test]
+    fn the_square_root_proves_and_verifies() {
+        let x = limbs([1, 2, 3, 4]);
+        let (claim, proof) = prove_square_root(x);
+        assert_eq!(claim, ExtensionClaim(x * x));
+        assert_eq!(verify_square_root(&claim.to_public_values(), &proof), Ok(claim));
+    }
+
+    #[test]
+    fn permuted_or_missing_limbs_are_refused() {
+        let (claim, proof) = prove_square_root(limbs([1, 2, 3, 4]));
+        let public_values = claim.to_public_values();
+        // The same four limbs in another order are another element
+        let mut permuted = public_values.clone();
+        permuted.rotate_left(1);
+        assert!(matches!(verify_square_root(&permuted, &proof), Err(ExtensionError::Verification(_))));
+        assert_eq!(
+            verify_square_root(&public_values[..3], &proof),
+            Err(ExtensionError::Claim(EncodingError::WrongLimbCount { expected: CHALLENGE_LIMBS, found: 3 }))
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use p3_air::AirBuilder;
+use p3_field::PrimeCharacteristicRing;
+
+// Constrains `selectors` to be boolean with exactly one of them set.
+//
+// When `enabled` is given (typically an is_real column, which the caller must
+// constrain to be boolean) the selectors must sum to `enabled` instead of one:
+// real rows still need exactly one selector, padding rows need all of them zero.
+pub fn assert_one_hot<AB: AirBuilder>(
+    builder: &mut AB,
+    selectors: &[AB::Expr],
+    enabled: Option<AB::Expr>,
+) {
+    let mut sum = AB::Expr::ZERO;
+    for selector in selectors {
+        builder.assert_bool(selector.clone());
+        sum += selector.clone();
+    }
+
+    match enabled {
+        Some(enabled) => builder.assert_eq(sum, enabled),
+        None => builder.assert_one(sum),
+    }
+}
@@ -0,0 +1,296 @@
+// A single-row gate built from an expression tree over named wires. Each wire
+// gets a column, inputs in order of first appearance and the output last, and
+// the only constraint is output = expression on every row:
+//
+//     let expr = wire("a") + wire("c") * wire("d");
+//     let air = GateAir::new("e", expr, 2)?;
+use core::fmt;
+use core::ops::{Add, Mul};
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::trace::{ColumnNames, GeneratedTrace};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Wire(&'static str),
+    Const(u64),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+pub fn wire(name: &'static str) -> Expr {
+    Expr::Wire(name)
+}
+
+pub fn constant(value: u64) -> Expr {
+    Expr::Const(value)
+}
+
+impl Add for Expr {
+    type Output = Expr;
+
+    fn add(self, rhs: Expr) -> Expr {
+        Expr::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, rhs: Expr) -> Expr {
+        Expr::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Expr {
+    pub fn degree(&self) -> usize {
+        match self {
+            Self::Wire(_) => 1,
+            Self::Const(_) => 0,
+            Self::Add(l, r) => l.degree().max(r.degree()),
+            Self::Mul(l, r) => l.degree() + r.degree(),
+        }
+    }
+
+    fn collect_wires(&self, wires: &mut Vec<&'static str>) {
+        match self {
+            Self::Wire(name) if !wires.contains(name) => wires.push(*name),
+            Self::Wire(_) | Self::Const(_) => {}
+            Self::Add(l, r) | Self::Mul(l, r) => {
+                l.collect_wires(wires);
+                r.collect_wires(wires);
+            }
+        }
+    }
+
+    // `value(name)` supplies each wire, so the same tree evaluates over field
+    // elements in the solver and over symbolic expressions in the AIR
+    fn evaluate<R: PrimeCharacteristicRing>(&self, value: &impl Fn(&str) -> R) -> R {
+        match self {
+            Self::Wire(name) => value(name),
+            Self::Const(c) => R::from_u64(*c),
+            Self::Add(l, r) => l.evaluate(value) + r.evaluate(value),
+            Self::Mul(l, r) => l.evaluate(value) * r.evaluate(value),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wire(name) => write!(f, "{}", name),
+            Self::Const(c) => write!(f, "{}", c),
+            Self::Add(l, r) => write!(f, "({} + {})", l, r),
+            Self::Mul(l, r) => write!(f, "{} * {}", l, r),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateError {
+    DegreeTooHigh { degree: usize, bound: usize },
+    // The output wire also appears in its own expression
+    OutputIsInput(&'static str),
+    MissingInput(&'static str),
+    // The assignment fixes the output to something the expression does not give
+    Unsatisfiable { output: &'static str, assigned: u64, computed: u64 },
+}
+
+impl fmt::Display for GateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DegreeTooHigh { degree, bound } => {
+                write!(f, "expression has degree {}, above the bound of {}", degree, bound)
+            }
+            Self::OutputIsInput(name) => write!(f, "output wire {} appears in its own expression", name),
+            Self::MissingInput(name) => write!(f, "no value assigned to input wire {}", name),
+            Self::Unsatisfiable { output, assigned, computed } => {
+                write!(f, "{} is assigned {} but the inputs give {}", output, assigned, computed)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GateError {}
+
+#[derive(Debug, Clone)]
+pub struct GateAir {
+    // Column order: inputs by first appearance, then the output
+    wires: Vec<&'static str>,
+    expr: Expr,
+}
+
+impl GateAir {
+    // `max_degree` is the largest constraint degree the caller's FRI
+    // parameters can take; it is checked here rather than at proving time
+    pub fn new(output: &'static str, expr: Expr, max_degree: usize) -> Result<Self, GateError> {
+        let degree = expr.degree();
+        if degree > max_degree {
+            return Err(GateError::DegreeTooHigh { degree, bound: max_degree });
+        }
+        let mut wires = Vec::new();
+        expr.collect_wires(&mut wires);
+        if wires.contains(&output) {
+            return Err(GateError::OutputIsInput(output));
+        }
+        wires.push(output);
+        Ok(Self { wires, expr })
+    }
+
+    pub fn wires(&self) -> &[&'static str] {
+        &self.wires
+    }
+
+    pub fn output(&self) -> &'static str {
+        self.wires[self.wires.len() - 1]
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    // Every wire of expr was collected into wires at construction, so only a
+    // name from outside the expression can miss
+    fn column(&self, name: &str) -> Option<usize> {
+        self.wires.iter().position(|&wire| wire == name)
+    }
+
+    // One trace row from named input values. An assignment may also name the
+    // output, which must then match what the inputs give.
+    pub fn solve<F: PrimeField64>(&self, assignment: &[(&str, u64)]) -> Result<Vec<F>, GateError> {
+        let lookup = |name: &str| assignment.iter().find(|(wire, _)| *wire == name).map(|&(_, v)| v);
+        let mut row = Vec::with_capacity(self.wires.len());
+        for &name in &self.wires[..self.wires.len() - 1] {
+            row.push(F::from_u64(lookup(name).ok_or(GateError::MissingInput(name))?));
+        }
+        let output = self.expr.evaluate(&|name: &str| self.column(name).map_or(F::ZERO, |col| row[col]));
+        if let Some(assigned) = lookup(self.output()) {
+            if F::from_u64(assigned) != output {
+                return Err(GateError::Unsatisfiable {
+                    output: self.output(),
+                    assigned,
+                    computed: output.as_canonical_u64(),
+                });
+            }
+        }
+        row.push(output);
+        Ok(row)
+    }
+}
+
+impl ColumnNames for GateAir {
+    fn column_names(&self) -> &[&'static str] {
+        &self.wires
+    }
+}
+
+impl<F> BaseAir<F> for GateAir {
+    fn width(&self) -> usize {
+        self.wires.len()
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for GateAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &[AB::Var] = &local;
+
+        let value = |name: &str| -> AB::Expr {
+            self.column(name).map_or(AB::Expr::ZERO, |col| local[col].clone().into())
+        };
+        let computed = self.expr.evaluate(&value);
+        builder.assert_eq(local[self.wires.len() - 1].clone(), computed);
+    }
+}
+
+// One row per assignment, padded to 256 rows with the row for all-zero
+// inputs, which satisfies the gate whatever its constants
+pub fn generate_gate_trace<F: PrimeField64>(
+    air: &GateAir,
+    assignments: &[Vec<(&str, u64)>],
+) -> Result<GeneratedTrace<(), F>, GateError> {
+    let width = air.wires.len();
+    let n = assignments.len().next_power_of_two().max(256);
+    let zeros: Vec<(&str, u64)> = air.wires[..width - 1].iter().map(|&name| (name, 0)).collect();
+    let padding = air.solve::<F>(&zeros)?;
+
+    let mut values = Vec::with_capacity(n * width);
+    for assignment in assignments {
+        values.extend(air.solve::<F>(assignment)?);
+    }
+    for _ in assignments.len()..n {
+        values.extend_from_slice(&padding);
+    }
+
+    Ok(GeneratedTrace {
+        matrix: RowMajorMatrix::new(values, width),
+        real_rows: assignments.len(),
+        public_values: vec![], // GateAir has no public inputs
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::assert_proof_roundtrip;
+    use test_support::checker::prover_accepts;
+
+    use super::*;
+    use crate::Val;
+    use crate::trace::{TraceView, TraceViewError};
+
+    fn arithmetic_gate() -> GateAir {
+        GateAir::new("e", wire("a") + wire("c") * wire("d"), 2).unwrap()
+    }
+
+    #[test]
+    fn wires_take_columns_by_first_appearance_then_the_output() {
+        let air = arithmetic_gate();
+        assert_eq!(air.wires(), ["a", "c", "d", "e"]);
+        assert_eq!(air.output(), "e");
+        assert_eq!(air.expr().to_string(), "(a + c * d)");
+        assert_eq!(air.solve::<Val>(&[("d", 5), ("c", 4), ("a", 3)]), Ok([3, 4, 5, 23].map(Val::from_u8).to_vec()));
+    }
+
+    #[test]
+    fn malformed_gates_and_assignments_are_refused() {
+        let cubic = wire("x") * wire("x") * wire("x");
+        assert_eq!(GateAir::new("y", cubic, 2).err(), Some(GateError::DegreeTooHigh { degree: 3, bound: 2 }));
+        let circular = wire("x") + wire("y");
+        assert_eq!(GateAir::new("y", circular, 2).err(), Some(GateError::OutputIsInput("y")));
+
+        let air = arithmetic_gate();
+        assert_eq!(air.solve::<Val>(&[("a", 3), ("c", 4)]), Err(GateError::MissingInput("d")));
+        let wrong = air.solve::<Val>(&[("a", 3), ("c", 4), ("d", 5), ("e", 24)]);
+        assert_eq!(wrong, Err(GateError::Unsatisfiable { output: "e", assigned: 24, computed: 23 }));
+    }
+
+    #[test]
+    fn a_gate_trace_proves_and_a_wrong_output_does_not() {
+        let air = GateAir::new("y", wire("x") * wire("x") + constant(7), 2).unwrap();
+        let assignments = vec![vec![("x", 2)], vec![("x", 3), ("y", 16)]];
+        let trace = generate_gate_trace::<Val>(&air, &assignments).unwrap();
+        assert_eq!((trace.real_rows, trace.matrix.height()), (2, 256));
+        // Padding is the all-zero input's row, 0 * 0 + 7
+        assert_eq!(trace.matrix.values[2 * 2..2 * 3], [Val::ZERO, Val::from_u8(7)]);
+        assert_proof_roundtrip(&air, trace.matrix.clone(), &trace.public_values);
+
+        let mut tampered = trace.matrix;
+        tampered.values[1] += Val::ONE;
+        assert!(prover_accepts(&air, tampered, &trace.public_values).is_err());
+    }
+
+    #[test]
+    fn a_trace_view_reads_the_gate_by_wire_name() {
+        let air = GateAir::new("y", wire("x") * wire("x") + constant(7), 2).unwrap();
+        let trace = generate_gate_trace::<Val>(&air, &[vec![("x", 2)], vec![("x", 3)]]).unwrap();
+        let view = TraceView::named(&trace.matrix, &air);
+        assert_eq!(view.get_named(1, "y"), Ok(Val::from_u8(16)));
+        assert_eq!(view.column_index("y"), Ok(1));
+        assert_eq!(view.get_named(1, "e"), Err(TraceViewError::NoSuchColumn("e".to_string())));
+    }
+}
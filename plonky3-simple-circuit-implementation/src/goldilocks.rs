@@ -0,0 +1,82 @@
+// ArithmeticAir over Goldilocks (p = 2^64 - 2^32 + 1) instead of BabyBear. The
+// AIR and trace generator are already generic over the field; what changes is
+// the config: Poseidon2 over 8 Goldilocks elements, 4-element digests, and a
+// degree-2 extension, which is as large as BabyBear's degree-4 one.
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::Field;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::TwoAdicFriPcs;
+use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::claims::ArithmeticClaims;
+use crate::trace::LengthOverflow;
+use crate::{
+    ArithmeticAir, DEFAULT_CONFIG_SEED, SecurityLevel, VerifyError, constants_rng, fri_params_for,
+    generate_arithmetic_trace,
+};
+
+pub type GoldilocksPerm = Poseidon2Goldilocks<8>;
+pub type GoldilocksHash = PaddingFreeSponge<GoldilocksPerm, 8, 4, 4>;
+pub type GoldilocksCompress = TruncatedPermutation<GoldilocksPerm, 2, 4, 8>;
+pub type GoldilocksValMmcs = MerkleTreeMmcs<
+    <Goldilocks as Field>::Packing,
+    <Goldilocks as Field>::Packing,
+    GoldilocksHash,
+    GoldilocksCompress,
+    4,
+>;
+pub type GoldilocksChallenge = BinomialExtensionField<Goldilocks, 2>;
+pub type GoldilocksChallengeMmcs = ExtensionMmcs<Goldilocks, GoldilocksChallenge, GoldilocksValMmcs>;
+pub type GoldilocksChallenger = DuplexChallenger<Goldilocks, GoldilocksPerm, 8, 4>;
+pub type GoldilocksDft = Radix2DitParallel<Goldilocks>;
+pub type GoldilocksPcs = TwoAdicFriPcs<Goldilocks, GoldilocksDft, GoldilocksValMmcs, GoldilocksChallengeMmcs>;
+pub type GoldilocksConfig = StarkConfig<GoldilocksPcs, GoldilocksChallenge, GoldilocksChallenger>;
+
+// create_config_with_seed for Goldilocks: same seed handling and FRI parameters
+pub fn create_goldilocks_config(seed: u64) -> GoldilocksConfig {
+    let mut rng = constants_rng(seed);
+    let perm = GoldilocksPerm::new_from_rng_128(&mut rng);
+    let hash = GoldilocksHash::new(perm.clone());
+    let compress = GoldilocksCompress::new(perm.clone());
+    let val_mmcs = GoldilocksValMmcs::new(hash, compress);
+    let challenge_mmcs = GoldilocksChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = GoldilocksPcs::new(GoldilocksDft::default(), val_mmcs, fri_params);
+    GoldilocksConfig::new(pcs, GoldilocksChallenger::new(perm))
+}
+
+// prove_arithmetic over Goldilocks
+pub fn prove_arithmetic_goldilocks() -> Result<(Proof<GoldilocksConfig>, Vec<Goldilocks>), LengthOverflow> {
+    let trace = generate_arithmetic_trace::<Goldilocks>()?;
+    let config = create_goldilocks_config(DEFAULT_CONFIG_SEED);
+    let proof = prove(&config, &ArithmeticAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_arithmetic_goldilocks(
+    proof: &Proof<GoldilocksConfig>,
+    public_values: &Vec<Goldilocks>,
+) -> Result<ArithmeticClaims, VerifyError> {
+    let claims = ArithmeticClaims::from_proof(public_values, proof)?;
+    verify(&create_goldilocks_config(DEFAULT_CONFIG_SEED), &ArithmeticAir, proof, public_values)
+        .map_err(|e| VerifyError::Proof(format!("{:?}", e)))?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_goldilocks_proof_verifies_under_its_seed_only() {
+        let (proof, public_values) = prove_arithmetic_goldilocks().unwrap();
+        assert_eq!(verify_arithmetic_goldilocks(&proof, &public_values), Ok(ArithmeticClaims { num_rows: 256 }));
+        let other_seed = create_goldilocks_config(DEFAULT_CONFIG_SEED + 1);
+        assert!(verify(&other_seed, &ArithmeticAir, &proof, &public_values).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use p3_field::PrimeCharacteristicRing;
+use p3_symmetric::CryptographicHasher;
+
+use crate::encoding::pack_bytes;
+use crate::{DEFAULT_CONFIG_SEED, MyHash, Perm, Val, constants_rng};
+
+// Elements produced by one sponge call
+const DIGEST_LEN: usize = 8;
+
+fn hasher() -> MyHash {
+    let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+    MyHash::new(Perm::new_from_rng_128(&mut rng))
+}
+
+// Hashes arbitrary bytes to `count` field elements with the Poseidon2 sponge
+// used by the Merkle trees. The first 8 outputs hash encoding::pack_bytes of
+// the input; each further block of 8 hashes the previous block with its block
+// index. The packing and the seed are part of the output format and must not
+// change.
+pub fn hash_bytes_to_fields(bytes: &[u8], count: usize) -> Vec<Val> {
+    let hasher = hasher();
+    let mut block = hasher.hash_iter(pack_bytes(bytes));
+    let mut out = Vec::with_capacity(count);
+    for index in 1.. {
+        let take = (count - out.len()).min(DIGEST_LEN);
+        out.extend_from_slice(&block[..take]);
+        if out.len() == count {
+            break;
+        }
+        block = hasher.hash_iter(block.into_iter().chain([Val::from_u32(index)]));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longer_outputs_extend_shorter_ones() {
+        let long = hash_bytes_to_fields(b"transfer 10 to alice", 20);
+        assert_eq!(long.len(), 20);
+        assert_eq!(hash_bytes_to_fields(b"transfer 10 to alice", 8), long[..8]);
+        assert_eq!(hash_bytes_to_fields(b"transfer 10 to alice", 3), long[..3]);
+        assert!(hash_bytes_to_fields(b"transfer 10 to alice", 0).is_empty());
+        // Each block after the first hashes its predecessor with its index
+        assert_ne!(long[..8], long[8..16]);
+    }
+
+    #[test]
+    fn inputs_differing_only_in_trailing_zeros_hash_apart() {
+        assert_ne!(hash_bytes_to_fields(b"ab", 8), hash_bytes_to_fields(b"ab\0", 8));
+        assert_ne!(hash_bytes_to_fields(b"", 8), hash_bytes_to_fields(b"\0", 8));
+    }
+}
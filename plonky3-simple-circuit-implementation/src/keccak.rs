@@ -0,0 +1,105 @@
+// MyConfig with Keccak-256 Merkle trees and a Keccak transcript in place of
+// Poseidon2, for verifiers that can only hash bytes. Field elements are
+// serialized to bytes before hashing and digests are 32 bytes; the field,
+// the extension and the FRI parameters are MyConfig's.
+//
+// KeccakChallengerConfig swaps only the transcript: MyConfig's Poseidon2
+// Merkle trees with the SerializingChallenger, so a verifier outside Rust
+// can replay the Fiat-Shamir challenges with nothing but Keccak-256.
+use p3_challenger::{HashChallenger, SerializingChallenger32};
+use p3_commit::ExtensionMmcs;
+use p3_fri::TwoAdicFriPcs;
+use p3_keccak::Keccak256Hash;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::claims::ArithmeticClaims;
+use crate::trace::LengthOverflow;
+use crate::{
+    ArithmeticAir, Challenge, ChallengeMmcs, DEFAULT_CONFIG_SEED, Dft, MyCompress, MyHash, Pcs, Perm, PublicValues,
+    SecurityLevel, Val, ValMmcs, VerifyError, constants_rng, fri_params_for, generate_arithmetic_trace,
+};
+
+pub type KeccakHash = SerializingHasher<Keccak256Hash>;
+pub type KeccakCompress = CompressionFunctionFromHasher<Keccak256Hash, 2, 32>;
+pub type KeccakValMmcs = MerkleTreeMmcs<Val, u8, KeccakHash, KeccakCompress, 32>;
+pub type KeccakChallengeMmcs = ExtensionMmcs<Val, Challenge, KeccakValMmcs>;
+pub type KeccakChallenger = SerializingChallenger32<Val, HashChallenger<u8, Keccak256Hash, 32>>;
+pub type KeccakPcs = TwoAdicFriPcs<Val, Dft, KeccakValMmcs, KeccakChallengeMmcs>;
+pub type KeccakConfig = StarkConfig<KeccakPcs, Challenge, KeccakChallenger>;
+pub type KeccakChallengerConfig = StarkConfig<Pcs, Challenge, KeccakChallenger>;
+
+// Keccak has no constants to sample, so unlike create_config_with_seed this
+// takes no seed
+pub fn create_keccak_config() -> KeccakConfig {
+    let val_mmcs = KeccakValMmcs::new(KeccakHash::new(Keccak256Hash {}), KeccakCompress::new(Keccak256Hash {}));
+    let challenge_mmcs = KeccakChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = KeccakPcs::new(Dft::default(), val_mmcs, fri_params);
+    KeccakConfig::new(pcs, KeccakChallenger::from_hasher(vec![], Keccak256Hash {}))
+}
+
+// create_default_config with the challenger swapped
+pub fn create_keccak_challenger_config() -> KeccakChallengerConfig {
+    let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_params);
+    KeccakChallengerConfig::new(pcs, KeccakChallenger::from_hasher(vec![], Keccak256Hash {}))
+}
+
+// prove_arithmetic under the Keccak config
+pub fn prove_arithmetic_keccak() -> Result<(Proof<KeccakConfig>, PublicValues), LengthOverflow> {
+    let trace = generate_arithmetic_trace::<Val>()?;
+    let proof = prove(&create_keccak_config(), &ArithmeticAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_arithmetic_keccak(
+    proof: &Proof<KeccakConfig>,
+    public_values: &PublicValues,
+) -> Result<ArithmeticClaims, VerifyError> {
+    let claims = ArithmeticClaims::from_proof(public_values, proof)?;
+    verify(&create_keccak_config(), &ArithmeticAir, proof, public_values)
+        .map_err(|e| VerifyError::Proof(format!("{:?}", e)))?;
+    Ok(claims)
+}
+
+// prove_arithmetic under the Keccak challenger config
+pub fn prove_arithmetic_keccak_challenger() -> Result<(Proof<KeccakChallengerConfig>, PublicValues), LengthOverflow> {
+    let trace = generate_arithmetic_trace::<Val>()?;
+    let proof = prove(&create_keccak_challenger_config(), &ArithmeticAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_arithmetic_keccak_challenger(
+    proof: &Proof<KeccakChallengerConfig>,
+    public_values: &PublicValues,
+) -> Result<ArithmeticClaims, VerifyError> {
+    let claims = ArithmeticClaims::from_proof(public_values, proof)?;
+    verify(&create_keccak_challenger_config(), &ArithmeticAir, proof, public_values)
+        .map_err(|e| VerifyError::Proof(format!("{:?}", e)))?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prove_arithmetic, verify_arithmetic};
+
+    #[test]
+    fn claims_decode_equal_across_configs() {
+        let (proof, public_values) = prove_arithmetic().unwrap();
+        let duplex = verify_arithmetic(&proof, &public_values).unwrap();
+        let (proof, public_values) = prove_arithmetic_keccak_challenger().unwrap();
+        let challenger = verify_arithmetic_keccak_challenger(&proof, &public_values).unwrap();
+        let (proof, public_values) = prove_arithmetic_keccak().unwrap();
+        let keccak = verify_arithmetic_keccak(&proof, &public_values).unwrap();
+        assert_eq!(duplex, challenger);
+        assert_eq!(duplex, keccak);
+        assert_eq!(duplex.num_rows, 256);
+    }
+}
@@ -0,0 +1,81 @@
+// ArithmeticAir over KoalaBear (p = 2^31 - 2^24 + 1) instead of BabyBear. Both
+// are 31-bit fields with a degree-4 extension and a width-16 Poseidon2, so the
+// config is BabyBear's with the field swapped.
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::Field;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::TwoAdicFriPcs;
+use p3_koala_bear::{KoalaBear, Poseidon2KoalaBear};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{Proof, StarkConfig, prove, verify};
+
+use crate::claims::ArithmeticClaims;
+use crate::trace::LengthOverflow;
+use crate::{
+    ArithmeticAir, DEFAULT_CONFIG_SEED, SecurityLevel, VerifyError, constants_rng, fri_params_for,
+    generate_arithmetic_trace,
+};
+
+pub type KoalaBearPerm = Poseidon2KoalaBear<16>;
+pub type KoalaBearHash = PaddingFreeSponge<KoalaBearPerm, 16, 8, 8>;
+pub type KoalaBearCompress = TruncatedPermutation<KoalaBearPerm, 2, 8, 16>;
+pub type KoalaBearValMmcs = MerkleTreeMmcs<
+    <KoalaBear as Field>::Packing,
+    <KoalaBear as Field>::Packing,
+    KoalaBearHash,
+    KoalaBearCompress,
+    8,
+>;
+pub type KoalaBearChallenge = BinomialExtensionField<KoalaBear, 4>;
+pub type KoalaBearChallengeMmcs = ExtensionMmcs<KoalaBear, KoalaBearChallenge, KoalaBearValMmcs>;
+pub type KoalaBearChallenger = DuplexChallenger<KoalaBear, KoalaBearPerm, 16, 8>;
+pub type KoalaBearDft = Radix2DitParallel<KoalaBear>;
+pub type KoalaBearPcs = TwoAdicFriPcs<KoalaBear, KoalaBearDft, KoalaBearValMmcs, KoalaBearChallengeMmcs>;
+pub type KoalaBearConfig = StarkConfig<KoalaBearPcs, KoalaBearChallenge, KoalaBearChallenger>;
+
+// create_config_with_seed for KoalaBear: same seed handling and FRI parameters
+pub fn create_koala_bear_config(seed: u64) -> KoalaBearConfig {
+    let mut rng = constants_rng(seed);
+    let perm = KoalaBearPerm::new_from_rng_128(&mut rng);
+    let hash = KoalaBearHash::new(perm.clone());
+    let compress = KoalaBearCompress::new(perm.clone());
+    let val_mmcs = KoalaBearValMmcs::new(hash, compress);
+    let challenge_mmcs = KoalaBearChallengeMmcs::new(val_mmcs.clone());
+    let fri_params = fri_params_for(SecurityLevel::Test, challenge_mmcs);
+    let pcs = KoalaBearPcs::new(KoalaBearDft::default(), val_mmcs, fri_params);
+    KoalaBearConfig::new(pcs, KoalaBearChallenger::new(perm))
+}
+
+// prove_arithmetic over KoalaBear
+pub fn prove_arithmetic_koala_bear() -> Result<(Proof<KoalaBearConfig>, Vec<KoalaBear>), LengthOverflow> {
+    let trace = generate_arithmetic_trace::<KoalaBear>()?;
+    let config = create_koala_bear_config(DEFAULT_CONFIG_SEED);
+    let proof = prove(&config, &ArithmeticAir, trace.matrix, &trace.public_values);
+    Ok((proof, trace.public_values))
+}
+
+pub fn verify_arithmetic_koala_bear(
+    proof: &Proof<KoalaBearConfig>,
+    public_values: &Vec<KoalaBear>,
+) -> Result<ArithmeticClaims, VerifyError> {
+    let claims = ArithmeticClaims::from_proof(public_values, proof)?;
+    verify(&create_koala_bear_config(DEFAULT_CONFIG_SEED), &ArithmeticAir, proof, public_values)
+        .map_err(|e| VerifyError::Proof(format!("{:?}", e)))?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_koala_bear_proof_verifies_under_its_seed_only() {
+        let (proof, public_values) = prove_arithmetic_koala_bear().unwrap();
+        assert_eq!(verify_arithmetic_koala_bear(&proof, &public_values), Ok(ArithmeticClaims { num_rows: 256 }));
+        let other_seed = create_koala_bear_config(DEFAULT_CONFIG_SEED + 1);
+        assert!(verify(&other_seed, &ArithmeticAir, &proof, &public_values).is_err());
+    }
+}
@@ -1,173 +1,112 @@
-use core::borrow::Borrow;
-use p3_air::{Air, AirBuilder, BaseAir};
-use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::DuplexChallenger;
-use p3_commit::ExtensionMmcs;
-use p3_dft::Radix2DitParallel;
-use p3_field::extension::BinomialExtensionField;
-use p3_field::{Field, PrimeField64};
-use p3_fri::{TwoAdicFriPcs, create_test_fri_params};
-use p3_matrix::Matrix;
-use p3_matrix::dense::RowMajorMatrix;
-use p3_merkle_tree::MerkleTreeMmcs;
-use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{StarkConfig, prove, verify};
-
-const NUM_ARITHMETIC_COLS: usize = 4;
-
-#[derive(Debug, Clone)]
-pub struct ArithmeticAir;
-
-impl<F> BaseAir<F> for ArithmeticAir {
-    fn width(&self) -> usize {
-        NUM_ARITHMETIC_COLS
-    }
-}
-
-impl<AB: AirBuilder> Air<AB> for ArithmeticAir {
-    fn eval(&self, builder: &mut AB) {
-        let main = builder.main();
-        let local = main.row_slice(0).expect("Matrix is empty?");
-        let local: &ArithmeticRow<AB::Var> = (*local).borrow();
-        
-        let constraint = local.a.clone() + local.c.clone() * local.d.clone() - local.e.clone();
-        builder.assert_zero(constraint);
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct ArithmeticRow<F> {
-    pub a: F, pub c: F, pub d: F, pub e: F,
-}
+use std::time::Instant;
 
-impl<F> ArithmeticRow<F> {
-    const fn new(a: F, c: F, d: F, e: F) -> Self {
-        Self { a, c, d, e }
-    }
-}
-
-impl<F> Borrow<ArithmeticRow<F>> for [F] {
-    fn borrow(&self) -> &ArithmeticRow<F> {
-        debug_assert_eq!(self.len(), NUM_ARITHMETIC_COLS);
-        let (prefix, rows, suffix) = unsafe { self.align_to::<ArithmeticRow<F>>() };
-        debug_assert!(prefix.is_empty(), "Alignment should match");
-        debug_assert!(suffix.is_empty(), "Alignment should match");
-        debug_assert_eq!(rows.len(), 1);
-        &rows[0]
-    }
-}
-
-pub fn generate_arithmetic_trace<F: PrimeField64>() -> RowMajorMatrix<F> {
-    let n = 256; // Increased to 256 rows to meet minimum FRI requirements
-    let mut trace = RowMajorMatrix::new(F::zero_vec(n * NUM_ARITHMETIC_COLS), NUM_ARITHMETIC_COLS);
+use p3_field::{BasedVectorSpace, PrimeCharacteristicRing};
+use p3_matrix::Matrix;
+use p3_uni_stark::{prove, verify};
+use simple_arithmetic_proof::bounded::{prove_bounded_arithmetic, verify_bounded_arithmetic};
+use simple_arithmetic_proof::commit_reveal::{
+    OpeningClaims, commit, commit_message, commitment_public_values, prove_opening, verify_opening,
+};
+use simple_arithmetic_proof::encoding::{challenge_from_le_bytes, challenge_to_le_bytes, check_test_vectors};
+use simple_arithmetic_proof::expect_failure::prove_expect_failure;
+use simple_arithmetic_proof::gate::{GateAir, constant, generate_gate_trace, wire};
+use simple_arithmetic_proof::hashing::hash_bytes_to_fields;
+use simple_arithmetic_proof::merkle::{Tree, verify_path};
+use simple_arithmetic_proof::one_hot::{OneHotAir, generate_one_hot_trace};
+use simple_arithmetic_proof::r1cs::{NUM_R1CS_COLS, R1csAir, cubic_witness, generate_r1cs_trace};
+use simple_arithmetic_proof::sbox::{
+    SBOX, SboxAir, eval_sbox_polynomial, generate_sbox_trace, interpolate_sbox, to_nibbles,
+};
+use simple_arithmetic_proof::trace::{ColumnNames, TraceView};
+use simple_arithmetic_proof::{
+    ArithmeticAir, Challenge, DEFAULT_CONFIG_SEED, NUM_ARITHMETIC_COLS, SecurityLevel, Val,
+    create_config_with_seed, create_default_config, generate_arithmetic_trace, prove_arithmetic, verify_arithmetic,
+};
 
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<ArithmeticRow<F>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), n);
+// Every flag this binary reads; --hash also takes a value
+const KNOWN_FLAGS: [&str; 4] = ["--allow-invalid", "--domain-check", "--security-levels", "--hash"];
 
-    // Fill all rows with the same arithmetic constraint: a + c * d = e
-    for i in 0..n {
-        rows[i] = ArithmeticRow::new(
-            F::from_u64(3), F::from_u64(4), F::from_u64(5), F::from_u64(23)
-        );
+// Refuses anything but KNOWN_FLAGS, so a mistyped flag is not quietly ignored
+fn check_args() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--hash" if args.next().is_none() => return Err("--hash takes poseidon2 or keccak".to_string()),
+            flag if KNOWN_FLAGS.contains(&flag) => {}
+            other => return Err(format!("Unknown argument {:?}; expected {}", other, KNOWN_FLAGS.join(", "))),
+        }
     }
-
-    trace
-}
-
-type Val = BabyBear;
-type Perm = Poseidon2BabyBear<16>;
-type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
-type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
-type ValMmcs = MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
-type Challenge = BinomialExtensionField<Val, 4>;
-type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
-type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
-type Dft = Radix2DitParallel<Val>;
-type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
-type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
-
-struct SimpleRng {
-    state: u64,
+    Ok(())
 }
 
-impl SimpleRng {
-    fn new(seed: u64) -> Self {
-        Self { state: seed }
+fn main() {
+    if let Err(e) = check_args() {
+        println!("❌ {}", e);
+        std::process::exit(2);
     }
-}
 
-impl rand::RngCore for SimpleRng {
-    fn next_u32(&mut self) -> u32 {
-        self.state = self.state.wrapping_mul(1103515245).wrapping_add(12345);
-        (self.state >> 32) as u32
+    if std::env::args().any(|arg| arg == "--allow-invalid") {
+        run_invalid_witness_demo();
+        return;
     }
 
-    fn next_u64(&mut self) -> u64 {
-        let high = self.next_u32() as u64;
-        let low = self.next_u32() as u64;
-        (high << 32) | low
+    if std::env::args().any(|arg| arg == "--domain-check") {
+        run_domain_check();
+        return;
     }
 
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        for chunk in dest.chunks_mut(4) {
-            let val = self.next_u32().to_le_bytes();
-            for (i, &byte) in val.iter().enumerate() {
-                if i < chunk.len() {
-                    chunk[i] = byte;
-                }
-            }
-        }
+    if std::env::args().any(|arg| arg == "--security-levels") {
+        run_security_levels_demo();
+        return;
     }
-}
 
-impl rand::CryptoRng for SimpleRng {}
-
-
-fn create_config() -> MyConfig {
-    let mut rng = SimpleRng::new(42);
-    let perm = Perm::new_from_rng_128(&mut rng);
-    let hash = MyHash::new(perm.clone());
-    let compress = MyCompress::new(perm.clone());
-    let val_mmcs = ValMmcs::new(hash, compress);
-    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-    let dft = Dft::default();
-    let fri_params = create_test_fri_params(challenge_mmcs, 4);
-    let pcs = Pcs::new(dft, val_mmcs, fri_params);
-    let challenger = Challenger::new(perm);
-    MyConfig::new(pcs, challenger)
-}
-
-fn main() {
     println!("🧮 Plonky3 Arithmetic Proof System");
     println!("   Proving: a + c * d = e");
     println!("   Values: 3 + 4 * 5 = 23");
     println!();
     
-    let air = ArithmeticAir;
-    let trace = generate_arithmetic_trace::<Val>();
-    let config = create_config();
+    let trace = match generate_arithmetic_trace::<Val>() {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
     
+    let view = TraceView::named(&trace.matrix, &ArithmeticAir);
+    let first_row: Vec<String> = ArithmeticAir
+        .column_names()
+        .iter()
+        .map(|name| match view.get_named(0, name) {
+            Ok(value) => format!("{}={}", name, value),
+            Err(e) => e.to_string(),
+        })
+        .collect();
     println!("✅ Generated execution trace:");
-    println!("   256 rows: [a=3, c=4, d=5, e=23] (repeated)");
+    println!("   {} rows: [{}] (repeated)", trace.real_rows, first_row.join(", "));
     println!("   Constraint: a + c * d - e = 0");
     println!("   Check: 3 + 4 * 5 - 23 = 0 ✅");
     println!();
     
     println!("🔄 Generating STARK proof...");
-    let proof = prove(&config, &air, trace, &vec![]);
+    let (proof, public_values) = match prove_arithmetic() {
+        Ok(proved) => proved,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
     
     println!("✅ Proof generated successfully!");
     println!();
     
     println!("🔍 Verifying proof...");
-    let verify_result = verify(&config, &air, &proof, &vec![]);
+    let verify_result = verify_arithmetic(&proof, &public_values);
     
     match verify_result {
-        Ok(()) => println!("🎉 Proof verified successfully!"),
+        Ok(claims) => println!("🎉 Proof verified successfully: {}", claims),
         Err(e) => {
-            println!("❌ Verification failed: {:?}", e);
+            println!("❌ Verification failed: {}", e);
             return;
         }
     }
@@ -177,4 +116,430 @@ fn main() {
     println!("   - Created STARK proof for: a + c*d = e");
     println!("   - Values: 3 + 4*5 = 23");
     println!("   - Proof verification completed ✅");
-}
\ No newline at end of file
+    println!();
+
+    match std::env::args().skip_while(|arg| arg != "--hash").nth(1).as_deref() {
+        None | Some("poseidon2") => {}
+        Some("keccak") => {
+            run_keccak_demo();
+            println!();
+        }
+        Some(other) => println!("❌ Unknown --hash {:?}; expected poseidon2 or keccak\n", other),
+    }
+
+    run_bounded_demo();
+    println!();
+
+    run_one_hot_demo();
+    println!();
+
+    run_commit_reveal_demo();
+    println!();
+
+    run_sbox_demo();
+    println!();
+
+    run_merkle_demo();
+    println!();
+
+    run_r1cs_demo();
+    println!();
+
+    run_gate_demo();
+    println!();
+
+    run_hashing_demo();
+}
+
+// --hash keccak: the same proof with Keccak-256 Merkle trees and challenger,
+// then with Poseidon2 Merkle trees and only the challenger on Keccak-256
+fn run_keccak_demo() {
+    use simple_arithmetic_proof::keccak::{
+        prove_arithmetic_keccak, prove_arithmetic_keccak_challenger, verify_arithmetic_keccak,
+        verify_arithmetic_keccak_challenger,
+    };
+
+    println!("🧮 Keccak-256 commitments: a + c * d = e");
+    let (proof, public_values) = match prove_arithmetic_keccak() {
+        Ok(proved) => proved,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    match verify_arithmetic_keccak(&proof, &public_values) {
+        Ok(claims) => println!("   Proved and verified ✅ {}", claims),
+        Err(e) => println!("   ❌ {}", e),
+    }
+
+    println!("🧮 Keccak-256 transcript, Poseidon2 commitments: a + c * d = e");
+    let (proof, public_values) = match prove_arithmetic_keccak_challenger() {
+        Ok(proved) => proved,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    match verify_arithmetic_keccak_challenger(&proof, &public_values) {
+        Ok(claims) => println!("   Proved and verified ✅ {}", claims),
+        Err(e) => println!("   ❌ {}", e),
+    }
+}
+
+// --domain-check: a proof bound to "arith-v1", verified under its own domain,
+// another, the empty one and none
+fn run_domain_check() {
+    use simple_arithmetic_proof::domain::{prove_arithmetic_in, verify_arithmetic_in};
+
+    println!("🧮 Domain separation: a + c * d = e proved under \"arith-v1\"");
+    let (proof, public_values) = match prove_arithmetic_in("arith-v1") {
+        Ok(proved) => proved,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    for domain in ["arith-v1", "arith-v2", ""] {
+        match verify_arithmetic_in(domain, &proof, &public_values) {
+            Ok(_) => println!("   Verified under {:?} ✅", domain),
+            Err(e) => println!("   Rejected under {:?} ❌ {}", domain, e),
+        }
+    }
+    match verify_arithmetic(&proof, &public_values) {
+        Ok(_) => println!("   Verified with no domain ✅"),
+        Err(e) => println!("   Rejected with no domain ❌ {}", e),
+    }
+}
+
+// --security-levels: the arithmetic proof at every SecurityLevel, with the
+// proving time of each
+fn run_security_levels_demo() {
+    println!("🧮 Security levels: a + c * d = e");
+    for level in SecurityLevel::ALL {
+        let fri = level.fri_choice();
+        println!(
+            "   {}: log_blowup {}, {} queries, {} pow bits, ~{} bits conjectured",
+            level,
+            fri.log_blowup,
+            fri.num_queries,
+            fri.pow_bits,
+            level.conjectured_security_bits()
+        );
+        let config = create_config_with_seed(DEFAULT_CONFIG_SEED, level);
+        let trace = match generate_arithmetic_trace::<Val>() {
+            Ok(trace) => trace,
+            Err(e) => {
+                println!("     ❌ {}", e);
+                continue;
+            }
+        };
+        let start = Instant::now();
+        let proof = prove(&config, &ArithmeticAir, trace.matrix, &trace.public_values);
+        let proving = start.elapsed();
+        match verify(&config, &ArithmeticAir, &proof, &trace.public_values) {
+            Ok(()) => println!("     Proved in {:?} and verified ✅", proving),
+            Err(e) => println!("     ❌ {:?}", e),
+        }
+    }
+}
+
+fn run_invalid_witness_demo() {
+    println!("🧮 Invalid witness: row 7 claims 3 + 4 * 5 = 24");
+
+    let mut trace = match generate_arithmetic_trace::<Val>() {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    let e = TraceView::named(&trace.matrix, &ArithmeticAir).column_index("e").expect("ArithmeticAir names e");
+    trace.matrix.values[7 * NUM_ARITHMETIC_COLS + e] = Val::from_u64(24);
+
+    let config = create_default_config(SecurityLevel::Test);
+    match prove_expect_failure(&config, &ArithmeticAir, trace.matrix, &trace.public_values) {
+        Ok(stage) => println!("   Rejected ✅ {}", stage),
+        Err(_) => println!("   ❌ The invalid witness produced a verifying proof"),
+    }
+}
+
+fn run_bounded_demo() {
+    println!("🧮 Bounded Arithmetic: a + c * d = e with private a in [0, bound]");
+
+    let bound = 100;
+    let (c, d) = (4, 5);
+    // e values giving a = bound, a = 0, and a = bound + 1
+    for e in [120, 20, 121] {
+        match prove_bounded_arithmetic(bound, c, d, e) {
+            Ok(proof) => match verify_bounded_arithmetic(bound, c, d, e, &proof) {
+                Ok(()) => println!("   e = {}: proof verified ✅ (public: bound, limbs, c, d, e)", e),
+                Err(err) => println!("   e = {}: ❌ {}", e, err),
+            },
+            Err(err) => println!("   e = {}: prover refused ❌ {}", e, err),
+        }
+    }
+}
+
+fn run_one_hot_demo() {
+    println!("🧮 One-hot selectors: exactly one of 4 flags per real row");
+
+    let choices = [0, 3, 1, 2, 2, 0, 1];
+    let trace = match generate_one_hot_trace::<Val>(&choices) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    println!("   {} real rows, padded to {}", trace.real_rows, trace.matrix.height());
+
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, &OneHotAir, trace.matrix, &trace.public_values);
+    match verify(&config, &OneHotAir, &proof, &trace.public_values) {
+        Ok(()) => println!("   Proof verified successfully ✅"),
+        Err(e) => println!("   ❌ Verification failed: {:?}", e),
+    }
+
+    if let Err(e) = generate_one_hot_trace::<Val>(&[4]) {
+        println!("   Rejected by the generator ✅ {}", e);
+    }
+}
+
+fn run_sbox_demo() {
+    println!("🧮 S-box without lookups: out = P(in), P interpolating the 16-entry table");
+
+    let coefficients = interpolate_sbox::<Val>();
+    let matches_table = (0..16u8).all(|x| {
+        eval_sbox_polynomial(&coefficients, Val::from_u8(x)) == Val::from_u8(SBOX[x as usize])
+    });
+    println!("   P agrees with the table on all 16 inputs {}", if matches_table { "✅" } else { "❌" });
+
+    let nibbles = to_nibbles(b"plonky3");
+    let trace = match generate_sbox_trace::<Val>(&nibbles) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    println!("   {:x?} -> {:x?}", nibbles, trace.meta);
+
+    let air = SboxAir::new();
+    let config = create_default_config(SecurityLevel::Test);
+    let proof = prove(&config, &air, trace.matrix.clone(), &trace.public_values);
+    match verify(&config, &air, &proof, &trace.public_values) {
+        Ok(()) => println!("   Proof verified successfully ✅"),
+        Err(e) => println!("   ❌ Verification failed: {:?}", e),
+    }
+
+    if let Err(e) = generate_sbox_trace::<Val>(&[16]) {
+        println!("   Rejected by the generator ✅ {}", e);
+    }
+    // Column 0 is in, column 1 is out
+    let width = trace.matrix.width();
+    let mut wrong_output = trace.matrix.clone();
+    wrong_output.values[width + 1] += Val::ONE;
+    let mut out_of_range = trace.matrix;
+    out_of_range.values[0] = Val::from_u8(16);
+    for (name, tampered) in [("wrong output", wrong_output), ("input 16", out_of_range)] {
+        match prove_expect_failure(&config, &air, tampered, &trace.public_values) {
+            Ok(stage) => println!("   {} rejected ✅ {}", name, stage),
+            Err(_) => println!("   ❌ {} produced a verifying proof", name),
+        }
+    }
+}
+
+fn run_merkle_demo() {
+    println!("🧮 Merkle tree: Poseidon2 leaves and compression, as in the MMCS");
+
+    let leaves: Vec<Vec<Val>> = (0..5u64).map(|i| vec![Val::from_u64(i), Val::from_u64(i * i)]).collect();
+    let tree = match Tree::build(&leaves) {
+        Ok(tree) => tree,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    println!("   {} leaves padded to depth {}, root {:?}...", leaves.len(), tree.depth(), &tree.root()[..2]);
+
+    let all_verify = (0..leaves.len()).all(|i| match tree.open(i) {
+        Ok(path) => verify_path(&tree.root(), &leaves[i], &path),
+        Err(_) => false,
+    });
+    println!("   Every leaf opens and verifies {}", if all_verify { "✅" } else { "❌" });
+
+    if let Ok(mut path) = tree.open(2) {
+        if !verify_path(&tree.root(), &leaves[3], &path) {
+            println!("   Wrong leaf rejected ✅");
+        }
+        path.directions[0] = !path.directions[0];
+        if !verify_path(&tree.root(), &leaves[2], &path) {
+            println!("   Flipped direction rejected ✅");
+        }
+    }
+    if let Err(e) = tree.open(5) {
+        println!("   Padding position refused: {} ✅", e);
+    }
+}
+
+fn run_r1cs_demo() {
+    println!("🧮 R1CS: x^3 + x + 5 = 35, one constraint per row as a_dot * b_dot = c_dot");
+
+    let config = create_default_config(SecurityLevel::Test);
+    let trace = match generate_r1cs_trace(&cubic_witness::<Val>(3)) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    println!("   Public x = {}, out = {}", trace.public_values[0], trace.public_values[1]);
+    let proof = prove(&config, &R1csAir, trace.matrix.clone(), &trace.public_values);
+    match verify(&config, &R1csAir, &proof, &trace.public_values) {
+        Ok(()) => println!("   x = 3: proof verified successfully ✅"),
+        Err(e) => println!("   ❌ Verification failed: {:?}", e),
+    }
+
+    // x = 4 with out still claimed to be 35: the output row has 73 * 1 != 35
+    let mut wrong_witness = cubic_witness::<Val>(4);
+    wrong_witness[2] = Val::from_u64(35);
+    let wrong = match generate_r1cs_trace(&wrong_witness) {
+        Ok(trace) => trace,
+        Err(e) => {
+            println!("   ❌ {}", e);
+            return;
+        }
+    };
+    // Row 1 is sym1 * x = y; column 2 is its c_dot
+    let mut tampered = trace.matrix;
+    tampered.values[NUM_R1CS_COLS + 2] += Val::ONE;
+    let cases = [
+        ("x = 4", wrong.matrix, wrong.public_values),
+        ("tampered c_dot", tampered, trace.public_values),
+    ];
+    for (name, matrix, public_values) in cases {
+        match prove_expect_failure(&config, &R1csAir, matrix, &public_values) {
+            Ok(stage) => println!("   {} rejected ✅ {}", name, stage),
+            Err(_) => println!("   ❌ {} produced a verifying proof", name),
+        }
+    }
+}
+
+fn run_gate_demo() {
+    println!("🧮 Gates from expression trees over named wires");
+
+    let config = create_default_config(SecurityLevel::Test);
+    let gates = [
+        ("e", wire("a") + wire("c") * wire("d"), vec![vec![("a", 3), ("c", 4), ("d", 5)]]),
+        ("y", wire("x") * wire("x") * wire("x") + constant(5), vec![vec![("x", 3)], vec![("x", 7)]]),
+        ("s", (wire("a") + wire("b")) * (wire("a") + wire("b")), vec![vec![("a", 2), ("b", 9)]]),
+    ];
+    for (output, expr, assignments) in gates {
+        let air = match GateAir::new(output, expr, 3) {
+            Ok(air) => air,
+            Err(e) => {
+                println!("   ❌ {}", e);
+                continue;
+            }
+        };
+        let trace = match generate_gate_trace::<Val>(&air, &assignments) {
+            Ok(trace) => trace,
+            Err(e) => {
+                println!("   ❌ {}", e);
+                continue;
+            }
+        };
+        let proof = prove(&config, &air, trace.matrix, &trace.public_values);
+        match verify(&config, &air, &proof, &trace.public_values) {
+            Ok(()) => println!("   {} = {} over {:?}: verified ✅", output, air.expr(), air.wires()),
+            Err(e) => println!("   ❌ {} = {}: {:?}", output, air.expr(), e),
+        }
+    }
+
+    // a + c * d = e again, with e assigned the wrong value
+    if let Ok(air) = GateAir::new("e", wire("a") + wire("c") * wire("d"), 2) {
+        if let Err(e) = air.solve::<Val>(&[("a", 3), ("c", 4), ("d", 5), ("e", 24)]) {
+            println!("   Unsatisfiable assignment rejected: {} ✅", e);
+        }
+        if let Ok(mut trace) = generate_gate_trace::<Val>(&air, &[vec![("a", 3), ("c", 4), ("d", 5)]]) {
+            let e = TraceView::named(&trace.matrix, &air).column_index("e").expect("the output wire is named");
+            trace.matrix.values[e] = Val::from_u64(24);
+            match prove_expect_failure(&config, &air, trace.matrix, &trace.public_values) {
+                Ok(stage) => println!("   Row claiming e = 24 rejected ✅ {}", stage),
+                Err(_) => println!("   ❌ Row claiming e = 24 produced a verifying proof"),
+            }
+        }
+    }
+    if let Err(e) = GateAir::new("z", wire("x") * wire("x") * wire("x") * wire("x"), 3) {
+        println!("   x^4 refused at construction: {} ✅", e);
+    }
+}
+
+fn run_commit_reveal_demo() {
+    println!("🧮 Commit-reveal: c = Poseidon2(value, blinding)");
+
+    let value = Val::from_u64(1234);
+    let (c, blinding) = commit(value);
+    println!("   Committed to value {}", value);
+
+    let (proven_c, proof) = prove_opening(value, &blinding);
+    assert_eq!(proven_c, c, "proof binds the same commitment");
+    let public_values = commitment_public_values(value, &c);
+    println!("   Public values: {:?} (value + digest, no blinding)", public_values);
+    match OpeningClaims::from_public_values(&public_values[..4]) {
+        Ok(claims) => println!("   ❌ Truncated public values read as {}", claims),
+        Err(e) => println!("   Truncated public values: {} ✅", e),
+    }
+
+    match verify_opening(&c, value, &proof) {
+        Ok(claims) => println!("   Verified: {} ✅", claims),
+        Err(e) => println!("   ❌ {}", e),
+    }
+    let other = value + Val::ONE;
+    match verify_opening(&c, other, &proof) {
+        Ok(_) => println!("   ❌ Opening to {} unexpectedly accepted", other),
+        Err(_) => println!("   Opening to {} rejected ✅", other),
+    }
+
+    // Byte messages are bound through their hash
+    let message = b"transfer 10 to alice";
+    let (digest, c, blinding) = commit_message(message);
+    let (_, proof) = prove_opening(digest, &blinding);
+    let reopened = hash_bytes_to_fields(message, 1)[0];
+    match verify_opening(&c, reopened, &proof) {
+        Ok(_) => println!("   Opening to {:?} verified ✅", String::from_utf8_lossy(message)),
+        Err(e) => println!("   ❌ {}", e),
+    }
+}
+
+fn run_hashing_demo() {
+    println!("🧮 Byte hashing: hash_bytes_to_fields(bytes, count)");
+
+    let megabyte: Vec<u8> = (0..1 << 20).map(|i| (i % 251) as u8).collect();
+    let inputs: [(&str, &[u8]); 4] = [
+        ("empty", b""),
+        ("1 byte", b"a"),
+        ("1 byte + zero", b"a\0"),
+        ("1 MB", &megabyte),
+    ];
+    let mut digests = Vec::new();
+    for (name, bytes) in inputs {
+        let digest = hash_bytes_to_fields(bytes, 12);
+        let deterministic = digest == hash_bytes_to_fields(bytes, 12);
+        println!("   {}: {:?}... deterministic {}", name, &digest[..2], if deterministic { "✅" } else { "❌" });
+        digests.push(digest);
+    }
+    let distinct = digests.iter().enumerate().all(|(i, a)| digests[i + 1..].iter().all(|b| a != b));
+    println!("   All digests distinct {}", if distinct { "✅" } else { "❌" });
+    let prefix = hash_bytes_to_fields(&megabyte, 4) == hash_bytes_to_fields(&megabyte, 12)[..4];
+    println!("   Shorter outputs are prefixes {}", if prefix { "✅" } else { "❌" });
+
+    match check_test_vectors() {
+        Ok(()) => println!("   Encoding test vectors match ✅"),
+        Err(e) => println!("   ❌ {}", e),
+    }
+    let coefficients = [1, 2, 3, 4].map(Val::from_u32);
+    let challenge = Challenge::from_basis_coefficients_slice(&coefficients).expect("four coefficients");
+    let round_trip = challenge_from_le_bytes(&challenge_to_le_bytes(challenge)) == Ok(challenge);
+    println!("   Challenge bytes round-trip {}", if round_trip { "✅" } else { "❌" });
+}
@@ -0,0 +1,165 @@
+// A Poseidon2 Merkle tree with the same hash and compression as the proof
+// system's MMCS: leaves are hashed with MyHash, each parent is MyCompress of
+// its (left, right) children.
+use core::fmt;
+use p3_field::PrimeCharacteristicRing;
+use p3_symmetric::{CryptographicHasher, PseudoCompressionFunction};
+
+use crate::{DEFAULT_CONFIG_SEED, MyCompress, MyHash, Perm, Val, constants_rng};
+
+pub const DIGEST_LEN: usize = 8;
+pub type Digest = [Val; DIGEST_LEN];
+
+// Stands in for the missing leaves when the count is not a power of two
+pub const PADDING_DIGEST: Digest = [Val::ZERO; DIGEST_LEN];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MerkleError {
+    NoLeaves,
+    IndexOutOfRange { index: usize, leaves: usize },
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoLeaves => write!(f, "a Merkle tree needs at least one leaf"),
+            Self::IndexOutOfRange { index, leaves } => {
+                write!(f, "leaf {} is out of range for {} leaves", index, leaves)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {}
+
+fn hash_and_compress() -> (MyHash, MyCompress) {
+    let mut rng = constants_rng(DEFAULT_CONFIG_SEED);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    (MyHash::new(perm.clone()), MyCompress::new(perm))
+}
+
+// Authentication path for one leaf, bottom level first. directions[i] is true
+// when the node at level i is a right child, i.e. siblings[i] goes on its left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePath {
+    pub index: usize,
+    pub siblings: Vec<Digest>,
+    pub directions: Vec<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tree {
+    num_leaves: usize,
+    // levels[0] holds the padded leaf digests, the last level the root
+    levels: Vec<Vec<Digest>>,
+}
+
+impl Tree {
+    // Pads to the next power of two with PADDING_DIGEST, so the shape depends
+    // only on the leaf count
+    pub fn build(leaves: &[Vec<Val>]) -> Result<Self, MerkleError> {
+        if leaves.is_empty() {
+            return Err(MerkleError::NoLeaves);
+        }
+        let (hash, compress) = hash_and_compress();
+        let mut level: Vec<Digest> = leaves.iter().map(|leaf| hash.hash_iter(leaf.iter().copied())).collect();
+        level.resize(leaves.len().next_power_of_two(), PADDING_DIGEST);
+
+        let mut levels = vec![level];
+        while levels[levels.len() - 1].len() > 1 {
+            let parents = levels[levels.len() - 1]
+                .chunks_exact(2)
+                .map(|pair| compress.compress([pair[0], pair[1]]))
+                .collect();
+            levels.push(parents);
+        }
+        Ok(Self { num_leaves: leaves.len(), levels })
+    }
+
+    pub fn root(&self) -> Digest {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn open(&self, index: usize) -> Result<MerklePath, MerkleError> {
+        if index >= self.num_leaves {
+            return Err(MerkleError::IndexOutOfRange { index, leaves: self.num_leaves });
+        }
+        let mut siblings = Vec::with_capacity(self.depth());
+        let mut directions = Vec::with_capacity(self.depth());
+        let mut node = index;
+        for level in &self.levels[..self.depth()] {
+            siblings.push(level[node ^ 1]);
+            directions.push(node & 1 == 1);
+            node >>= 1;
+        }
+        Ok(MerklePath { index, siblings, directions })
+    }
+}
+
+// Native check of a path, the reference for what a Merkle-path AIR must accept
+pub fn verify_path(root: &Digest, leaf: &[Val], path: &MerklePath) -> bool {
+    if path.siblings.len() != path.directions.len() {
+        return false;
+    }
+    // A tree of this depth has 2^depth leaf slots; a larger index would share
+    // its low bits, and so its path, with one of them
+    let depth = path.siblings.len();
+    if path.index.checked_shr(depth as u32).unwrap_or(0) != 0 {
+        return false;
+    }
+    // The directions are the index bits, which pins the leaf's position
+    let index_bit = |i: usize| path.index.checked_shr(i as u32).unwrap_or(0) & 1 == 1;
+    if path.directions.iter().enumerate().any(|(i, &is_right)| is_right != index_bit(i)) {
+        return false;
+    }
+    let (hash, compress) = hash_and_compress();
+    let mut node: Digest = hash.hash_iter(leaf.iter().copied());
+    for (sibling, &is_right) in path.siblings.iter().zip(&path.directions) {
+        node = if is_right {
+            compress.compress([*sibling, node])
+        } else {
+            compress.compress([node, *sibling])
+        };
+    }
+    node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves() -> Vec<Vec<Val>> {
+        (0..5u8).map(|i| vec![Val::from_u8(i), Val::from_u8(i + 1)]).collect()
+    }
+
+    #[test]
+    fn every_leaf_opens_to_a_path_that_verifies() {
+        let leaves = leaves();
+        let tree = Tree::build(&leaves).unwrap();
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert!(verify_path(&tree.root(), leaf, &tree.open(index).unwrap()));
+        }
+        assert_eq!(tree.open(5), Err(MerkleError::IndexOutOfRange { index: 5, leaves: 5 }));
+    }
+
+    #[test]
+    fn an_index_past_the_last_slot_is_rejected() {
+        let leaves = leaves();
+        let tree = Tree::build(&leaves).unwrap();
+        let path = tree.open(2).unwrap();
+        for index in [2 + (1 << tree.depth()), usize::MAX] {
+            assert!(!verify_path(&tree.root(), &leaves[2], &MerklePath { index, ..path.clone() }));
+        }
+    }
+
+    #[test]
+    fn a_path_for_another_leaf_is_rejected() {
+        let leaves = leaves();
+        let tree = Tree::build(&leaves).unwrap();
+        assert!(!verify_path(&tree.root(), &leaves[1], &tree.open(2).unwrap()));
+    }
+}
@@ -0,0 +1,164 @@
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+
+use crate::gadgets::assert_one_hot;
+use crate::trace::{GeneratedTrace, LengthOverflow, generate_rows};
+
+pub const NUM_SELECTORS: usize = 4;
+
+// Columns: is_real, the k selectors, and the index of the selected one
+const NUM_ONE_HOT_COLS: usize = NUM_SELECTORS + 2;
+
+// Each real row picks exactly one of NUM_SELECTORS options; padding rows pick none
+#[derive(Debug, Clone)]
+pub struct OneHotAir;
+
+impl<F> BaseAir<F> for OneHotAir {
+    fn width(&self) -> usize {
+        NUM_ONE_HOT_COLS
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for OneHotAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &OneHotRow<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_real.clone());
+
+        let selectors: Vec<AB::Expr> = local.selectors.iter().map(|s| s.clone().into()).collect();
+        assert_one_hot(builder, &selectors, Some(local.is_real.clone().into()));
+
+        // index = sum(i * selectors[i]) names the selected option
+        let mut index = AB::Expr::ZERO;
+        for (i, selector) in selectors.into_iter().enumerate() {
+            index += selector * AB::Expr::from_usize(i);
+        }
+        builder.assert_eq(local.index.clone(), index);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct OneHotRow<F> {
+    pub is_real: F,
+    pub selectors: [F; NUM_SELECTORS],
+    pub index: F,
+}
+
+impl<F> Borrow<OneHotRow<F>> for [F] {
+    fn borrow(&self) -> &OneHotRow<F> {
+        debug_assert_eq!(self.len(), NUM_ONE_HOT_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<OneHotRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OneHotError {
+    // A choice that is not a selector index
+    InvalidChoice { row: usize, choice: usize },
+    Trace(LengthOverflow),
+}
+
+impl fmt::Display for OneHotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChoice { row, choice } => {
+                write!(f, "row {} chooses {}, but there are only {} selectors", row, choice, NUM_SELECTORS)
+            }
+            Self::Trace(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for OneHotError {}
+
+// Lays out one row per choice, padded with all-zero rows to a power of two
+pub fn generate_one_hot_trace<F: PrimeField64>(choices: &[usize]) -> Result<GeneratedTrace<(), F>, OneHotError> {
+    if let Some((row, &choice)) = choices.iter().enumerate().find(|&(_, &choice)| choice >= NUM_SELECTORS) {
+        return Err(OneHotError::InvalidChoice { row, choice });
+    }
+
+    let overflow = OneHotError::Trace(LengthOverflow { height: choices.len(), width: NUM_ONE_HOT_COLS });
+    let n = choices.len().checked_next_power_of_two().ok_or(overflow)?.max(256);
+    // Padding rows are all-zero, which the gadget accepts because is_real = 0
+    let trace = generate_rows(n, |range: Range<usize>| {
+        range.map(move |i| {
+            let mut row = OneHotRow { is_real: F::ZERO, selectors: [F::ZERO; NUM_SELECTORS], index: F::ZERO };
+            if let Some(&choice) = choices.get(i) {
+                row.is_real = F::ONE;
+                row.selectors[choice] = F::ONE;
+                row.index = F::from_usize(choice);
+            }
+            row
+        })
+    })
+    .map_err(OneHotError::Trace)?;
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: choices.len(),
+        public_values: vec![],
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::assert_proof_roundtrip;
+    use test_support::checker::prover_accepts;
+
+    use super::*;
+    use crate::Val;
+
+    fn trace() -> GeneratedTrace<(), Val> {
+        generate_one_hot_trace(&[0, 3, 1, 2, 3]).unwrap()
+    }
+
+    #[test]
+    fn real_rows_pick_one_option_and_padding_none() {
+        let trace = trace();
+        assert_eq!(trace.real_rows, 5);
+        assert_eq!(trace.matrix.values[..NUM_ONE_HOT_COLS], [1, 1, 0, 0, 0, 0].map(Val::from_u8));
+        assert_eq!(trace.matrix.values[NUM_ONE_HOT_COLS..2 * NUM_ONE_HOT_COLS], [1, 0, 0, 0, 1, 3].map(Val::from_u8));
+        assert_proof_roundtrip(&OneHotAir, trace.matrix, &trace.public_values);
+    }
+
+    #[test]
+    fn an_out_of_range_choice_has_no_trace() {
+        let found = generate_one_hot_trace::<Val>(&[1, NUM_SELECTORS]).err();
+        assert_eq!(found, Some(OneHotError::InvalidChoice { row: 1, choice: NUM_SELECTORS }));
+    }
+
+    #[test]
+    fn every_break_of_the_gadget_is_refused() {
+        let edits: [(&str, usize, usize, u8); 4] = [
+            // (what, row, column, new value)
+            ("two selectors on a real row", 0, 2, 1),
+            ("no selector on a real row", 0, 1, 0),
+            ("a selector on a padding row", 200, 3, 1),
+            ("a non-boolean is_real", 200, 0, 2),
+        ];
+        for (what, row, col, value) in edits {
+            let mut trace = trace();
+            trace.matrix.values[row * NUM_ONE_HOT_COLS + col] = Val::from_u8(value);
+            assert!(prover_accepts(&OneHotAir, trace.matrix, &trace.public_values).is_err(), "{}", what);
+        }
+    }
+
+    #[test]
+    fn the_index_must_name_the_selected_option() {
+        let mut trace = trace();
+        trace.matrix.values[NUM_ONE_HOT_COLS + NUM_ONE_HOT_COLS - 1] = Val::from_u8(2);
+        assert!(prover_accepts(&OneHotAir, trace.matrix, &trace.public_values).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use core::borrow::Borrow;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+
+use crate::trace::{GeneratedTrace, LengthOverflow, generate_rows};
+
+// Witness layout: [one, x, out, sym1 = x^2, y = x^3, sym2 = x^3 + x]
+pub const NUM_VARS: usize = 6;
+
+// One R1CS constraint <a, w> * <b, w> = <c, w>
+#[derive(Debug, Clone, Copy)]
+pub struct R1csConstraint {
+    pub a: [u64; NUM_VARS],
+    pub b: [u64; NUM_VARS],
+    pub c: [u64; NUM_VARS],
+}
+
+// x^3 + x + 5 = out, flattened. The output constraint comes first and x * x
+// last so the AIR's first/last-row constraints can bind out and x.
+pub const CUBIC: [R1csConstraint; 4] = [
+    // (sym2 + 5) * 1 = out
+    R1csConstraint { a: [5, 0, 0, 0, 0, 1], b: [1, 0, 0, 0, 0, 0], c: [0, 0, 1, 0, 0, 0] },
+    // sym1 * x = y
+    R1csConstraint { a: [0, 0, 0, 1, 0, 0], b: [0, 1, 0, 0, 0, 0], c: [0, 0, 0, 0, 1, 0] },
+    // (y + x) * 1 = sym2
+    R1csConstraint { a: [0, 1, 0, 0, 1, 0], b: [1, 0, 0, 0, 0, 0], c: [0, 0, 0, 0, 0, 1] },
+    // x * x = sym1
+    R1csConstraint { a: [0, 1, 0, 0, 0, 0], b: [0, 1, 0, 0, 0, 0], c: [0, 0, 0, 1, 0, 0] },
+];
+
+// The witness for x, with every intermediate filled in honestly
+pub fn cubic_witness<F: PrimeCharacteristicRing + Copy>(x: u64) -> [F; NUM_VARS] {
+    let x = F::from_u64(x);
+    let sym1 = x * x;
+    let y = sym1 * x;
+    let sym2 = y + x;
+    [F::ONE, x, sym2 + F::from_u64(5), sym1, y, sym2]
+}
+
+fn dot<F: PrimeCharacteristicRing + Copy>(coefficients: &[u64; NUM_VARS], witness: &[F; NUM_VARS]) -> F {
+    coefficients
+        .iter()
+        .zip(witness)
+        .fold(F::ZERO, |acc, (&c, &w)| acc + F::from_u64(c) * w)
+}
+
+pub const NUM_R1CS_COLS: usize = 3;
+
+// Public values: [x, out]
+const NUM_R1CS_PUBLIC_VALUES: usize = 2;
+
+// One R1CS constraint per row, as its three dot products, with
+// a_dot * b_dot = c_dot on every row. Padding rows are all zero, which
+// satisfies it. The output constraint is bound to the public out on the first
+// row and the x * x constraint to the public x on the last.
+//
+// Only the row-wise shape of R1CS maps onto this AIR. The dot products are
+// computed by the generator, and nothing ties the witness entries shared
+// between rows together; that wiring needs a permutation argument.
+#[derive(Debug, Clone)]
+pub struct R1csAir;
+
+impl<F> BaseAir<F> for R1csAir {
+    fn width(&self) -> usize {
+        NUM_R1CS_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for R1csAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &R1csRow<AB::Var> = (*local).borrow();
+        let pis = builder.public_values();
+        let (x, out) = (pis[0], pis[1]);
+
+        builder.assert_eq(local.a_dot.clone() * local.b_dot.clone(), local.c_dot.clone());
+
+        builder.when_first_row().assert_eq(local.c_dot.clone(), out);
+        let mut when_last_row = builder.when_last_row();
+        when_last_row.assert_eq(local.a_dot.clone(), x);
+        when_last_row.assert_eq(local.b_dot.clone(), x);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct R1csRow<F> {
+    pub a_dot: F,
+    pub b_dot: F,
+    pub c_dot: F,
+}
+
+impl<F> Borrow<R1csRow<F>> for [F] {
+    fn borrow(&self) -> &R1csRow<F> {
+        debug_assert_eq!(self.len(), NUM_R1CS_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<R1csRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+// Lays CUBIC out over 256 rows: the first three constraints on rows 0..3,
+// zero padding, and x * x on the last row. The dot products come from
+// `witness` as given, so a wrong witness yields a row with a * b != c.
+pub fn generate_r1cs_trace<F: PrimeField64>(witness: &[F; NUM_VARS]) -> Result<GeneratedTrace<(), F>, LengthOverflow> {
+    let n = 256;
+    let cubic: &'static [R1csConstraint] = &CUBIC;
+    let (head, last) = cubic.split_at(cubic.len() - 1);
+    let constraint_at = move |row: usize| if row == n - 1 { last.first() } else { head.get(row) };
+    let trace = generate_rows(n, |range: Range<usize>| {
+        range.map(move |row| match constraint_at(row) {
+            Some(constraint) => R1csRow {
+                a_dot: dot(&constraint.a, witness),
+                b_dot: dot(&constraint.b, witness),
+                c_dot: dot(&constraint.c, witness),
+            },
+            None => R1csRow { a_dot: F::ZERO, b_dot: F::ZERO, c_dot: F::ZERO },
+        })
+    })?;
+
+    let mut public_values = Vec::with_capacity(NUM_R1CS_PUBLIC_VALUES);
+    public_values.extend([witness[1], witness[2]]);
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: CUBIC.len(),
+        public_values,
+        meta: (),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::assert_proof_roundtrip;
+    use test_support::checker::prover_accepts;
+
+    use super::*;
+    use crate::Val;
+
+    #[test]
+    fn the_honest_witness_satisfies_every_constraint_and_proves() {
+        let witness = cubic_witness::<Val>(3);
+        assert_eq!(witness[2], Val::from_u8(35));
+        for constraint in &CUBIC {
+            let (a, b, c) = (dot(&constraint.a, &witness), dot(&constraint.b, &witness), dot(&constraint.c, &witness));
+            assert_eq!(a * b, c);
+        }
+        let trace = generate_r1cs_trace(&witness).unwrap();
+        assert_eq!(trace.public_values, [Val::from_u8(3), Val::from_u8(35)]);
+        assert_proof_roundtrip(&R1csAir, trace.matrix, &trace.public_values);
+    }
+
+    #[test]
+    fn a_wrong_intermediate_or_claim_is_refused() {
+        let mut witness = cubic_witness::<Val>(3);
+        witness[4] += Val::ONE;
+        let trace = generate_r1cs_trace(&witness).unwrap();
+        assert!(prover_accepts(&R1csAir, trace.matrix, &trace.public_values).is_err());
+
+        let trace = generate_r1cs_trace(&cubic_witness::<Val>(3)).unwrap();
+        let wrong_out = [Val::from_u8(3), Val::from_u8(36)];
+        assert!(prover_accepts(&R1csAir, trace.matrix.clone(), &wrong_out).is_err());
+        let wrong_x = [Val::from_u8(4), Val::from_u8(35)];
+        assert!(prover_accepts(&R1csAir, trace.matrix, &wrong_x).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Range;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{Field, PrimeCharacteristicRing, PrimeField64};
+use p3_matrix::Matrix;
+
+use crate::Val;
+use crate::trace::{GeneratedTrace, LengthOverflow, generate_rows};
+
+// The PRESENT cipher's 4-bit S-box
+pub const SBOX: [u8; 16] = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2];
+
+const NIBBLE_BITS: usize = 4;
+// in^2 ..= in^15, so every constraint stays degree 2
+const NUM_POWERS: usize = SBOX.len() - 2;
+
+// Columns: in, out, the powers of in, then the bits of in
+const NUM_SBOX_COLS: usize = 2 + NUM_POWERS + NIBBLE_BITS;
+
+// Coefficients of the degree-15 polynomial P with P(i) = SBOX[i] for i in
+// 0..16, lowest degree first, by Lagrange interpolation
+pub fn interpolate_sbox<F: Field>() -> [F; 16] {
+    let mut coefficients = [F::ZERO; 16];
+    for (i, &y) in SBOX.iter().enumerate() {
+        // prod_{j != i} (x - j) and its value at i
+        let mut basis = vec![F::ONE];
+        let mut denominator = F::ONE;
+        for j in (0..SBOX.len()).filter(|&j| j != i) {
+            let root = F::from_usize(j);
+            let mut next = vec![F::ZERO; basis.len() + 1];
+            for (k, &c) in basis.iter().enumerate() {
+                next[k + 1] += c;
+                next[k] -= c * root;
+            }
+            basis = next;
+            denominator *= F::from_usize(i) - root;
+        }
+        let scale = F::from_u8(y) * denominator.inverse();
+        for (coefficient, b) in coefficients.iter_mut().zip(basis) {
+            *coefficient += scale * b;
+        }
+    }
+    coefficients
+}
+
+pub fn eval_sbox_polynomial<F: Field>(coefficients: &[F; 16], x: F) -> F {
+    coefficients.iter().rev().fold(F::ZERO, |acc, &c| acc * x + c)
+}
+
+// Applies the S-box without a lookup: the table is the polynomial P, and each
+// row proves out = P(in) with in < 16. Evaluating P directly would be a
+// degree-15 constraint, far above what the test FRI blowup allows, so the
+// powers of in get their own columns; 14 extra columns buy degree 2.
+#[derive(Debug, Clone)]
+pub struct SboxAir {
+    coefficients: [Val; 16],
+}
+
+impl SboxAir {
+    pub fn new() -> Self {
+        Self { coefficients: interpolate_sbox() }
+    }
+}
+
+impl Default for SboxAir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> BaseAir<F> for SboxAir {
+    fn width(&self) -> usize {
+        NUM_SBOX_COLS
+    }
+}
+
+impl<AB: AirBuilder<F = Val>> Air<AB> for SboxAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("Matrix is empty?");
+        let local: &SboxRow<AB::Var> = (*local).borrow();
+        let input: AB::Expr = local.input.clone().into();
+
+        // in < 16: it is the sum of four boolean bits
+        let mut recomposed = AB::Expr::ZERO;
+        for (i, bit) in local.bits.iter().enumerate() {
+            builder.assert_bool(bit.clone());
+            recomposed += AB::Expr::from_u64(1 << i) * bit.clone();
+        }
+        builder.assert_eq(input.clone(), recomposed);
+
+        // powers[k] = in^(k + 2)
+        builder.assert_eq(local.powers[0].clone(), input.clone() * input.clone());
+        for k in 1..NUM_POWERS {
+            builder.assert_eq(local.powers[k].clone(), local.powers[k - 1].clone() * input.clone());
+        }
+
+        // out = P(in), linear in the power columns
+        let mut value = AB::Expr::from(self.coefficients[0]) + AB::Expr::from(self.coefficients[1]) * input;
+        for (k, power) in local.powers.iter().enumerate() {
+            value += AB::Expr::from(self.coefficients[k + 2]) * power.clone();
+        }
+        builder.assert_eq(local.output.clone(), value);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct SboxRow<F> {
+    pub input: F,
+    pub output: F,
+    pub powers: [F; NUM_POWERS],
+    pub bits: [F; NIBBLE_BITS],
+}
+
+impl<F> Borrow<SboxRow<F>> for [F] {
+    fn borrow(&self) -> &SboxRow<F> {
+        debug_assert_eq!(self.len(), NUM_SBOX_COLS);
+        let (prefix, rows, suffix) = unsafe { self.align_to::<SboxRow<F>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(rows.len(), 1);
+        &rows[0]
+    }
+}
+
+// Splits bytes into nibbles, high nibble first
+pub fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|&byte| [byte >> 4, byte & 0xF]).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SboxError {
+    // A value of 16 or more where a nibble was expected
+    InvalidNibble { position: usize, nibble: u8 },
+    Trace(LengthOverflow),
+}
+
+impl fmt::Display for SboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNibble { position, nibble } => {
+                write!(f, "{} at position {} is not a nibble", nibble, position)
+            }
+            Self::Trace(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SboxError {}
+
+fn sbox_row<F: PrimeField64>(nibble: u8) -> SboxRow<F> {
+    let input = F::from_u8(nibble);
+    let mut power = input;
+    SboxRow {
+        input,
+        output: F::from_u8(SBOX[nibble as usize]),
+        powers: core::array::from_fn(|_| {
+            power *= input;
+            power
+        }),
+        bits: core::array::from_fn(|i| F::from_u8((nibble >> i) & 1)),
+    }
+}
+
+// One row per nibble, padded to a power of two with rows substituting 0,
+// which satisfy the constraints like any other row. The meta is the
+// substituted stream.
+pub fn generate_sbox_trace<F: PrimeField64>(nibbles: &[u8]) -> Result<GeneratedTrace<Vec<u8>, F>, SboxError> {
+    if let Some((position, &nibble)) = nibbles.iter().enumerate().find(|&(_, &n)| n as usize >= SBOX.len()) {
+        return Err(SboxError::InvalidNibble { position, nibble });
+    }
+
+    let overflow = SboxError::Trace(LengthOverflow { height: nibbles.len(), width: NUM_SBOX_COLS });
+    let n = nibbles.len().checked_next_power_of_two().ok_or(overflow)?.max(256);
+    let trace = generate_rows(n, |range: Range<usize>| {
+        range.map(move |i| sbox_row(nibbles.get(i).copied().unwrap_or(0)))
+    })
+    .map_err(SboxError::Trace)?;
+
+    Ok(GeneratedTrace {
+        matrix: trace,
+        real_rows: nibbles.len(),
+        public_values: vec![],
+        meta: nibbles.iter().map(|&nibble| SBOX[nibble as usize]).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_support::assert_proof_roundtrip;
+    use test_support::checker::prover_accepts;
+
+    use super::*;
+
+    #[test]
+    fn the_polynomial_is_the_table() {
+        let coefficients = interpolate_sbox::<Val>();
+        for (i, &y) in SBOX.iter().enumerate() {
+            assert_eq!(eval_sbox_polynomial(&coefficients, Val::from_usize(i)), Val::from_u8(y), "SBOX[{}]", i);
+        }
+    }
+
+    #[test]
+    fn a_message_substitutes_nibble_by_nibble() {
+        assert_eq!(to_nibbles(&[0xAB, 0x10]), [0xA, 0xB, 0x1, 0x0]);
+        let trace = generate_sbox_trace::<Val>(&to_nibbles(b"hi")).unwrap();
+        // 'h' = 0x68, 'i' = 0x69
+        assert_eq!(trace.meta, [SBOX[6], SBOX[8], SBOX[6], SBOX[9]]);
+        assert_proof_roundtrip(&SboxAir::new(), trace.matrix, &trace.public_values);
+        let found = generate_sbox_trace::<Val>(&[3, 16]).err();
+        assert_eq!(found, Some(SboxError::InvalidNibble { position: 1, nibble: 16 }));
+    }
+
+    #[test]
+    fn a_wrong_output_or_wide_input_is_refused() {
+        let trace = generate_sbox_trace::<Val>(&[0, 1, 2, 3]).unwrap();
+        let mut wrong_output = trace.matrix.clone();
+        wrong_output.values[NUM_SBOX_COLS + 1] += Val::ONE;
+        assert!(prover_accepts(&SboxAir::new(), wrong_output, &[]).is_err());
+
+        // in = 16 with out = P(16) and honest powers still fails the bits
+        let mut wide = trace.matrix;
+        let x = Val::from_u8(16);
+        let mut power = x * x;
+        wide.values[0] = x;
+        wide.values[1] = eval_sbox_polynomial(&interpolate_sbox(), x);
+        for k in 0..NUM_POWERS {
+            wide.values[2 + k] = power;
+            power *= x;
+        }
+        assert!(prover_accepts(&SboxAir::new(), wide, &[]).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use core::fmt;
+use core::mem::{MaybeUninit, align_of, size_of};
+use core::ops::Range;
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+
+use crate::Val;
+
+// Below this height spawning rayon tasks costs more than it saves
+const PARALLEL_MIN_ROWS: usize = 1 << 12;
+
+// Rows handed to each rayon task
+const CHUNK_ROWS: usize = 1 << 10;
+
+// A generated trace plus the facts its generator already knows about it, so callers
+// never have to re-derive the row count or the claimed public values by convention
+#[derive(Debug, Clone)]
+pub struct GeneratedTrace<M, F = Val> {
+    pub matrix: RowMajorMatrix<F>,
+    // Rows carrying real computation; the rest (if any) is padding
+    pub real_rows: usize,
+    // Public values the trace was generated against, passed to prove by default
+    pub public_values: Vec<F>,
+    pub meta: M,
+}
+
+// Arithmetic metadata: the e value computed on every real row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArithmeticMeta<F = Val> {
+    pub results: Vec<F>,
+}
+
+// Names an AIR gives its leading columns, in column order. Columns past the
+// end of the list are reachable by index only.
+pub trait ColumnNames {
+    fn column_names(&self) -> &[&'static str];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceViewError {
+    NoSuchColumn(String),
+    RowOutOfRange { row: usize, height: usize },
+}
+
+impl fmt::Display for TraceViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchColumn(name) => write!(f, "no column named {:?}", name),
+            Self::RowOutOfRange { row, height } => write!(f, "row {} is past the {}-row trace", row, height),
+        }
+    }
+}
+
+impl std::error::Error for TraceViewError {}
+
+// Bounds-checked reads from a trace: an out-of-range row or column gives None
+// or an error instead of an index panic, and named columns follow the AIR's
+// layout when it changes
+#[derive(Debug, Clone, Copy)]
+pub struct TraceView<'a, F = Val> {
+    matrix: &'a RowMajorMatrix<F>,
+    names: &'a [&'static str],
+}
+
+impl<'a, F: Copy + Send + Sync> TraceView<'a, F> {
+    // Index access only
+    pub fn new(matrix: &'a RowMajorMatrix<F>) -> Self {
+        Self { matrix, names: &[] }
+    }
+
+    pub fn named(matrix: &'a RowMajorMatrix<F>, air: &'a impl ColumnNames) -> Self {
+        Self { matrix, names: air.column_names() }
+    }
+
+    pub fn height(&self) -> usize {
+        self.matrix.height()
+    }
+
+    pub fn width(&self) -> usize {
+        self.matrix.width()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<F> {
+        if col >= self.width() {
+            return None;
+        }
+        let index = row.checked_mul(self.width())?.checked_add(col)?;
+        self.matrix.values.get(index).copied()
+    }
+
+    // Every row's value in `col`, top to bottom
+    pub fn column(&self, col: usize) -> Option<impl Iterator<Item = F> + 'a> {
+        let width = self.width();
+        let values: &'a [F] = &self.matrix.values;
+        (col < width).then(|| values.iter().skip(col).step_by(width).copied())
+    }
+
+    pub fn column_index(&self, name: &str) -> Result<usize, TraceViewError> {
+        self.names
+            .iter()
+            .position(|&column| column == name)
+            .filter(|&col| col < self.width())
+            .ok_or_else(|| TraceViewError::NoSuchColumn(name.to_string()))
+    }
+
+    pub fn get_named(&self, row: usize, name: &str) -> Result<F, TraceViewError> {
+        let col = self.column_index(name)?;
+        self.get(row, col).ok_or(TraceViewError::RowOutOfRange { row, height: self.height() })
+    }
+}
+
+// height * width field elements do not fit in usize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthOverflow {
+    pub height: usize,
+    pub width: usize,
+}
+
+impl fmt::Display for LengthOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a {} x {} trace does not fit in memory on this target", self.height, self.width)
+    }
+}
+
+impl std::error::Error for LengthOverflow {}
+
+// Columns in a row struct R, which must be laid out as consecutive F values.
+// The asserts depend only on the types, so they hold for every call or none.
+fn row_width<F, R>() -> usize {
+    assert_eq!(size_of::<R>() % size_of::<F>(), 0, "rows must consist of field elements");
+    assert_eq!(align_of::<R>(), align_of::<F>(), "rows must consist of field elements");
+    size_of::<R>() / size_of::<F>()
+}
+
+fn trace_len(height: usize, width: usize) -> Result<usize, LengthOverflow> {
+    height.checked_mul(width).ok_or(LengthOverflow { height, width })
+}
+
+// Builds a `height`-row trace, padding included, as the Fibonacci example's
+// trace::generate_rows does. `rows(range)` must yield the rows of `range` in
+// order (a generator that stops early is a bug and panics); chunks of rows
+// are generated on rayon workers and written once each into an uninitialized
+// buffer, skipping the zero fill.
+pub fn generate_rows<F, R, I, G>(height: usize, rows: G) -> Result<RowMajorMatrix<F>, LengthOverflow>
+where
+    F: Field,
+    R: Send,
+    I: Iterator<Item = R>,
+    G: Fn(Range<usize>) -> I + Sync,
+{
+    if height < PARALLEL_MIN_ROWS {
+        return generate_rows_serial(height, rows);
+    }
+
+    let width = row_width::<F, R>();
+    let len = trace_len(height, width)?;
+    let mut values: Vec<F> = Vec::with_capacity(len);
+    let spare = &mut values.spare_capacity_mut()[..len];
+    let (prefix, slots, suffix) = unsafe { spare.align_to_mut::<MaybeUninit<R>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(slots.len(), height);
+
+    slots.par_chunks_mut(CHUNK_ROWS).enumerate().for_each(|(chunk, slots)| {
+        let start = chunk * CHUNK_ROWS;
+        let mut written = 0;
+        for (slot, row) in slots.iter_mut().zip(rows(start..start + slots.len())) {
+            slot.write(row);
+            written += 1;
+        }
+        assert_eq!(written, slots.len(), "row generator ended early at row {}", start + written);
+    });
+
+    // SAFETY: each chunk checked that all of its rows were written, and the
+    // chunks cover all `height` rows
+    unsafe { values.set_len(len) };
+    Ok(RowMajorMatrix::new(values, width))
+}
+
+// Safe single-threaded equivalent of generate_rows: zero fill, then overwrite
+pub fn generate_rows_serial<F, R, I, G>(height: usize, rows: G) -> Result<RowMajorMatrix<F>, LengthOverflow>
+where
+    F: Field,
+    I: Iterator<Item = R>,
+    G: Fn(Range<usize>) -> I,
+{
+    let width = row_width::<F, R>();
+    let mut values = F::zero_vec(trace_len(height, width)?);
+    let (prefix, slots, suffix) = unsafe { values.align_to_mut::<R>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(slots.len(), height);
+
+    let mut written = 0;
+    for (slot, row) in slots.iter_mut().zip(rows(0..height)) {
+        *slot = row;
+        written += 1;
+    }
+    assert_eq!(written, height, "row generator ended early at row {}", written);
+    Ok(RowMajorMatrix::new(values, width))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+
+    use super::*;
+    use crate::{ArithmeticAir, generate_arithmetic_trace};
+
+    // Row i is [i + 1; 4], so no generated element is zero, the serial path's
+    // fill value and the value a skipped slot is most likely to hold
+    fn sentinel_rows(range: Range<usize>) -> impl Iterator<Item = [Val; 4]> {
+        range.map(|i| [Val::from_usize(i + 1); 4])
+    }
+
+    #[test]
+    fn trace_len_refuses_a_product_past_usize() {
+        assert_eq!(trace_len(0, 4), Ok(0));
+        assert_eq!(trace_len(usize::MAX / 4, 4), Ok(usize::MAX - 3));
+        let height = usize::MAX / 4 + 1;
+        assert_eq!(trace_len(height, 4), Err(LengthOverflow { height, width: 4 }));
+        assert_eq!(trace_len(usize::MAX, 2), Err(LengthOverflow { height: usize::MAX, width: 2 }));
+    }
+
+    #[test]
+    fn every_element_is_written() {
+        for height in [16, PARALLEL_MIN_ROWS, PARALLEL_MIN_ROWS + CHUNK_ROWS / 2] {
+            let parallel = generate_rows(height, sentinel_rows).unwrap();
+            let serial = generate_rows_serial(height, sentinel_rows).unwrap();
+            assert_eq!(parallel.values.len(), height * 4);
+            assert!(parallel.values.iter().all(|&v| v != Val::ZERO), "height {}", height);
+            assert_eq!(parallel.values, serial.values, "height {}", height);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "row generator ended early")]
+    fn a_short_generator_panics() {
+        let _ = generate_rows(PARALLEL_MIN_ROWS, |range: Range<usize>| sentinel_rows(range).take(1));
+    }
+
+    #[test]
+    fn named_reads_follow_the_air_and_stop_at_the_edges() {
+        let trace = generate_arithmetic_trace::<Val>().unwrap();
+        let view = TraceView::named(&trace.matrix, &ArithmeticAir);
+        assert_eq!((view.height(), view.width()), (256, 4));
+        assert_eq!(view.get_named(5, "c"), Ok(Val::from_u8(4)));
+        assert_eq!(view.get_named(5, "e"), Ok(Val::from_u8(23)));
+        assert_eq!(view.get_named(5, "b"), Err(TraceViewError::NoSuchColumn("b".to_string())));
+        assert_eq!(view.get_named(256, "e"), Err(TraceViewError::RowOutOfRange { row: 256, height: 256 }));
+
+        assert_eq!(view.get(255, 3), Some(Val::from_u8(23)));
+        assert_eq!(view.get(256, 0), None);
+        assert_eq!(view.get(0, 4), None);
+        assert_eq!(view.get(usize::MAX, 0), None);
+        assert!(view.column(3).unwrap().all(|e| e == Val::from_u8(23)));
+        assert!(view.column(4).is_none());
+    }
+}
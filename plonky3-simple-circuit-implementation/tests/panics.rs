@@ -0,0 +1,68 @@
+// The library's entry points, honest and along their error paths, under a
+// panic hook that counts. Every failure has to come back as an error value;
+// one panic anywhere fails the test, even if something caught it.
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use p3_field::PrimeCharacteristicRing;
+use simple_arithmetic_proof::bounded::{
+    generate_bounded_arithmetic_trace, prove_bounded_arithmetic, verify_bounded_arithmetic,
+};
+use simple_arithmetic_proof::commit_reveal::{commit, prove_opening, verify_opening};
+use simple_arithmetic_proof::encoding::{challenge_from_le_bytes, challenge_from_limbs, val_from_le_bytes};
+use simple_arithmetic_proof::gate::{GateAir, generate_gate_trace, wire};
+use simple_arithmetic_proof::merkle::{Tree, verify_path};
+use simple_arithmetic_proof::one_hot::generate_one_hot_trace;
+use simple_arithmetic_proof::r1cs::{cubic_witness, generate_r1cs_trace};
+use simple_arithmetic_proof::sbox::generate_sbox_trace;
+use simple_arithmetic_proof::{Val, prove_arithmetic, verify_arithmetic};
+
+static PANICS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn no_library_flow_panics() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {
+        PANICS.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    let (proof, public_values) = prove_arithmetic().unwrap();
+    assert!(verify_arithmetic(&proof, &public_values).is_ok());
+    assert!(verify_arithmetic(&proof, &vec![Val::ONE]).is_err());
+
+    let bounded = prove_bounded_arithmetic(100, 4, 5, 23).unwrap();
+    assert!(verify_bounded_arithmetic(100, 4, 5, 24, &bounded).is_err());
+    assert!(prove_bounded_arithmetic(1 << 20, 4, 5, 23).is_err());
+    assert!(generate_bounded_arithmetic_trace::<Val>(2, 4, 5, 23).is_err());
+    assert!(generate_bounded_arithmetic_trace::<Val>(100, u64::MAX, 2, 23).is_err());
+
+    let value = Val::from_u64(1234);
+    let (commitment, blinding) = commit(value);
+    let (_, opening) = prove_opening(value, &blinding);
+    assert!(verify_opening(&commitment, value + Val::ONE, &opening).is_err());
+
+    assert!(generate_one_hot_trace::<Val>(&[0, 9]).is_err());
+    assert!(generate_sbox_trace::<Val>(&[0xF, 0x10]).is_err());
+    assert!(generate_r1cs_trace(&cubic_witness::<Val>(3)).is_ok());
+
+    assert!(val_from_le_bytes(&[0xff; 4]).is_err());
+    assert!(val_from_le_bytes(&[0; 3]).is_err());
+    assert!(challenge_from_le_bytes(&[0; 15]).is_err());
+    assert!(challenge_from_limbs(&[Val::ONE; 5]).is_err());
+
+    assert!(GateAir::new("y", wire("x") * wire("x") * wire("x"), 2).is_err());
+    let gate = GateAir::new("e", wire("a") + wire("c") * wire("d"), 2).unwrap();
+    assert!(generate_gate_trace::<Val>(&gate, &[vec![("a", 3), ("c", 4)]]).is_err());
+    assert!(gate.solve::<Val>(&[("a", 3), ("c", 4), ("d", 5), ("e", 24)]).is_err());
+
+    assert!(Tree::build(&[]).is_err());
+    let leaves: Vec<Vec<Val>> = (0..5u64).map(|i| vec![Val::from_u64(i)]).collect();
+    let tree = Tree::build(&leaves).unwrap();
+    assert!(tree.open(5).is_err());
+    let mut path = tree.open(2).unwrap();
+    path.siblings.pop();
+    assert!(!verify_path(&tree.root(), &leaves[2], &path));
+
+    panic::set_hook(hook);
+    assert_eq!(PANICS.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,87 @@
+// p3's prover checks every constraint on the trace before committing to it in
+// debug builds, and panics on the first one that fails. These adapters turn
+// that panic, or in a release build the failed verification that follows, into
+// a Result, so a test can assert that an edited trace is refused.
+use std::panic::{self, AssertUnwindSafe};
+
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{prove, verify};
+use stark_config::{Val, test_config};
+
+use crate::TestAir;
+use crate::corpus::{Mutation, effective_corpus};
+
+// Ok if `trace` proves and verifies under `public_values`, otherwise why not
+pub fn prover_accepts<A: TestAir>(air: &A, trace: RowMajorMatrix<Val>, public_values: &[Val]) -> Result<(), String> {
+    let public_values = public_values.to_vec();
+    let run = || {
+        let config = test_config();
+        let proof = prove(&config, air, trace, &public_values);
+        verify(&config, air, &proof, &public_values).map_err(|e| format!("verification failed: {:?}", e))
+    };
+    panic::catch_unwind(AssertUnwindSafe(run))
+        .unwrap_or_else(|payload| Err(format!("prover panicked: {}", panic_message(payload.as_ref()))))
+}
+
+// The corpus mutations of `trace` the prover accepted under `public_values`,
+// which should be none for a sound AIR
+pub fn accepted_mutations<A: TestAir>(air: &A, trace: &RowMajorMatrix<Val>, public_values: &[Val]) -> Vec<Mutation> {
+    effective_corpus(trace)
+        .into_iter()
+        .filter(|mutation| prover_accepts(air, mutation.apply(trace), public_values).is_ok())
+        .collect()
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_air::{Air, AirBuilder, BaseAir};
+    use p3_field::PrimeCharacteristicRing;
+    use p3_matrix::Matrix;
+
+    use super::*;
+
+    // x(i + 1) = x(i) + 1 from x(0) = 0, in one column
+    struct Counter;
+
+    impl<F> BaseAir<F> for Counter {
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    impl<AB: AirBuilder> Air<AB> for Counter {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let (local, next) = (main.row_slice(0).unwrap(), main.row_slice(1).unwrap());
+            let (local, next) = (local[0].clone(), next[0].clone());
+            builder.when_first_row().assert_zero(local.clone());
+            builder.when_transition().assert_eq(next, local + AB::Expr::ONE);
+        }
+    }
+
+    fn counter_trace() -> RowMajorMatrix<Val> {
+        RowMajorMatrix::new((0..16).map(Val::from_usize).collect(), 1)
+    }
+
+    #[test]
+    fn honest_trace_is_accepted() {
+        assert_eq!(prover_accepts(&Counter, counter_trace(), &[]), Ok(()));
+    }
+
+    #[test]
+    fn every_corpus_mutation_is_refused() {
+        let trace = counter_trace();
+        assert_eq!(trace.height(), 16);
+        assert_eq!(accepted_mutations(&Counter, &trace, &[]), vec![]);
+    }
+}
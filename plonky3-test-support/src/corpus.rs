@@ -0,0 +1,128 @@
+// The malicious-prover corpus: edits a cheating prover could make to an
+// honest trace. Each one changes at least one cell, so an AIR that accepts the
+// edited trace under the same public values leaves that cell unconstrained.
+use core::fmt;
+
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_matrix::dense::RowMajorMatrix;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation {
+    // Adds one to a single cell
+    Bump { row: usize, col: usize },
+    // Swaps two whole rows
+    SwapRows(usize, usize),
+    // Overwrites row `to` with a copy of row `from`
+    CopyRow { from: usize, to: usize },
+    // Sets every cell of a row to zero
+    ZeroRow(usize),
+}
+
+impl Mutation {
+    // The edited copy of `trace`; the original is left as it was
+    pub fn apply<F: Field>(&self, trace: &RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        let width = trace.width();
+        let mut edited = trace.clone();
+        let row = |r: usize| r * width..(r + 1) * width;
+        match *self {
+            Self::Bump { row, col } => edited.values[row * width + col] += F::ONE,
+            Self::SwapRows(a, b) => {
+                for col in 0..width {
+                    edited.values.swap(a * width + col, b * width + col);
+                }
+            }
+            Self::CopyRow { from, to } => edited.values.copy_within(row(from), to * width),
+            Self::ZeroRow(r) => edited.values[row(r)].fill(F::ZERO),
+        }
+        edited
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bump { row, col } => write!(f, "cell ({}, {}) plus one", row, col),
+            Self::SwapRows(a, b) => write!(f, "rows {} and {} swapped", a, b),
+            Self::CopyRow { from, to } => write!(f, "row {} copied over row {}", from, to),
+            Self::ZeroRow(row) => write!(f, "row {} zeroed", row),
+        }
+    }
+}
+
+// The standard corpus for a `height` x `width` trace: every column bumped on
+// the first, a middle and the last row, the first two rows swapped, a row
+// copied onto its successor, and the middle row zeroed. Mutations that leave
+// a given trace unchanged (a zeroed row that was already zero, two equal rows
+// swapped) are filtered out by `effective_corpus`.
+pub fn corpus(height: usize, width: usize) -> Vec<Mutation> {
+    assert!(height >= 2 && width >= 1, "a {} x {} trace has no transitions to attack", height, width);
+    let middle = height / 2;
+    let mut mutations = Vec::new();
+    for row in [0, middle, height - 1] {
+        mutations.extend((0..width).map(|col| Mutation::Bump { row, col }));
+    }
+    mutations.push(Mutation::SwapRows(0, 1));
+    mutations.push(Mutation::CopyRow { from: middle - 1, to: middle });
+    mutations.push(Mutation::ZeroRow(middle));
+    mutations
+}
+
+// `corpus` without the mutations that would leave `trace` as it is
+pub fn effective_corpus<F: Field>(trace: &RowMajorMatrix<F>) -> Vec<Mutation> {
+    corpus(trace.height(), trace.width()).into_iter().filter(|m| m.apply(trace).values != trace.values).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+    use stark_config::Val;
+
+    use super::*;
+
+    fn counting_trace(height: usize, width: usize) -> RowMajorMatrix<Val> {
+        RowMajorMatrix::new((0..height * width).map(|i| Val::from_usize(i + 1)).collect(), width)
+    }
+
+    #[test]
+    fn every_mutation_changes_a_trace_of_distinct_cells() {
+        let trace = counting_trace(8, 3);
+        let mutations = corpus(8, 3);
+        assert_eq!(mutations.len(), 3 * 3 + 3);
+        for mutation in mutations {
+            assert_ne!(mutation.apply(&trace).values, trace.values, "{}", mutation);
+        }
+    }
+
+    #[test]
+    fn apply_leaves_the_original_alone() {
+        let trace = counting_trace(4, 2);
+        let before = trace.values.clone();
+        let _ = Mutation::ZeroRow(1).apply(&trace);
+        assert_eq!(trace.values, before);
+    }
+
+    #[test]
+    fn swapping_twice_restores_the_trace() {
+        let trace = counting_trace(4, 2);
+        let once = Mutation::SwapRows(0, 3).apply(&trace);
+        assert_eq!(once.values[..2], trace.values[6..]);
+        assert_eq!(Mutation::SwapRows(0, 3).apply(&once).values, trace.values);
+    }
+
+    #[test]
+    fn copy_row_duplicates_the_source() {
+        let trace = counting_trace(4, 2);
+        let copied = Mutation::CopyRow { from: 1, to: 2 }.apply(&trace);
+        assert_eq!(copied.values[4..6], trace.values[2..4]);
+        assert_eq!(copied.values[6..], trace.values[6..]);
+    }
+
+    #[test]
+    fn effective_corpus_drops_no_op_mutations() {
+        let zeros = RowMajorMatrix::new(vec![Val::ZERO; 8], 2);
+        let effective = effective_corpus(&zeros);
+        assert!(effective.iter().all(|m| matches!(m, Mutation::Bump { .. })));
+        assert_eq!(effective.len(), 3 * 2);
+    }
+}
@@ -0,0 +1,63 @@
+// Checked-in fixtures, read from a package's fixtures/ directory. Tests pass
+// env!("CARGO_MANIFEST_DIR") so the path does not depend on where cargo test
+// was started.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn fixture_path(manifest_dir: &str, name: &str) -> PathBuf {
+    Path::new(manifest_dir).join("fixtures").join(name)
+}
+
+// The fixture's contents; panics naming the file if it cannot be read
+pub fn read_fixture(manifest_dir: &str, name: &str) -> String {
+    let path = fixture_path(manifest_dir, name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("cannot read fixture {}: {}", path.display(), e))
+}
+
+// The lines of a fixture that carry data: comments (#) and blank lines dropped
+pub fn fixture_entries(contents: &str) -> Vec<&str> {
+    contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')).collect()
+}
+
+// Panics with the first differing entry if `actual` and the fixture disagree
+// on anything but comments and blank lines, pointing at `regenerate`
+pub fn assert_matches_fixture(manifest_dir: &str, name: &str, actual: &str, regenerate: &str) {
+    let expected = read_fixture(manifest_dir, name);
+    let (expected, actual) = (fixture_entries(&expected), fixture_entries(actual));
+    assert!(!expected.is_empty(), "fixture {} has no entries; run {}", name, regenerate);
+    for (index, (e, a)) in expected.iter().zip(&actual).enumerate() {
+        assert_eq!(e, a, "fixture {} differs at entry {}; if the change is intended, run {}", name, index, regenerate);
+    }
+    assert_eq!(
+        expected.len(),
+        actual.len(),
+        "fixture {} has {} entries, the build produces {}; run {}",
+        name,
+        expected.len(),
+        actual.len(),
+        regenerate
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_blank_lines_are_not_entries() {
+        let contents = "# header\n\nfirst line\n  # indented comment\n second \n";
+        assert_eq!(fixture_entries(contents), vec!["first line", "second"]);
+    }
+
+    #[test]
+    fn fixture_round_trips_through_a_directory() {
+        let dir = std::env::temp_dir().join(format!("test-support-{}", std::process::id()));
+        fs::create_dir_all(dir.join("fixtures")).unwrap();
+        let manifest_dir = dir.to_str().unwrap();
+        fs::write(fixture_path(manifest_dir, "values.txt"), "# values\n1\n2\n").unwrap();
+        assert_matches_fixture(manifest_dir, "values.txt", "1\n2\n", "regen");
+        let mismatch = || assert_matches_fixture(manifest_dir, "values.txt", "1\n3\n", "regen");
+        assert!(std::panic::catch_unwind(mismatch).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
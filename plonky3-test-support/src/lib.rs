@@ -0,0 +1,48 @@
+// Helpers shared by both examples' tests: the malicious-prover corpus, the
+// Fibonacci oracles, fixture IO, the prover-side constraint check as a
+// Result, and a prove/encode/decode/verify round trip. Everything proves
+// under stark_config::test_config, so none of it says anything about
+// security.
+use p3_air::Air;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{
+    DebugConstraintBuilder, Proof, ProverConstraintFolder, SymbolicAirBuilder, VerifierConstraintFolder, prove,
+    verify,
+};
+use stark_config::{MyConfig, Val, test_config};
+
+pub mod checker;
+pub mod corpus;
+pub mod fixtures;
+pub mod oracle;
+
+// AIRs the helpers can prove and verify under MyConfig
+pub trait TestAir:
+    Air<SymbolicAirBuilder<Val>>
+    + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+    + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+    + for<'a> Air<DebugConstraintBuilder<'a, Val>>
+{
+}
+
+impl<A> TestAir for A where
+    A: Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val>>
+{
+}
+
+// Proves `trace` under test_config, encodes the proof with bincode, decodes
+// it and verifies the copy under a separately built test_config. Panics
+// naming the step that failed; returns the encoded proof.
+pub fn assert_proof_roundtrip<A: TestAir>(air: &A, trace: RowMajorMatrix<Val>, public_values: &[Val]) -> Vec<u8> {
+    let public_values = public_values.to_vec();
+    let proof = prove(&test_config(), air, trace, &public_values);
+    let bytes = bincode::serialize(&proof).expect("proof does not encode");
+    let decoded: Proof<MyConfig> = bincode::deserialize(&bytes).expect("encoded proof does not decode");
+    if let Err(e) = verify(&test_config(), air, &decoded, &public_values) {
+        panic!("decoded proof does not verify: {:?}", e);
+    }
+    bytes
+}
@@ -0,0 +1,83 @@
+// Fibonacci numbers computed without the examples' own code, to check
+// generators against: exact integers by plain iteration, and F(n) mod p by
+// fast doubling over u128, for n far past what iterating allows.
+use num_bigint::BigUint;
+use p3_field::PrimeField64;
+
+// F(n) over the integers, from F(0) = 0, F(1) = 1
+pub fn fibonacci_big(n: usize) -> BigUint {
+    let (mut a, mut b) = (BigUint::from(0u32), BigUint::from(1u32));
+    for _ in 0..n {
+        let next = &a + &b;
+        a = core::mem::replace(&mut b, next);
+    }
+    a
+}
+
+// (F(n), F(n + 1)) mod p by fast doubling
+pub fn fibonacci_pair_mod(n: u64, p: u64) -> (u64, u64) {
+    let p = u128::from(p);
+    let (mut a, mut b) = (0u128, 1u128);
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        // F(2m) = F(m) * (2 F(m + 1) - F(m)), F(2m + 1) = F(m)^2 + F(m + 1)^2
+        let even = a * ((2 * b + p - a) % p) % p;
+        let odd = (a * a + b * b) % p;
+        (a, b) = if (n >> bit) & 1 == 0 { (even, odd) } else { (odd, (even + odd) % p) };
+    }
+    (a as u64, b as u64)
+}
+
+// F(n) in the field F
+pub fn fibonacci_mod<F: PrimeField64>(n: u64) -> F {
+    F::from_u64(fibonacci_pair_mod(n, F::ORDER_U64).0)
+}
+
+// G(n) for the recurrence G(n) = G(n - 1) + G(n - 2) from (a0, b0), in F:
+// a0 * F(n - 1) + b0 * F(n), with F(-1) = 1
+pub fn recurrence_mod<F: PrimeField64>(a0: F, b0: F, n: u64) -> F {
+    if n == 0 {
+        return a0;
+    }
+    let (f_prev, f) = fibonacci_pair_mod(n - 1, F::ORDER_U64);
+    a0 * F::from_u64(f_prev) + b0 * F::from_u64(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::PrimeCharacteristicRing;
+    use stark_config::Val;
+
+    use super::*;
+
+    #[test]
+    fn big_oracle_matches_published_values() {
+        assert_eq!(fibonacci_big(0), BigUint::from(0u32));
+        assert_eq!(fibonacci_big(10), BigUint::from(55u32));
+        assert_eq!(fibonacci_big(100).to_string(), "354224848179261915075");
+    }
+
+    #[test]
+    fn fast_doubling_agrees_with_the_big_oracle_mod_p() {
+        let p = BigUint::from(Val::ORDER_U64);
+        for n in 0..300 {
+            let expected = (fibonacci_big(n) % &p).to_u64_digits().first().copied().unwrap_or(0);
+            assert_eq!(fibonacci_pair_mod(n as u64, Val::ORDER_U64).0, expected, "F({})", n);
+        }
+    }
+
+    #[test]
+    fn fast_doubling_works_near_u64_max() {
+        // F(n + 1) = F(n) + F(n - 1) holds mod p however far out n is
+        let n = u64::MAX - 1;
+        let (prev, _) = fibonacci_pair_mod(n - 1, Val::ORDER_U64);
+        let (f, next) = fibonacci_pair_mod(n, Val::ORDER_U64);
+        assert_eq!((prev + f) % Val::ORDER_U64, next);
+    }
+
+    #[test]
+    fn recurrence_from_two_one_gives_lucas_numbers() {
+        let lucas = recurrence_mod(Val::TWO, Val::ONE, 10);
+        assert_eq!(lucas, Val::from_u64(123));
+        assert_eq!(recurrence_mod(Val::ZERO, Val::ONE, 20), fibonacci_mod::<Val>(20));
+    }
+}